@@ -1,15 +1,24 @@
-use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use cosmwasm_std::{
+    entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdResult,
+};
 
-use stone_types::{FactoryExecuteMsg, FactoryInstantiateMsg, FactoryQueryMsg};
+use stone_types::{FactoryExecuteMsg, FactoryInstantiateMsg, FactoryMigrateMsg, FactoryQueryMsg};
 
 use crate::error::ContractError;
 use crate::execute::{
-    accept_ownership, create_market, handle_instantiate_reply, transfer_ownership, update_config,
-    update_market_code_id, INSTANTIATE_REPLY_ID,
+    accept_ownership, accept_protocol_fee_collector_role, batch_create_markets, create_market,
+    disable_market, emergency_withdraw_market, enable_market, freeze_market_ltv,
+    handle_batch_instantiate_reply, handle_instantiate_reply, propagate_fee_collector,
+    propose_protocol_fee_collector, rebalance_market_liquidity_index, set_market_admin,
+    set_min_market_creation_fee, transfer_ownership, update_config, update_market_code_id,
+    update_market_collateral_denom, BATCH_INSTANTIATE_REPLY_ID, INSTANTIATE_REPLY_ID_START,
 };
 use crate::query;
-use crate::state::{CONFIG, CONTRACT_NAME, CONTRACT_VERSION, MARKET_COUNT};
-use stone_types::FactoryConfig;
+use crate::state::{
+    CONTRACT_NAME, CONTRACT_VERSION, FACTORY_PARAMS, LEGACY_CONFIG, MARKETS, MARKETS_BY_TIME,
+    MARKET_COUNT,
+};
+use stone_types::FactoryParams;
 
 #[entry_point]
 pub fn instantiate(
@@ -20,14 +29,16 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let config = FactoryConfig {
+    let config = FactoryParams {
         owner: deps.api.addr_validate(&msg.owner)?,
         protocol_fee_collector: deps.api.addr_validate(&msg.protocol_fee_collector)?,
-        market_creation_fee: msg.market_creation_fee,
+        market_creation_fee: msg.market_creation_fee.clone(),
+        max_market_creation_fee: msg.market_creation_fee,
         market_code_id: msg.market_code_id,
+        paused: false,
     };
 
-    CONFIG.save(deps.storage, &config)?;
+    FACTORY_PARAMS.save(deps.storage, &config)?;
     MARKET_COUNT.save(deps.storage, &0)?;
 
     Ok(Response::new()
@@ -50,6 +61,7 @@ pub fn execute(
             oracle_config,
             params,
             salt,
+            fee_amount,
         } => create_market(
             deps,
             env,
@@ -59,18 +71,56 @@ pub fn execute(
             oracle_config,
             *params,
             salt,
+            fee_amount,
         ),
+        FactoryExecuteMsg::BatchCreateMarkets { markets } => {
+            batch_create_markets(deps, env, info, markets)
+        }
         FactoryExecuteMsg::UpdateConfig {
-            protocol_fee_collector,
             market_creation_fee,
-        } => update_config(deps, info, protocol_fee_collector, market_creation_fee),
+            paused,
+        } => update_config(deps, info, market_creation_fee, paused),
+        FactoryExecuteMsg::SetMinMarketCreationFee {
+            min_market_creation_fee,
+            max_market_creation_fee,
+        } => set_min_market_creation_fee(
+            deps,
+            info,
+            min_market_creation_fee,
+            max_market_creation_fee,
+        ),
         FactoryExecuteMsg::UpdateMarketCodeId { code_id } => {
             update_market_code_id(deps, info, code_id)
         }
+        FactoryExecuteMsg::DisableMarket { market } => disable_market(deps, info, market),
+        FactoryExecuteMsg::EnableMarket { market } => enable_market(deps, info, market),
+        FactoryExecuteMsg::EmergencyWithdrawMarket { market, recipient } => {
+            emergency_withdraw_market(deps, info, market, recipient)
+        }
+        FactoryExecuteMsg::RebalanceMarketLiquidityIndex {
+            market,
+            target_index,
+        } => rebalance_market_liquidity_index(deps, info, market, target_index),
+        FactoryExecuteMsg::FreezeMarketLtv { market } => freeze_market_ltv(deps, info, market),
+        FactoryExecuteMsg::UpdateMarketCollateralDenom { market, new_denom } => {
+            update_market_collateral_denom(deps, env, info, market, new_denom)
+        }
+        FactoryExecuteMsg::SetMarketAdmin { market, admin } => {
+            set_market_admin(deps, env, info, market, admin)
+        }
         FactoryExecuteMsg::TransferOwnership { new_owner } => {
             transfer_ownership(deps, info, new_owner)
         }
         FactoryExecuteMsg::AcceptOwnership {} => accept_ownership(deps, info),
+        FactoryExecuteMsg::ProposeProtocolFeeCollector { new_collector } => {
+            propose_protocol_fee_collector(deps, info, new_collector)
+        }
+        FactoryExecuteMsg::AcceptProtocolFeeCollectorRole {} => {
+            accept_protocol_fee_collector_role(deps, info)
+        }
+        FactoryExecuteMsg::PropagateFeeCollector { start_after, limit } => {
+            propagate_fee_collector(deps, start_after, limit)
+        }
     }
 }
 
@@ -84,9 +134,19 @@ pub fn query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> Result<Binary, Cont
         FactoryQueryMsg::MarketByAddress { address } => {
             to_json_binary(&query::market_by_address(deps, address)?)?
         }
-        FactoryQueryMsg::Markets { start_after, limit } => {
-            to_json_binary(&query::markets(deps, start_after, limit)?)?
-        }
+        FactoryQueryMsg::MarketByDenoms {
+            collateral_denom,
+            debt_denom,
+        } => to_json_binary(&query::market_by_denoms(
+            deps,
+            collateral_denom,
+            debt_denom,
+        )?)?,
+        FactoryQueryMsg::Markets {
+            start_after,
+            limit,
+            sort_by,
+        } => to_json_binary(&query::markets(deps, start_after, limit, sort_by)?)?,
         FactoryQueryMsg::MarketsByCurator {
             curator,
             start_after,
@@ -117,7 +177,13 @@ pub fn query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> Result<Binary, Cont
             start_after,
             limit,
         )?)?,
+        FactoryQueryMsg::MarketsBy {
+            filter,
+            start_after,
+            limit,
+        } => to_json_binary(&query::markets_by(deps, filter, start_after, limit)?)?,
         FactoryQueryMsg::MarketCount {} => to_json_binary(&query::market_count(deps)?)?,
+        FactoryQueryMsg::MarketCode {} => to_json_binary(&query::market_code(deps)?)?,
         FactoryQueryMsg::ComputeMarketId {
             collateral_denom,
             debt_denom,
@@ -129,6 +195,12 @@ pub fn query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> Result<Binary, Cont
             curator,
             salt,
         ))?,
+        FactoryQueryMsg::IsSaltUsed { salt } => to_json_binary(&query::is_salt_used(deps, salt)?)?,
+        FactoryQueryMsg::PendingMarkets { start_after, limit } => {
+            to_json_binary(&query::pending_markets(deps, start_after, limit)?)?
+        }
+        FactoryQueryMsg::FactoryParams {} => to_json_binary(&query::factory_params(deps)?)?,
+        FactoryQueryMsg::TotalProtocolTvl {} => to_json_binary(&query::total_protocol_tvl(deps)?)?,
     };
 
     Ok(result)
@@ -137,19 +209,70 @@ pub fn query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> Result<Binary, Cont
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
-        INSTANTIATE_REPLY_ID => handle_instantiate_reply(deps, env, msg),
+        BATCH_INSTANTIATE_REPLY_ID => handle_batch_instantiate_reply(deps, env, msg),
+        id if id >= INSTANTIATE_REPLY_ID_START => handle_instantiate_reply(deps, env, msg),
         id => Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
             format!("Unknown reply id: {}", id),
         ))),
     }
 }
 
+/// Moves parameters written under the legacy `config` key into `FACTORY_PARAMS`
+/// and adds markets registered before `MARKETS_BY_TIME` existed to that index.
+/// A no-op when the contract has already been migrated.
+#[entry_point]
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: FactoryMigrateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let migrated = match LEGACY_CONFIG.may_load(deps.storage)? {
+        Some(legacy) if !FACTORY_PARAMS.exists(deps.storage) => {
+            // The legacy fee was fixed, so it becomes both the minimum and the cap
+            FACTORY_PARAMS.save(
+                deps.storage,
+                &FactoryParams {
+                    owner: legacy.owner,
+                    protocol_fee_collector: legacy.protocol_fee_collector,
+                    max_market_creation_fee: legacy.market_creation_fee.clone(),
+                    market_creation_fee: legacy.market_creation_fee,
+                    market_code_id: legacy.market_code_id,
+                    paused: false,
+                },
+            )?;
+            LEGACY_CONFIG.remove(deps.storage);
+            true
+        }
+        _ => false,
+    };
+
+    let unindexed: Vec<_> = MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(market_id, record)| {
+            !MARKETS_BY_TIME.has(deps.storage, (record.created_at, market_id))
+        })
+        .collect();
+    for (market_id, record) in &unindexed {
+        MARKETS_BY_TIME.save(deps.storage, (record.created_at, market_id), &())?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_attribute("params_migrated", migrated.to_string())
+        .add_attribute("markets_indexed", unindexed.len().to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::{from_json, Coin, Uint128};
-    use stone_types::FactoryConfigResponse;
+    use cosmwasm_std::{coin, from_json, Addr, Coin, Uint128};
+    use stone_types::{FactoryConfigResponse, MarketCodeResponse};
 
     #[test]
     fn test_instantiate() {
@@ -175,7 +298,7 @@ mod tests {
         assert_eq!(res.attributes.len(), 3);
 
         // Verify config was saved
-        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        let config = FACTORY_PARAMS.load(deps.as_ref().storage).unwrap();
         assert_eq!(config.owner, owner);
         assert_eq!(config.market_code_id, 1);
     }
@@ -208,4 +331,124 @@ mod tests {
         assert_eq!(config.owner, owner.to_string());
         assert_eq!(config.market_code_id, 42);
     }
+
+    #[test]
+    fn test_query_market_code() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let api = MockApi::default();
+        let creator = api.addr_make("creator");
+        let info = message_info(&creator, &[]);
+
+        let msg = FactoryInstantiateMsg {
+            owner: api.addr_make("owner").to_string(),
+            protocol_fee_collector: api.addr_make("collector").to_string(),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 42,
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), env, FactoryQueryMsg::MarketCode {}).unwrap();
+        let code: MarketCodeResponse = from_json(res).unwrap();
+        assert_eq!(code.code_id, 42);
+    }
+
+    #[test]
+    fn test_migrate_moves_legacy_config() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+
+        // Configuration as written by the pre-migration code: a single fixed
+        // fee, no `paused` field, stored under the old `config` key
+        #[cosmwasm_schema::cw_serde]
+        struct BaselineFactoryConfig {
+            owner: Addr,
+            protocol_fee_collector: Addr,
+            market_creation_fee: Coin,
+            market_code_id: u64,
+        }
+        cw_storage_plus::Item::<BaselineFactoryConfig>::new("config")
+            .save(
+                deps.as_mut().storage,
+                &BaselineFactoryConfig {
+                    owner: owner.clone(),
+                    protocol_fee_collector: api.addr_make("collector"),
+                    market_creation_fee: coin(1_000, "uosmo"),
+                    market_code_id: 7,
+                },
+            )
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), FactoryMigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "params_migrated" && a.value == "true"));
+
+        let params: FactoryParams =
+            from_json(query(deps.as_ref(), mock_env(), FactoryQueryMsg::FactoryParams {}).unwrap())
+                .unwrap();
+        assert_eq!(params.owner, owner);
+        assert_eq!(params.market_creation_fee, coin(1_000, "uosmo"));
+        assert_eq!(params.protocol_fee_collector, api.addr_make("collector"));
+        assert_eq!(params.max_market_creation_fee, coin(1_000, "uosmo"));
+        assert_eq!(params.market_code_id, 7);
+        assert!(!params.paused);
+        assert!(!LEGACY_CONFIG.exists(deps.as_ref().storage));
+
+        // Running the migration again leaves the parameters untouched
+        let res = migrate(deps.as_mut(), mock_env(), FactoryMigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "params_migrated" && a.value == "false"));
+        assert_eq!(FACTORY_PARAMS.load(deps.as_ref().storage).unwrap(), params);
+    }
+
+    #[test]
+    fn test_migrate_indexes_markets_by_time() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+
+        // Markets registered before the creation time index existed
+        for (market_id, created_at) in [("market1", 2000), ("market2", 1000)] {
+            let record = stone_types::MarketRecord {
+                market_id: market_id.to_string(),
+                address: api.addr_make(market_id),
+                curator: api.addr_make("curator"),
+                collateral_denom: "uatom".to_string(),
+                debt_denom: "uusdc".to_string(),
+                created_at,
+            };
+            MARKETS
+                .save(deps.as_mut().storage, market_id, &record)
+                .unwrap();
+        }
+
+        let res = migrate(deps.as_mut(), mock_env(), FactoryMigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "markets_indexed" && a.value == "2"));
+
+        let indexed: Vec<(u64, String)> = MARKETS_BY_TIME
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        assert_eq!(
+            indexed,
+            vec![(1000, "market2".to_string()), (2000, "market1".to_string())]
+        );
+
+        // Already indexed markets are skipped
+        let res = migrate(deps.as_mut(), mock_env(), FactoryMigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "markets_indexed" && a.value == "0"));
+    }
 }
@@ -7,7 +7,10 @@ pub enum ContractError {
     Std(#[from] StdError),
 
     #[error("{0}")]
-    Types(#[from] stone_types::ContractError),
+    Types(#[from] stone_types::errors::TypesError),
+
+    #[error("{0}")]
+    InvalidMarketParams(#[from] stone_types::ContractError),
 
     #[error("Unauthorized")]
     Unauthorized,
@@ -15,23 +18,50 @@ pub enum ContractError {
     #[error("Market already exists: {market_id}")]
     MarketAlreadyExists { market_id: String },
 
+    #[error("Salt already used: {salt}")]
+    SaltAlreadyUsed { salt: u64 },
+
     #[error("Market not found: {market_id}")]
     MarketNotFound { market_id: String },
 
     #[error("Insufficient creation fee: required {required}, sent {sent}")]
     InsufficientCreationFee { required: String, sent: String },
 
+    #[error("Invalid creation fee range: min {min}, max {max}")]
+    InvalidCreationFeeRange { min: String, max: String },
+
+    #[error("Batch creation fee mismatch: required {required}, provided {provided}")]
+    InsufficientBatchFee { required: String, provided: String },
+
+    #[error("Batch must contain at least one market")]
+    EmptyBatch,
+
+    #[error("Market creation is paused")]
+    FactoryPaused,
+
     #[error("Invalid denom: collateral and debt must be different")]
     SameDenom,
 
+    #[error("Invalid market code ID: must be greater than zero")]
+    InvalidCodeId,
+
     #[error("Invalid oracle: failed to query price for {denom}")]
     InvalidOracle { denom: String },
 
+    #[error("Factory is not the admin of market {market}")]
+    NotMarketAdmin { market: String },
+
     #[error("Pending ownership transfer not found")]
     NoPendingOwnership,
 
     #[error("Not the pending owner")]
     NotPendingOwner,
+
+    #[error("Pending protocol fee collector not found")]
+    NoPendingFeeCollector,
+
+    #[error("Not the pending protocol fee collector")]
+    NotPendingFeeCollector,
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -1,26 +1,32 @@
 use cosmwasm_std::{
-    to_json_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, SubMsg, WasmMsg,
-    WasmQuery,
+    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage, SubMsg, SubMsgResult, Uint128, WasmMsg,
 };
 
+use cw_storage_plus::Bound;
 use stone_types::{
-    compute_market_id, ContractError as TypesError, CreateMarketParams, MarketInstantiateMsg,
+    compute_market_id, errors::TypesError, CreateMarketAttempt, CreateMarketParams,
+    CreateMarketRequest, CreateMarketResult, FactoryParams, MarketExecuteMsg, MarketInstantiateMsg,
     MarketRecord, OracleConfig, OracleConfigUnchecked, OracleQueryMsg, PriceResponse,
 };
 
 use crate::error::ContractError;
 use crate::state::{
-    CONFIG, MARKETS, MARKETS_BY_ADDRESS, MARKETS_BY_COLLATERAL, MARKETS_BY_CURATOR,
-    MARKETS_BY_DEBT, MARKET_COUNT, PENDING_MARKET_SALTS, PENDING_OWNER,
+    BATCH_REPLY_COUNT, FACTORY_PARAMS, MARKETS, MARKETS_BY_ADDRESS, MARKETS_BY_COLLATERAL,
+    MARKETS_BY_CURATOR, MARKETS_BY_DEBT, MARKETS_BY_DENOMS, MARKETS_BY_TIME, MARKET_CODE_HISTORY,
+    MARKET_COUNT, MARKET_COUNTER, NEXT_INSTANTIATE_REPLY_ID, PENDING_BATCH_SALTS,
+    PENDING_FEE_COLLECTOR, PENDING_MARKETS, PENDING_OWNER, USED_SALTS,
 };
 
-/// Reply ID for market instantiation
-pub const INSTANTIATE_REPLY_ID: u64 = 1;
+/// First reply ID used for `CreateMarket` instantiations. Each creation takes the
+/// next ID so its `PENDING_MARKETS` entry is tracked independently.
+pub const INSTANTIATE_REPLY_ID_START: u64 = 1_000;
+
+/// Reply ID for market instantiations dispatched by `BatchCreateMarkets`
+pub const BATCH_INSTANTIATE_REPLY_ID: u64 = 2;
 
 /// Validate market creation parameters.
 fn validate_market_params(params: &CreateMarketParams) -> Result<(), ContractError> {
-    use stone_types::ContractError as TypesError;
-
     // LTV must be less than liquidation threshold
     if params.loan_to_value >= params.liquidation_threshold {
         return Err(TypesError::InvalidLtv.into());
@@ -73,6 +79,10 @@ fn validate_market_params(params: &CreateMarketParams) -> Result<(), ContractErr
         return Err(TypesError::InvalidInterestRateModel.into());
     }
 
+    // Everything the market checks at instantiation (close factor, liquidation
+    // incentive, caps), so invalid params fail here instead of in the reply
+    params.clone().into_market_params(0).validate()?;
+
     Ok(())
 }
 
@@ -86,22 +96,9 @@ fn validate_oracle(
     debt_denom: &str,
 ) -> Result<(), ContractError> {
     // 1. Validate code ID if required by oracle type
-    if let Some(expected_code_id) = oracle_config.oracle_type.expected_code_id() {
-        let contract_info: cosmwasm_std::ContractInfoResponse = deps.querier.query(
-            &WasmQuery::ContractInfo {
-                contract_addr: oracle_config.address.to_string(),
-            }
-            .into(),
-        )?;
-
-        if contract_info.code_id != expected_code_id {
-            return Err(TypesError::OracleCodeIdMismatch {
-                expected: expected_code_id,
-                actual: contract_info.code_id,
-            }
-            .into());
-        }
-    }
+    oracle_config
+        .oracle_type
+        .validate_code_id(deps.as_ref(), &oracle_config.address)?;
 
     // 2. Validate prices can be fetched and are valid for both denoms
     validate_price_query(deps, env, oracle_config, collateral_denom)?;
@@ -146,90 +143,75 @@ fn validate_price_query(
         .into());
     }
 
-    // Validate staleness
-    let max_staleness = oracle_config.oracle_type.max_staleness_secs();
-    let current_time = env.block.time.seconds();
-
-    if current_time > response.updated_at + max_staleness {
-        return Err(TypesError::OraclePriceStale {
-            updated_at: response.updated_at,
-            current_time,
-            max_staleness,
-        }
-        .into());
-    }
+    // Validate the price is neither stale nor in the future
+    oracle_config.validate_fresh(&response, env.block.time.seconds())?;
 
     Ok(())
 }
 
+/// Validate a market creation request and build its instantiate message.
+/// Returns the market ID and the `WasmMsg::Instantiate` for the market contract.
 #[allow(clippy::too_many_arguments)]
-pub fn create_market(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
+fn build_market_instantiate(
+    deps: &DepsMut,
+    env: &Env,
+    config: &FactoryParams,
+    curator: &Addr,
     collateral_denom: String,
     debt_denom: String,
     oracle_config: OracleConfigUnchecked,
     params: CreateMarketParams,
     salt: Option<u64>,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-
+) -> Result<(String, WasmMsg), ContractError> {
     // Validate denoms are different
     if collateral_denom == debt_denom {
         return Err(ContractError::SameDenom);
     }
 
-    // Validate creation fee
-    let fee_required = &config.market_creation_fee;
-    let fee_sent = info
-        .funds
-        .iter()
-        .find(|c| c.denom == fee_required.denom)
-        .map(|c| c.amount)
-        .unwrap_or_default();
-
-    if fee_sent < fee_required.amount {
-        return Err(ContractError::InsufficientCreationFee {
-            required: fee_required.to_string(),
-            sent: format!("{}{}", fee_sent, fee_required.denom),
-        });
-    }
-
     // Validate parameters
     validate_market_params(&params)?;
 
     // Validate oracle configuration (code ID and price queries)
     let validated_oracle_config = oracle_config.validate(deps.api)?;
     validate_oracle(
-        &deps,
-        &env,
+        deps,
+        env,
         &validated_oracle_config,
         &collateral_denom,
         &debt_denom,
     )?;
 
     // Generate market ID
-    let curator = info.sender.as_str();
-    let market_id = compute_market_id(&collateral_denom, &debt_denom, curator, salt);
+    let market_id = compute_market_id(&collateral_denom, &debt_denom, curator.as_str(), salt);
 
     // Check for collision
     if MARKETS.has(deps.storage, &market_id) {
         return Err(ContractError::MarketAlreadyExists { market_id });
     }
 
+    // An explicit salt seeds at most one market
+    if let Some(salt) = salt {
+        if USED_SALTS.has(deps.storage, salt) {
+            return Err(ContractError::SaltAlreadyUsed { salt });
+        }
+    }
+
     // Create instantiate message for market contract
     // Pass the unchecked config - market will re-validate on instantiation
     let market_instantiate_msg = MarketInstantiateMsg {
-        curator: info.sender.to_string(),
+        curator: curator.to_string(),
         oracle_config: OracleConfigUnchecked {
             address: validated_oracle_config.address.to_string(),
             oracle_type: validated_oracle_config.oracle_type,
         },
-        collateral_denom: collateral_denom.clone(),
-        debt_denom: debt_denom.clone(),
+        collateral_denom,
+        debt_denom,
         protocol_fee_collector: config.protocol_fee_collector.to_string(),
         params,
+        // Collateral swaps are not yet enabled for factory-created markets
+        allow_collateral_swap: false,
+        // Receipt NFTs are only configured on directly instantiated markets
+        receipt_nft_contract: None,
     };
 
     // Create submessage to instantiate market contract
@@ -241,18 +223,216 @@ pub fn create_market(
         label: format!("stone-market-{}", &market_id[..8]),
     };
 
-    // We'll need to handle the reply to get the contract address
-    // For now, store pending market info that will be completed in reply
-    // This is a simplified version - full implementation needs reply handling
+    Ok((market_id, instantiate_msg))
+}
+
+/// Salt a new market is created with. An explicit salt is used as given; `None`
+/// takes the next auto salt from `MARKET_COUNTER`, skipping salts already taken.
+fn resolve_salt(storage: &mut dyn Storage, salt: Option<u64>) -> StdResult<u64> {
+    if let Some(salt) = salt {
+        return Ok(salt);
+    }
+
+    let mut next = MARKET_COUNTER.may_load(storage)?.unwrap_or(0);
+    while USED_SALTS.has(storage, next) {
+        next += 1;
+    }
+    MARKET_COUNTER.save(storage, &(next + 1))?;
+    Ok(next)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collateral_denom: String,
+    debt_denom: String,
+    oracle_config: OracleConfigUnchecked,
+    params: CreateMarketParams,
+    salt: Option<u64>,
+    fee_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if config.paused {
+        return Err(ContractError::FactoryPaused);
+    }
+
+    // Validate creation fee: the chosen amount must be at least the minimum and
+    // covered by the funds sent
+    let min_fee = &config.market_creation_fee;
+    let fee_amount = fee_amount.unwrap_or(min_fee.amount);
+    let fee_sent = info
+        .funds
+        .iter()
+        .find(|c| c.denom == min_fee.denom)
+        .map(|c| c.amount)
+        .unwrap_or_default();
+
+    if fee_amount < min_fee.amount || fee_sent < fee_amount {
+        return Err(ContractError::InsufficientCreationFee {
+            required: format!("{}{}", fee_amount.max(min_fee.amount), min_fee.denom),
+            sent: format!("{}{}", fee_sent, min_fee.denom),
+        });
+    }
+
+    // Only up to the maximum fee is charged; the rest of the sent funds are refunded
+    let fee_paid = Coin {
+        denom: min_fee.denom.clone(),
+        amount: fee_amount.min(config.max_market_creation_fee.amount),
+    };
+    let fee_refunded = fee_sent - fee_paid.amount;
+
+    let salt = resolve_salt(deps.storage, salt)?;
+
+    let (market_id, instantiate_msg) = build_market_instantiate(
+        &deps,
+        &env,
+        &config,
+        &info.sender,
+        collateral_denom.clone(),
+        debt_denom.clone(),
+        oracle_config,
+        params,
+        Some(salt),
+    )?;
+
+    // Track the attempt under its own reply ID until the reply registers the market
+    let reply_id = NEXT_INSTANTIATE_REPLY_ID
+        .may_load(deps.storage)?
+        .unwrap_or(INSTANTIATE_REPLY_ID_START);
+    NEXT_INSTANTIATE_REPLY_ID.save(deps.storage, &(reply_id + 1))?;
+    PENDING_MARKETS.save(
+        deps.storage,
+        reply_id,
+        &CreateMarketAttempt {
+            reply_id,
+            market_id: market_id.clone(),
+            curator: info.sender.clone(),
+            collateral_denom: collateral_denom.clone(),
+            debt_denom: debt_denom.clone(),
+            salt: Some(salt),
+            started_at: env.block.time.seconds(),
+            fee_paid: fee_paid.clone(),
+        },
+    )?;
+
+    // The creation fee is collected (or refunded) and the market counted in the reply,
+    // so a failed instantiation leaves its pending entry without charging the curator
+    let mut response = Response::new()
+        .add_submessage(SubMsg::reply_always(instantiate_msg, reply_id))
+        .add_attribute("action", "create_market")
+        .add_attribute("market_id", &market_id)
+        .add_attribute("curator", &info.sender)
+        .add_attribute("collateral_denom", collateral_denom)
+        .add_attribute("debt_denom", debt_denom)
+        .add_attribute("salt", salt.to_string())
+        .add_attribute("reply_id", reply_id.to_string())
+        .add_attribute("fee_paid", fee_paid.to_string());
+
+    if !fee_refunded.is_zero() {
+        response = response
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: fee_paid.denom,
+                    amount: fee_refunded,
+                }],
+            })
+            .add_attribute("fee_refunded", fee_refunded);
+    }
+
+    Ok(response)
+}
+
+/// Create several markets in one transaction.
+/// Exactly `markets.len() * market_creation_fee` must be sent. Every market is
+/// validated before any instantiation is dispatched, so the batch is all-or-nothing.
+pub fn batch_create_markets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    markets: Vec<CreateMarketRequest>,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if config.paused {
+        return Err(ContractError::FactoryPaused);
+    }
+
+    if markets.is_empty() {
+        return Err(ContractError::EmptyBatch);
+    }
+
+    // Validate creation fee: exactly N * market_creation_fee
+    let fee_required = &config.market_creation_fee;
+    let batch_size = markets.len() as u128;
+    let required = fee_required
+        .amount
+        .checked_mul(Uint128::new(batch_size))
+        .map_err(cosmwasm_std::StdError::from)?;
+    let provided = info
+        .funds
+        .iter()
+        .find(|c| c.denom == fee_required.denom)
+        .map(|c| c.amount)
+        .unwrap_or_default();
+
+    if provided != required {
+        return Err(ContractError::InsufficientBatchFee {
+            required: format!("{}{}", required, fee_required.denom),
+            provided: format!("{}{}", provided, fee_required.denom),
+        });
+    }
+
+    let mut submessages = Vec::with_capacity(markets.len());
+    let mut market_ids: Vec<String> = Vec::with_capacity(markets.len());
+    let mut salts = Vec::with_capacity(markets.len());
+
+    for market in markets {
+        let salt = Some(resolve_salt(deps.storage, market.salt)?);
+        let (market_id, instantiate_msg) = build_market_instantiate(
+            &deps,
+            &env,
+            &config,
+            &info.sender,
+            market.collateral_denom,
+            market.debt_denom,
+            market.oracle_config,
+            market.params,
+            salt,
+        )?;
+
+        // Markets within the batch must not collide with each other either
+        if market_ids.contains(&market_id) {
+            return Err(ContractError::MarketAlreadyExists { market_id });
+        }
+        if let Some(salt) = salt {
+            if salts.contains(&Some(salt)) {
+                return Err(ContractError::SaltAlreadyUsed { salt });
+            }
+        }
+
+        submessages.push(SubMsg::reply_on_success(
+            instantiate_msg,
+            BATCH_INSTANTIATE_REPLY_ID,
+        ));
+        market_ids.push(market_id);
+        salts.push(salt);
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    // Transfer creation fee to fee collector
-    if !fee_required.amount.is_zero() {
+    // Transfer all creation fees to fee collector
+    if !required.is_zero() {
         messages.push(
             BankMsg::Send {
                 to_address: config.protocol_fee_collector.to_string(),
-                amount: vec![fee_required.clone()],
+                amount: vec![Coin {
+                    denom: fee_required.denom.clone(),
+                    amount: required,
+                }],
             }
             .into(),
         );
@@ -260,191 +440,741 @@ pub fn create_market(
 
     // Increment market count
     let count = MARKET_COUNT.may_load(deps.storage)?.unwrap_or(0);
-    MARKET_COUNT.save(deps.storage, &(count + 1))?;
+    MARKET_COUNT.save(deps.storage, &(count + market_ids.len() as u64))?;
 
-    // Store the salt for the reply handler to properly compute market_id
-    // This ensures the market ID computed at creation matches the one in reply
-    // Note: This assumes only one pending instantiation at a time (see state.rs comment)
-    PENDING_MARKET_SALTS.save(deps.storage, &salt)?;
+    // Replies arrive in submessage order; the reply handler walks the salts with a counter
+    PENDING_BATCH_SALTS.save(deps.storage, &salts)?;
+    BATCH_REPLY_COUNT.save(deps.storage, &0)?;
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_submessage(SubMsg::reply_on_success(
-            instantiate_msg,
-            INSTANTIATE_REPLY_ID,
-        ))
-        .add_attribute("action", "create_market")
-        .add_attribute("market_id", &market_id)
+        .add_submessages(submessages)
+        .add_attribute("action", "batch_create_markets")
         .add_attribute("curator", info.sender)
-        .add_attribute("collateral_denom", collateral_denom)
-        .add_attribute("debt_denom", debt_denom))
+        .add_attribute("market_count", market_ids.len().to_string())
+        .add_attribute("market_ids", market_ids.join(",")))
 }
 
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    protocol_fee_collector: Option<String>,
     market_creation_fee: Option<Coin>,
+    paused: Option<bool>,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let mut config = FACTORY_PARAMS.load(deps.storage)?;
 
     // Only owner can update config
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized);
     }
 
-    if let Some(collector) = protocol_fee_collector {
-        config.protocol_fee_collector = deps.api.addr_validate(&collector)?;
-    }
-
     if let Some(fee) = market_creation_fee {
+        config.max_market_creation_fee = fee.clone();
         config.market_creation_fee = fee;
     }
 
-    CONFIG.save(deps.storage, &config)?;
+    if let Some(paused) = paused {
+        config.paused = paused;
+    }
+
+    FACTORY_PARAMS.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("paused", config.paused.to_string()))
+}
+
+/// Set the range of creation fees a curator may pay for `CreateMarket`.
+pub fn set_min_market_creation_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_market_creation_fee: Coin,
+    max_market_creation_fee: Coin,
+) -> Result<Response, ContractError> {
+    let mut config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    if min_market_creation_fee.denom != max_market_creation_fee.denom
+        || min_market_creation_fee.amount > max_market_creation_fee.amount
+    {
+        return Err(ContractError::InvalidCreationFeeRange {
+            min: min_market_creation_fee.to_string(),
+            max: max_market_creation_fee.to_string(),
+        });
+    }
+
+    config.market_creation_fee = min_market_creation_fee;
+    config.max_market_creation_fee = max_market_creation_fee;
+    FACTORY_PARAMS.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    Ok(Response::new()
+        .add_attribute("action", "set_min_market_creation_fee")
+        .add_attribute(
+            "min_market_creation_fee",
+            config.market_creation_fee.to_string(),
+        )
+        .add_attribute(
+            "max_market_creation_fee",
+            config.max_market_creation_fee.to_string(),
+        ))
 }
 
+/// Point future `CreateMarket` calls at a new market code ID.
+/// Existing markets are not migrated; the previous code ID is kept in `MARKET_CODE_HISTORY`.
 pub fn update_market_code_id(
     deps: DepsMut,
     info: MessageInfo,
     code_id: u64,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let mut config = FACTORY_PARAMS.load(deps.storage)?;
 
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized);
     }
 
+    if code_id == 0 {
+        return Err(ContractError::InvalidCodeId);
+    }
+
+    let old_code_id = config.market_code_id;
+    MARKET_CODE_HISTORY.push_back(deps.storage, &old_code_id)?;
+
     config.market_code_id = code_id;
-    CONFIG.save(deps.storage, &config)?;
+    FACTORY_PARAMS.save(deps.storage, &config)?;
 
     Ok(Response::new()
         .add_attribute("action", "update_market_code_id")
-        .add_attribute("code_id", code_id.to_string()))
+        .add_attribute("old_code_id", old_code_id.to_string())
+        .add_attribute("new_code_id", code_id.to_string()))
 }
 
-pub fn transfer_ownership(
+/// Resolve a market contract address registered by this factory to its market ID.
+/// Owner actions forwarded to markets go through this, so the owner cannot use
+/// the factory to call arbitrary contracts.
+fn registered_market(deps: &DepsMut, market: &str) -> Result<(Addr, String), ContractError> {
+    let market_addr = deps.api.addr_validate(market)?;
+    let market_id = MARKETS_BY_ADDRESS
+        .may_load(deps.storage, &market_addr)?
+        .ok_or_else(|| ContractError::MarketNotFound {
+            market_id: market.to_string(),
+        })?;
+    Ok((market_addr, market_id))
+}
+
+/// Pause a market created by this factory (owner only).
+pub fn disable_market(
     deps: DepsMut,
     info: MessageInfo,
-    new_owner: String,
+    market: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let config = FACTORY_PARAMS.load(deps.storage)?;
 
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized);
     }
 
-    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
-    PENDING_OWNER.save(deps.storage, &new_owner_addr)?;
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+
+    let pause = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::Pause {})?,
+        funds: vec![],
+    };
 
     Ok(Response::new()
-        .add_attribute("action", "transfer_ownership")
-        .add_attribute("pending_owner", new_owner))
+        .add_message(pause)
+        .add_attribute("action", "disable_market")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id))
 }
 
-pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let pending = PENDING_OWNER
-        .may_load(deps.storage)?
-        .ok_or(ContractError::NoPendingOwnership)?;
+/// Lift the emergency pause on a market created by this factory (owner only).
+pub fn enable_market(
+    deps: DepsMut,
+    info: MessageInfo,
+    market: String,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
 
-    if info.sender != pending {
-        return Err(ContractError::NotPendingOwner);
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
     }
 
-    let mut config = CONFIG.load(deps.storage)?;
-    config.owner = pending.clone();
-    CONFIG.save(deps.storage, &config)?;
-    PENDING_OWNER.remove(deps.storage);
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+
+    let unpause = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::Unpause {})?,
+        funds: vec![],
+    };
 
     Ok(Response::new()
-        .add_attribute("action", "accept_ownership")
-        .add_attribute("new_owner", pending))
+        .add_message(unpause)
+        .add_attribute("action", "enable_market")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id))
 }
 
-/// Handle reply from market instantiation to capture contract address.
-#[allow(deprecated)] // data field still needed for older CosmWasm versions
-pub fn handle_instantiate_reply(
+/// Recover the accrued protocol fees of a market created by this factory (owner
+/// only). Sends `EmergencyWithdraw` to the market.
+pub fn emergency_withdraw_market(
     deps: DepsMut,
-    env: Env,
-    msg: cosmwasm_std::Reply,
+    info: MessageInfo,
+    market: String,
+    recipient: String,
 ) -> Result<Response, ContractError> {
-    // Parse the instantiate response to get the contract address
-    let res = cw_utils::parse_instantiate_response_data(
-        msg.result
-            .into_result()
-            .map_err(cosmwasm_std::StdError::generic_err)?
-            .data
-            .ok_or_else(|| cosmwasm_std::StdError::generic_err("no data in reply"))?
-            .as_slice(),
-    )
-    .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
+    let config = FACTORY_PARAMS.load(deps.storage)?;
 
-    let market_address = deps.api.addr_validate(&res.contract_address)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
 
-    // Query the market contract to get its configuration
-    let market_config: stone_types::MarketConfigResponse = deps
-        .querier
-        .query_wasm_smart(&market_address, &stone_types::MarketQueryMsg::Config {})?;
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
 
-    // Retrieve the salt that was used during market creation
-    // This is critical for computing the correct market_id that was checked for collision
-    let salt = PENDING_MARKET_SALTS
-        .may_load(deps.storage)?
-        .ok_or_else(|| {
-            cosmwasm_std::StdError::generic_err("no pending market salt - internal error")
-        })?;
+    let withdraw = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::EmergencyWithdraw {
+            recipient: recipient.to_string(),
+        })?,
+        funds: vec![],
+    };
 
-    // Compute the market ID using the SAME salt that was used during creation
-    // This ensures the market ID matches what was checked for collision in create_market
-    let market_id = compute_market_id(
-        &market_config.collateral_denom,
-        &market_config.debt_denom,
-        &market_config.curator,
-        salt,
-    );
+    Ok(Response::new()
+        .add_message(withdraw)
+        .add_attribute("action", "emergency_withdraw_market")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id)
+        .add_attribute("recipient", recipient))
+}
 
-    let curator_addr = deps.api.addr_validate(&market_config.curator)?;
+/// Correct rounding drift in the liquidity index of a market created by this
+/// factory (owner only). Sends `RebalanceLiquidityIndex` to the market, which
+/// enforces the maximum deviation.
+pub fn rebalance_market_liquidity_index(
+    deps: DepsMut,
+    info: MessageInfo,
+    market: String,
+    target_index: cosmwasm_std::Decimal,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
 
-    // Create market record
-    let market_record = MarketRecord {
-        market_id: market_id.clone(),
-        address: market_address.clone(),
-        curator: curator_addr.clone(),
-        collateral_denom: market_config.collateral_denom.clone(),
-        debt_denom: market_config.debt_denom.clone(),
-        created_at: env.block.time.seconds(),
-    };
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
 
-    // Save to all indices
-    MARKETS.save(deps.storage, &market_id, &market_record)?;
-    MARKETS_BY_ADDRESS.save(deps.storage, &market_address, &market_id)?;
-    MARKETS_BY_CURATOR.save(deps.storage, (&curator_addr, &market_id), &())?;
-    MARKETS_BY_COLLATERAL.save(
-        deps.storage,
-        (&market_config.collateral_denom, &market_id),
-        &(),
-    )?;
-    MARKETS_BY_DEBT.save(deps.storage, (&market_config.debt_denom, &market_id), &())?;
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
 
-    // Clean up the pending salt - we've successfully registered the market
-    PENDING_MARKET_SALTS.remove(deps.storage);
+    let rebalance = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::RebalanceLiquidityIndex { target_index })?,
+        funds: vec![],
+    };
 
     Ok(Response::new()
-        .add_attribute("action", "market_instantiated")
+        .add_message(rebalance)
+        .add_attribute("action", "rebalance_market_liquidity_index")
+        .add_attribute("market", market_addr)
         .add_attribute("market_id", market_id)
-        .add_attribute("market_address", market_address))
+        .add_attribute("target_index", target_index.to_string()))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{message_info, mock_dependencies, MockApi};
-    use cosmwasm_std::{Coin, Decimal, Uint128};
-    use stone_types::{FactoryConfig, InterestRateModel};
+/// Permanently lock the LTV of a market created by this factory (owner only).
+pub fn freeze_market_ltv(
+    deps: DepsMut,
+    info: MessageInfo,
+    market: String,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
 
-    fn valid_params() -> CreateMarketParams {
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+
+    let freeze = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::FreezeLtv {})?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(freeze)
+        .add_attribute("action", "freeze_market_ltv")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id))
+}
+
+/// Switch the collateral denom of a market created by this factory after a
+/// collateral token migration (owner only). The market's oracle must return a
+/// fresh price for the new denom. The market keeps its ID; the factory's denom
+/// indices are moved to the new denom.
+pub fn update_market_collateral_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market: String,
+    new_denom: String,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+    let mut record = MARKETS.load(deps.storage, &market_id)?;
+
+    if new_denom == record.debt_denom {
+        return Err(ContractError::SameDenom);
+    }
+
+    let market_config: stone_types::MarketConfigResponse = deps
+        .querier
+        .query_wasm_smart(&market_addr, &stone_types::MarketQueryMsg::Config {})?;
+    let oracle_config = OracleConfig {
+        address: deps.api.addr_validate(&market_config.oracle)?,
+        oracle_type: market_config.oracle_type,
+    };
+    validate_price_query(&deps, &env, &oracle_config, &new_denom)?;
+
+    // Move the denom indices over to the new collateral denom
+    let previous_denom = std::mem::replace(&mut record.collateral_denom, new_denom.clone());
+    MARKETS.save(deps.storage, &market_id, &record)?;
+    MARKETS_BY_COLLATERAL.remove(deps.storage, (&previous_denom, &market_id));
+    MARKETS_BY_COLLATERAL.save(deps.storage, (&new_denom, &market_id), &())?;
+
+    let previous_key = (previous_denom.as_str(), record.debt_denom.as_str());
+    if MARKETS_BY_DENOMS.may_load(deps.storage, previous_key)? == Some(market_addr.clone()) {
+        MARKETS_BY_DENOMS.remove(deps.storage, previous_key);
+    }
+    let new_key = (new_denom.as_str(), record.debt_denom.as_str());
+    if !MARKETS_BY_DENOMS.has(deps.storage, new_key) {
+        MARKETS_BY_DENOMS.save(deps.storage, new_key, &market_addr)?;
+    }
+
+    let update = WasmMsg::Execute {
+        contract_addr: market_addr.to_string(),
+        msg: to_json_binary(&MarketExecuteMsg::UpdateCollateralDenom {
+            new_denom: new_denom.clone(),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(update)
+        .add_attribute("action", "update_market_collateral_denom")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id)
+        .add_attribute("previous_collateral_denom", previous_denom)
+        .add_attribute("collateral_denom", new_denom))
+}
+
+/// Rotate the CosmWasm migrate admin of a market created by this factory (owner only).
+/// `None` clears the admin. Fails unless the factory is the market's current admin.
+pub fn set_market_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market: String,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let (market_addr, market_id) = registered_market(&deps, &market)?;
+
+    let contract_info = deps.querier.query_wasm_contract_info(&market_addr)?;
+    if contract_info.admin.as_ref() != Some(&env.contract.address) {
+        return Err(ContractError::NotMarketAdmin {
+            market: market_addr.to_string(),
+        });
+    }
+
+    let admin_addr = admin
+        .map(|admin| deps.api.addr_validate(&admin))
+        .transpose()?;
+
+    let msg = match &admin_addr {
+        Some(admin) => WasmMsg::UpdateAdmin {
+            contract_addr: market_addr.to_string(),
+            admin: admin.to_string(),
+        },
+        None => WasmMsg::ClearAdmin {
+            contract_addr: market_addr.to_string(),
+        },
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "set_market_admin")
+        .add_attribute("market", market_addr)
+        .add_attribute("market_id", market_id)
+        .add_attribute(
+            "admin",
+            admin_addr
+                .map(|a| a.to_string())
+                .unwrap_or("none".to_string()),
+        ))
+}
+
+pub fn transfer_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(deps.storage, &new_owner_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_ownership")
+        .add_attribute("pending_owner", new_owner))
+}
+
+pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwnership)?;
+
+    if info.sender != pending {
+        return Err(ContractError::NotPendingOwner);
+    }
+
+    let mut config = FACTORY_PARAMS.load(deps.storage)?;
+    config.owner = pending.clone();
+    FACTORY_PARAMS.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("new_owner", pending))
+}
+
+/// Propose a new protocol fee collector (owner only). Nothing changes until the
+/// proposed address accepts the role.
+pub fn propose_protocol_fee_collector(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_collector: String,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let new_collector_addr = deps.api.addr_validate(&new_collector)?;
+    PENDING_FEE_COLLECTOR.save(deps.storage, &new_collector_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_protocol_fee_collector")
+        .add_attribute("pending_collector", new_collector_addr))
+}
+
+/// Default number of markets sent the fee collector in one call.
+const DEFAULT_PROPAGATE_LIMIT: u32 = 10;
+/// Maximum number of markets sent the fee collector in one call.
+const MAX_PROPAGATE_LIMIT: u32 = 30;
+
+/// `UpdateProtocolFeeCollector` messages for one page of registered markets, in
+/// market ID order. Also returns the last market ID of the page when the page is
+/// full, so the caller knows where to continue.
+fn fee_collector_updates(
+    storage: &dyn Storage,
+    collector: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<(Vec<WasmMsg>, Option<String>), ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_PROPAGATE_LIMIT)
+        .min(MAX_PROPAGATE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let update = to_json_binary(&MarketExecuteMsg::UpdateProtocolFeeCollector {
+        new_collector: collector.to_string(),
+    })?;
+
+    let records = MARKETS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = if records.len() == limit {
+        records.last().map(|record| record.market_id.clone())
+    } else {
+        None
+    };
+    let messages = records
+        .into_iter()
+        .map(|record| WasmMsg::Execute {
+            contract_addr: record.address.to_string(),
+            msg: update.clone(),
+            funds: vec![],
+        })
+        .collect();
+
+    Ok((messages, next_start_after))
+}
+
+/// Accept the protocol fee collector role (pending collector only). The factory
+/// switches to the new collector and the first page of registered markets is told
+/// to do the same; `PropagateFeeCollector` covers the rest.
+pub fn accept_protocol_fee_collector_role(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_FEE_COLLECTOR
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingFeeCollector)?;
+
+    if info.sender != pending {
+        return Err(ContractError::NotPendingFeeCollector);
+    }
+
+    let mut config = FACTORY_PARAMS.load(deps.storage)?;
+    let previous_collector = std::mem::replace(&mut config.protocol_fee_collector, pending.clone());
+    FACTORY_PARAMS.save(deps.storage, &config)?;
+    PENDING_FEE_COLLECTOR.remove(deps.storage);
+
+    let (messages, next_start_after) = fee_collector_updates(deps.storage, &pending, None, None)?;
+    let markets_updated = messages.len();
+
+    let mut response = Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "accept_protocol_fee_collector_role")
+        .add_attribute("previous_collector", previous_collector)
+        .add_attribute("protocol_fee_collector", pending)
+        .add_attribute("markets_updated", markets_updated.to_string());
+    if let Some(next_start_after) = next_start_after {
+        response = response.add_attribute("next_start_after", next_start_after);
+    }
+    Ok(response)
+}
+
+/// Send the current protocol fee collector to one page of registered markets.
+/// Anyone can call this; it only pushes the collector the factory already has.
+pub fn propagate_fee_collector(
+    deps: DepsMut,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+    let (messages, next_start_after) = fee_collector_updates(
+        deps.storage,
+        &config.protocol_fee_collector,
+        start_after,
+        limit,
+    )?;
+    let markets_updated = messages.len();
+
+    let mut response = Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "propagate_fee_collector")
+        .add_attribute("protocol_fee_collector", config.protocol_fee_collector)
+        .add_attribute("markets_updated", markets_updated.to_string());
+    if let Some(next_start_after) = next_start_after {
+        response = response.add_attribute("next_start_after", next_start_after);
+    }
+    Ok(response)
+}
+
+/// Handle reply from a `CreateMarket` instantiation.
+///
+/// On success the market is registered, the creation fee is forwarded to the fee
+/// collector and pending attempts for the market are cleared. On failure the attempt
+/// stays in `PENDING_MARKETS` and the creation fee is refunded to the curator.
+pub fn handle_instantiate_reply(
+    mut deps: DepsMut,
+    env: Env,
+    msg: cosmwasm_std::Reply,
+) -> Result<Response, ContractError> {
+    let reply_id = msg.id;
+    let attempt = PENDING_MARKETS
+        .may_load(deps.storage, reply_id)?
+        .ok_or_else(|| {
+            cosmwasm_std::StdError::generic_err("no pending market for reply - internal error")
+        })?;
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+    let fee = attempt.fee_paid.clone();
+
+    if let SubMsgResult::Err(err) = &msg.result {
+        let mut response = Response::new()
+            .add_attribute("action", "create_market_failed")
+            .add_attribute("market_id", &attempt.market_id)
+            .add_attribute("reply_id", reply_id.to_string())
+            .add_attribute("error", err);
+        if !fee.amount.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: attempt.curator.to_string(),
+                amount: vec![fee],
+            });
+        }
+        return Ok(response);
+    }
+
+    let mut response = register_market(deps.branch(), env, msg, attempt.salt)?;
+
+    // Clear this attempt along with any earlier failed attempts for the same market
+    let settled: Vec<u64> = PENDING_MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<cosmwasm_std::StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, pending)| pending.market_id == attempt.market_id)
+        .map(|(id, _)| id)
+        .collect();
+    for id in settled {
+        PENDING_MARKETS.remove(deps.storage, id);
+    }
+
+    // Increment market count
+    let count = MARKET_COUNT.may_load(deps.storage)?.unwrap_or(0);
+    MARKET_COUNT.save(deps.storage, &(count + 1))?;
+
+    // Transfer creation fee to fee collector
+    if !fee.amount.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: config.protocol_fee_collector.to_string(),
+            amount: vec![fee],
+        });
+    }
+
+    Ok(response)
+}
+
+/// Handle reply from one market instantiation of a `BatchCreateMarkets` call.
+pub fn handle_batch_instantiate_reply(
+    mut deps: DepsMut,
+    env: Env,
+    msg: cosmwasm_std::Reply,
+) -> Result<Response, ContractError> {
+    let salts = PENDING_BATCH_SALTS.may_load(deps.storage)?.ok_or_else(|| {
+        cosmwasm_std::StdError::generic_err("no pending batch salts - internal error")
+    })?;
+    let completed = BATCH_REPLY_COUNT.may_load(deps.storage)?.unwrap_or(0);
+    let salt = *salts.get(completed as usize).ok_or_else(|| {
+        cosmwasm_std::StdError::generic_err("batch reply count exceeds batch size")
+    })?;
+
+    let response = register_market(deps.branch(), env, msg, salt)?;
+
+    let completed = completed + 1;
+    if completed as usize == salts.len() {
+        // Last market of the batch - clean up
+        PENDING_BATCH_SALTS.remove(deps.storage);
+        BATCH_REPLY_COUNT.remove(deps.storage);
+    } else {
+        BATCH_REPLY_COUNT.save(deps.storage, &completed)?;
+    }
+
+    Ok(response.add_attribute("batch_completed", completed.to_string()))
+}
+
+/// Record a newly instantiated market in all factory indices.
+#[allow(deprecated)] // data field still needed for older CosmWasm versions
+fn register_market(
+    deps: DepsMut,
+    env: Env,
+    msg: cosmwasm_std::Reply,
+    salt: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Parse the instantiate response to get the contract address
+    let res = cw_utils::parse_instantiate_response_data(
+        msg.result
+            .into_result()
+            .map_err(cosmwasm_std::StdError::generic_err)?
+            .data
+            .ok_or_else(|| cosmwasm_std::StdError::generic_err("no data in reply"))?
+            .as_slice(),
+    )
+    .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
+
+    let market_address = deps.api.addr_validate(&res.contract_address)?;
+
+    // Query the market contract to get its configuration
+    let market_config: stone_types::MarketConfigResponse = deps
+        .querier
+        .query_wasm_smart(&market_address, &stone_types::MarketQueryMsg::Config {})?;
+
+    // Compute the market ID using the SAME salt that was used during creation
+    // This ensures the market ID matches what was checked for collision in create_market
+    let market_id = compute_market_id(
+        &market_config.collateral_denom,
+        &market_config.debt_denom,
+        &market_config.curator,
+        salt,
+    );
+
+    let curator_addr = deps.api.addr_validate(&market_config.curator)?;
+
+    // Create market record
+    let market_record = MarketRecord {
+        market_id: market_id.clone(),
+        address: market_address.clone(),
+        curator: curator_addr.clone(),
+        collateral_denom: market_config.collateral_denom.clone(),
+        debt_denom: market_config.debt_denom.clone(),
+        created_at: env.block.time.seconds(),
+    };
+
+    // Save to all indices
+    MARKETS.save(deps.storage, &market_id, &market_record)?;
+    MARKETS_BY_ADDRESS.save(deps.storage, &market_address, &market_id)?;
+    MARKETS_BY_CURATOR.save(deps.storage, (&curator_addr, &market_id), &())?;
+    MARKETS_BY_COLLATERAL.save(
+        deps.storage,
+        (&market_config.collateral_denom, &market_id),
+        &(),
+    )?;
+    MARKETS_BY_DEBT.save(deps.storage, (&market_config.debt_denom, &market_id), &())?;
+    MARKETS_BY_TIME.save(deps.storage, (market_record.created_at, &market_id), &())?;
+    let denoms_key = (
+        market_config.collateral_denom.as_str(),
+        market_config.debt_denom.as_str(),
+    );
+    if !MARKETS_BY_DENOMS.has(deps.storage, denoms_key) {
+        MARKETS_BY_DENOMS.save(deps.storage, denoms_key, &market_address)?;
+    }
+    if let Some(salt) = salt {
+        USED_SALTS.save(deps.storage, salt, &true)?;
+    }
+
+    let result = CreateMarketResult {
+        market_address: market_address.to_string(),
+        collateral_denom: market_config.collateral_denom,
+        debt_denom: market_config.debt_denom,
+        code_id: FACTORY_PARAMS.load(deps.storage)?.market_code_id,
+        created_at: env.block.time.seconds(),
+    };
+
+    Ok(Response::new()
+        .add_event(result.to_event())
+        .add_attribute("action", "market_instantiated")
+        .add_attribute("market_id", market_id)
+        .add_attribute("market_address", market_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{
+        coin, from_json, ContractResult as CwContractResult, Decimal, SystemResult, WasmQuery,
+    };
+    use stone_types::{FactoryParams, InterestRateModel, OracleType};
+
+    fn valid_params() -> CreateMarketParams {
         CreateMarketParams {
             loan_to_value: Decimal::percent(80),
             liquidation_threshold: Decimal::percent(85),
@@ -539,6 +1269,30 @@ mod tests {
         assert!(validate_market_params(&params).is_ok());
     }
 
+    #[test]
+    fn test_validate_market_params_close_factor_zero() {
+        let mut params = valid_params();
+        params.close_factor = Decimal::zero();
+        assert!(matches!(
+            validate_market_params(&params).unwrap_err(),
+            ContractError::InvalidMarketParams(
+                stone_types::ContractError::InvalidCloseFactor { .. }
+            )
+        ));
+    }
+
+    #[test]
+    fn test_validate_market_params_liquidation_incentive_too_high() {
+        let mut params = valid_params();
+        params.liquidation_protocol_fee = Decimal::percent(45); // 5% bonus + 45% = 50%
+        assert!(matches!(
+            validate_market_params(&params).unwrap_err(),
+            ContractError::InvalidMarketParams(
+                stone_types::ContractError::InvalidLiquidationIncentive { .. }
+            )
+        ));
+    }
+
     #[test]
     fn test_update_config_unauthorized() {
         let mut deps = mock_dependencies();
@@ -547,16 +1301,21 @@ mod tests {
         let collector = api.addr_make("collector");
         let not_owner = api.addr_make("not_owner");
 
-        let config = FactoryConfig {
+        let config = FactoryParams {
             owner: owner.clone(),
             protocol_fee_collector: collector,
             market_creation_fee: Coin {
                 denom: "uosmo".to_string(),
                 amount: Uint128::new(1000000),
             },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
             market_code_id: 1,
+            paused: false,
         };
-        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
 
         let info = message_info(&not_owner, &[]);
         let result = update_config(deps.as_mut(), info, None, None);
@@ -570,25 +1329,295 @@ mod tests {
         let api = MockApi::default();
         let owner = api.addr_make("owner");
         let collector = api.addr_make("collector");
-        let new_collector = api.addr_make("new_collector");
 
-        let config = FactoryConfig {
+        let config = FactoryParams {
             owner: owner.clone(),
             protocol_fee_collector: collector,
             market_creation_fee: Coin {
                 denom: "uosmo".to_string(),
                 amount: Uint128::new(1000000),
             },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
             market_code_id: 1,
+            paused: false,
         };
-        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
 
         let info = message_info(&owner, &[]);
-        let result = update_config(deps.as_mut(), info, Some(new_collector.to_string()), None);
+        let result = update_config(deps.as_mut(), info, Some(coin(2_000_000, "uosmo")), None);
         assert!(result.is_ok());
 
-        let updated = CONFIG.load(deps.as_ref().storage).unwrap();
-        assert_eq!(updated.protocol_fee_collector, new_collector);
+        let updated = FACTORY_PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(updated.market_creation_fee, coin(2_000_000, "uosmo"));
+        assert_eq!(updated.max_market_creation_fee, coin(2_000_000, "uosmo"));
+        assert_eq!(updated.protocol_fee_collector, collector);
+    }
+
+    #[test]
+    fn test_update_config_cannot_change_fee_collector() {
+        // The collector is not part of `UpdateConfig`; a message that tries to
+        // set it is rejected before reaching the contract
+        let msg = r#"{"update_config":{"protocol_fee_collector":"new_collector"}}"#;
+        assert!(from_json::<stone_types::FactoryExecuteMsg>(msg).is_err());
+
+        let msg = r#"{"update_config":{"paused":true}}"#;
+        assert!(from_json::<stone_types::FactoryExecuteMsg>(msg).is_ok());
+    }
+
+    #[test]
+    fn test_update_market_code_id_unauthorized() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+        let not_owner = api.addr_make("not_owner");
+
+        let config = FactoryParams {
+            owner,
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+
+        let info = message_info(&not_owner, &[]);
+        let err = update_market_code_id(deps.as_mut(), info, 2).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        assert_eq!(
+            FACTORY_PARAMS
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .market_code_id,
+            1
+        );
+    }
+
+    #[test]
+    fn test_update_market_code_id_rejects_zero() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+
+        let config = FactoryParams {
+            owner: owner.clone(),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+
+        let info = message_info(&owner, &[]);
+        let err = update_market_code_id(deps.as_mut(), info, 0).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCodeId));
+        assert_eq!(MARKET_CODE_HISTORY.len(deps.as_ref().storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_update_market_code_id_tracks_history() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+
+        let config = FactoryParams {
+            owner: owner.clone(),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+
+        let info = message_info(&owner, &[]);
+        let res = update_market_code_id(deps.as_mut(), info.clone(), 2).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "old_code_id" && a.value == "1"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "new_code_id" && a.value == "2"));
+
+        update_market_code_id(deps.as_mut(), info, 3).unwrap();
+
+        let history: Vec<u64> = MARKET_CODE_HISTORY
+            .iter(deps.as_ref().storage)
+            .unwrap()
+            .collect::<cosmwasm_std::StdResult<_>>()
+            .unwrap();
+        assert_eq!(history, vec![1, 2]);
+        assert_eq!(
+            FACTORY_PARAMS
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .market_code_id,
+            3
+        );
+    }
+
+    #[test]
+    fn test_disable_market_unauthorized() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+        let market = api.addr_make("market");
+
+        let config = FactoryParams {
+            owner,
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+        MARKETS_BY_ADDRESS
+            .save(deps.as_mut().storage, &market, &"abc123".to_string())
+            .unwrap();
+
+        let info = message_info(&api.addr_make("not_owner"), &[]);
+        let err = disable_market(deps.as_mut(), info, market.to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn test_disable_market_rejects_unknown_market() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+
+        let config = FactoryParams {
+            owner: owner.clone(),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+
+        // Any contract not registered by the factory is rejected
+        let info = message_info(&owner, &[]);
+        let other = api.addr_make("other_contract");
+        let err = disable_market(deps.as_mut(), info, other.to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::MarketNotFound { .. }));
+    }
+
+    #[test]
+    fn test_freeze_market_ltv_forwards_to_market() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+        let market = api.addr_make("market");
+
+        let config = FactoryParams {
+            owner: owner.clone(),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+        MARKETS_BY_ADDRESS
+            .save(deps.as_mut().storage, &market, &"abc123".to_string())
+            .unwrap();
+
+        let info = message_info(&api.addr_make("not_owner"), &[]);
+        let err = freeze_market_ltv(deps.as_mut(), info, market.to_string()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let info = message_info(&owner, &[]);
+        let res = freeze_market_ltv(deps.as_mut(), info, market.to_string()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: to_json_binary(&MarketExecuteMsg::FreezeLtv {}).unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_market_admin_rejects_unauthorized_and_unknown_market() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+        let market = api.addr_make("market");
+
+        let config = FactoryParams {
+            owner: owner.clone(),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+        MARKETS_BY_ADDRESS
+            .save(deps.as_mut().storage, &market, &"abc123".to_string())
+            .unwrap();
+
+        let info = message_info(&api.addr_make("not_owner"), &[]);
+        let err = set_market_admin(deps.as_mut(), mock_env(), info, market.to_string(), None)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let info = message_info(&owner, &[]);
+        let other = api.addr_make("other_contract");
+        let err =
+            set_market_admin(deps.as_mut(), mock_env(), info, other.to_string(), None).unwrap_err();
+        assert!(matches!(err, ContractError::MarketNotFound { .. }));
     }
 
     #[test]
@@ -600,16 +1629,21 @@ mod tests {
         let new_owner = api.addr_make("new_owner");
         let random = api.addr_make("random");
 
-        let config = FactoryConfig {
+        let config = FactoryParams {
             owner: owner.clone(),
             protocol_fee_collector: collector,
             market_creation_fee: Coin {
                 denom: "uosmo".to_string(),
                 amount: Uint128::new(1000000),
             },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
             market_code_id: 1,
+            paused: false,
         };
-        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
 
         // Transfer ownership
         let info = message_info(&owner, &[]);
@@ -629,7 +1663,253 @@ mod tests {
         let result = accept_ownership(deps.as_mut(), info);
         assert!(result.is_ok());
 
-        let updated = CONFIG.load(deps.as_ref().storage).unwrap();
+        let updated = FACTORY_PARAMS.load(deps.as_ref().storage).unwrap();
         assert_eq!(updated.owner, new_owner);
     }
+
+    /// Factory with a 1_000 - 5_000 uosmo creation fee range and an oracle that
+    /// returns a fresh price for every denom.
+    fn setup_fee_range(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let api = MockApi::default();
+        let config = FactoryParams {
+            owner: api.addr_make("owner"),
+            protocol_fee_collector: api.addr_make("collector"),
+            market_creation_fee: coin(1_000, "uosmo"),
+            max_market_creation_fee: coin(5_000, "uosmo"),
+            market_code_id: 1,
+            paused: false,
+        };
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+
+        let now = mock_env().block.time.seconds();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let response = PriceResponse {
+                    denom,
+                    price: Decimal::one(),
+                    updated_at: now,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    fn create_with_fee(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        sent: u128,
+        fee_amount: Option<u128>,
+    ) -> Result<Response, ContractError> {
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("curator"), &[coin(sent, "uosmo")]);
+        create_market(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            "uatom".to_string(),
+            "uusdc".to_string(),
+            OracleConfigUnchecked {
+                address: api.addr_make("oracle").to_string(),
+                oracle_type: OracleType::default(),
+            },
+            valid_params(),
+            None,
+            fee_amount.map(Uint128::new),
+        )
+    }
+
+    fn attr<'a>(res: &'a Response, key: &str) -> Option<&'a str> {
+        res.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.as_str())
+    }
+
+    #[test]
+    fn test_create_market_fee_below_min_fails() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+
+        let err = create_with_fee(&mut deps, 999, None).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientCreationFee { .. }));
+
+        // Choosing a fee below the minimum fails even when enough is sent
+        let err = create_with_fee(&mut deps, 1_000, Some(999)).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientCreationFee { .. }));
+
+        // The chosen fee must be covered by the funds sent
+        let err = create_with_fee(&mut deps, 1_500, Some(2_000)).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientCreationFee { .. }));
+    }
+
+    #[test]
+    fn test_paused_factory_rejects_market_creation() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+        let owner = MockApi::default().addr_make("owner");
+
+        let info = message_info(&owner, &[]);
+        update_config(deps.as_mut(), info, None, Some(true)).unwrap();
+
+        let err = create_with_fee(&mut deps, 1_000, None).unwrap_err();
+        assert_eq!(err, ContractError::FactoryPaused);
+
+        let info = message_info(&owner, &[]);
+        update_config(deps.as_mut(), info, None, Some(false)).unwrap();
+        assert!(create_with_fee(&mut deps, 1_000, None).is_ok());
+    }
+
+    #[test]
+    fn test_create_market_fee_within_range() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+
+        let res = create_with_fee(&mut deps, 3_000, Some(3_000)).unwrap();
+        assert_eq!(attr(&res, "fee_paid"), Some("3000uosmo"));
+        assert_eq!(attr(&res, "fee_refunded"), None);
+        // Only the instantiate submessage, no refund
+        assert_eq!(res.messages.len(), 1);
+
+        let attempt = PENDING_MARKETS
+            .load(deps.as_ref().storage, INSTANTIATE_REPLY_ID_START)
+            .unwrap();
+        assert_eq!(attempt.fee_paid, coin(3_000, "uosmo"));
+    }
+
+    #[test]
+    fn test_create_market_fee_defaults_to_min_and_refunds_excess() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+
+        let res = create_with_fee(&mut deps, 1_200, None).unwrap();
+        assert_eq!(attr(&res, "fee_paid"), Some("1000uosmo"));
+        assert_eq!(attr(&res, "fee_refunded"), Some("200"));
+    }
+
+    #[test]
+    fn test_create_market_fee_above_max_refunds_excess() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+
+        let res = create_with_fee(&mut deps, 8_000, Some(7_000)).unwrap();
+        assert_eq!(attr(&res, "fee_paid"), Some("5000uosmo"));
+        assert_eq!(attr(&res, "fee_refunded"), Some("3000"));
+
+        let curator = MockApi::default().addr_make("curator");
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: curator.to_string(),
+                amount: vec![coin(3_000, "uosmo")],
+            })
+        );
+
+        let attempt = PENDING_MARKETS
+            .load(deps.as_ref().storage, INSTANTIATE_REPLY_ID_START)
+            .unwrap();
+        assert_eq!(attempt.fee_paid, coin(5_000, "uosmo"));
+    }
+
+    #[test]
+    fn test_set_min_market_creation_fee() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+        let api = MockApi::default();
+
+        let info = message_info(&api.addr_make("not_owner"), &[]);
+        let err = set_min_market_creation_fee(
+            deps.as_mut(),
+            info,
+            coin(2_000, "uosmo"),
+            coin(4_000, "uosmo"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let owner = api.addr_make("owner");
+        let err = set_min_market_creation_fee(
+            deps.as_mut(),
+            message_info(&owner, &[]),
+            coin(4_000, "uosmo"),
+            coin(2_000, "uosmo"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCreationFeeRange { .. }));
+
+        let err = set_min_market_creation_fee(
+            deps.as_mut(),
+            message_info(&owner, &[]),
+            coin(2_000, "uosmo"),
+            coin(4_000, "uatom"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCreationFeeRange { .. }));
+
+        set_min_market_creation_fee(
+            deps.as_mut(),
+            message_info(&owner, &[]),
+            coin(2_000, "uosmo"),
+            coin(4_000, "uosmo"),
+        )
+        .unwrap();
+        let config = FACTORY_PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.market_creation_fee, coin(2_000, "uosmo"));
+        assert_eq!(config.max_market_creation_fee, coin(4_000, "uosmo"));
+    }
+
+    #[test]
+    fn test_propagate_fee_collector_pages_through_markets() {
+        let mut deps = mock_dependencies();
+        setup_fee_range(&mut deps);
+        let api = MockApi::default();
+
+        for id in ["market-a", "market-b", "market-c"] {
+            let record = MarketRecord {
+                market_id: id.to_string(),
+                address: api.addr_make(id),
+                curator: api.addr_make("curator"),
+                collateral_denom: "uatom".to_string(),
+                debt_denom: "uusdc".to_string(),
+                created_at: 0,
+            };
+            MARKETS.save(deps.as_mut().storage, id, &record).unwrap();
+        }
+
+        let res = propagate_fee_collector(deps.as_mut(), None, Some(2)).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "next_start_after" && attr.value == "market-b"));
+
+        let res =
+            propagate_fee_collector(deps.as_mut(), Some("market-b".to_string()), Some(2)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: api.addr_make("market-c").to_string(),
+                msg: to_json_binary(&MarketExecuteMsg::UpdateProtocolFeeCollector {
+                    new_collector: api.addr_make("collector").to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert!(!res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "next_start_after"));
+    }
 }
@@ -1,30 +1,36 @@
-use cosmwasm_std::{Deps, Order};
+use cosmwasm_std::{Addr, Decimal, Deps, Order, StdError, StdResult};
 use cw_storage_plus::Bound;
 
 use stone_types::{
-    compute_market_id, ComputeMarketIdResponse, FactoryConfigResponse, MarketCountResponse,
-    MarketResponse, MarketsResponse,
+    compute_market_id, ComputeMarketIdResponse, CreateMarketAttempt, FactoryConfigResponse,
+    FactoryParams, MarketCodeResponse, MarketCountResponse, MarketFilter, MarketQueryMsg,
+    MarketResponse, MarketSortField, MarketSummaryResponse, MarketsResponse, ProtocolTvlResponse,
 };
 
 use crate::error::ContractResult;
 use crate::state::{
-    CONFIG, MARKETS, MARKETS_BY_ADDRESS, MARKETS_BY_COLLATERAL, MARKETS_BY_CURATOR,
-    MARKETS_BY_DEBT, MARKET_COUNT,
+    FACTORY_PARAMS, MARKETS, MARKETS_BY_ADDRESS, MARKETS_BY_COLLATERAL, MARKETS_BY_CURATOR,
+    MARKETS_BY_DEBT, MARKETS_BY_DENOMS, MARKETS_BY_TIME, MARKET_COUNT, PENDING_MARKETS, USED_SALTS,
 };
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
 
 pub fn config(deps: Deps) -> ContractResult<FactoryConfigResponse> {
-    let config = CONFIG.load(deps.storage)?;
+    let config = FACTORY_PARAMS.load(deps.storage)?;
     Ok(FactoryConfigResponse {
         owner: config.owner.to_string(),
         protocol_fee_collector: config.protocol_fee_collector.to_string(),
         market_creation_fee: config.market_creation_fee,
+        max_market_creation_fee: config.max_market_creation_fee,
         market_code_id: config.market_code_id,
     })
 }
 
+pub fn factory_params(deps: Deps) -> ContractResult<FactoryParams> {
+    Ok(FACTORY_PARAMS.load(deps.storage)?)
+}
+
 pub fn market(deps: Deps, market_id: String) -> ContractResult<MarketResponse> {
     let record = MARKETS.load(deps.storage, &market_id)?;
     Ok(MarketResponse {
@@ -43,33 +49,103 @@ pub fn market_by_address(deps: Deps, address: String) -> ContractResult<MarketRe
     market(deps, market_id)
 }
 
+pub fn market_by_denoms(
+    deps: Deps,
+    collateral_denom: String,
+    debt_denom: String,
+) -> ContractResult<Option<MarketResponse>> {
+    match MARKETS_BY_DENOMS.may_load(deps.storage, (&collateral_denom, &debt_denom))? {
+        Some(addr) => {
+            let market_id = MARKETS_BY_ADDRESS.load(deps.storage, &addr)?;
+            Ok(Some(market(deps, market_id)?))
+        }
+        None => Ok(None),
+    }
+}
+
 pub fn markets(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
+    sort_by: Option<MarketSortField>,
 ) -> ContractResult<MarketsResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.as_deref().map(Bound::exclusive);
 
-    let markets: Vec<MarketResponse> = MARKETS
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            let (_, record) = item?;
-            Ok(MarketResponse {
-                market_id: record.market_id,
-                address: record.address.to_string(),
-                curator: record.curator.to_string(),
-                collateral_denom: record.collateral_denom,
-                debt_denom: record.debt_denom,
-                created_at: record.created_at,
-            })
-        })
+    let market_ids: Vec<String> = match sort_by {
+        None => {
+            let start = start_after.as_deref().map(Bound::exclusive);
+            MARKETS
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        Some(sort_by) => sorted_market_ids(deps, sort_by, start_after, limit)?,
+    };
+
+    let markets: Vec<MarketResponse> = market_ids
+        .into_iter()
+        .map(|id| market(deps, id))
         .collect::<ContractResult<Vec<_>>>()?;
 
     Ok(MarketsResponse { markets })
 }
 
+/// Market IDs ordered by `sort_by`. The `start_after` market's record gives the
+/// sort key to resume after, so pages can keep using market IDs as cursors.
+fn sorted_market_ids(
+    deps: Deps,
+    sort_by: MarketSortField,
+    start_after: Option<String>,
+    limit: usize,
+) -> ContractResult<Vec<String>> {
+    let cursor = start_after
+        .map(|id| MARKETS.load(deps.storage, &id))
+        .transpose()?;
+
+    let market_ids = match sort_by {
+        MarketSortField::Address => {
+            let start = cursor.as_ref().map(|c| Bound::exclusive(&c.address));
+            MARKETS_BY_ADDRESS
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| Ok(item?.1))
+                .collect::<ContractResult<Vec<_>>>()?
+        }
+        MarketSortField::CollateralDenom => {
+            let start = cursor
+                .as_ref()
+                .map(|c| Bound::exclusive((c.collateral_denom.as_str(), c.market_id.as_str())));
+            MARKETS_BY_COLLATERAL
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|key| Ok(key?.1))
+                .collect::<ContractResult<Vec<_>>>()?
+        }
+        MarketSortField::DebtDenom => {
+            let start = cursor
+                .as_ref()
+                .map(|c| Bound::exclusive((c.debt_denom.as_str(), c.market_id.as_str())));
+            MARKETS_BY_DEBT
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|key| Ok(key?.1))
+                .collect::<ContractResult<Vec<_>>>()?
+        }
+        MarketSortField::CreatedAt => {
+            let start = cursor
+                .as_ref()
+                .map(|c| Bound::exclusive((c.created_at, c.market_id.as_str())));
+            MARKETS_BY_TIME
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|key| Ok(key?.1))
+                .collect::<ContractResult<Vec<_>>>()?
+        }
+    };
+
+    Ok(market_ids)
+}
+
 pub fn markets_by_curator(
     deps: Deps,
     curator: String,
@@ -182,11 +258,116 @@ pub fn markets_by_debt(
     Ok(MarketsResponse { markets })
 }
 
+/// Markets matching `filter`, each variant served by its own index.
+pub fn markets_by(
+    deps: Deps,
+    filter: MarketFilter,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> ContractResult<MarketsResponse> {
+    match filter {
+        MarketFilter::CollateralDenom(denom) => {
+            markets_by_collateral(deps, denom, start_after, limit)
+        }
+        MarketFilter::DebtDenom(denom) => markets_by_debt(deps, denom, start_after, limit),
+        MarketFilter::Curator(curator) => markets_by_curator(deps, curator, start_after, limit),
+        MarketFilter::All => markets(deps, start_after, limit, None),
+    }
+}
+
+/// Markets covered by `TotalProtocolTvl`, to bound the number of sub-queries
+pub const MAX_TVL_MARKETS: usize = 20;
+
+/// Aggregate supply and debt value over the first `MAX_TVL_MARKETS` markets.
+/// Markets that can't be valued are listed in `failed_markets` instead of
+/// failing the query.
+pub fn total_protocol_tvl(deps: Deps) -> ContractResult<ProtocolTvlResponse> {
+    let records = MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_TVL_MARKETS)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut response = ProtocolTvlResponse {
+        total_supply_usd: Decimal::zero(),
+        total_debt_usd: Decimal::zero(),
+        market_count: 0,
+        failed_markets: vec![],
+    };
+    for (market_id, record) in records {
+        match market_tvl(deps, &record.address) {
+            Some((supply_usd, debt_usd)) => {
+                response.total_supply_usd = response
+                    .total_supply_usd
+                    .checked_add(supply_usd)
+                    .map_err(StdError::from)?;
+                response.total_debt_usd = response
+                    .total_debt_usd
+                    .checked_add(debt_usd)
+                    .map_err(StdError::from)?;
+                response.market_count += 1;
+            }
+            None => response.failed_markets.push(market_id),
+        }
+    }
+
+    Ok(response)
+}
+
+/// Supply and debt value of a market in USD, or None if the market doesn't
+/// answer or has no recent debt asset price.
+fn market_tvl(deps: Deps, market: &Addr) -> Option<(Decimal, Decimal)> {
+    let summary: MarketSummaryResponse = deps
+        .querier
+        .query_wasm_smart(market, &MarketQueryMsg::MarketSummary {})
+        .ok()?;
+    let supply_usd = summary.total_supply_usd?;
+
+    // Supply and debt are the same asset, so debt is valued at the supply's price
+    let debt_usd = if summary.total_supply.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::checked_from_ratio(summary.total_debt, summary.total_supply)
+            .ok()?
+            .checked_mul(supply_usd)
+            .ok()?
+    };
+
+    Some((supply_usd, debt_usd))
+}
+
 pub fn market_count(deps: Deps) -> ContractResult<MarketCountResponse> {
     let count = MARKET_COUNT.may_load(deps.storage)?.unwrap_or(0);
     Ok(MarketCountResponse { count })
 }
 
+pub fn market_code(deps: Deps) -> ContractResult<MarketCodeResponse> {
+    let config = FACTORY_PARAMS.load(deps.storage)?;
+    Ok(MarketCodeResponse {
+        code_id: config.market_code_id,
+    })
+}
+
+/// Whether an explicit salt has already seeded a registered market.
+pub fn is_salt_used(deps: Deps, salt: u64) -> ContractResult<bool> {
+    Ok(USED_SALTS.has(deps.storage, salt))
+}
+
+/// All `CreateMarket` attempts still awaiting (or stuck before) market registration.
+pub fn pending_markets(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> ContractResult<Vec<CreateMarketAttempt>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let attempts = PENDING_MARKETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect::<ContractResult<Vec<_>>>()?;
+    Ok(attempts)
+}
+
 pub fn compute_market_id_query(
     collateral_denom: String,
     debt_denom: String,
@@ -202,7 +383,7 @@ mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, MockApi};
     use cosmwasm_std::{Addr, Coin, Uint128};
-    use stone_types::{FactoryConfig, MarketRecord};
+    use stone_types::{FactoryParams, MarketRecord};
 
     // Generate consistent test addresses
     fn test_addrs() -> (Addr, Addr, Addr, Addr, Addr, Addr, Addr) {
@@ -228,16 +409,21 @@ mod tests {
         let (owner, collector, addr1, addr2, addr3, curator1, curator2) = test_addrs();
 
         // Setup config
-        let config = FactoryConfig {
+        let config = FactoryParams {
             owner,
             protocol_fee_collector: collector,
             market_creation_fee: Coin {
                 denom: "uosmo".to_string(),
                 amount: Uint128::new(1000000),
             },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
             market_code_id: 1,
+            paused: false,
         };
-        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
 
         // Setup some markets
         let markets = vec![
@@ -295,6 +481,20 @@ mod tests {
                     &(),
                 )
                 .unwrap();
+            MARKETS_BY_DENOMS
+                .save(
+                    deps.as_mut().storage,
+                    (&market.collateral_denom, &market.debt_denom),
+                    &market.address,
+                )
+                .unwrap();
+            MARKETS_BY_TIME
+                .save(
+                    deps.as_mut().storage,
+                    (market.created_at, &market.market_id),
+                    &(),
+                )
+                .unwrap();
         }
         MARKET_COUNT.save(deps.as_mut().storage, &3).unwrap();
     }
@@ -332,21 +532,113 @@ mod tests {
         assert_eq!(result.market_id, "market2");
     }
 
+    #[test]
+    fn test_query_market_by_denoms() {
+        let mut deps = mock_dependencies();
+        setup_test_data(&mut deps);
+
+        let result = market_by_denoms(deps.as_ref(), "uosmo".to_string(), "uusdc".to_string())
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.market_id, "market2");
+
+        // Order of denoms matters: collateral first, then debt
+        let result =
+            market_by_denoms(deps.as_ref(), "uusdc".to_string(), "uosmo".to_string()).unwrap();
+        assert!(result.is_none());
+    }
+
     #[test]
     fn test_query_markets_pagination() {
         let mut deps = mock_dependencies();
         setup_test_data(&mut deps);
 
         // Get first page
-        let result = markets(deps.as_ref(), None, Some(2)).unwrap();
+        let result = markets(deps.as_ref(), None, Some(2), None).unwrap();
         assert_eq!(result.markets.len(), 2);
 
         // Get second page
         let last_id = result.markets.last().unwrap().market_id.clone();
-        let result = markets(deps.as_ref(), Some(last_id), Some(2)).unwrap();
+        let result = markets(deps.as_ref(), Some(last_id), Some(2), None).unwrap();
         assert_eq!(result.markets.len(), 1);
     }
 
+    fn market_ids(result: MarketsResponse) -> Vec<String> {
+        result.markets.into_iter().map(|m| m.market_id).collect()
+    }
+
+    #[test]
+    fn test_query_markets_sorted_by_created_at() {
+        let mut deps = mock_dependencies();
+        setup_test_data(&mut deps);
+
+        // Created later than market3 despite the smaller ID
+        let mut record = MARKETS.load(deps.as_ref().storage, "market2").unwrap();
+        MARKETS_BY_TIME.remove(deps.as_mut().storage, (record.created_at, "market2"));
+        record.created_at = 4000;
+        MARKETS
+            .save(deps.as_mut().storage, "market2", &record)
+            .unwrap();
+        MARKETS_BY_TIME
+            .save(deps.as_mut().storage, (4000, "market2"), &())
+            .unwrap();
+
+        let sort_by = Some(MarketSortField::CreatedAt);
+        let result = markets(deps.as_ref(), None, None, sort_by.clone()).unwrap();
+        assert_eq!(market_ids(result), vec!["market1", "market3", "market2"]);
+
+        let result = markets(deps.as_ref(), None, Some(2), sort_by.clone()).unwrap();
+        assert_eq!(market_ids(result), vec!["market1", "market3"]);
+        let result = markets(deps.as_ref(), Some("market3".to_string()), Some(2), sort_by).unwrap();
+        assert_eq!(market_ids(result), vec!["market2"]);
+    }
+
+    #[test]
+    fn test_query_markets_sorted_by_address() {
+        let mut deps = mock_dependencies();
+        setup_test_data(&mut deps);
+
+        let result = markets(deps.as_ref(), None, None, Some(MarketSortField::Address)).unwrap();
+        let addresses: Vec<String> = result.markets.iter().map(|m| m.address.clone()).collect();
+        let mut sorted = addresses.clone();
+        sorted.sort();
+        assert_eq!(addresses, sorted);
+
+        let first = result.markets[0].market_id.clone();
+        let rest = markets(
+            deps.as_ref(),
+            Some(first),
+            None,
+            Some(MarketSortField::Address),
+        )
+        .unwrap();
+        assert_eq!(rest.markets, result.markets[1..].to_vec());
+    }
+
+    #[test]
+    fn test_query_markets_sorted_by_denoms() {
+        let mut deps = mock_dependencies();
+        setup_test_data(&mut deps);
+
+        let result = markets(
+            deps.as_ref(),
+            None,
+            None,
+            Some(MarketSortField::CollateralDenom),
+        )
+        .unwrap();
+        assert_eq!(market_ids(result), vec!["market1", "market3", "market2"]);
+
+        let result = markets(
+            deps.as_ref(),
+            Some("market3".to_string()),
+            None,
+            Some(MarketSortField::DebtDenom),
+        )
+        .unwrap();
+        assert_eq!(market_ids(result), vec!["market1", "market2"]);
+    }
+
     #[test]
     fn test_query_markets_by_curator() {
         let mut deps = mock_dependencies();
@@ -387,6 +679,41 @@ mod tests {
         assert_eq!(result.count, 3);
     }
 
+    #[test]
+    fn test_query_pending_markets_paginates() {
+        let mut deps = mock_dependencies();
+        let (_, _, _, _, _, curator, _) = test_addrs();
+
+        for reply_id in 1000..1035u64 {
+            let attempt = CreateMarketAttempt {
+                reply_id,
+                market_id: format!("market{}", reply_id),
+                curator: curator.clone(),
+                collateral_denom: "uatom".to_string(),
+                debt_denom: "uusdc".to_string(),
+                salt: None,
+                started_at: 1000,
+                fee_paid: Coin::new(1_000u128, "uosmo"),
+            };
+            PENDING_MARKETS
+                .save(deps.as_mut().storage, reply_id, &attempt)
+                .unwrap();
+        }
+
+        let page = pending_markets(deps.as_ref(), None, None).unwrap();
+        assert_eq!(page.len(), DEFAULT_LIMIT as usize);
+        assert_eq!(page[0].reply_id, 1000);
+
+        // Limit is capped and the cursor is exclusive
+        let page = pending_markets(deps.as_ref(), Some(1000), Some(100)).unwrap();
+        assert_eq!(page.len(), MAX_LIMIT as usize);
+        assert_eq!(page[0].reply_id, 1001);
+
+        let page = pending_markets(deps.as_ref(), Some(1030), None).unwrap();
+        let ids: Vec<u64> = page.iter().map(|a| a.reply_id).collect();
+        assert_eq!(ids, vec![1031, 1032, 1033, 1034]);
+    }
+
     #[test]
     fn test_compute_market_id_query() {
         let result = compute_market_id_query(
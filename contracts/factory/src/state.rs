@@ -1,26 +1,53 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
-use stone_types::{FactoryConfig, MarketRecord};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin};
+use cw_storage_plus::{Deque, Item, Map};
+use stone_types::{CreateMarketAttempt, FactoryParams, MarketRecord};
 
 /// Contract name for cw2 migration info
 pub const CONTRACT_NAME: &str = "crates.io:stone-factory";
 /// Contract version for cw2 migration info
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Factory configuration
-pub const CONFIG: Item<FactoryConfig> = Item::new("config");
+/// Factory governance parameters
+pub const FACTORY_PARAMS: Item<FactoryParams> = Item::new("factory_params");
+
+/// Factory configuration as stored before `FACTORY_PARAMS`: a single fixed
+/// creation fee and no pause flag.
+#[cw_serde]
+pub(crate) struct LegacyFactoryConfig {
+    pub owner: Addr,
+    pub protocol_fee_collector: Addr,
+    pub market_creation_fee: Coin,
+    pub market_code_id: u64,
+}
+
+/// Storage slot the configuration lived in before `FACTORY_PARAMS`.
+/// Only read by `migrate`, which moves its contents into `FACTORY_PARAMS`.
+pub(crate) const LEGACY_CONFIG: Item<LegacyFactoryConfig> = Item::new("config");
 
 /// Pending owner for two-step ownership transfer
 pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
 
-/// Pending market salt for the currently in-flight market instantiation.
-///
-/// This is a single Item (not a Map) because the factory only supports one pending
-/// market instantiation at a time. If future changes allow multiple instantiations
-/// per execute/tx, this should be reconsidered (e.g., keyed by a sequence counter).
+/// Proposed protocol fee collector for two-step collector handoff
+pub const PENDING_FEE_COLLECTOR: Item<Addr> = Item::new("pending_fee_collector");
+
+/// In-flight and failed `CreateMarket` instantiations, keyed by submessage reply ID.
 ///
-/// Stores the salt used during market creation to properly compute market ID in reply handler.
-pub const PENDING_MARKET_SALTS: Item<Option<u64>> = Item::new("pending_market_salts");
+/// An entry is written before the instantiate submessage is dispatched and removed
+/// once the reply registers the market. If instantiation fails the entry is kept so
+/// operators can see the stuck creation; a successful retry of the same market clears it.
+/// The attempt also carries the salt needed to recompute the market ID in the reply.
+pub const PENDING_MARKETS: Map<u64, CreateMarketAttempt> = Map::new("pending_markets");
+
+/// Reply ID to use for the next `CreateMarket` instantiation
+pub const NEXT_INSTANTIATE_REPLY_ID: Item<u64> = Item::new("next_instantiate_reply_id");
+
+/// Salts for the in-flight `BatchCreateMarkets` instantiations, in submessage order.
+/// Replies arrive in the same order, so `BATCH_REPLY_COUNT` indexes the next salt.
+pub const PENDING_BATCH_SALTS: Item<Vec<Option<u64>>> = Item::new("pending_batch_salts");
+
+/// Number of batch market instantiations that have completed so far
+pub const BATCH_REPLY_COUNT: Item<u32> = Item::new("batch_reply_count");
 
 /// Markets indexed by market_id
 pub const MARKETS: Map<&str, MarketRecord> = Map::new("markets");
@@ -37,9 +64,29 @@ pub const MARKETS_BY_COLLATERAL: Map<(&str, &str), ()> = Map::new("markets_by_co
 /// Index of markets by debt denom
 pub const MARKETS_BY_DEBT: Map<(&str, &str), ()> = Map::new("markets_by_debt");
 
+/// Index of markets by creation time (block time in seconds)
+pub const MARKETS_BY_TIME: Map<(u64, &str), ()> = Map::new("markets_by_time");
+
+/// Market address by (collateral denom, debt denom).
+/// Holds the first market registered for a pair; later markets for the same
+/// pair (different curator or salt) remain discoverable via the other indices.
+pub const MARKETS_BY_DENOMS: Map<(&str, &str), Addr> = Map::new("markets_by_denoms");
+
 /// Total number of markets created
 pub const MARKET_COUNT: Item<u64> = Item::new("market_count");
 
+/// Salts of registered markets, explicit or auto-generated. Each salt can seed
+/// only one market, regardless of pair or curator.
+pub const USED_SALTS: Map<u64, bool> = Map::new("used_salts");
+
+/// Next candidate salt for `CreateMarket { salt: None }`. The first auto salt is 0,
+/// which gives the same market ID as the old unsalted derivation.
+pub const MARKET_COUNTER: Item<u64> = Item::new("market_counter");
+
+/// Previous market code IDs, oldest first, recorded on each `UpdateMarketCodeId`.
+/// Existing markets are not migrated, so this identifies the code older markets run.
+pub const MARKET_CODE_HISTORY: Deque<u64> = Deque::new("market_code_history");
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,18 +97,23 @@ mod tests {
     fn test_config_storage() {
         let mut deps = mock_dependencies();
 
-        let config = FactoryConfig {
+        let config = FactoryParams {
             owner: Addr::unchecked("owner"),
             protocol_fee_collector: Addr::unchecked("fee_collector"),
             market_creation_fee: Coin {
                 denom: "uosmo".to_string(),
                 amount: Uint128::new(1000000),
             },
+            max_market_creation_fee: Coin {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000000),
+            },
             market_code_id: 1,
+            paused: false,
         };
 
-        CONFIG.save(deps.as_mut().storage, &config).unwrap();
-        let loaded = CONFIG.load(deps.as_ref().storage).unwrap();
+        FACTORY_PARAMS.save(deps.as_mut().storage, &config).unwrap();
+        let loaded = FACTORY_PARAMS.load(deps.as_ref().storage).unwrap();
 
         assert_eq!(loaded.owner, Addr::unchecked("owner"));
         assert_eq!(loaded.market_code_id, 1);
@@ -1,15 +1,22 @@
 use cosmwasm_std::testing::MockApi;
-use cosmwasm_std::{coin, Addr, Decimal, Empty, Uint128};
+use cosmwasm_std::{
+    coin, Addr, Decimal, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
 use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
 use stone_factory::contract as factory_contract;
 use stone_market::contract as market_contract;
+use stone_market::ContractError as MarketError;
 use stone_testing::{
-    default_market_params, mock_oracle_contract, MockOracleInstantiateMsg, COLLATERAL_DENOM,
-    DEBT_DENOM,
+    default_market_params, mock_oracle_contract, MockOracleExecuteMsg, MockOracleInstantiateMsg,
+    COLLATERAL_DENOM, DEBT_DENOM,
 };
 use stone_types::{
-    FactoryExecuteMsg, FactoryInstantiateMsg, FactoryQueryMsg, MarketConfigResponse,
-    MarketCountResponse, MarketQueryMsg, MarketsResponse, OracleConfigUnchecked, OracleType,
+    ComputeMarketIdResponse, CreateMarketAttempt, CreateMarketRequest, CreateMarketResult,
+    FactoryConfigResponse, FactoryExecuteMsg, FactoryInstantiateMsg, FactoryQueryMsg,
+    HealthMetrics, MarketConfigResponse, MarketCountResponse, MarketExecuteMsg, MarketFilter,
+    MarketInstantiateMsg, MarketQueryMsg, MarketResponse, MarketSortField, MarketStateResponse,
+    MarketSummaryResponse, MarketsResponse, OracleConfigUnchecked, OracleType, ProtocolTvlResponse,
+    UserBalanceResponse, UserSupplyResponse,
 };
 
 fn factory_wrapper() -> Box<dyn Contract<Empty>> {
@@ -31,6 +38,25 @@ fn market_wrapper() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+fn failing_market_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: MarketInstantiateMsg,
+) -> StdResult<Response> {
+    Err(StdError::generic_err("market instantiation failed"))
+}
+
+/// Market code whose instantiation always fails
+fn failing_market_wrapper() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        market_contract::execute,
+        failing_market_instantiate,
+        market_contract::query,
+    );
+    Box::new(contract)
+}
+
 struct TestEnv {
     app: App,
     curator: Addr,
@@ -118,6 +144,7 @@ fn create_market_deploys_and_indexes() {
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     env.app
@@ -144,6 +171,7 @@ fn create_market_deploys_and_indexes() {
             &FactoryQueryMsg::Markets {
                 start_after: None,
                 limit: None,
+                sort_by: None,
             },
         )
         .unwrap();
@@ -184,6 +212,141 @@ fn create_market_deploys_and_indexes() {
     assert_eq!(collector_balance.amount, Uint128::new(1_000));
 }
 
+#[test]
+fn create_market_emits_typed_created_event() {
+    let mut env = setup_env();
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    let res = env
+        .app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market = &markets.markets[0];
+    let config: FactoryConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+
+    // Custom contract events are prefixed with "wasm-" by the runtime
+    let event_type = format!("wasm-{}", CreateMarketResult::EVENT_TYPE);
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.ty == event_type)
+        .expect("typed market created event");
+    let attr = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+            .unwrap()
+    };
+    assert_eq!(attr("market_address"), market.address);
+    assert_eq!(attr("collateral_denom"), COLLATERAL_DENOM);
+    assert_eq!(attr("debt_denom"), DEBT_DENOM);
+    assert_eq!(attr("code_id"), config.market_code_id.to_string());
+    assert_eq!(attr("created_at"), market.created_at.to_string());
+}
+
+#[test]
+fn create_market_with_custom_oracle() {
+    let mut env = setup_env();
+
+    // A code ID that doesn't match the mock oracle: Custom never validates it
+    let oracle_type = OracleType::Custom {
+        expected_code_id: Some(9_999),
+        max_staleness_secs: 300,
+    };
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: oracle_type.clone(),
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(markets.markets[0].address.clone());
+
+    let market_config: MarketConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(market_addr.clone(), &MarketQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(market_config.oracle_type, oracle_type);
+
+    // Prices are served through the standard OracleQueryMsg::Price interface
+    let user = MockApi::default().addr_make("user");
+    let metrics: HealthMetrics = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            market_addr,
+            &MarketQueryMsg::HealthMetrics {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(metrics.max_borrow.value, Some(Uint128::zero()));
+    assert!(metrics.max_borrow.calculation_error.is_none());
+}
+
 #[test]
 fn create_market_requires_fee() {
     let mut env = setup_env();
@@ -200,6 +363,7 @@ fn create_market_requires_fee() {
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     let err = env
@@ -240,6 +404,7 @@ fn create_market_rejects_invalid_oracle() {
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     let err = env
@@ -274,7 +439,9 @@ fn create_market_rejects_duplicates() {
             },
         },
         params: Box::new(default_market_params()),
-        salt: None,
+        // `salt: None` gets a fresh auto salt each time, so reuse an explicit one
+        salt: Some(5),
+        fee_amount: None,
     };
 
     env.app
@@ -305,6 +472,84 @@ fn create_market_rejects_duplicates() {
     );
 }
 
+#[test]
+fn create_market_without_salt_uses_counter() {
+    let mut env = setup_env();
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+
+    // Salt 1 is taken explicitly, so the auto salts are 0 and then 2
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &FactoryExecuteMsg::CreateMarket {
+                collateral_denom: COLLATERAL_DENOM.to_string(),
+                debt_denom: DEBT_DENOM.to_string(),
+                oracle_config: OracleConfigUnchecked {
+                    address: env.oracle_addr.to_string(),
+                    oracle_type: OracleType::Generic {
+                        expected_code_id: None,
+                        max_staleness_secs: 300,
+                    },
+                },
+                params: Box::new(default_market_params()),
+                salt: Some(1),
+                fee_amount: None,
+            },
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    for expected_salt in ["0", "2"] {
+        let res = env
+            .app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+        assert!(res.events.iter().any(|e| e
+            .attributes
+            .iter()
+            .any(|a| a.key == "salt" && a.value == expected_salt)));
+    }
+
+    for salt in [0, 1, 2] {
+        let used: bool = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::IsSaltUsed { salt },
+            )
+            .unwrap();
+        assert!(used, "salt {salt} should be used");
+    }
+
+    let count: MarketCountResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::MarketCount {})
+        .unwrap();
+    assert_eq!(count.count, 3);
+}
+
 /// Test that salt is properly handled in market creation and registration.
 /// This verifies the fix for security issue I-3 (Factory Reply Salt Mismatch).
 #[test]
@@ -324,6 +569,7 @@ fn create_market_with_salt_registers_correct_address() {
         },
         params: Box::new(default_market_params()),
         salt: None, // Same as salt: Some(0)
+        fee_amount: None,
     };
 
     env.app
@@ -348,6 +594,7 @@ fn create_market_with_salt_registers_correct_address() {
         },
         params: Box::new(default_market_params()),
         salt: Some(42), // Different salt = different market_id
+        fee_amount: None,
     };
 
     env.app
@@ -376,6 +623,7 @@ fn create_market_with_salt_registers_correct_address() {
             &FactoryQueryMsg::Markets {
                 start_after: None,
                 limit: None,
+                sort_by: None,
             },
         )
         .unwrap();
@@ -466,3 +714,1710 @@ fn create_market_with_salt_registers_correct_address() {
     assert_eq!(config_2.collateral_denom, COLLATERAL_DENOM);
     assert_eq!(config_2.debt_denom, DEBT_DENOM);
 }
+
+/// MarketSummary TVL should match the sum of individual positions once
+/// the simulated interest is actually applied.
+#[test]
+fn market_summary_tvl_matches_positions() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let supplier1 = api.addr_make("supplier1");
+    let supplier2 = api.addr_make("supplier2");
+    let borrower = api.addr_make("borrower");
+
+    env.app.init_modules(|router, _, storage| {
+        for supplier in [&supplier1, &supplier2] {
+            router
+                .bank
+                .init_balance(storage, supplier, vec![coin(1_000_000, DEBT_DENOM)])
+                .unwrap();
+        }
+        router
+            .bank
+            .init_balance(storage, &borrower, vec![coin(1_000_000, COLLATERAL_DENOM)])
+            .unwrap();
+    });
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(markets.markets[0].address.clone());
+
+    for (supplier, amount) in [(&supplier1, 300_000u128), (&supplier2, 700_000u128)] {
+        env.app
+            .execute_contract(
+                supplier.clone(),
+                market_addr.clone(),
+                &MarketExecuteMsg::Supply {
+                    recipient: None,
+                    referrer: None,
+                    force: false,
+                    nonce: None,
+                },
+                &[coin(amount, DEBT_DENOM)],
+            )
+            .unwrap();
+    }
+    env.app
+        .execute_contract(
+            borrower.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::SupplyCollateral { recipient: None },
+            &[coin(100_000, COLLATERAL_DENOM)],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            borrower.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: Uint128::new(500_000),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Let interest accrue without touching the market
+    env.app.update_block(|block| {
+        block.time = block.time.plus_seconds(30 * 24 * 60 * 60);
+        block.height += 1;
+    });
+
+    let summary: MarketSummaryResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(market_addr.clone(), &MarketQueryMsg::MarketSummary {})
+        .unwrap();
+    assert!(summary.total_debt > Uint128::new(500_000));
+    assert_eq!(summary.total_collateral, Uint128::new(100_000));
+
+    // Apply the interest so per-user queries reflect the same block
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::AccrueInterest {},
+            &[],
+        )
+        .unwrap();
+
+    let state: MarketStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(market_addr.clone(), &MarketQueryMsg::State {})
+        .unwrap();
+    assert_eq!(summary.total_supply, state.total_supply);
+    assert_eq!(summary.total_debt, state.total_debt);
+
+    let mut supply_sum = Uint128::zero();
+    for supplier in [&supplier1, &supplier2] {
+        let balance: UserSupplyResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                market_addr.clone(),
+                &MarketQueryMsg::UserSupply {
+                    user: supplier.to_string(),
+                },
+            )
+            .unwrap();
+        supply_sum += balance.actual_supply;
+    }
+    let debt: UserBalanceResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            market_addr.clone(),
+            &MarketQueryMsg::UserDebt {
+                user: borrower.to_string(),
+            },
+        )
+        .unwrap();
+
+    // Per-user amounts round individually, so allow one unit per position
+    assert!(summary.total_supply.abs_diff(supply_sum) <= Uint128::new(2));
+    assert!(summary.total_debt.abs_diff(debt.amount) <= Uint128::one());
+
+    // Debt asset is priced at $1 by the mock oracle
+    assert_eq!(
+        summary.total_supply_usd,
+        Some(Decimal::from_ratio(summary.total_supply, 1u128))
+    );
+}
+
+#[test]
+fn market_by_denoms_lookup() {
+    let mut env = setup_env_with_oracle(vec![
+        (
+            COLLATERAL_DENOM.to_string(),
+            Decimal::from_ratio(10u128, 1u128),
+        ),
+        ("uosmo".to_string(), Decimal::percent(50)),
+        (DEBT_DENOM.to_string(), Decimal::one()),
+    ]);
+
+    for collateral_denom in [COLLATERAL_DENOM, "uosmo"] {
+        let create_msg = FactoryExecuteMsg::CreateMarket {
+            collateral_denom: collateral_denom.to_string(),
+            debt_denom: DEBT_DENOM.to_string(),
+            oracle_config: OracleConfigUnchecked {
+                address: env.oracle_addr.to_string(),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            params: Box::new(default_market_params()),
+            salt: None,
+            fee_amount: None,
+        };
+        env.app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+    }
+
+    for collateral_denom in [COLLATERAL_DENOM, "uosmo"] {
+        let market: Option<MarketResponse> = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::MarketByDenoms {
+                    collateral_denom: collateral_denom.to_string(),
+                    debt_denom: DEBT_DENOM.to_string(),
+                },
+            )
+            .unwrap();
+        let market = market.expect("market should exist for pair");
+        assert_eq!(market.collateral_denom, collateral_denom);
+        assert_eq!(market.debt_denom, DEBT_DENOM);
+
+        let config: MarketConfigResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(Addr::unchecked(market.address), &MarketQueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.collateral_denom, collateral_denom);
+    }
+
+    let missing: Option<MarketResponse> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::MarketByDenoms {
+                collateral_denom: DEBT_DENOM.to_string(),
+                debt_denom: COLLATERAL_DENOM.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+#[test]
+fn create_market_rejects_reused_salt() {
+    let mut env = setup_env_with_oracle(vec![
+        (
+            COLLATERAL_DENOM.to_string(),
+            Decimal::from_ratio(10u128, 1u128),
+        ),
+        ("uosmo".to_string(), Decimal::percent(50)),
+        (DEBT_DENOM.to_string(), Decimal::one()),
+    ]);
+
+    let create_msg = |collateral_denom: &str| FactoryExecuteMsg::CreateMarket {
+        collateral_denom: collateral_denom.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: Some(1),
+        fee_amount: None,
+    };
+    let is_salt_used = |env: &TestEnv| -> bool {
+        env.app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::IsSaltUsed { salt: 1 },
+            )
+            .unwrap()
+    };
+
+    assert!(!is_salt_used(&env));
+    let first = create_msg(COLLATERAL_DENOM);
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &first,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+    assert!(is_salt_used(&env));
+
+    // A different pair still can't reuse the salt
+    let second = create_msg("uosmo");
+    let err = env
+        .app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &second,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap_err();
+    let err_chain: Vec<String> = err.chain().map(|err| err.to_string()).collect();
+    assert!(
+        err_chain
+            .iter()
+            .any(|msg| msg.contains("Salt already used: 1")),
+        "{err_chain:?}"
+    );
+
+    let count: MarketCountResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::MarketCount {})
+        .unwrap();
+    assert_eq!(count.count, 1);
+}
+
+fn batch_request(env: &TestEnv, salt: Option<u64>) -> CreateMarketRequest {
+    CreateMarketRequest {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: default_market_params(),
+        salt,
+    }
+}
+
+#[test]
+fn batch_create_markets_deploys_all() {
+    let mut env = setup_env();
+
+    let salts = [None, Some(1), Some(2)];
+    let batch_msg = FactoryExecuteMsg::BatchCreateMarkets {
+        markets: salts
+            .iter()
+            .map(|salt| batch_request(&env, *salt))
+            .collect(),
+    };
+
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &batch_msg,
+            &[coin(3_000, "uosmo")],
+        )
+        .unwrap();
+
+    let count: MarketCountResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::MarketCount {})
+        .unwrap();
+    assert_eq!(count.count, 3);
+
+    // Each reply registered its market under the ID computed from its own salt
+    let mut addresses = vec![];
+    for salt in salts {
+        let expected: ComputeMarketIdResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::ComputeMarketId {
+                    collateral_denom: COLLATERAL_DENOM.to_string(),
+                    debt_denom: DEBT_DENOM.to_string(),
+                    curator: env.curator.to_string(),
+                    salt,
+                },
+            )
+            .unwrap();
+        let market: MarketResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::Market {
+                    market_id: expected.market_id,
+                },
+            )
+            .unwrap();
+
+        let config: MarketConfigResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(Addr::unchecked(&market.address), &MarketQueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.curator, env.curator.to_string());
+        assert_eq!(config.factory, env.factory_addr.to_string());
+        addresses.push(market.address);
+    }
+    addresses.sort();
+    addresses.dedup();
+    assert_eq!(addresses.len(), 3);
+
+    let collector_balance = env
+        .app
+        .wrap()
+        .query_balance(env.collector.clone(), "uosmo")
+        .unwrap();
+    assert_eq!(collector_balance.amount, Uint128::new(3_000));
+}
+
+#[test]
+fn batch_create_markets_requires_exact_fee() {
+    let mut env = setup_env();
+
+    let batch_msg = FactoryExecuteMsg::BatchCreateMarkets {
+        markets: vec![batch_request(&env, None), batch_request(&env, Some(1))],
+    };
+
+    for sent in [1_000u128, 3_000] {
+        let err = env
+            .app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &batch_msg,
+                &[coin(sent, "uosmo")],
+            )
+            .unwrap_err();
+
+        let err_chain: Vec<String> = err.chain().map(|err| err.to_string()).collect();
+        assert!(
+            err_chain
+                .iter()
+                .any(|msg| msg.contains("Batch creation fee mismatch")),
+            "{err_chain:?}"
+        );
+    }
+}
+
+#[test]
+fn batch_create_markets_rejects_duplicates_within_batch() {
+    let mut env = setup_env();
+
+    let batch_msg = FactoryExecuteMsg::BatchCreateMarkets {
+        markets: vec![batch_request(&env, Some(7)), batch_request(&env, Some(7))],
+    };
+
+    let err = env
+        .app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &batch_msg,
+            &[coin(2_000, "uosmo")],
+        )
+        .unwrap_err();
+
+    let err_chain: Vec<String> = err.chain().map(|err| err.to_string()).collect();
+    assert!(
+        err_chain
+            .iter()
+            .any(|msg| msg.contains("Market already exists")),
+        "{err_chain:?}"
+    );
+
+    // Nothing was created
+    let count: MarketCountResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::MarketCount {})
+        .unwrap();
+    assert_eq!(count.count, 0);
+}
+
+#[test]
+fn failed_market_instantiation_stays_pending_until_retried() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+
+    let config: FactoryConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+    let failing_code_id = env.app.store_code(failing_market_wrapper());
+    env.app
+        .execute_contract(
+            owner.clone(),
+            env.factory_addr.clone(),
+            &FactoryExecuteMsg::UpdateMarketCodeId {
+                code_id: failing_code_id,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+
+    // Instantiation fails, but the attempt is recorded and the fee refunded
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let expected_id: ComputeMarketIdResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::ComputeMarketId {
+                collateral_denom: COLLATERAL_DENOM.to_string(),
+                debt_denom: DEBT_DENOM.to_string(),
+                curator: env.curator.to_string(),
+                salt: None,
+            },
+        )
+        .unwrap();
+    let pending: Vec<CreateMarketAttempt> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::PendingMarkets {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].market_id, expected_id.market_id);
+    assert_eq!(pending[0].curator, env.curator);
+
+    let count: MarketCountResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::MarketCount {})
+        .unwrap();
+    assert_eq!(count.count, 0);
+    let curator_balance = env
+        .app
+        .wrap()
+        .query_balance(env.curator.clone(), "uosmo")
+        .unwrap();
+    assert_eq!(curator_balance.amount, Uint128::new(2_000_000));
+
+    // Restore the working market code and retry
+    env.app
+        .execute_contract(
+            owner,
+            env.factory_addr.clone(),
+            &FactoryExecuteMsg::UpdateMarketCodeId {
+                code_id: config.market_code_id,
+            },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let pending: Vec<CreateMarketAttempt> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::PendingMarkets {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(pending.is_empty());
+
+    let market: MarketResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Market {
+                market_id: expected_id.market_id,
+            },
+        )
+        .unwrap();
+    assert_eq!(market.curator, env.curator.to_string());
+
+    let collector_balance = env
+        .app
+        .wrap()
+        .query_balance(env.collector.clone(), "uosmo")
+        .unwrap();
+    assert_eq!(collector_balance.amount, Uint128::new(1_000));
+}
+
+#[test]
+fn owner_can_disable_market() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let supplier = api.addr_make("supplier");
+
+    env.app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &supplier, vec![coin(1_000_000, DEBT_DENOM)])
+            .unwrap();
+    });
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(markets.markets[0].address.clone());
+
+    env.app
+        .execute_contract(
+            supplier.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(500_000, DEBT_DENOM)],
+        )
+        .unwrap();
+
+    let disable_msg = FactoryExecuteMsg::DisableMarket {
+        market: market_addr.to_string(),
+    };
+
+    // Only the factory owner can disable markets, not even the curator
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &disable_msg,
+            &[],
+        )
+        .unwrap_err();
+
+    let res = env
+        .app
+        .execute_contract(owner.clone(), env.factory_addr.clone(), &disable_msg, &[])
+        .unwrap();
+    assert!(res.events.iter().any(|e| e
+        .attributes
+        .iter()
+        .any(|a| a.key == "action" && a.value == "disable_market")));
+
+    let err = env
+        .app
+        .execute_contract(
+            supplier.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(100_000, DEBT_DENOM)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().downcast_ref::<MarketError>(),
+        Some(&MarketError::MarketPaused)
+    );
+
+    // Suppliers can still exit a disabled market
+    env.app
+        .execute_contract(
+            supplier.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::Withdraw {
+                amount: None,
+                recipient: None,
+                max_withdraw: false,
+                nonce: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Only the factory owner can lift the pause
+    let enable_msg = FactoryExecuteMsg::EnableMarket {
+        market: market_addr.to_string(),
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &enable_msg,
+            &[],
+        )
+        .unwrap_err();
+    env.app
+        .execute_contract(owner, env.factory_addr.clone(), &enable_msg, &[])
+        .unwrap();
+
+    env.app
+        .execute_contract(
+            supplier,
+            market_addr,
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(100_000, DEBT_DENOM)],
+        )
+        .unwrap();
+}
+
+#[test]
+fn owner_can_emergency_withdraw_market_fees() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let supplier = api.addr_make("supplier");
+    let borrower = api.addr_make("borrower");
+    let treasury = api.addr_make("treasury");
+
+    env.app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &supplier, vec![coin(1_000_000, DEBT_DENOM)])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &borrower, vec![coin(100_000, COLLATERAL_DENOM)])
+            .unwrap();
+    });
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    let res = env
+        .app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "market_address")
+            .unwrap()
+            .value
+            .clone(),
+    );
+
+    env.app
+        .execute_contract(
+            supplier,
+            market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(1_000_000, DEBT_DENOM)],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            borrower.clone(),
+            market_addr.clone(),
+            &MarketExecuteMsg::SupplyCollateral { recipient: None },
+            &[coin(100_000, COLLATERAL_DENOM)],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            borrower,
+            market_addr.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: Uint128::new(500_000),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Let protocol fees accrue
+    env.app.update_block(|block| {
+        block.time = block.time.plus_seconds(365 * 24 * 60 * 60);
+        block.height += 1;
+    });
+
+    let withdraw_msg = FactoryExecuteMsg::EmergencyWithdrawMarket {
+        market: market_addr.to_string(),
+        recipient: treasury.to_string(),
+    };
+
+    // Only the factory owner can trigger the withdrawal
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap_err();
+    let res = env
+        .app
+        .execute_contract(owner.clone(), env.factory_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+
+    let withdrawn: Uint128 = res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "protocol_withdrawn")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+    assert!(!withdrawn.is_zero());
+    let treasury_balance = env.app.wrap().query_balance(&treasury, DEBT_DENOM).unwrap();
+    assert_eq!(treasury_balance.amount, withdrawn);
+
+    // The accrued fees were cleared
+    env.app
+        .execute_contract(owner, env.factory_addr.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+}
+
+#[test]
+fn owner_can_rebalance_market_liquidity_index() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    let res = env
+        .app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "market_address")
+            .unwrap()
+            .value
+            .clone(),
+    );
+
+    let target_index = Decimal::from_ratio(100_005u128, 100_000u128);
+    let rebalance_msg = FactoryExecuteMsg::RebalanceMarketLiquidityIndex {
+        market: market_addr.to_string(),
+        target_index,
+    };
+
+    // Only the factory owner can rebalance
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &rebalance_msg,
+            &[],
+        )
+        .unwrap_err();
+
+    // The market still enforces the maximum deviation
+    let err = env
+        .app
+        .execute_contract(
+            owner.clone(),
+            env.factory_addr.clone(),
+            &FactoryExecuteMsg::RebalanceMarketLiquidityIndex {
+                market: market_addr.to_string(),
+                target_index: Decimal::percent(101),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.root_cause().downcast_ref::<MarketError>(),
+        Some(MarketError::IndexRebalanceTooLarge { .. })
+    ));
+
+    env.app
+        .execute_contract(owner, env.factory_addr.clone(), &rebalance_msg, &[])
+        .unwrap();
+    let state: MarketStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(market_addr, &MarketQueryMsg::State {})
+        .unwrap();
+    assert_eq!(state.liquidity_index, target_index);
+}
+
+#[test]
+fn owner_can_rotate_market_admin() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let new_admin = api.addr_make("new_admin");
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market_addr = Addr::unchecked(markets.markets[0].address.clone());
+
+    // Markets are instantiated with the factory as their admin
+    let data = env.app.contract_data(&market_addr).unwrap();
+    assert_eq!(data.admin, Some(env.factory_addr.clone()));
+
+    let set_admin_msg = FactoryExecuteMsg::SetMarketAdmin {
+        market: market_addr.to_string(),
+        admin: Some(new_admin.to_string()),
+    };
+
+    // Only the factory owner can rotate the admin
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &set_admin_msg,
+            &[],
+        )
+        .unwrap_err();
+
+    env.app
+        .execute_contract(owner.clone(), env.factory_addr.clone(), &set_admin_msg, &[])
+        .unwrap();
+    let data = env.app.contract_data(&market_addr).unwrap();
+    assert_eq!(data.admin, Some(new_admin));
+
+    // The factory is no longer the admin, so it cannot rotate it again
+    let err = env
+        .app
+        .execute_contract(owner, env.factory_addr.clone(), &set_admin_msg, &[])
+        .unwrap_err();
+    let err_chain: Vec<String> = err.chain().map(|err| err.to_string()).collect();
+    assert!(
+        err_chain
+            .iter()
+            .any(|msg| msg.contains("Factory is not the admin of market")),
+        "{err_chain:?}"
+    );
+}
+
+#[test]
+fn owner_can_update_market_collateral_denom() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+    let new_denom = "ustatom".to_string();
+
+    let create_msg = FactoryExecuteMsg::CreateMarket {
+        collateral_denom: COLLATERAL_DENOM.to_string(),
+        debt_denom: DEBT_DENOM.to_string(),
+        oracle_config: OracleConfigUnchecked {
+            address: env.oracle_addr.to_string(),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        },
+        params: Box::new(default_market_params()),
+        salt: None,
+        fee_amount: None,
+    };
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &create_msg,
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    let market = markets.markets[0].clone();
+
+    let update_msg = FactoryExecuteMsg::UpdateMarketCollateralDenom {
+        market: market.address.clone(),
+        new_denom: new_denom.clone(),
+    };
+
+    // The oracle has no price for the new denom yet
+    env.app
+        .execute_contract(owner.clone(), env.factory_addr.clone(), &update_msg, &[])
+        .unwrap_err();
+
+    env.app
+        .execute_contract(
+            owner.clone(),
+            env.oracle_addr.clone(),
+            &MockOracleExecuteMsg::SetPrice {
+                denom: new_denom.clone(),
+                price: Decimal::from_ratio(10u128, 1u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Only the factory owner can migrate the collateral denom
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &update_msg,
+            &[],
+        )
+        .unwrap_err();
+
+    env.app
+        .execute_contract(owner, env.factory_addr.clone(), &update_msg, &[])
+        .unwrap();
+
+    let config: MarketConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(market.address.clone(), &MarketQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.collateral_denom, new_denom);
+
+    // The market keeps its ID and moves to the new collateral index
+    let record: MarketResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Market {
+                market_id: market.market_id.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(record.address, market.address);
+    assert_eq!(record.collateral_denom, new_denom);
+
+    let by_new: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::MarketsByCollateral {
+                collateral_denom: new_denom,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(by_new.markets.len(), 1);
+    assert_eq!(by_new.markets[0].market_id, market.market_id);
+
+    let by_old: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::MarketsByCollateral {
+                collateral_denom: COLLATERAL_DENOM.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(by_old.markets.is_empty());
+}
+
+#[test]
+fn markets_query_sorts_by_creation_time() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+
+    let collateral_denoms = ["ujuno", COLLATERAL_DENOM, "uosmo"];
+    for collateral_denom in collateral_denoms {
+        env.app
+            .execute_contract(
+                owner.clone(),
+                env.oracle_addr.clone(),
+                &MockOracleExecuteMsg::SetPrice {
+                    denom: collateral_denom.to_string(),
+                    price: Decimal::from_ratio(10u128, 1u128),
+                },
+                &[],
+            )
+            .unwrap();
+        let create_msg = FactoryExecuteMsg::CreateMarket {
+            collateral_denom: collateral_denom.to_string(),
+            debt_denom: DEBT_DENOM.to_string(),
+            oracle_config: OracleConfigUnchecked {
+                address: env.oracle_addr.to_string(),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            params: Box::new(default_market_params()),
+            salt: None,
+            fee_amount: None,
+        };
+        env.app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+        env.app.update_block(|block| {
+            block.time = block.time.plus_seconds(60);
+            block.height += 1;
+        });
+    }
+
+    let query_markets = |start_after: Option<String>, limit: Option<u32>| -> MarketsResponse {
+        env.app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::Markets {
+                    start_after,
+                    limit,
+                    sort_by: Some(MarketSortField::CreatedAt),
+                },
+            )
+            .unwrap()
+    };
+
+    let markets = query_markets(None, None).markets;
+    let denoms: Vec<&str> = markets
+        .iter()
+        .map(|m| m.collateral_denom.as_str())
+        .collect();
+    assert_eq!(denoms, collateral_denoms);
+    assert!(markets
+        .windows(2)
+        .all(|pair| pair[0].created_at < pair[1].created_at));
+
+    // Pages continue after the last market ID returned
+    let first_page = query_markets(None, Some(2)).markets;
+    assert_eq!(first_page, markets[..2]);
+    let second_page = query_markets(Some(first_page[1].market_id.clone()), Some(2)).markets;
+    assert_eq!(second_page, markets[2..]);
+}
+
+#[test]
+fn markets_by_filter_returns_matching_subsets() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let other_curator = api.addr_make("other_curator");
+
+    env.app
+        .execute_contract(
+            owner,
+            env.oracle_addr.clone(),
+            &MockOracleExecuteMsg::SetPrice {
+                denom: "ujuno".to_string(),
+                price: Decimal::one(),
+            },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .send_tokens(
+            env.curator.clone(),
+            other_curator.clone(),
+            &[coin(1_000, "uosmo")],
+        )
+        .unwrap();
+
+    let pairs = [
+        (env.curator.clone(), "ujuno", DEBT_DENOM),
+        (env.curator.clone(), COLLATERAL_DENOM, DEBT_DENOM),
+        (other_curator.clone(), COLLATERAL_DENOM, "ujuno"),
+    ];
+    for (curator, collateral_denom, debt_denom) in &pairs {
+        let create_msg = FactoryExecuteMsg::CreateMarket {
+            collateral_denom: collateral_denom.to_string(),
+            debt_denom: debt_denom.to_string(),
+            oracle_config: OracleConfigUnchecked {
+                address: env.oracle_addr.to_string(),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            params: Box::new(default_market_params()),
+            salt: None,
+            fee_amount: None,
+        };
+        env.app
+            .execute_contract(
+                curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+    }
+
+    let query_pairs = |filter: MarketFilter| -> Vec<(String, String)> {
+        let res: MarketsResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::MarketsBy {
+                    filter,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let mut pairs: Vec<_> = res
+            .markets
+            .into_iter()
+            .map(|m| (m.collateral_denom, m.debt_denom))
+            .collect();
+        pairs.sort();
+        pairs
+    };
+    let pair = |collateral: &str, debt: &str| (collateral.to_string(), debt.to_string());
+
+    assert_eq!(
+        query_pairs(MarketFilter::CollateralDenom(COLLATERAL_DENOM.to_string())),
+        vec![
+            pair(COLLATERAL_DENOM, DEBT_DENOM),
+            pair(COLLATERAL_DENOM, "ujuno")
+        ]
+    );
+    assert_eq!(
+        query_pairs(MarketFilter::DebtDenom(DEBT_DENOM.to_string())),
+        vec![
+            pair(COLLATERAL_DENOM, DEBT_DENOM),
+            pair("ujuno", DEBT_DENOM)
+        ]
+    );
+    assert_eq!(
+        query_pairs(MarketFilter::Curator(other_curator.to_string())),
+        vec![pair(COLLATERAL_DENOM, "ujuno")]
+    );
+    assert_eq!(query_pairs(MarketFilter::All).len(), 3);
+    assert!(query_pairs(MarketFilter::DebtDenom("uunknown".to_string())).is_empty());
+
+    // Pagination uses market IDs as the cursor
+    let first_page: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::MarketsBy {
+                filter: MarketFilter::All,
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+    let second_page: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::MarketsBy {
+                filter: MarketFilter::All,
+                start_after: Some(first_page.markets[1].market_id.clone()),
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+    assert_eq!(first_page.markets.len(), 2);
+    assert_eq!(second_page.markets.len(), 1);
+}
+
+#[test]
+fn total_protocol_tvl_sums_markets_and_reports_failures() {
+    let mut env = setup_env();
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let supplier = api.addr_make("supplier");
+    let borrower = api.addr_make("borrower");
+
+    env.app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &supplier, vec![coin(1_000_000, DEBT_DENOM)])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &borrower, vec![coin(100_000, COLLATERAL_DENOM)])
+            .unwrap();
+    });
+    for denom in ["ujuno", "uatom"] {
+        env.app
+            .execute_contract(
+                owner.clone(),
+                env.oracle_addr.clone(),
+                &MockOracleExecuteMsg::SetPrice {
+                    denom: denom.to_string(),
+                    price: Decimal::from_ratio(2u128, 1u128),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    let pairs = [
+        ("ujuno", DEBT_DENOM),
+        (COLLATERAL_DENOM, DEBT_DENOM),
+        (COLLATERAL_DENOM, "uatom"),
+    ];
+    let mut market_addrs = vec![];
+    for (collateral_denom, debt_denom) in pairs {
+        let create_msg = FactoryExecuteMsg::CreateMarket {
+            collateral_denom: collateral_denom.to_string(),
+            debt_denom: debt_denom.to_string(),
+            oracle_config: OracleConfigUnchecked {
+                address: env.oracle_addr.to_string(),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            params: Box::new(default_market_params()),
+            salt: None,
+            fee_amount: None,
+        };
+        env.app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+        let market: Option<MarketResponse> = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                env.factory_addr.clone(),
+                &FactoryQueryMsg::MarketByDenoms {
+                    collateral_denom: collateral_denom.to_string(),
+                    debt_denom: debt_denom.to_string(),
+                },
+            )
+            .unwrap();
+        let market = market.unwrap();
+        market_addrs.push((market.market_id, Addr::unchecked(market.address)));
+    }
+
+    // Supply to the first two markets and borrow half of the second
+    for ((_, market_addr), amount) in market_addrs.iter().zip([300_000u128, 500_000]) {
+        env.app
+            .execute_contract(
+                supplier.clone(),
+                market_addr.clone(),
+                &MarketExecuteMsg::Supply {
+                    recipient: None,
+                    referrer: None,
+                    force: false,
+                    nonce: None,
+                },
+                &[coin(amount, DEBT_DENOM)],
+            )
+            .unwrap();
+    }
+    env.app
+        .execute_contract(
+            borrower.clone(),
+            market_addrs[1].1.clone(),
+            &MarketExecuteMsg::SupplyCollateral { recipient: None },
+            &[coin(100_000, COLLATERAL_DENOM)],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            borrower.clone(),
+            market_addrs[1].1.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: Uint128::new(250_000),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // A zero debt asset price leaves the third market without a USD value
+    env.app
+        .execute_contract(
+            owner.clone(),
+            env.oracle_addr.clone(),
+            &MockOracleExecuteMsg::SetPrice {
+                denom: "uatom".to_string(),
+                price: Decimal::zero(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let tvl: ProtocolTvlResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::TotalProtocolTvl {},
+        )
+        .unwrap();
+    // Debt asset is priced at $1 and no time has passed for interest to accrue
+    assert_eq!(
+        tvl.total_supply_usd,
+        Decimal::from_ratio(800_000u128, 1u128)
+    );
+    assert_eq!(tvl.total_debt_usd, Decimal::from_ratio(250_000u128, 1u128));
+    assert_eq!(tvl.market_count, 2);
+    assert_eq!(tvl.failed_markets, vec![market_addrs[2].0.clone()]);
+}
+
+#[test]
+fn protocol_fee_collector_two_step_handoff_updates_markets() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+    let new_collector = MockApi::default().addr_make("new_collector");
+
+    for collateral_denom in [COLLATERAL_DENOM, "uosmo"] {
+        env.app
+            .execute_contract(
+                owner.clone(),
+                env.oracle_addr.clone(),
+                &MockOracleExecuteMsg::SetPrice {
+                    denom: collateral_denom.to_string(),
+                    price: Decimal::from_ratio(10u128, 1u128),
+                },
+                &[],
+            )
+            .unwrap();
+        let create_msg = FactoryExecuteMsg::CreateMarket {
+            collateral_denom: collateral_denom.to_string(),
+            debt_denom: DEBT_DENOM.to_string(),
+            oracle_config: OracleConfigUnchecked {
+                address: env.oracle_addr.to_string(),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            params: Box::new(default_market_params()),
+            salt: None,
+            fee_amount: None,
+        };
+        env.app
+            .execute_contract(
+                env.curator.clone(),
+                env.factory_addr.clone(),
+                &create_msg,
+                &[coin(1_000, "uosmo")],
+            )
+            .unwrap();
+    }
+
+    let propose_msg = FactoryExecuteMsg::ProposeProtocolFeeCollector {
+        new_collector: new_collector.to_string(),
+    };
+    let accept_msg = FactoryExecuteMsg::AcceptProtocolFeeCollectorRole {};
+
+    // Nothing to accept before a proposal
+    env.app
+        .execute_contract(
+            new_collector.clone(),
+            env.factory_addr.clone(),
+            &accept_msg,
+            &[],
+        )
+        .unwrap_err();
+
+    // Only the owner can propose
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &propose_msg,
+            &[],
+        )
+        .unwrap_err();
+    env.app
+        .execute_contract(owner, env.factory_addr.clone(), &propose_msg, &[])
+        .unwrap();
+
+    // Proposing does not change the collector yet
+    let config: FactoryConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.protocol_fee_collector, env.collector.to_string());
+
+    // Only the proposed collector can accept
+    env.app
+        .execute_contract(
+            env.curator.clone(),
+            env.factory_addr.clone(),
+            &accept_msg,
+            &[],
+        )
+        .unwrap_err();
+    let res = env
+        .app
+        .execute_contract(
+            new_collector.clone(),
+            env.factory_addr.clone(),
+            &accept_msg,
+            &[],
+        )
+        .unwrap();
+    let market_updates = res
+        .events
+        .iter()
+        .filter(|event| {
+            event.ty == "wasm"
+                && event.attributes.iter().any(|attr| {
+                    attr.key == "action" && attr.value == "update_protocol_fee_collector"
+                })
+        })
+        .count();
+    assert_eq!(market_updates, 2);
+
+    let config: FactoryConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.factory_addr.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.protocol_fee_collector, new_collector.to_string());
+
+    let markets: MarketsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.factory_addr.clone(),
+            &FactoryQueryMsg::Markets {
+                start_after: None,
+                limit: None,
+                sort_by: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(markets.markets.len(), 2);
+    for market in markets.markets {
+        let market_config: MarketConfigResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(market.address, &MarketQueryMsg::Config {})
+            .unwrap();
+        assert_eq!(
+            market_config.protocol_fee_collector,
+            new_collector.to_string()
+        );
+    }
+
+    // The proposal is consumed
+    env.app
+        .execute_contract(new_collector, env.factory_addr.clone(), &accept_msg, &[])
+        .unwrap_err();
+}
+
+#[test]
+fn disable_market_rejects_unregistered_contract() {
+    let mut env = setup_env();
+    let owner = MockApi::default().addr_make("owner");
+
+    // The oracle is a contract, but not a market created by this factory
+    let err = env
+        .app
+        .execute_contract(
+            owner,
+            env.factory_addr.clone(),
+            &FactoryExecuteMsg::DisableMarket {
+                market: env.oracle_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    let err_chain: Vec<String> = err.chain().map(|err| err.to_string()).collect();
+    assert!(
+        err_chain.iter().any(|msg| msg.contains("Market not found")),
+        "{err_chain:?}"
+    );
+}
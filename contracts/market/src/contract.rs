@@ -1,9 +1,9 @@
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
 };
 
 use stone_types::{
-    MarketConfig, MarketExecuteMsg, MarketInstantiateMsg, MarketParams, MarketQueryMsg, MarketState,
+    MarketConfig, MarketExecuteMsg, MarketInstantiateMsg, MarketQueryMsg, MarketState,
 };
 
 use crate::error::ContractError;
@@ -28,24 +28,15 @@ pub fn instantiate(
         debt_denom: msg.debt_denom,
         protocol_fee_collector: deps.api.addr_validate(&msg.protocol_fee_collector)?,
         salt: None,
+        allow_collateral_swap: msg.allow_collateral_swap,
+        receipt_nft_contract: msg
+            .receipt_nft_contract
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
     };
 
-    let params = MarketParams {
-        loan_to_value: msg.params.loan_to_value,
-        liquidation_threshold: msg.params.liquidation_threshold,
-        liquidation_bonus: msg.params.liquidation_bonus,
-        liquidation_protocol_fee: msg.params.liquidation_protocol_fee,
-        close_factor: msg.params.close_factor,
-        dust_debt_threshold: msg.params.dust_debt_threshold,
-        interest_rate_model: msg.params.interest_rate_model,
-        protocol_fee: msg.params.protocol_fee,
-        curator_fee: msg.params.curator_fee,
-        supply_cap: msg.params.supply_cap,
-        borrow_cap: msg.params.borrow_cap,
-        enabled: true,
-        is_mutable: msg.params.is_mutable,
-        ltv_last_update: env.block.time.seconds(),
-    };
+    let params = msg.params.into_market_params(env.block.time.seconds());
+    params.validate()?;
 
     let state = MarketState::new(env.block.time.seconds());
 
@@ -53,12 +44,14 @@ pub fn instantiate(
     PARAMS.save(deps.storage, &params)?;
     STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new()
+    let response = Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("factory", info.sender)
         .add_attribute("curator", config.curator)
         .add_attribute("collateral_denom", config.collateral_denom)
-        .add_attribute("debt_denom", config.debt_denom))
+        .add_attribute("debt_denom", config.debt_denom);
+
+    Ok(execute::with_dust_threshold_warning(response, &params))
 }
 
 #[entry_point]
@@ -68,12 +61,35 @@ pub fn execute(
     info: MessageInfo,
     msg: MarketExecuteMsg,
 ) -> Result<Response, ContractError> {
+    execute::ensure_no_pending_swap(deps.storage)?;
+
     match msg {
-        MarketExecuteMsg::Supply { recipient } => {
-            execute::execute_supply(deps, env, info, recipient)
+        MarketExecuteMsg::Supply {
+            recipient,
+            referrer,
+            force,
+            nonce,
+        } => {
+            execute::use_nonce(deps.storage, &env, &info.sender, nonce)?;
+            execute::execute_supply(deps, env, info, recipient, referrer, force)
+        }
+        MarketExecuteMsg::SupplyOnBehalfMany { recipients, force } => {
+            execute::execute_supply_on_behalf_many(deps, env, info, recipients, force)
+        }
+        MarketExecuteMsg::SupplyPreview { amount } => {
+            execute::execute_supply_preview(deps, env, amount)
         }
-        MarketExecuteMsg::Withdraw { amount, recipient } => {
-            execute::execute_withdraw(deps, env, info, amount, recipient)
+        MarketExecuteMsg::TransferSupplyShares { recipient, amount } => {
+            execute::execute_transfer_supply_shares(deps, env, info, recipient, amount)
+        }
+        MarketExecuteMsg::Withdraw {
+            amount,
+            recipient,
+            max_withdraw,
+            nonce,
+        } => {
+            execute::use_nonce(deps.storage, &env, &info.sender, nonce)?;
+            execute::execute_withdraw(deps, env, info, amount, recipient, max_withdraw)
         }
         MarketExecuteMsg::SupplyCollateral { recipient } => {
             execute::execute_supply_collateral(deps, env, info, recipient)
@@ -84,17 +100,102 @@ pub fn execute(
         MarketExecuteMsg::Borrow { amount, recipient } => {
             execute::execute_borrow(deps, env, info, amount, recipient)
         }
+        MarketExecuteMsg::BorrowPreview { amount } => {
+            execute::execute_borrow_preview(deps, env, info, amount)
+        }
+        MarketExecuteMsg::SupplyAndBorrow {
+            supply_amount,
+            borrow_amount,
+        } => execute::execute_supply_and_borrow(deps, env, info, supply_amount, borrow_amount),
+        MarketExecuteMsg::SupplyCollateralAndBorrow {
+            collateral_amount,
+            borrow_amount,
+        } => execute::execute_supply_collateral_and_borrow(
+            deps,
+            env,
+            info,
+            collateral_amount,
+            borrow_amount,
+        ),
         MarketExecuteMsg::Repay { on_behalf_of } => {
             execute::execute_repay(deps, env, info, on_behalf_of)
         }
+        MarketExecuteMsg::RepayMax {} => execute::execute_repay_max(deps, env, info),
         MarketExecuteMsg::Liquidate { borrower } => {
             execute::execute_liquidate(deps, env, info, borrower)
         }
+        MarketExecuteMsg::LiquidateAndSupply { borrower } => {
+            execute::execute_liquidate_and_supply(deps, env, info, borrower)
+        }
+        MarketExecuteMsg::LiquidateMultiple { borrowers } => {
+            execute::execute_liquidate_multiple(deps, env, info, borrowers)
+        }
+        MarketExecuteMsg::SweepDust { borrower } => {
+            execute::execute_sweep_dust(deps, env, info, borrower)
+        }
+        MarketExecuteMsg::CollateralSwap {
+            router,
+            swap_msg,
+            min_receive,
+        } => execute::execute_collateral_swap(deps, env, info, router, swap_msg, min_receive),
+        MarketExecuteMsg::RepayWithCollateral {
+            collateral_to_use,
+            max_debt_repaid,
+            router,
+            route_msg,
+        } => execute::execute_repay_with_collateral(
+            deps,
+            env,
+            info,
+            collateral_to_use,
+            max_debt_repaid,
+            router,
+            route_msg,
+        ),
         MarketExecuteMsg::UpdateParams { updates } => {
-            execute::execute_update_params(deps, env, info, updates)
+            execute::execute_update_params(deps, env, info, *updates)
+        }
+        MarketExecuteMsg::SetPrivilegedBorrower { borrower, limit } => {
+            execute::execute_set_privileged_borrower(deps, info, borrower, limit)
+        }
+        MarketExecuteMsg::SetCollateralWhitelist { addresses, allowed } => {
+            execute::execute_set_collateral_whitelist(deps, info, addresses, allowed)
+        }
+        MarketExecuteMsg::Pause {} => execute::execute_pause(deps, info),
+        MarketExecuteMsg::Unpause {} => execute::execute_unpause(deps, info),
+        MarketExecuteMsg::FreezeLtv {} => execute::execute_freeze_ltv(deps, info),
+        MarketExecuteMsg::UpdateProtocolFeeCollector { new_collector } => {
+            execute::execute_update_protocol_fee_collector(deps, info, new_collector)
+        }
+        MarketExecuteMsg::EmergencyWithdraw { recipient } => {
+            execute::execute_emergency_withdraw(deps, env, info, recipient)
         }
-        MarketExecuteMsg::AccrueInterest {} => execute::execute_accrue_interest(deps, env),
+        MarketExecuteMsg::RebalanceLiquidityIndex { target_index } => {
+            execute::execute_rebalance_liquidity_index(deps, env, info, target_index)
+        }
+        MarketExecuteMsg::UpdateCollateralDenom { new_denom } => {
+            execute::execute_update_collateral_denom(deps, env, info, new_denom)
+        }
+        MarketExecuteMsg::AccrueInterest {} => execute::execute_accrue_interest(deps, env, info),
         MarketExecuteMsg::ClaimFees {} => execute::execute_claim_fees(deps, env, info),
+        MarketExecuteMsg::ClaimFeesPartial {
+            protocol_amount,
+            curator_amount,
+        } => execute::execute_claim_fees_partial(deps, env, info, protocol_amount, curator_amount),
+    }
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        execute::COLLATERAL_SWAP_REPLY_ID => execute::handle_collateral_swap_reply(deps, env, msg),
+        execute::REPAY_WITH_COLLATERAL_REPLY_ID => {
+            execute::handle_repay_with_collateral_reply(deps, env, msg)
+        }
+        execute::RECEIPT_NFT_REPLY_ID => execute::handle_receipt_nft_reply(deps, env, msg),
+        id => Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
+            format!("Unknown reply id: {}", id),
+        ))),
     }
 }
 
@@ -118,6 +219,42 @@ pub fn query(deps: Deps, env: Env, msg: MarketQueryMsg) -> Result<Binary, Contra
             to_json_binary(&query::query_is_liquidatable(deps, env, user)?)?
         }
         MarketQueryMsg::AccruedFees {} => to_json_binary(&query::accrued_fees(deps)?)?,
+        MarketQueryMsg::ClaimableFeesPreview {} => {
+            to_json_binary(&query::claimable_fees_preview(deps, env)?)?
+        }
+        MarketQueryMsg::MarketSummary {} => to_json_binary(&query::market_summary(deps, env)?)?,
+        MarketQueryMsg::HealthMetrics { user } => {
+            to_json_binary(&query::health_metrics(deps, env, user)?)?
+        }
+        MarketQueryMsg::CurrentRates {} => to_json_binary(&query::current_rates(deps, env)?)?,
+        MarketQueryMsg::UserShareBalance { user } => {
+            to_json_binary(&query::user_share_balance(deps, user)?)?
+        }
+        MarketQueryMsg::UserShareValue { user } => {
+            to_json_binary(&query::user_share_value(deps, env, user)?)?
+        }
+        MarketQueryMsg::LiquidationHistory { limit } => {
+            to_json_binary(&query::liquidation_history(deps, limit)?)?
+        }
+        MarketQueryMsg::InterestHistory { limit } => {
+            to_json_binary(&query::interest_history(deps, limit)?)?
+        }
+        MarketQueryMsg::PreviewBorrow { user, amount } => {
+            to_json_binary(&query::preview_borrow(deps, env, user, amount)?)?
+        }
+        MarketQueryMsg::SimulateLiquidation {
+            borrower,
+            debt_amount,
+        } => to_json_binary(&query::simulate_liquidation(
+            deps,
+            env,
+            borrower,
+            debt_amount,
+        )?)?,
+        MarketQueryMsg::ReferralVolume { referrer } => {
+            to_json_binary(&query::referral_volume(deps, referrer)?)?
+        }
+        MarketQueryMsg::SocializedLosses {} => to_json_binary(&query::socialized_losses(deps)?)?,
     };
 
     Ok(result)
@@ -176,6 +313,8 @@ mod tests {
                 borrow_cap: None,
                 is_mutable: false,
             },
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         }
     }
 
@@ -206,6 +345,35 @@ mod tests {
         assert_eq!(state.borrow_index, Decimal::one());
     }
 
+    #[test]
+    fn test_instantiate_dust_threshold_against_borrow_cap() {
+        let (factory, ..) = test_addrs();
+
+        // 200 > 1_000 / 10: allowed but flagged
+        let mut deps = mock_dependencies();
+        let mut msg = default_instantiate_msg();
+        msg.params.dust_debt_threshold = Uint128::new(200);
+        msg.params.borrow_cap = Some(Uint128::new(1_000));
+        let res = instantiate(deps.as_mut(), mock_env(), message_info(&factory, &[]), msg).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "dust_threshold_warning"
+                && a.value == "threshold exceeds 10% of borrow cap"));
+
+        // Above the cap itself is rejected
+        let mut deps = mock_dependencies();
+        let mut msg = default_instantiate_msg();
+        msg.params.dust_debt_threshold = Uint128::new(1_001);
+        msg.params.borrow_cap = Some(Uint128::new(1_000));
+        let err =
+            instantiate(deps.as_mut(), mock_env(), message_info(&factory, &[]), msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(stone_types::errors::TypesError::DustDebtThresholdTooHigh { .. })
+        ));
+    }
+
     #[test]
     fn test_query_config() {
         let mut deps = mock_dependencies();
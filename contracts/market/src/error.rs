@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
-    DivideByZeroError, OverflowError, StdError,
+    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError, Decimal,
+    DivideByZeroError, OverflowError, StdError, Uint128,
 };
 use thiserror::Error;
 
@@ -10,7 +10,7 @@ pub enum ContractError {
     Std(#[from] StdError),
 
     #[error("{0}")]
-    Types(#[from] stone_types::ContractError),
+    Types(#[from] stone_types::errors::TypesError),
 
     #[error("{0}")]
     Overflow(#[from] OverflowError),
@@ -33,6 +33,9 @@ pub enum ContractError {
     #[error("Market is disabled")]
     MarketDisabled,
 
+    #[error("Market is paused")]
+    MarketPaused,
+
     #[error("Zero amount not allowed")]
     ZeroAmount,
 
@@ -42,12 +45,30 @@ pub enum ContractError {
     #[error("No funds sent")]
     NoFundsSent,
 
+    #[error("Possible duplicate supply: {amount} was already supplied at block {block_height} (set force to supply again)")]
+    PossibleDuplicate { block_height: u64, amount: Uint128 },
+
+    #[error("Nonce {nonce} was already used in the last 24 hours")]
+    DuplicateNonce { nonce: u64 },
+
+    #[error("Insufficient funds sent: required {required}, sent {sent}")]
+    InsufficientFundsSent { required: String, sent: String },
+
+    #[error("Sent funds do not match the requested amount: expected {expected}, sent {sent}")]
+    FundsMismatch { expected: String, sent: String },
+
+    #[error("Recipient amounts sum to {expected} but {sent} was sent")]
+    AmountMismatch { expected: String, sent: String },
+
     #[error("Borrow would exceed LTV limit: max {max_borrow}, requested {requested}")]
     ExceedsLtv {
         max_borrow: String,
         requested: String,
     },
 
+    #[error("Health factor after borrow too low: required {required}, actual {actual}")]
+    HealthFactorTooLow { required: Decimal, actual: Decimal },
+
     #[error("Insufficient collateral for withdrawal: health factor would be {health_factor}")]
     InsufficientCollateral { health_factor: String },
 
@@ -57,15 +78,90 @@ pub enum ContractError {
         requested: String,
     },
 
+    #[error("Requested {fee} fees {requested} exceed accrued {accrued}")]
+    ExceedsAccruedFees {
+        fee: String,
+        requested: Uint128,
+        accrued: Uint128,
+    },
+
+    #[error("Insufficient reserve: withdrawal would leave reserve ratio {reserve_ratio}, minimum {min_required}")]
+    InsufficientReserve {
+        reserve_ratio: Decimal,
+        min_required: Decimal,
+    },
+
+    #[error("Withdraw cooldown active: available at block {available_at_block}")]
+    WithdrawCooldownActive { available_at_block: u64 },
+
+    #[error("Cannot specify both amount and max_withdraw")]
+    ConflictingWithdrawParams,
+
     #[error("Supply cap exceeded: cap {cap}, would be {would_be}")]
     SupplyCapExceeded { cap: String, would_be: String },
 
-    #[error("Borrow cap exceeded: cap {cap}, would be {would_be}")]
-    BorrowCapExceeded { cap: String, would_be: String },
+    #[error("Borrow cap exceeded: cap {cap}, current debt {current}, requested {requested}")]
+    ExceedsBorrowCap {
+        cap: Uint128,
+        current: Uint128,
+        requested: Uint128,
+    },
+
+    #[error("Borrow amount too small: minimum {min}, provided {provided}")]
+    BorrowAmountTooSmall { min: Uint128, provided: Uint128 },
 
     #[error("Position is not liquidatable: health factor is {health_factor}")]
     NotLiquidatable { health_factor: String },
 
+    #[error("Cannot liquidate own position")]
+    SelfLiquidation,
+
+    #[error("Cannot refer own supply")]
+    SelfReferral,
+
+    #[error("Cannot sweep own dust debt")]
+    SelfSweep,
+
+    #[error("Position not eligible for dust sweep: debt {debt}, threshold {threshold}, health factor {health_factor}")]
+    NotEligibleForSweep {
+        debt: String,
+        threshold: String,
+        health_factor: String,
+    },
+
+    #[error("Collateral swap is not enabled for this market")]
+    CollateralSwapDisabled,
+
+    #[error("A collateral swap is already in progress")]
+    CollateralSwapInProgress,
+
+    #[error("Collateral swap slippage exceeded: min receive {min_receive}, received {received}")]
+    SwapSlippageExceeded {
+        min_receive: String,
+        received: String,
+    },
+
+    #[error("A repay with collateral is already in progress")]
+    CollateralRepayInProgress,
+
+    #[error("Insufficient collateral: available {available}, requested {requested}")]
+    InsufficientCollateralBalance {
+        available: String,
+        requested: String,
+    },
+
+    #[error("Insufficient supply shares: available {available}, requested {requested}")]
+    InsufficientShares {
+        available: String,
+        requested: String,
+    },
+
+    #[error("Router returned no {denom} to repay with")]
+    NoRepayProceeds { denom: String },
+
+    #[error("Repay amount too small to reduce debt: minimum {minimum_implied}")]
+    RepayAmountTooSmall { minimum_implied: Uint128 },
+
     #[error("No debt to repay")]
     NoDebt,
 
@@ -75,6 +171,9 @@ pub enum ContractError {
     #[error("No collateral to withdraw")]
     NoCollateral,
 
+    #[error("Address {address} is not whitelisted to deposit collateral")]
+    NotWhitelisted { address: String },
+
     #[error("LTV update cooldown not elapsed: {remaining_seconds} seconds remaining")]
     LtvCooldownNotElapsed { remaining_seconds: u64 },
 
@@ -87,6 +186,11 @@ pub enum ContractError {
     #[error("Curator fee exceeds maximum of 25%")]
     CuratorFeeExceedsMax,
 
+    #[error(
+        "Interest rate model produces a borrow rate of {rate} at current utilization (maximum 5)"
+    )]
+    InterestRateModelProducesExtremeRate { rate: Decimal },
+
     #[error("Total fees must be less than 100%: protocol {protocol_fee}, curator {curator_fee}")]
     TotalFeeExceedsMax {
         protocol_fee: String,
@@ -99,25 +203,21 @@ pub enum ContractError {
     #[error("Oracle query failed for {denom}: {reason}")]
     OracleError { denom: String, reason: String },
 
-    #[error("Oracle price is stale for {denom}: age={age_seconds}s, max={max_staleness}s")]
-    OraclePriceStale {
-        denom: String,
-        age_seconds: u64,
-        max_staleness: u64,
-    },
-
     #[error("Oracle price is zero for {denom}")]
     OracleZeroPrice { denom: String },
 
-    #[error("Oracle price timestamp is in the future for {denom}: updated_at={updated_at}, current={current}")]
-    OraclePriceFuture {
-        denom: String,
-        updated_at: u64,
-        current: u64,
-    },
+    #[error("Target time {target_time} is before last interest update {last_update}")]
+    TargetTimeBeforeLastUpdate { target_time: u64, last_update: u64 },
 
     #[error("Math overflow: {reason}")]
     MathOverflow { reason: String },
+
+    #[error("Liquidity index rebalance from {current} to {target} exceeds the maximum deviation of {max_deviation}")]
+    IndexRebalanceTooLarge {
+        current: Decimal,
+        target: Decimal,
+        max_deviation: Decimal,
+    },
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -1,9 +1,16 @@
-use cosmwasm_std::{BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128,
+};
 
 use crate::error::ContractError;
+use crate::health::query_price;
 use crate::interest::apply_accumulated_interest;
-use crate::state::{ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, CONFIG, PARAMS, STATE};
-use stone_types::MarketParamsUpdate;
+use crate::state::{
+    ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, COLLATERAL_WHITELIST, CONFIG, PARAMS, PAUSED,
+    PRIVILEGED_BORROWERS, PRIVILEGED_SUPPLY, STATE,
+};
+use stone_types::errors::TypesError;
+use stone_types::{MarketConfig, MarketParams, MarketParamsUpdate};
 
 /// 7 days in seconds (LTV update cooldown)
 pub const LTV_COOLDOWN_SECONDS: u64 = 604_800; // TODO don't hardcode - should be parameterisable and set on init
@@ -11,6 +18,13 @@ pub const LTV_COOLDOWN_SECONDS: u64 = 604_800; // TODO don't hardcode - should b
 /// Maximum LTV change per update (5%)
 pub const MAX_LTV_CHANGE: Decimal = Decimal::raw(50_000_000_000_000_000); // 0.05 // todo don;t hardcode, should be parameterisbable and set on init
 
+/// Largest liquidity index correction `RebalanceLiquidityIndex` may apply (0.0001)
+pub const MAX_INDEX_REBALANCE_DEVIATION: Decimal = Decimal::raw(100_000_000_000_000);
+
+/// Highest borrow rate a new interest rate model may produce at the market's
+/// current utilization (500% APR)
+pub const MAX_BORROW_RATE_AFTER_MODEL_UPDATE: Decimal = Decimal::raw(5_000_000_000_000_000_000);
+
 /// Update market parameters (curator only).
 pub fn execute_update_params(
     deps: DepsMut,
@@ -60,27 +74,29 @@ pub fn execute_update_params(
             });
         }
 
-        // Validate LTV bounds
+        // Validate LTV bounds (ordering against the liquidation threshold is
+        // checked with the rest of the params below)
         if new_ltv < Decimal::percent(1) || new_ltv > Decimal::percent(95) {
             return Err(ContractError::InvalidLtv);
         }
 
-        // Must be less than liquidation threshold
-        if new_ltv >= params.liquidation_threshold {
-            return Err(ContractError::InvalidLtv);
-        }
-
+        response = response
+            .add_attribute("previous_ltv", params.loan_to_value.to_string())
+            .add_attribute("new_ltv", new_ltv.to_string());
         params.loan_to_value = new_ltv;
         params.ltv_last_update = env.block.time.seconds();
-        response = response.add_attribute("new_ltv", new_ltv.to_string());
     }
 
-    // Update interest rate model (always allowed)
+    // Update interest rate model (always allowed, as long as it doesn't produce
+    // an extreme rate at the current utilization)
     if let Some(new_model) = updates.interest_rate_model {
         if !new_model.validate() {
-            return Err(ContractError::Types(
-                stone_types::ContractError::InvalidInterestRateModel,
-            ));
+            return Err(TypesError::InvalidInterestRateModel.into());
+        }
+        let utilization = STATE.load(deps.storage)?.utilization();
+        let rate = new_model.calculate_borrow_rate(utilization);
+        if rate > MAX_BORROW_RATE_AFTER_MODEL_UPDATE {
+            return Err(ContractError::InterestRateModelProducesExtremeRate { rate });
         }
         params.interest_rate_model = new_model;
         response = response.add_attribute("interest_rate_model", "updated");
@@ -98,53 +114,146 @@ pub fn execute_update_params(
                 curator_fee: new_fee.to_string(),
             });
         }
+        response = response
+            .add_attribute("previous_curator_fee", params.curator_fee.to_string())
+            .add_attribute("curator_fee", new_fee.to_string());
         params.curator_fee = new_fee;
-        response = response.add_attribute("curator_fee", new_fee.to_string());
     }
 
     // Update dust debt threshold (always allowed, capped at 10_000_000)
     if let Some(new_threshold) = updates.dust_debt_threshold {
         let max_dust_threshold = Uint128::new(10_000_000);
         if new_threshold > max_dust_threshold {
-            return Err(ContractError::Types(
-                stone_types::ContractError::DustDebtThresholdTooHigh {
-                    value: new_threshold.to_string(),
-                    max: max_dust_threshold.to_string(),
-                },
-            ));
+            return Err(ContractError::Types(TypesError::DustDebtThresholdTooHigh {
+                value: new_threshold.to_string(),
+                max: max_dust_threshold.to_string(),
+            }));
         }
         params.dust_debt_threshold = new_threshold;
         response = response.add_attribute("dust_debt_threshold", new_threshold.to_string());
     }
 
+    // Update close factor (always allowed, must be in (0, 1])
+    if let Some(new_close_factor) = updates.close_factor {
+        params.close_factor = new_close_factor;
+        response = response.add_attribute("close_factor", new_close_factor.to_string());
+
+        // A very small close factor lets liquidators repay too little per call
+        // to restore health, so flag it without rejecting
+        let liquidation_capacity = new_close_factor.checked_mul(params.liquidation_threshold)?;
+        if liquidation_capacity < Decimal::percent(10) {
+            response = response.add_attribute(
+                "close_factor_warning",
+                "may result in partial liquidations leaving unhealthy positions",
+            );
+        }
+    }
+
     // Update supply cap (always allowed)
     if let Some(new_cap) = updates.supply_cap {
+        response = response
+            .add_attribute("previous_supply_cap", cap_attribute(params.supply_cap))
+            .add_attribute("supply_cap", cap_attribute(new_cap));
         params.supply_cap = new_cap;
-        response = response.add_attribute(
-            "supply_cap",
-            new_cap.map(|c| c.to_string()).unwrap_or("none".to_string()),
-        );
     }
 
     // Update borrow cap (always allowed)
     if let Some(new_cap) = updates.borrow_cap {
+        response = response
+            .add_attribute("previous_borrow_cap", cap_attribute(params.borrow_cap))
+            .add_attribute("borrow_cap", cap_attribute(new_cap));
         params.borrow_cap = new_cap;
+    }
+
+    // Update minimum borrow amount (always allowed)
+    if let Some(min_borrow_amount) = updates.min_borrow_amount {
+        params.min_borrow_amount = min_borrow_amount;
+        response = response.add_attribute("min_borrow_amount", min_borrow_amount);
+    }
+
+    // Update minimum reserve ratio (always allowed, must be in [0, 0.5])
+    if let Some(min_reserve_ratio) = updates.min_reserve_ratio {
+        params.min_reserve_ratio = min_reserve_ratio;
+        response = response.add_attribute("min_reserve_ratio", min_reserve_ratio.to_string());
+    }
+
+    // Update supply-to-withdraw cooldown (always allowed)
+    if let Some(min_supply_blocks) = updates.min_supply_blocks {
+        params.min_supply_blocks = min_supply_blocks;
+        response = response.add_attribute("min_supply_blocks", min_supply_blocks.to_string());
+    }
+
+    // Update per-liquidation value cap (always allowed, must be non-zero)
+    if let Some(max_value) = updates.max_liquidation_value_usd {
+        params.max_liquidation_value_usd = max_value;
+        response = response.add_attribute(
+            "max_liquidation_value_usd",
+            max_value
+                .map(|v| v.to_string())
+                .unwrap_or("none".to_string()),
+        );
+    }
+
+    // Toggle the collateral depositor whitelist (always allowed)
+    if let Some(whitelist_enabled) = updates.collateral_whitelist_enabled {
+        params.collateral_whitelist_enabled = whitelist_enabled;
+        response = response.add_attribute(
+            "collateral_whitelist_enabled",
+            whitelist_enabled.to_string(),
+        );
+    }
+
+    // Update minimum post-borrow health factor (always allowed, must be at least 1)
+    if let Some(min_health_factor) = updates.min_post_borrow_health_factor {
+        params.min_post_borrow_health_factor = min_health_factor;
+        response = response.add_attribute(
+            "min_post_borrow_health_factor",
+            min_health_factor
+                .map(|v| v.to_string())
+                .unwrap_or("none".to_string()),
+        );
+    }
+
+    // Update flat borrow fee (always allowed, at most 1% of the borrow cap)
+    if let Some(fee) = updates.protocol_borrow_fee_flat {
+        params.protocol_borrow_fee_flat = fee;
+        response = response.add_attribute("protocol_borrow_fee_flat", fee);
+    }
+
+    // Toggle clearing of dust debt after repayments (always allowed)
+    if let Some(auto_clear_dust) = updates.auto_clear_dust {
+        params.auto_clear_dust = auto_clear_dust;
+        response = response.add_attribute("auto_clear_dust", auto_clear_dust.to_string());
+    }
+
+    // Replace the liquidation bonus tiers (always allowed, validated below)
+    if let Some(tiers) = updates.liquidation_bonus_by_health_factor {
+        params.liquidation_bonus_by_health_factor = tiers;
         response = response.add_attribute(
-            "borrow_cap",
-            new_cap.map(|c| c.to_string()).unwrap_or("none".to_string()),
+            "liquidation_bonus_by_health_factor",
+            bonus_tiers_attribute(&params.liquidation_bonus_by_health_factor),
         );
     }
 
+    // Update withdraw fee (always allowed, at most 2%)
+    if let Some(fee) = updates.withdraw_fee {
+        params.withdraw_fee = fee;
+        response = response.add_attribute("withdraw_fee", fee.to_string());
+    }
+
     // Update enabled status (always allowed)
     if let Some(enabled) = updates.enabled {
+        response = response
+            .add_attribute("previous_enabled", params.enabled.to_string())
+            .add_attribute("enabled", enabled.to_string());
         params.enabled = enabled;
-        response = response.add_attribute("enabled", enabled.to_string());
     }
 
+    params.validate()?;
     PARAMS.save(deps.storage, &params)?;
 
     // Add full parameter snapshot for indexer
-    response = response
+    response = with_dust_threshold_warning(response, &params)
         .add_attribute("final_ltv", params.loan_to_value.to_string())
         .add_attribute(
             "final_liquidation_threshold",
@@ -165,32 +274,308 @@ pub fn execute_update_params(
         )
         .add_attribute("final_protocol_fee", params.protocol_fee.to_string())
         .add_attribute("final_curator_fee", params.curator_fee.to_string())
+        .add_attribute("final_supply_cap", cap_attribute(params.supply_cap))
+        .add_attribute("final_borrow_cap", cap_attribute(params.borrow_cap))
+        .add_attribute("final_min_borrow_amount", params.min_borrow_amount)
         .add_attribute(
-            "final_supply_cap",
+            "final_min_reserve_ratio",
+            params.min_reserve_ratio.to_string(),
+        )
+        .add_attribute(
+            "final_min_supply_blocks",
+            params.min_supply_blocks.to_string(),
+        )
+        .add_attribute(
+            "final_max_liquidation_value_usd",
             params
-                .supply_cap
-                .map(|c| c.to_string())
+                .max_liquidation_value_usd
+                .map(|v| v.to_string())
                 .unwrap_or("none".to_string()),
         )
         .add_attribute(
-            "final_borrow_cap",
+            "final_collateral_whitelist_enabled",
+            params.collateral_whitelist_enabled.to_string(),
+        )
+        .add_attribute(
+            "final_min_post_borrow_health_factor",
             params
-                .borrow_cap
-                .map(|c| c.to_string())
+                .min_post_borrow_health_factor
+                .map(|v| v.to_string())
                 .unwrap_or("none".to_string()),
         )
+        .add_attribute(
+            "final_protocol_borrow_fee_flat",
+            params.protocol_borrow_fee_flat,
+        )
+        .add_attribute("final_auto_clear_dust", params.auto_clear_dust.to_string())
+        .add_attribute(
+            "final_liquidation_bonus_by_health_factor",
+            bonus_tiers_attribute(&params.liquidation_bonus_by_health_factor),
+        )
+        .add_attribute("final_withdraw_fee", params.withdraw_fee.to_string())
         .add_attribute("final_enabled", params.enabled.to_string())
         .add_attribute("final_is_mutable", params.is_mutable.to_string());
 
     Ok(response)
 }
 
+/// Flag a dust debt threshold above 10% of the borrow cap. Such markets are
+/// allowed but almost every position would be fully liquidatable.
+pub(crate) fn with_dust_threshold_warning(response: Response, params: &MarketParams) -> Response {
+    if params.has_high_dust_debt_threshold() {
+        response.add_attribute(
+            "dust_threshold_warning",
+            "threshold exceeds 10% of borrow cap",
+        )
+    } else {
+        response
+    }
+}
+
+/// Set the liquidity reserved for a privileged borrower (curator only).
+/// The privileged reserve grows or shrinks by the change in the borrower's limit;
+/// a zero limit removes the borrower.
+pub fn execute_set_privileged_borrower(
+    deps: DepsMut,
+    info: MessageInfo,
+    borrower: String,
+    limit: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.curator {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let borrower_addr = deps.api.addr_validate(&borrower)?;
+    let previous = PRIVILEGED_BORROWERS
+        .may_load(deps.storage, borrower_addr.as_str())?
+        .unwrap_or_default();
+
+    if limit.is_zero() {
+        PRIVILEGED_BORROWERS.remove(deps.storage, borrower_addr.as_str());
+    } else {
+        PRIVILEGED_BORROWERS.save(deps.storage, borrower_addr.as_str(), &limit)?;
+    }
+
+    let privileged_supply = PRIVILEGED_SUPPLY
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_sub(previous)?
+        .checked_add(limit)?;
+    PRIVILEGED_SUPPLY.save(deps.storage, &privileged_supply)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_privileged_borrower")
+        .add_attribute("borrower", borrower_addr)
+        .add_attribute("previous_limit", previous)
+        .add_attribute("limit", limit)
+        .add_attribute("privileged_supply", privileged_supply))
+}
+
+/// Add or remove collateral depositors from the whitelist (curator only).
+/// The list can be maintained while the whitelist is disabled.
+pub fn execute_set_collateral_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.curator {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let addresses = addresses
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<_>>>()?;
+    for address in &addresses {
+        if allowed {
+            COLLATERAL_WHITELIST.save(deps.storage, address.as_str(), &true)?;
+        } else {
+            COLLATERAL_WHITELIST.remove(deps.storage, address.as_str());
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_collateral_whitelist")
+        .add_attribute("allowed", allowed.to_string())
+        .add_attribute(
+            "addresses",
+            addresses
+                .iter()
+                .map(|a| a.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        ))
+}
+
+/// Pause the market (factory only).
+/// Blocks supply, collateral deposits and borrows while leaving withdrawals,
+/// repayments and liquidations available. Kept apart from `params.enabled` so
+/// the curator cannot lift it; only `Unpause {}` from the factory does.
+pub fn execute_pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    set_paused(deps, info, true)
+}
+
+/// Lift an emergency pause (factory only).
+pub fn execute_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    set_paused(deps, info, false)
+}
+
+fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("action", if paused { "pause" } else { "unpause" })
+        .add_attribute("sender", info.sender)
+        .add_attribute("paused", paused.to_string()))
+}
+
+/// Reject new supply, collateral and borrows while the factory has paused the market.
+pub(crate) fn ensure_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if PAUSED.may_load(storage)?.unwrap_or(false) {
+        return Err(ContractError::MarketPaused);
+    }
+    Ok(())
+}
+
+/// Permanently lock the LTV at its current value (factory only).
+/// Clears `is_mutable`; there is no way to make the market mutable again.
+pub fn execute_freeze_ltv(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let mut params = PARAMS.load(deps.storage)?;
+    if !params.is_mutable {
+        return Err(ContractError::MarketImmutable);
+    }
+    params.is_mutable = false;
+    PARAMS.save(deps.storage, &params)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze_ltv")
+        .add_attribute("locked_ltv", params.loan_to_value.to_string()))
+}
+
+/// Switch the collateral denom after a collateral token migration (factory only).
+/// Collateral balances are not touched: they are denominated in the new token
+/// from now on. The oracle must already serve a fresh price for the new denom.
+pub fn execute_update_collateral_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_denom: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    if new_denom == config.debt_denom {
+        return Err(TypesError::SameDenom.into());
+    }
+
+    // Fails if the oracle has no fresh, non-zero price for the new denom
+    query_price(deps.as_ref(), &env, &config.oracle_config, &new_denom)?;
+
+    let previous_denom = std::mem::replace(&mut config.collateral_denom, new_denom);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_collateral_denom")
+        .add_attribute("previous_collateral_denom", previous_denom)
+        .add_attribute("collateral_denom", config.collateral_denom))
+}
+
+/// Replace the protocol fee collector (factory only). Sent by the factory when a
+/// new collector accepts the role; fees claimed from now on go to the new address.
+pub fn execute_update_protocol_fee_collector(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_collector: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let new_collector = deps.api.addr_validate(&new_collector)?;
+    let previous_collector = std::mem::replace(&mut config.protocol_fee_collector, new_collector);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_protocol_fee_collector")
+        .add_attribute("previous_collector", previous_collector)
+        .add_attribute("protocol_fee_collector", config.protocol_fee_collector))
+}
+
+/// Set the liquidity index to `target_index` to correct accumulated rounding
+/// drift (factory only). Interest is applied first; the target must then be
+/// less than `MAX_INDEX_REBALANCE_DEVIATION` away from the current index.
+pub fn execute_rebalance_liquidity_index(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target_index: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let old_index = state.liquidity_index;
+    if old_index.abs_diff(target_index) >= MAX_INDEX_REBALANCE_DEVIATION {
+        return Err(ContractError::IndexRebalanceTooLarge {
+            current: old_index,
+            target: target_index,
+            max_deviation: MAX_INDEX_REBALANCE_DEVIATION,
+        });
+    }
+
+    state.liquidity_index = target_index;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "rebalance_liquidity_index")
+        .add_attribute("old_index", old_index.to_string())
+        .add_attribute("new_index", target_index.to_string()))
+}
+
 /// Accrue interest without performing any other action.
-pub fn execute_accrue_interest(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+/// Callable by anyone; a no-op if interest was already accrued this second.
+pub fn execute_accrue_interest(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // Already accrued this second, so skip the state write
+    let last_update = STATE.load(deps.storage)?.last_update;
+    if env.block.time.seconds().saturating_sub(last_update) < 1 {
+        return Ok(Response::new().add_attribute("action", "no_op"));
+    }
+
     crate::interest::apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
 
     // Load updated state to emit in events
-    let state = crate::state::STATE.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
 
     // Load accrued fees
     let accrued_protocol = ACCRUED_PROTOCOL_FEES
@@ -202,6 +587,7 @@ pub fn execute_accrue_interest(deps: DepsMut, env: Env) -> Result<Response, Cont
 
     Ok(Response::new()
         .add_attribute("action", "accrue_interest")
+        .add_attribute("caller", info.sender)
         .add_attribute("borrow_index", state.borrow_index.to_string())
         .add_attribute("liquidity_index", state.liquidity_index.to_string())
         .add_attribute("borrow_rate", state.borrow_rate.to_string())
@@ -253,9 +639,63 @@ pub fn execute_claim_fees(
     //    fee reductions equals the total claimed, preserving the invariant
     let available_liquidity = state.available_liquidity();
 
-    // Calculate how much can actually be claimed
-    // We need to ensure there's enough liquidity for all supplier withdrawals
-    // So we can't claim more than available_liquidity
+    let (claimable_protocol, claimable_curator) = claimable_fees(
+        available_liquidity,
+        accrued_protocol,
+        accrued_curator,
+        is_protocol_collector,
+        is_curator,
+    );
+
+    if claimable_protocol.is_zero() && claimable_curator.is_zero() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
+            "No fees available to claim (insufficient liquidity)",
+        )));
+    }
+
+    let messages = settle_fee_claim(
+        deps.storage,
+        &config,
+        (accrued_protocol, claimable_protocol),
+        (accrued_curator, claimable_curator),
+    )?;
+
+    let total_claimed = claimable_protocol.checked_add(claimable_curator)?;
+
+    let mut response = Response::new();
+    // Flag markets where liquidity can't cover both parties' accrued fees
+    if available_liquidity < accrued_protocol.checked_add(accrued_curator)? {
+        response = response.add_attribute("fee_coverage_partial", "true");
+    }
+
+    Ok(response
+        .add_messages(messages)
+        .add_attribute("action", "claim_fees")
+        .add_attribute("caller", info.sender)
+        .add_attribute("protocol_claimed", claimable_protocol)
+        .add_attribute("curator_claimed", claimable_curator)
+        .add_attribute("total_claimed", total_claimed)
+        .add_attribute(
+            "accrued_protocol_remaining",
+            accrued_protocol.saturating_sub(claimable_protocol),
+        )
+        .add_attribute(
+            "accrued_curator_remaining",
+            accrued_curator.saturating_sub(claimable_curator),
+        ))
+}
+
+/// Fees a `ClaimFees` caller with the given roles would receive.
+/// Claims can't exceed available liquidity, which is needed for supplier
+/// withdrawals. Protocol fees are served first; a caller holding both roles
+/// gets curator fees from whatever liquidity is left.
+pub(crate) fn claimable_fees(
+    available_liquidity: Uint128,
+    accrued_protocol: Uint128,
+    accrued_curator: Uint128,
+    is_protocol_collector: bool,
+    is_curator: bool,
+) -> (Uint128, Uint128) {
     let claimable_protocol = if is_protocol_collector {
         accrued_protocol.min(available_liquidity)
     } else {
@@ -263,87 +703,233 @@ pub fn execute_claim_fees(
     };
 
     let claimable_curator = if is_curator {
-        // Curator gets what's left after protocol claims (if both are the same caller, they get both)
         let remaining_liquidity = available_liquidity.saturating_sub(claimable_protocol);
         accrued_curator.min(remaining_liquidity)
     } else {
         Uint128::zero()
     };
 
-    if claimable_protocol.is_zero() && claimable_curator.is_zero() {
-        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
-            "No fees available to claim (insufficient liquidity)",
-        )));
-    }
+    (claimable_protocol, claimable_curator)
+}
 
-    // Update accrued fees state
-    if !claimable_protocol.is_zero() {
-        let new_accrued = accrued_protocol.saturating_sub(claimable_protocol);
-        if new_accrued.is_zero() {
-            ACCRUED_PROTOCOL_FEES.remove(deps.storage);
-        } else {
-            ACCRUED_PROTOCOL_FEES.save(deps.storage, &new_accrued)?;
-        }
-    }
+/// Claim exact amounts of accrued fees.
+/// Each requested amount needs the matching role (protocol fee collector or
+/// curator), must not exceed the accrued fees and, in total, must be covered by
+/// available liquidity. Unlike `ClaimFees`, nothing is reduced to fit.
+pub fn execute_claim_fees_partial(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    protocol_amount: Option<Uint128>,
+    curator_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
 
-    if !claimable_curator.is_zero() {
-        let new_accrued = accrued_curator.saturating_sub(claimable_curator);
-        if new_accrued.is_zero() {
-            ACCRUED_CURATOR_FEES.remove(deps.storage);
-        } else {
-            ACCRUED_CURATOR_FEES.save(deps.storage, &new_accrued)?;
-        }
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    let protocol_amount = protocol_amount.unwrap_or_default();
+    let curator_amount = curator_amount.unwrap_or_default();
+
+    if protocol_amount.is_zero() && curator_amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+    if (!protocol_amount.is_zero() && info.sender != config.protocol_fee_collector)
+        || (!curator_amount.is_zero() && info.sender != config.curator)
+    {
+        return Err(ContractError::Unauthorized);
     }
 
-    // Build transfer messages
-    let mut messages = vec![];
+    let accrued_protocol = ACCRUED_PROTOCOL_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let accrued_curator = ACCRUED_CURATOR_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
 
-    if !claimable_protocol.is_zero() {
-        messages.push(BankMsg::Send {
-            to_address: config.protocol_fee_collector.to_string(),
-            amount: vec![Coin {
-                denom: config.debt_denom.clone(),
-                amount: claimable_protocol,
-            }],
+    if protocol_amount > accrued_protocol {
+        return Err(ContractError::ExceedsAccruedFees {
+            fee: "protocol".to_string(),
+            requested: protocol_amount,
+            accrued: accrued_protocol,
+        });
+    }
+    if curator_amount > accrued_curator {
+        return Err(ContractError::ExceedsAccruedFees {
+            fee: "curator".to_string(),
+            requested: curator_amount,
+            accrued: accrued_curator,
         });
     }
 
-    if !claimable_curator.is_zero() {
-        messages.push(BankMsg::Send {
-            to_address: config.curator.to_string(),
-            amount: vec![Coin {
-                denom: config.debt_denom,
-                amount: claimable_curator,
-            }],
+    let total_claimed = protocol_amount.checked_add(curator_amount)?;
+    let available_liquidity = state.available_liquidity();
+    if total_claimed > available_liquidity {
+        return Err(ContractError::InsufficientLiquidity {
+            available: available_liquidity.to_string(),
+            requested: total_claimed.to_string(),
         });
     }
 
-    let total_claimed = claimable_protocol.checked_add(claimable_curator)?;
+    let messages = settle_fee_claim(
+        deps.storage,
+        &config,
+        (accrued_protocol, protocol_amount),
+        (accrued_curator, curator_amount),
+    )?;
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("action", "claim_fees")
+        .add_attribute("action", "claim_fees_partial")
         .add_attribute("caller", info.sender)
-        .add_attribute("protocol_claimed", claimable_protocol)
-        .add_attribute("curator_claimed", claimable_curator)
+        .add_attribute("protocol_claimed", protocol_amount)
+        .add_attribute("curator_claimed", curator_amount)
         .add_attribute("total_claimed", total_claimed)
         .add_attribute(
             "accrued_protocol_remaining",
-            accrued_protocol.saturating_sub(claimable_protocol),
+            accrued_protocol - protocol_amount,
         )
         .add_attribute(
             "accrued_curator_remaining",
-            accrued_curator.saturating_sub(claimable_curator),
+            accrued_curator - curator_amount,
         ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::state::STATE;
-    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::Uint128;
-    use stone_types::{
+/// Send accrued protocol fees to `recipient` (factory only).
+/// Meant for sunsetting a market: unlike `ClaimFees`, the amount is not capped by
+/// available liquidity, only by the debt tokens the market actually holds; any
+/// remainder stays accrued. Curator fees are untouched. The market holds no
+/// collateral on the protocol's behalf (liquidation protocol fees are paid out
+/// immediately), so only the debt asset is transferred.
+pub fn execute_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let accrued_protocol = ACCRUED_PROTOCOL_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if accrued_protocol.is_zero() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
+            "No protocol fees to withdraw",
+        )));
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.debt_denom)?
+        .amount;
+    let withdrawn = accrued_protocol.min(balance);
+    if withdrawn.is_zero() {
+        return Err(ContractError::InsufficientLiquidity {
+            available: balance.to_string(),
+            requested: accrued_protocol.to_string(),
+        });
+    }
+    let remaining = accrued_protocol - withdrawn;
+    if remaining.is_zero() {
+        ACCRUED_PROTOCOL_FEES.remove(deps.storage);
+    } else {
+        ACCRUED_PROTOCOL_FEES.save(deps.storage, &remaining)?;
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: config.debt_denom,
+                amount: withdrawn,
+            }],
+        })
+        .add_attribute("action", "emergency_withdraw")
+        .add_attribute("factory", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("protocol_withdrawn", withdrawn)
+        .add_attribute("protocol_remaining", remaining))
+}
+
+/// Deduct claimed fees from the accrued totals and build the transfers.
+/// Each fee is given as `(accrued, claimed)`; `claimed` must not exceed `accrued`.
+fn settle_fee_claim(
+    storage: &mut dyn Storage,
+    config: &MarketConfig,
+    (accrued_protocol, claimed_protocol): (Uint128, Uint128),
+    (accrued_curator, claimed_curator): (Uint128, Uint128),
+) -> StdResult<Vec<BankMsg>> {
+    let mut messages = vec![];
+
+    if !claimed_protocol.is_zero() {
+        let new_accrued = accrued_protocol.saturating_sub(claimed_protocol);
+        if new_accrued.is_zero() {
+            ACCRUED_PROTOCOL_FEES.remove(storage);
+        } else {
+            ACCRUED_PROTOCOL_FEES.save(storage, &new_accrued)?;
+        }
+        messages.push(BankMsg::Send {
+            to_address: config.protocol_fee_collector.to_string(),
+            amount: vec![Coin {
+                denom: config.debt_denom.clone(),
+                amount: claimed_protocol,
+            }],
+        });
+    }
+
+    if !claimed_curator.is_zero() {
+        let new_accrued = accrued_curator.saturating_sub(claimed_curator);
+        if new_accrued.is_zero() {
+            ACCRUED_CURATOR_FEES.remove(storage);
+        } else {
+            ACCRUED_CURATOR_FEES.save(storage, &new_accrued)?;
+        }
+        messages.push(BankMsg::Send {
+            to_address: config.curator.to_string(),
+            amount: vec![Coin {
+                denom: config.debt_denom.clone(),
+                amount: claimed_curator,
+            }],
+        });
+    }
+
+    Ok(messages)
+}
+
+/// Attribute value for an optional cap, `"none"` when uncapped.
+fn cap_attribute(cap: Option<Uint128>) -> String {
+    cap.map(|c| c.to_string()).unwrap_or("none".to_string())
+}
+
+/// Attribute value for liquidation bonus tiers as `threshold:bonus` pairs,
+/// `"none"` when there are no tiers.
+fn bonus_tiers_attribute(tiers: &[(Decimal, Decimal)]) -> String {
+    if tiers.is_empty() {
+        return "none".to_string();
+    }
+    tiers
+        .iter()
+        .map(|(threshold, bonus)| format!("{threshold}:{bonus}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::STATE;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{coins, Uint128};
+    use std::str::FromStr;
+    use stone_types::{
         InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleType,
     };
 
@@ -369,6 +955,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -384,6 +972,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: true, // Mutable market
             ltv_last_update: 0,
@@ -404,13 +1002,8 @@ mod tests {
         let info = message_info(&not_curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
             curator_fee: Some(Decimal::percent(10)),
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -427,13 +1020,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
             curator_fee: Some(Decimal::percent(20)),
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let res = execute_update_params(deps.as_mut(), env, info, updates).unwrap();
@@ -443,6 +1031,329 @@ mod tests {
         assert_eq!(params.curator_fee, Decimal::percent(20));
     }
 
+    #[test]
+    fn test_update_interest_rate_model_to_jump_rate() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let model = InterestRateModel::JumpRate {
+            base: Decimal::percent(2),
+            kink: Decimal::percent(80),
+            multiplier: Decimal::percent(10),
+            jump_multiplier: Decimal::percent(200),
+        };
+        let updates = MarketParamsUpdate {
+            interest_rate_model: Some(model.clone()),
+            ..Default::default()
+        };
+
+        let res = execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "interest_rate_model" && a.value == "updated"));
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.interest_rate_model, model);
+    }
+
+    #[test]
+    fn test_update_interest_rate_model_rejects_extreme_rate() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        // 95% utilization
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(1000);
+        state.total_debt_scaled = Uint128::new(950);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let curator = MockApi::default().addr_make("curator");
+        let model_update = |jump_multiplier: Decimal| MarketParamsUpdate {
+            interest_rate_model: Some(InterestRateModel::JumpRate {
+                base: Decimal::zero(),
+                kink: Decimal::percent(80),
+                multiplier: Decimal::percent(10),
+                jump_multiplier,
+            }),
+            ..close_factor_update(Decimal::percent(50))
+        };
+
+        // 8% + 15% * 5000% = 758% APR
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            model_update(Decimal::percent(5000)),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InterestRateModelProducesExtremeRate {
+                rate: Decimal::percent(758)
+            }
+        );
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.interest_rate_model, InterestRateModel::default());
+
+        // 8% + 15% * 300% = 53% APR
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            model_update(Decimal::percent(300)),
+        )
+        .unwrap();
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            params
+                .interest_rate_model
+                .calculate_borrow_rate(Decimal::percent(95)),
+            Decimal::percent(53)
+        );
+    }
+
+    #[test]
+    fn test_update_interest_rate_model_invalid_jump_rate() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let updates = MarketParamsUpdate {
+            interest_rate_model: Some(InterestRateModel::JumpRate {
+                base: Decimal::zero(),
+                kink: Decimal::percent(120), // > 100%
+                multiplier: Decimal::percent(10),
+                jump_multiplier: Decimal::percent(200),
+            }),
+            ..Default::default()
+        };
+
+        let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidInterestRateModel)
+        ));
+    }
+
+    fn close_factor_update(close_factor: Decimal) -> MarketParamsUpdate {
+        MarketParamsUpdate {
+            close_factor: Some(close_factor),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update_liquidation_bonus_tiers() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+        let tiers = vec![
+            (Decimal::percent(95), Decimal::percent(3)),
+            (Decimal::percent(80), Decimal::percent(10)),
+        ];
+
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            MarketParamsUpdate {
+                liquidation_bonus_by_health_factor: Some(tiers.clone()),
+                ..close_factor_update(Decimal::percent(50))
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.iter().any(|a| {
+            a.key == "liquidation_bonus_by_health_factor" && a.value == "0.95:0.03,0.8:0.1"
+        }));
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.liquidation_bonus_by_health_factor, tiers);
+
+        // Ascending thresholds are rejected
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            MarketParamsUpdate {
+                liquidation_bonus_by_health_factor: Some(tiers.into_iter().rev().collect()),
+                ..close_factor_update(Decimal::percent(50))
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidLiquidationBonusTiers { .. })
+        ));
+
+        // An empty list removes the tiers
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            MarketParamsUpdate {
+                liquidation_bonus_by_health_factor: Some(vec![]),
+                ..close_factor_update(Decimal::percent(50))
+            },
+        )
+        .unwrap();
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert!(params.liquidation_bonus_by_health_factor.is_empty());
+    }
+
+    #[test]
+    fn test_update_withdraw_fee() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+        let withdraw_fee_update = |fee: Decimal| MarketParamsUpdate {
+            withdraw_fee: Some(fee),
+            ..close_factor_update(Decimal::percent(50))
+        };
+
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            withdraw_fee_update(Decimal::percent(2)),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_withdraw_fee" && a.value == "0.02"));
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.withdraw_fee, Decimal::percent(2));
+
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            withdraw_fee_update(Decimal::permille(21)),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidWithdrawFee { .. })
+        ));
+    }
+
+    #[test]
+    fn test_update_close_factor() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            close_factor_update(Decimal::percent(40)),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "close_factor" && a.value == "0.4"));
+        assert!(!res
+            .attributes
+            .iter()
+            .any(|a| a.key == "close_factor_warning"));
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.close_factor, Decimal::percent(40));
+    }
+
+    #[test]
+    fn test_update_close_factor_boundaries() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+
+        // Zero is rejected
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            close_factor_update(Decimal::zero()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidCloseFactor { .. })
+        ));
+
+        // Above 100% is rejected
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            close_factor_update(Decimal::percent(101)),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidCloseFactor { .. })
+        ));
+
+        // Exactly 100% is allowed
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            close_factor_update(Decimal::one()),
+        )
+        .unwrap();
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.close_factor, Decimal::one());
+    }
+
+    #[test]
+    fn test_update_close_factor_warning() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+
+        // 10% * 85% liquidation threshold = 8.5% < 10%
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            close_factor_update(Decimal::percent(10)),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "close_factor_warning"
+                && a.value == "may result in partial liquidations leaving unhealthy positions"));
+
+        // 12% * 85% = 10.2%, no warning
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            close_factor_update(Decimal::percent(12)),
+        )
+        .unwrap();
+        assert!(!res
+            .attributes
+            .iter()
+            .any(|a| a.key == "close_factor_warning"));
+    }
+
     #[test]
     fn test_update_curator_fee_exceeds_max() {
         let mut deps = mock_dependencies();
@@ -453,13 +1364,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: Some(Decimal::percent(30)), // > 25%
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            curator_fee: Some(Decimal::percent(30)),
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -480,13 +1386,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
             curator_fee: Some(Decimal::percent(25)),
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -513,13 +1414,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
             curator_fee: Some(Decimal::percent(25)),
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -548,13 +1444,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: Some(Decimal::percent(75)), // 80% -> 75% = 5% change
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            loan_to_value: Some(Decimal::percent(75)),
+            ..Default::default()
         };
 
         let res = execute_update_params(deps.as_mut(), env.clone(), info, updates).unwrap();
@@ -581,12 +1472,7 @@ mod tests {
 
         let updates = MarketParamsUpdate {
             loan_to_value: Some(Decimal::percent(75)),
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -611,12 +1497,7 @@ mod tests {
 
         let updates = MarketParamsUpdate {
             loan_to_value: Some(Decimal::percent(75)),
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
@@ -639,68 +1520,656 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: Some(Decimal::percent(70)), // 80% -> 70% = 10% change > 5%
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            loan_to_value: Some(Decimal::percent(70)),
+            ..Default::default()
+        };
+
+        let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
+        assert!(matches!(err, ContractError::LtvChangeExceedsMax { .. }));
+    }
+
+    #[test]
+    fn test_update_enabled() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let updates = MarketParamsUpdate {
+            enabled: Some(false),
+            ..Default::default()
+        };
+
+        execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert!(!params.enabled);
+    }
+
+    #[test]
+    fn test_set_privileged_borrower_tracks_reserved_supply() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("curator"), &[]);
+        let alice = api.addr_make("alice").to_string();
+        let bob = api.addr_make("bob").to_string();
+
+        execute_set_privileged_borrower(
+            deps.as_mut(),
+            info.clone(),
+            alice.clone(),
+            Uint128::new(1000),
+        )
+        .unwrap();
+        execute_set_privileged_borrower(deps.as_mut(), info.clone(), bob, Uint128::new(500))
+            .unwrap();
+        assert_eq!(
+            PRIVILEGED_SUPPLY.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(1500)
+        );
+
+        // Lowering a limit shrinks the reserve by the difference
+        let res = execute_set_privileged_borrower(
+            deps.as_mut(),
+            info.clone(),
+            alice.clone(),
+            Uint128::new(200),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "previous_limit" && a.value == "1000"));
+        assert_eq!(
+            PRIVILEGED_SUPPLY.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(700)
+        );
+
+        // A zero limit removes the borrower
+        execute_set_privileged_borrower(deps.as_mut(), info, alice.clone(), Uint128::zero())
+            .unwrap();
+        assert!(!PRIVILEGED_BORROWERS.has(deps.as_ref().storage, &alice));
+        assert_eq!(
+            PRIVILEGED_SUPPLY.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(500)
+        );
+    }
+
+    #[test]
+    fn test_set_privileged_borrower_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("random"), &[]);
+        let err = execute_set_privileged_borrower(
+            deps.as_mut(),
+            info,
+            api.addr_make("alice").to_string(),
+            Uint128::new(1000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn test_set_collateral_whitelist() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let api = MockApi::default();
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let info = message_info(&api.addr_make("curator"), &[]);
+
+        let res = execute_set_collateral_whitelist(
+            deps.as_mut(),
+            info.clone(),
+            vec![alice.to_string(), bob.to_string()],
+            true,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "allowed" && a.value == "true"));
+        assert!(COLLATERAL_WHITELIST.has(deps.as_ref().storage, alice.as_str()));
+        assert!(COLLATERAL_WHITELIST.has(deps.as_ref().storage, bob.as_str()));
+
+        execute_set_collateral_whitelist(deps.as_mut(), info, vec![bob.to_string()], false)
+            .unwrap();
+        assert!(COLLATERAL_WHITELIST.has(deps.as_ref().storage, alice.as_str()));
+        assert!(!COLLATERAL_WHITELIST.has(deps.as_ref().storage, bob.as_str()));
+    }
+
+    #[test]
+    fn test_set_collateral_whitelist_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("random"), &[]);
+        let err = execute_set_collateral_whitelist(
+            deps.as_mut(),
+            info,
+            vec![api.addr_make("alice").to_string()],
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn test_pause_by_factory() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let factory = MockApi::default().addr_make("factory");
+        execute_pause(deps.as_mut(), message_info(&factory, &[])).unwrap();
+        let err = ensure_not_paused(deps.as_ref().storage).unwrap_err();
+        assert!(matches!(err, ContractError::MarketPaused));
+
+        // The curator re-enabling the market does not lift the pause
+        let curator = MockApi::default().addr_make("curator");
+        let updates = MarketParamsUpdate {
+            enabled: Some(true),
+            ..close_factor_update(Decimal::percent(50))
+        };
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates,
+        )
+        .unwrap();
+        let err = ensure_not_paused(deps.as_ref().storage).unwrap_err();
+        assert!(matches!(err, ContractError::MarketPaused));
+
+        // Only the factory can
+        let err = execute_unpause(deps.as_mut(), message_info(&curator, &[])).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        execute_unpause(deps.as_mut(), message_info(&factory, &[])).unwrap();
+        ensure_not_paused(deps.as_ref().storage).unwrap();
+    }
+
+    #[test]
+    fn test_pause_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        // Not even the curator can use the factory's emergency pause
+        let curator = MockApi::default().addr_make("curator");
+        let err = execute_pause(deps.as_mut(), message_info(&curator, &[])).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        ensure_not_paused(deps.as_ref().storage).unwrap();
+    }
+
+    #[test]
+    fn test_freeze_ltv() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let factory = MockApi::default().addr_make("factory");
+        let res = execute_freeze_ltv(deps.as_mut(), message_info(&factory, &[])).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "locked_ltv" && a.value == Decimal::percent(80).to_string()));
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert!(!params.is_mutable);
+        assert_eq!(params.loan_to_value, Decimal::percent(80));
+
+        // One-way: freezing again is rejected
+        let err = execute_freeze_ltv(deps.as_mut(), message_info(&factory, &[])).unwrap_err();
+        assert!(matches!(err, ContractError::MarketImmutable));
+    }
+
+    #[test]
+    fn test_update_ltv_after_freeze_fails() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let factory = MockApi::default().addr_make("factory");
+        execute_freeze_ltv(deps.as_mut(), message_info(&factory, &[])).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(LTV_COOLDOWN_SECONDS + 1);
+        let curator = MockApi::default().addr_make("curator");
+        let updates = MarketParamsUpdate {
+            loan_to_value: Some(Decimal::percent(78)),
+            ..Default::default()
+        };
+
+        let err = execute_update_params(deps.as_mut(), env, message_info(&curator, &[]), updates)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::MarketImmutable));
+    }
+
+    #[test]
+    fn test_freeze_ltv_unauthorized() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let curator = MockApi::default().addr_make("curator");
+        let err = execute_freeze_ltv(deps.as_mut(), message_info(&curator, &[])).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert!(params.is_mutable);
+    }
+
+    /// Oracle that only knows the migrated collateral token, "ustatom".
+    fn mock_migrated_collateral_oracle(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        updated_at: u64,
+    ) {
+        deps.querier.update_wasm(move |query| match query {
+            cosmwasm_std::WasmQuery::Smart { msg, .. } => {
+                let stone_types::OracleQueryMsg::Price { denom } =
+                    cosmwasm_std::from_json(msg).unwrap();
+                if denom != "ustatom" {
+                    return cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Err(
+                        format!("no price for {denom}"),
+                    ));
+                }
+                let response = stone_types::PriceResponse {
+                    denom,
+                    price: Decimal::from_ratio(12u128, 1u128),
+                    updated_at,
+                };
+                cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(
+                    cosmwasm_std::to_json_binary(&response).unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    #[test]
+    fn test_update_collateral_denom() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+        let env = mock_env();
+        mock_migrated_collateral_oracle(&mut deps, env.block.time.seconds());
+
+        let api = MockApi::default();
+        let factory = api.addr_make("factory");
+        let user = api.addr_make("user");
+        crate::state::COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1_000))
+            .unwrap();
+
+        let res = execute_update_collateral_denom(
+            deps.as_mut(),
+            env,
+            message_info(&factory, &[]),
+            "ustatom".to_string(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "previous_collateral_denom" && a.value == "uatom"));
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.collateral_denom, "ustatom");
+        // Balances carry over unchanged
+        assert_eq!(
+            crate::state::COLLATERAL
+                .load(deps.as_ref().storage, user.as_str())
+                .unwrap(),
+            Uint128::new(1_000)
+        );
+    }
+
+    #[test]
+    fn test_update_collateral_denom_rejections() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+        let env = mock_env();
+        mock_migrated_collateral_oracle(&mut deps, env.block.time.seconds() - 301);
+
+        let api = MockApi::default();
+        let factory = api.addr_make("factory");
+        let curator = api.addr_make("curator");
+
+        let err = execute_update_collateral_denom(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&curator, &[]),
+            "ustatom".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        let err = execute_update_collateral_denom(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&factory, &[]),
+            "uusdc".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Types(TypesError::SameDenom));
+
+        // No oracle feed for the new denom
+        let err = execute_update_collateral_denom(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&factory, &[]),
+            "uunknown".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OracleError { .. }));
+
+        // Feed exists but is stale
+        let err = execute_update_collateral_denom(
+            deps.as_mut(),
+            env,
+            message_info(&factory, &[]),
+            "ustatom".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::OraclePriceStale { .. })
+        ));
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.collateral_denom, "uatom");
+    }
+
+    #[test]
+    fn test_update_protocol_fee_collector() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let api = MockApi::default();
+        let factory = api.addr_make("factory");
+        let curator = api.addr_make("curator");
+        let new_collector = api.addr_make("new_collector");
+
+        let err = execute_update_protocol_fee_collector(
+            deps.as_mut(),
+            message_info(&curator, &[]),
+            new_collector.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        let res = execute_update_protocol_fee_collector(
+            deps.as_mut(),
+            message_info(&factory, &[]),
+            new_collector.to_string(),
+        )
+        .unwrap();
+        assert!(res.attributes.iter().any(
+            |a| a.key == "previous_collector" && a.value == api.addr_make("collector").as_str()
+        ));
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.protocol_fee_collector, new_collector);
+    }
+
+    #[test]
+    fn test_rebalance_liquidity_index_within_deviation() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.liquidity_index = Decimal::from_str("1.05").unwrap();
+        state.last_update = env.block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let factory = MockApi::default().addr_make("factory");
+        let target = Decimal::from_str("1.05009").unwrap();
+
+        let err = execute_rebalance_liquidity_index(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&MockApi::default().addr_make("curator"), &[]),
+            target,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        let res = execute_rebalance_liquidity_index(
+            deps.as_mut(),
+            env,
+            message_info(&factory, &[]),
+            target,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "old_index" && a.value == "1.05"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "new_index" && a.value == "1.05009"));
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.liquidity_index, target);
+    }
+
+    #[test]
+    fn test_rebalance_liquidity_index_rejects_large_deviation() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.last_update = env.block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let factory = MockApi::default().addr_make("factory");
+
+        // Exactly the maximum deviation, in either direction, is rejected
+        for target in ["1.0001", "0.9999", "1.5"] {
+            let target = Decimal::from_str(target).unwrap();
+            let err = execute_rebalance_liquidity_index(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&factory, &[]),
+                target,
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::IndexRebalanceTooLarge {
+                    current: Decimal::one(),
+                    target,
+                    max_deviation: MAX_INDEX_REBALANCE_DEVIATION,
+                }
+            );
+        }
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.liquidity_index, Decimal::one());
+    }
+
+    #[test]
+    fn test_update_caps() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let updates = MarketParamsUpdate {
+            supply_cap: Some(Some(Uint128::new(1000000))),
+            borrow_cap: Some(Some(Uint128::new(500000))),
+            ..Default::default()
+        };
+
+        execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.supply_cap, Some(Uint128::new(1000000)));
+        assert_eq!(params.borrow_cap, Some(Uint128::new(500000)));
+    }
+
+    #[test]
+    fn test_update_params_emits_previous_values() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let updates = MarketParamsUpdate {
+            curator_fee: Some(Decimal::percent(10)),
+            supply_cap: Some(Some(Uint128::new(1000000))),
+            ..Default::default()
+        };
+
+        let res = execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+        let attr = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+        };
+
+        assert_eq!(
+            attr("previous_curator_fee"),
+            Some(Decimal::percent(5).to_string())
+        );
+        assert_eq!(attr("curator_fee"), Some(Decimal::percent(10).to_string()));
+        assert_eq!(attr("previous_supply_cap"), Some("none".to_string()));
+        assert_eq!(attr("supply_cap"), Some("1000000".to_string()));
+
+        // Fields that were not updated emit no previous value
+        assert_eq!(attr("previous_borrow_cap"), None);
+        assert_eq!(attr("previous_enabled"), None);
+    }
+
+    #[test]
+    fn test_update_min_borrow_amount() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+
+        let env = mock_env();
+        let curator = MockApi::default().addr_make("curator");
+        let info = message_info(&curator, &[]);
+
+        let updates = MarketParamsUpdate {
+            min_borrow_amount: Some(Uint128::new(1000)),
+            ..Default::default()
         };
 
-        let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
-        assert!(matches!(err, ContractError::LtvChangeExceedsMax { .. }));
+        let res = execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_min_borrow_amount" && a.value == "1000"));
+
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.min_borrow_amount, Uint128::new(1000));
     }
 
     #[test]
-    fn test_update_enabled() {
+    fn test_update_min_reserve_ratio() {
         let mut deps = mock_dependencies();
         setup_mutable_market(&mut deps);
 
-        let env = mock_env();
         let curator = MockApi::default().addr_make("curator");
-        let info = message_info(&curator, &[]);
-
-        let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: Some(false),
+        let updates = |min_reserve_ratio| MarketParamsUpdate {
+            min_reserve_ratio: Some(min_reserve_ratio),
+            ..Default::default()
         };
 
-        execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates(Decimal::percent(60)),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::InvalidMinReserveRatio { .. })
+        ));
+
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates(Decimal::percent(5)),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_min_reserve_ratio" && a.value == "0.05"));
 
         let params = PARAMS.load(deps.as_ref().storage).unwrap();
-        assert!(!params.enabled);
+        assert_eq!(params.min_reserve_ratio, Decimal::percent(5));
     }
 
     #[test]
-    fn test_update_caps() {
+    fn test_update_max_liquidation_value_usd() {
         let mut deps = mock_dependencies();
         setup_mutable_market(&mut deps);
 
-        let env = mock_env();
         let curator = MockApi::default().addr_make("curator");
-        let info = message_info(&curator, &[]);
-
-        let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: None,
-            curator_fee: None,
-            supply_cap: Some(Some(Uint128::new(1000000))),
-            borrow_cap: Some(Some(Uint128::new(500000))),
-            enabled: None,
+        let updates = |max_value| MarketParamsUpdate {
+            max_liquidation_value_usd: Some(max_value),
+            ..Default::default()
         };
 
-        execute_update_params(deps.as_mut(), env, info, updates).unwrap();
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates(Some(Decimal::from_ratio(50_000u128, 1u128))),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_max_liquidation_value_usd" && a.value == "50000"));
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            params.max_liquidation_value_usd,
+            Some(Decimal::from_ratio(50_000u128, 1u128))
+        );
+
+        // A zero cap would block every liquidation
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates(Some(Decimal::zero())),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Types(TypesError::InvalidMaxLiquidationValue)
+        );
 
+        // Removing the cap
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            updates(None),
+        )
+        .unwrap();
         let params = PARAMS.load(deps.as_ref().storage).unwrap();
-        assert_eq!(params.supply_cap, Some(Uint128::new(1000000)));
-        assert_eq!(params.borrow_cap, Some(Uint128::new(500000)));
+        assert_eq!(params.max_liquidation_value_usd, None);
     }
 
     #[test]
@@ -713,13 +2182,8 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
             dust_debt_threshold: Some(Uint128::new(5_000_000)),
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            ..Default::default()
         };
 
         let res = execute_update_params(deps.as_mut(), env, info, updates).unwrap();
@@ -742,26 +2206,95 @@ mod tests {
         let info = message_info(&curator, &[]);
 
         let updates = MarketParamsUpdate {
-            loan_to_value: None,
-            interest_rate_model: None,
-            dust_debt_threshold: Some(Uint128::new(10_000_001)), // > 10M max
-            curator_fee: None,
-            supply_cap: None,
-            borrow_cap: None,
-            enabled: None,
+            dust_debt_threshold: Some(Uint128::new(10_000_001)),
+            ..Default::default()
         };
 
         let err = execute_update_params(deps.as_mut(), env, info, updates).unwrap_err();
         assert!(
             matches!(
                 err,
-                ContractError::Types(stone_types::ContractError::DustDebtThresholdTooHigh { .. })
+                ContractError::Types(TypesError::DustDebtThresholdTooHigh { .. })
             ),
             "Expected DustDebtThresholdTooHigh error, got {:?}",
             err
         );
     }
 
+    fn dust_and_cap_update(dust: u128, borrow_cap: u128) -> MarketParamsUpdate {
+        MarketParamsUpdate {
+            dust_debt_threshold: Some(Uint128::new(dust)),
+            borrow_cap: Some(Some(Uint128::new(borrow_cap))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update_dust_debt_threshold_warns_above_tenth_of_borrow_cap() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+        let curator = MockApi::default().addr_make("curator");
+
+        let warning = |res: &Response| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == "dust_threshold_warning")
+                .map(|a| a.value.clone())
+        };
+
+        // Exactly 10% of the cap is not flagged
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            dust_and_cap_update(1_000, 10_000),
+        )
+        .unwrap();
+        assert_eq!(warning(&res), None);
+
+        let res = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            dust_and_cap_update(1_001, 10_000),
+        )
+        .unwrap();
+        assert_eq!(
+            warning(&res),
+            Some("threshold exceeds 10% of borrow cap".to_string())
+        );
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(params.dust_debt_threshold, Uint128::new(1_001));
+    }
+
+    #[test]
+    fn test_update_dust_debt_threshold_above_borrow_cap_rejected() {
+        let mut deps = mock_dependencies();
+        setup_mutable_market(&mut deps);
+        let curator = MockApi::default().addr_make("curator");
+
+        let err = execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            dust_and_cap_update(10_001, 10_000),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Types(TypesError::DustDebtThresholdTooHigh { .. })
+        ));
+
+        // The threshold may equal the cap
+        execute_update_params(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&curator, &[]),
+            dust_and_cap_update(10_000, 10_000),
+        )
+        .unwrap();
+    }
+
     // ============================================================================
     // Claim Fees Tests
     // ============================================================================
@@ -790,6 +2323,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -805,6 +2340,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -995,25 +2540,374 @@ mod tests {
             .any(|a| a.key == "accrued_curator_remaining" && a.value == "500"));
     }
 
+    #[test]
+    fn test_claim_fees_flags_partial_coverage() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        // 1200 available: covers the protocol fees, but not both parties' fees
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(8800);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let mut env = mock_env();
+        // Set time to match state creation time (1000) to prevent interest accrual
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let collector = MockApi::default().addr_make("collector");
+        let res = execute_claim_fees(deps.as_mut(), env, message_info(&collector, &[])).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "fee_coverage_partial" && a.value == "true"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "protocol_claimed" && a.value == "1000"));
+    }
+
+    #[test]
+    fn test_claim_fees_full_coverage_not_flagged() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        let mut env = mock_env();
+        // Set time to match state creation time (1000) to prevent interest accrual
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let collector = MockApi::default().addr_make("collector");
+        let res = execute_claim_fees(deps.as_mut(), env, message_info(&collector, &[])).unwrap();
+        assert!(!res
+            .attributes
+            .iter()
+            .any(|a| a.key == "fee_coverage_partial"));
+    }
+
+    fn claim_partial(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        caller: &str,
+        protocol_amount: Option<u128>,
+        curator_amount: Option<u128>,
+    ) -> Result<Response, ContractError> {
+        let mut env = mock_env();
+        // Set time to match state creation time (1000) to prevent interest accrual
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let info = message_info(&MockApi::default().addr_make(caller), &[]);
+        execute_claim_fees_partial(
+            deps.as_mut(),
+            env,
+            info,
+            protocol_amount.map(Uint128::new),
+            curator_amount.map(Uint128::new),
+        )
+    }
+
+    #[test]
+    fn test_claim_fees_partial_protocol() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        let res = claim_partial(&mut deps, "collector", Some(300), None).unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: MockApi::default().addr_make("collector").to_string(),
+                amount: vec![Coin {
+                    denom: "uusdc".to_string(),
+                    amount: Uint128::new(300),
+                }],
+            })
+        );
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(700)
+        );
+        assert_eq!(
+            ACCRUED_CURATOR_FEES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(500)
+        );
+    }
+
+    #[test]
+    fn test_claim_fees_partial_curator() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        // Claiming the exact accrued amount clears it
+        let res = claim_partial(&mut deps, "curator", None, Some(500)).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "curator_claimed" && a.value == "500"));
+        assert!(ACCRUED_CURATOR_FEES
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+
+        // The curator cannot claim protocol fees
+        let err = claim_partial(&mut deps, "curator", Some(100), None).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn test_claim_fees_partial_exceeds_accrued() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        let err = claim_partial(&mut deps, "collector", Some(1001), None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExceedsAccruedFees {
+                fee: "protocol".to_string(),
+                requested: Uint128::new(1001),
+                accrued: Uint128::new(1000),
+            }
+        );
+
+        // Nothing was claimed
+        assert_eq!(
+            ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(1000)
+        );
+    }
+
+    #[test]
+    fn test_claim_fees_partial_exceeds_liquidity() {
+        let mut deps = mock_dependencies();
+        // Accrued protocol fees above the 5000 available liquidity
+        setup_market_with_fees(&mut deps, Uint128::new(8000), Uint128::zero());
+
+        let err = claim_partial(&mut deps, "collector", Some(6000), None).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientLiquidity { .. }));
+
+        claim_partial(&mut deps, "collector", Some(5000), None).unwrap();
+    }
+
+    #[test]
+    fn test_emergency_withdraw_requires_factory() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
+
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let api = MockApi::default();
+
+        for sender in ["collector", "curator"] {
+            let err = execute_emergency_withdraw(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&api.addr_make(sender), &[]),
+                api.addr_make("treasury").to_string(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized);
+        }
+
+        let accrued = ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap();
+        assert_eq!(accrued, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_emergency_withdraw_bypasses_liquidity() {
+        let mut deps = mock_dependencies();
+        // Accrued protocol fees above the 5000 available liquidity
+        setup_market_with_fees(&mut deps, Uint128::new(8000), Uint128::new(500));
+
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        // The market holds enough debt tokens to pay the fees in full
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(9000, "uusdc"));
+        let api = MockApi::default();
+        let factory = api.addr_make("factory");
+        let treasury = api.addr_make("treasury");
+
+        let res = execute_emergency_withdraw(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&factory, &[]),
+            treasury.to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: treasury.to_string(),
+                amount: vec![Coin {
+                    denom: "uusdc".to_string(),
+                    amount: Uint128::new(8000),
+                }],
+            })
+        );
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "emergency_withdraw"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "factory" && a.value == factory.as_str()));
+
+        // Protocol fees are cleared, curator fees are left for the curator
+        assert!(ACCRUED_PROTOCOL_FEES
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+        let curator_remaining = ACCRUED_CURATOR_FEES.load(deps.as_ref().storage).unwrap();
+        assert_eq!(curator_remaining, Uint128::new(500));
+
+        // Nothing left to withdraw
+        let err = execute_emergency_withdraw(
+            deps.as_mut(),
+            env,
+            message_info(&factory, &[]),
+            treasury.to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+    }
+
+    #[test]
+    fn test_emergency_withdraw_capped_at_balance() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::new(8000), Uint128::new(500));
+
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let api = MockApi::default();
+        let factory = api.addr_make("factory");
+        let treasury = api.addr_make("treasury");
+
+        // An empty market has nothing to send
+        let err = execute_emergency_withdraw(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&factory, &[]),
+            treasury.to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientLiquidity { .. }));
+
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(5000, "uusdc"));
+        let res = execute_emergency_withdraw(
+            deps.as_mut(),
+            env,
+            message_info(&factory, &[]),
+            treasury.to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: treasury.to_string(),
+                amount: coins(5000, "uusdc"),
+            })
+        );
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "protocol_remaining" && a.value == "3000"));
+
+        // The unpaid part stays accrued
+        let accrued = ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap();
+        assert_eq!(accrued, Uint128::new(3000));
+    }
+
     #[test]
     fn test_accrue_interest_emits_accrued_fees() {
         let mut deps = mock_dependencies();
         setup_market_with_fees(&mut deps, Uint128::new(1000), Uint128::new(500));
 
-        // Set time to the same as state creation to prevent interest accrual
+        // One year after state creation, at 50% utilization
         let mut env = mock_env();
-        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000); // Same as state.created_at
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000 + 31_536_000);
+
+        let res = execute_accrue_interest(
+            deps.as_mut(),
+            env,
+            message_info(&MockApi::default().addr_make("anyone"), &[]),
+        )
+        .unwrap();
+
+        // Should emit the accrued fees now in storage
+        let accrued_protocol = ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap();
+        let accrued_curator = ACCRUED_CURATOR_FEES.load(deps.as_ref().storage).unwrap();
+        assert!(accrued_protocol > Uint128::new(1000));
+        assert!(accrued_curator > Uint128::new(500));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "accrued_protocol_fees" && a.value == accrued_protocol.to_string()));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "accrued_curator_fees" && a.value == accrued_curator.to_string()));
+    }
 
-        let res = execute_accrue_interest(deps.as_mut(), env).unwrap();
+    #[test]
+    fn test_accrue_interest_is_permissionless() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::zero(), Uint128::zero());
+
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(2000);
+        let random = MockApi::default().addr_make("random");
 
-        // Should emit accrued fees in attributes (fees unchanged since no time elapsed)
+        let res = execute_accrue_interest(deps.as_mut(), env, message_info(&random, &[])).unwrap();
         assert!(res
             .attributes
             .iter()
-            .any(|a| a.key == "accrued_protocol_fees" && a.value == "1000"));
+            .any(|a| a.key == "action" && a.value == "accrue_interest"));
         assert!(res
             .attributes
             .iter()
-            .any(|a| a.key == "accrued_curator_fees" && a.value == "500"));
+            .any(|a| a.key == "caller" && a.value == random.as_str()));
+    }
+
+    #[test]
+    fn test_accrue_interest_same_second_is_no_op() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::zero(), Uint128::zero());
+        let before = STATE.load(deps.as_ref().storage).unwrap();
+
+        // Same second as the last update
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000);
+        let info = message_info(&MockApi::default().addr_make("random"), &[]);
+
+        let res = execute_accrue_interest(deps.as_mut(), env, info).unwrap();
+        assert_eq!(res.attributes.len(), 1);
+        assert_eq!(res.attributes[0].key, "action");
+        assert_eq!(res.attributes[0].value, "no_op");
+        assert_eq!(STATE.load(deps.as_ref().storage).unwrap(), before);
+    }
+
+    #[test]
+    fn test_accrue_interest_updates_state() {
+        let mut deps = mock_dependencies();
+        setup_market_with_fees(&mut deps, Uint128::zero(), Uint128::zero());
+
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1000 + 31_536_000);
+        let info = message_info(&MockApi::default().addr_make("random"), &[]);
+
+        execute_accrue_interest(deps.as_mut(), env, info).unwrap();
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.last_update, 1000 + 31_536_000);
+        assert!(state.borrow_index > Decimal::one());
+        assert!(state.liquidity_index > Decimal::one());
+        // Debt grows faster than supply, so the borrow index leads
+        assert!(state.borrow_index > state.liquidity_index);
     }
 }
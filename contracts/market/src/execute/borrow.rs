@@ -1,9 +1,21 @@
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage,
+    Uint128,
+};
 
 use crate::error::ContractError;
-use crate::health::check_borrow_allowed;
-use crate::interest::apply_accumulated_interest;
-use crate::state::{CONFIG, DEBTS, PARAMS, STATE};
+use crate::execute::ensure_not_paused;
+use crate::health::{calculate_position_health, check_borrow_allowed_with_config};
+use crate::interest::{
+    apply_accumulated_interest_with_params, calculate_current_rates_with_params, calculate_rates,
+    simulate_accumulated_interest,
+};
+use crate::math256::u128_to_decimal256;
+use crate::state::{
+    ACCRUED_PROTOCOL_FEES, COLLATERAL, CONFIG, DEBTS, PARAMS, PRIVILEGED_BORROWERS,
+    PRIVILEGED_SUPPLY, STATE,
+};
+use stone_types::{BorrowPreviewResponse, MarketParams, MarketState};
 
 /// Borrow debt asset against collateral.
 pub fn execute_borrow(
@@ -13,46 +25,32 @@ pub fn execute_borrow(
     amount: Uint128,
     recipient: Option<String>,
 ) -> Result<Response, ContractError> {
+    // Config and params are loaded once and passed down
     let config = CONFIG.load(deps.storage)?;
     let params = PARAMS.load(deps.storage)?;
 
-    if !params.enabled {
-        return Err(ContractError::MarketDisabled);
-    }
-
-    if amount.is_zero() {
-        return Err(ContractError::ZeroAmount);
-    }
-
     // Apply accumulated interest (fees are accrued to state, not sent immediately)
-    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+    apply_accumulated_interest_with_params(deps.storage, &params, env.block.time.seconds())?;
 
     let state = STATE.load(deps.storage)?;
     let user = info.sender.as_str();
 
-    // Check available liquidity
-    let available = state.available_liquidity();
-    if amount > available {
-        return Err(ContractError::InsufficientLiquidity {
-            available: available.to_string(),
-            requested: amount.to_string(),
-        });
-    }
-
-    // Check borrow cap
-    let current_debt = state.total_debt();
-    if let Some(cap) = params.borrow_cap {
-        let would_be = current_debt.checked_add(amount)?;
-        if would_be > cap {
-            return Err(ContractError::BorrowCapExceeded {
-                cap: cap.to_string(),
-                would_be: would_be.to_string(),
-            });
-        }
+    // Check market status, amount, liquidity and borrow cap
+    let liquidity = borrowable_liquidity(deps.storage, &state, user)?;
+    check_borrow_limits(deps.storage, &params, &state, amount, liquidity.total())?;
+
+    // Without collateral the LTV check can only fail; bail out before it spends
+    // two oracle queries (one per denom) on the doomed attempt
+    if COLLATERAL
+        .may_load(deps.storage, user)?
+        .unwrap_or_default()
+        .is_zero()
+    {
+        return Err(ContractError::NoCollateral);
     }
 
     // Check LTV constraint
-    check_borrow_allowed(deps.as_ref(), &env, user, amount)?;
+    check_borrow_allowed_with_config(deps.as_ref(), &env, user, amount, &config, &params)?;
 
     // Calculate scaled debt amount: scaled = ceil(amount / borrow_index)
     // Use ceiling to ensure recorded debt >= actual borrowed amount (C-1 fix)
@@ -68,18 +66,37 @@ pub fn execute_borrow(
     state.total_debt_scaled = state.total_debt_scaled.checked_add(scaled_amount)?;
     STATE.save(deps.storage, &state)?;
 
+    // Whatever the general pool can't cover comes out of the privileged reserve
+    let reserve_used = amount.saturating_sub(liquidity.general);
+    if !reserve_used.is_zero() {
+        let limit = PRIVILEGED_BORROWERS.load(deps.storage, user)?;
+        PRIVILEGED_BORROWERS.save(deps.storage, user, &(limit - reserve_used))?;
+        let reserved = PRIVILEGED_SUPPLY.load(deps.storage)?;
+        PRIVILEGED_SUPPLY.save(deps.storage, &(reserved - reserve_used))?;
+    }
+
     // Determine and validate recipient
     let recipient_addr = match recipient {
         Some(addr) => deps.api.addr_validate(&addr)?,
         None => info.sender.clone(),
     };
 
+    // The flat fee stays in the market as claimable protocol fees; the full
+    // amount is still recorded as debt
+    let borrow_fee = params.protocol_borrow_fee_flat;
+    if !borrow_fee.is_zero() {
+        let accrued = ACCRUED_PROTOCOL_FEES
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        ACCRUED_PROTOCOL_FEES.save(deps.storage, &accrued.checked_add(borrow_fee)?)?;
+    }
+
     // Create transfer message
     let transfer_msg = BankMsg::Send {
         to_address: recipient_addr.to_string(),
         amount: vec![Coin {
             denom: config.debt_denom,
-            amount,
+            amount: amount - borrow_fee,
         }],
     };
 
@@ -89,9 +106,9 @@ pub fn execute_borrow(
     let utilization = state.utilization();
 
     // Calculate current rates based on post-transaction state
-    let (borrow_rate, liquidity_rate) = crate::interest::calculate_current_rates(deps.storage)?;
+    let (borrow_rate, liquidity_rate) = calculate_current_rates_with_params(deps.storage, &params)?;
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_message(transfer_msg)
         .add_attribute("action", "borrow")
         .add_attribute("borrower", info.sender)
@@ -104,13 +121,207 @@ pub fn execute_borrow(
         .add_attribute("liquidity_rate", liquidity_rate.to_string())
         .add_attribute("total_supply", total_supply)
         .add_attribute("total_debt", total_debt)
-        .add_attribute("utilization", utilization.to_string()))
+        .add_attribute("utilization", utilization.to_string());
+
+    if !reserve_used.is_zero() {
+        response = response.add_attribute("reserve_used", reserve_used);
+    }
+
+    if !borrow_fee.is_zero() {
+        response = response.add_attribute("borrow_fee", borrow_fee);
+    }
+
+    Ok(response)
+}
+
+/// Preview the market after the sender borrows `amount`, without borrowing.
+/// Anything that would make the borrow fail (market limits, no collateral, LTV)
+/// is reported with `eligible = false` in the `BorrowPreviewResponse` returned
+/// as the response data. Nothing is written, not even accrued interest.
+pub fn execute_borrow_preview(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let preview = preview_borrow(deps.as_ref(), &env, info.sender.as_str(), amount)?;
+
+    let mut response = Response::new()
+        .set_data(to_json_binary(&preview)?)
+        .add_attribute("action", "borrow_preview")
+        .add_attribute("borrower", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("eligible", preview.eligible.to_string())
+        .add_attribute("new_utilization", preview.new_utilization.to_string())
+        .add_attribute("new_borrow_rate", preview.new_borrow_rate.to_string())
+        .add_attribute("max_borrow", preview.max_borrow);
+
+    if let Some(error) = &preview.error {
+        response = response.add_attribute("error", error);
+    }
+
+    Ok(response)
+}
+
+/// Utilization and borrow rate the market would have right after `user`
+/// borrows `amount` in the current block, and whether the borrow would pass.
+fn preview_borrow(
+    deps: Deps,
+    env: &Env,
+    user: &str,
+    amount: Uint128,
+) -> Result<BorrowPreviewResponse, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+    let state = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?.state;
+    let liquidity = borrowable_liquidity(deps.storage, &state, user)?.total();
+
+    let mut post_borrow = state.clone();
+    let scaled_amount = stone_types::amount_to_scaled_ceil(amount, state.borrow_index);
+    post_borrow.total_debt_scaled = post_borrow.total_debt_scaled.checked_add(scaled_amount)?;
+    let new_utilization = post_borrow.utilization();
+    let (new_borrow_rate, _) = calculate_rates(&params, new_utilization)?;
+
+    // Same checks as `Borrow`, in the same order. The stored debt is valued at
+    // the simulated borrow index, as `Borrow` would after applying interest.
+    let position = if COLLATERAL
+        .may_load(deps.storage, user)?
+        .unwrap_or_default()
+        .is_zero()
+    {
+        Err(ContractError::NoCollateral)
+    } else {
+        calculate_position_health(deps, env, user).and_then(|position| {
+            let scaled_debt = DEBTS.may_load(deps.storage, user)?.unwrap_or_default();
+            let debt = stone_types::scaled_to_amount_ceil(scaled_debt, state.borrow_index);
+            position.with_additional_debt(debt.saturating_sub(position.debt_amount))
+        })
+    };
+    let max_borrow = match &position {
+        Ok(position) => position.max_borrow_amount()?.min(liquidity),
+        Err(_) => Uint128::zero(),
+    };
+    let check = check_borrow_limits(deps.storage, &params, &state, amount, liquidity)
+        .and_then(|_| position)
+        .and_then(|position| {
+            position.check_borrow_allowed(amount)?;
+            match params.min_post_borrow_health_factor {
+                Some(min_health_factor) => {
+                    position.check_post_borrow_health_factor(amount, min_health_factor)
+                }
+                None => Ok(()),
+            }
+        });
+
+    Ok(BorrowPreviewResponse {
+        eligible: check.is_ok(),
+        new_utilization,
+        new_borrow_rate,
+        max_borrow,
+        error: check.err().map(|e| e.to_string()),
+    })
+}
+
+/// Liquidity a borrower can draw on, split between the general pool and the
+/// privileged reserve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BorrowableLiquidity {
+    /// Available liquidity not reserved for privileged borrowers
+    pub general: Uint128,
+    /// Part of the privileged reserve this borrower may draw
+    pub reserved: Uint128,
+}
+
+impl BorrowableLiquidity {
+    pub fn total(&self) -> Uint128 {
+        self.general + self.reserved
+    }
+}
+
+/// Split the market's available liquidity for `user`. Everyone can use the
+/// liquidity above `PRIVILEGED_SUPPLY`; privileged borrowers can additionally
+/// draw on the reserve up to their remaining limit.
+pub(crate) fn borrowable_liquidity(
+    storage: &dyn Storage,
+    state: &MarketState,
+    user: &str,
+) -> StdResult<BorrowableLiquidity> {
+    let available = state.available_liquidity();
+    let privileged_supply = PRIVILEGED_SUPPLY.may_load(storage)?.unwrap_or_default();
+    let general = available.saturating_sub(privileged_supply);
+
+    let limit = PRIVILEGED_BORROWERS
+        .may_load(storage, user)?
+        .unwrap_or_default();
+    let reserved = limit
+        .min(privileged_supply)
+        .min(available.saturating_sub(general));
+
+    Ok(BorrowableLiquidity { general, reserved })
+}
+
+/// Market-level limits a borrow of `amount` must satisfy: market not paused and enabled, amount
+/// non-zero, at least the minimum and above the flat borrow fee, enough of the borrower's `available`
+/// liquidity and room under the borrow cap. `state` should have interest applied.
+pub(crate) fn check_borrow_limits(
+    storage: &dyn Storage,
+    params: &MarketParams,
+    state: &MarketState,
+    amount: Uint128,
+    available: Uint128,
+) -> Result<(), ContractError> {
+    ensure_not_paused(storage)?;
+
+    if !params.enabled {
+        return Err(ContractError::MarketDisabled);
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    if amount < params.min_borrow_amount {
+        return Err(ContractError::BorrowAmountTooSmall {
+            min: params.min_borrow_amount,
+            provided: amount,
+        });
+    }
+
+    // The flat fee comes out of the proceeds, so the borrow must exceed it
+    if !params.protocol_borrow_fee_flat.is_zero() && amount <= params.protocol_borrow_fee_flat {
+        return Err(ContractError::BorrowAmountTooSmall {
+            min: params.protocol_borrow_fee_flat + Uint128::one(),
+            provided: amount,
+        });
+    }
+
+    // Check available liquidity
+    if amount > available {
+        return Err(ContractError::InsufficientLiquidity {
+            available: available.to_string(),
+            requested: amount.to_string(),
+        });
+    }
+
+    // Check borrow cap using Decimal256 to prevent overflow near Uint128::MAX
+    let current_debt = state.total_debt();
+    if let Some(cap) = params.borrow_cap {
+        let would_be = u128_to_decimal256(current_debt).checked_add(u128_to_decimal256(amount))?;
+        if would_be > u128_to_decimal256(cap) {
+            return Err(ContractError::ExceedsBorrowCap {
+                cap,
+                current: current_debt,
+                requested: amount,
+            });
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::COLLATERAL;
+    use crate::interest::SECONDS_PER_YEAR;
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier};
     use cosmwasm_std::{
         from_json, to_json_binary, ContractResult, Decimal, QuerierResult, WasmQuery,
@@ -143,6 +354,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -158,6 +371,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -230,6 +453,138 @@ mod tests {
         assert_eq!(state.total_debt_scaled, Uint128::new(5000));
     }
 
+    #[test]
+    fn test_borrow_preview_matches_borrow() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        // 1000 ATOM ($10,000) collateral
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let info = message_info(&user1, &[]);
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        let res = execute_borrow_preview(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info.clone(),
+            Uint128::new(5000),
+        )
+        .unwrap();
+        let preview: BorrowPreviewResponse = from_json(res.data.unwrap()).unwrap();
+        assert!(preview.eligible);
+        assert_eq!(preview.error, None);
+        assert_eq!(preview.new_utilization, Decimal::percent(50));
+        // 80% LTV of $10,000
+        assert_eq!(preview.max_borrow, Uint128::new(8000));
+
+        // The preview leaves state untouched
+        assert_eq!(STATE.load(deps.as_ref().storage).unwrap(), state);
+
+        let res = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(5000),
+            None,
+        )
+        .unwrap();
+        let attr = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+                .unwrap()
+        };
+        assert_eq!(attr("utilization"), preview.new_utilization.to_string());
+        assert_eq!(attr("borrow_rate"), preview.new_borrow_rate.to_string());
+    }
+
+    #[test]
+    fn test_borrow_preview_includes_accrued_interest() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        // Keep the mock price (updated_at = 0) fresh a year later
+        let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+        config.oracle_config.oracle_type = OracleType::Generic {
+            expected_code_id: None,
+            max_staleness_secs: 2 * SECONDS_PER_YEAR,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        // 1000 ATOM ($10,000) collateral and 7000 USDC of debt at 70% utilization
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+        DEBTS
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(7000))
+            .unwrap();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(7000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let env = mock_env_at_time(1000 + SECONDS_PER_YEAR);
+        let borrow_index =
+            simulate_accumulated_interest(deps.as_ref().storage, 1000 + SECONDS_PER_YEAR)
+                .unwrap()
+                .state
+                .borrow_index;
+        let debt = stone_types::scaled_to_amount_ceil(Uint128::new(7000), borrow_index);
+        assert!(debt > Uint128::new(7000));
+
+        // 900 fits under the stored debt but not once a year of interest is added
+        let info = message_info(&user1, &[]);
+        let res =
+            execute_borrow_preview(deps.as_mut(), env.clone(), info.clone(), Uint128::new(900))
+                .unwrap();
+        let preview: BorrowPreviewResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(preview.max_borrow, Uint128::new(8000) - debt);
+        assert!(!preview.eligible);
+
+        let err = execute_borrow(deps.as_mut(), env, info, Uint128::new(900), None).unwrap_err();
+        assert!(matches!(err, ContractError::ExceedsLtv { .. }));
+        assert_eq!(preview.error, Some(err.to_string()));
+    }
+
+    #[test]
+    fn test_borrow_preview_reports_ineligible_borrow() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        // No collateral: the preview still succeeds and explains why
+        let res = execute_borrow_preview(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info.clone(),
+            Uint128::new(5000),
+        )
+        .unwrap();
+        let preview: BorrowPreviewResponse = from_json(res.data.unwrap()).unwrap();
+        assert!(!preview.eligible);
+        assert_eq!(preview.max_borrow, Uint128::zero());
+        assert_eq!(preview.error, Some(ContractError::NoCollateral.to_string()));
+        assert_eq!(preview.new_utilization, Decimal::percent(50));
+
+        // Above the LTV limit
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+        let res =
+            execute_borrow_preview(deps.as_mut(), mock_env_at_time(0), info, Uint128::new(9000))
+                .unwrap();
+        let preview: BorrowPreviewResponse = from_json(res.data.unwrap()).unwrap();
+        assert!(!preview.eligible);
+        assert_eq!(preview.max_borrow, Uint128::new(8000));
+        assert!(preview.error.unwrap().contains("LTV"));
+    }
+
     #[test]
     fn test_borrow_exceeds_ltv() {
         let mut deps = mock_dependencies();
@@ -249,6 +604,131 @@ mod tests {
         assert!(matches!(err, ContractError::ExceedsLtv { .. }));
     }
 
+    #[test]
+    fn test_borrow_min_post_borrow_health_factor_boundary() {
+        let user1 = MockApi::default().addr_make("user1");
+        let setup = || {
+            let mut deps = mock_dependencies();
+            setup_market_with_oracle(&mut deps);
+            let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+            params.min_post_borrow_health_factor = Some(Decimal::percent(125));
+            PARAMS.save(deps.as_mut().storage, &params).unwrap();
+            // 1000 ATOM ($10,000) collateral
+            COLLATERAL
+                .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+                .unwrap();
+            deps
+        };
+
+        // HF = 10000 * 0.85 / 6800 = 1.25, exactly the minimum
+        let mut deps = setup();
+        let info = message_info(&user1, &[]);
+        execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(6800),
+            None,
+        )
+        .unwrap();
+
+        // Within LTV (max 8000) but one unit past the health factor buffer
+        let mut deps = setup();
+        let info = message_info(&user1, &[]);
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(6801),
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::HealthFactorTooLow { required, actual } => {
+                assert_eq!(required, Decimal::percent(125));
+                assert!(actual < required);
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_borrow_deducts_flat_fee() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.protocol_borrow_fee_flat = Uint128::new(10);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let info = message_info(&user1, &[]);
+        let res = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(5000),
+            None,
+        )
+        .unwrap();
+
+        // Borrower receives the amount net of the fee but owes the full amount
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: user1.to_string(),
+                amount: vec![Coin {
+                    denom: "uusdc".to_string(),
+                    amount: Uint128::new(4990),
+                }],
+            })
+        );
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "borrow_fee" && a.value == "10"));
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(5000));
+        assert_eq!(
+            ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(10)
+        );
+    }
+
+    #[test]
+    fn test_borrow_not_above_flat_fee() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.protocol_borrow_fee_flat = Uint128::new(10);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let info = message_info(&user1, &[]);
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(10),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BorrowAmountTooSmall {
+                min: Uint128::new(11),
+                provided: Uint128::new(10),
+            }
+        );
+    }
+
     #[test]
     fn test_borrow_no_collateral() {
         let mut deps = mock_dependencies();
@@ -260,7 +740,23 @@ mod tests {
 
         // Try to borrow without collateral
         let err = execute_borrow(deps.as_mut(), env, info, Uint128::new(1000), None).unwrap_err();
-        assert!(matches!(err, ContractError::ExceedsLtv { .. }));
+        assert!(matches!(err, ContractError::NoCollateral));
+    }
+
+    #[test]
+    fn test_borrow_no_collateral_skips_oracle() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        deps.querier
+            .update_wasm(|_| panic!("oracle must not be queried without collateral"));
+
+        let env = mock_env_at_time(0);
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        let err = execute_borrow(deps.as_mut(), env, info, Uint128::new(1000), None).unwrap_err();
+        assert!(matches!(err, ContractError::NoCollateral));
+        assert!(!DEBTS.has(deps.as_ref().storage, user1.as_str()));
     }
 
     #[test]
@@ -301,7 +797,140 @@ mod tests {
         let info = message_info(&user1, &[]);
 
         let err = execute_borrow(deps.as_mut(), env, info, Uint128::new(5000), None).unwrap_err();
-        assert!(matches!(err, ContractError::BorrowCapExceeded { .. }));
+        assert_eq!(
+            err,
+            ContractError::ExceedsBorrowCap {
+                cap: Uint128::new(3000),
+                current: Uint128::zero(),
+                requested: Uint128::new(5000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_borrow_cap_boundary() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.borrow_cap = Some(Uint128::new(3000));
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        // Borrowing exactly up to the cap is allowed
+        execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            message_info(&user1, &[]),
+            Uint128::new(3000),
+            None,
+        )
+        .unwrap();
+
+        // One more unit exceeds it
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            message_info(&user1, &[]),
+            Uint128::one(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExceedsBorrowCap {
+                cap: Uint128::new(3000),
+                current: Uint128::new(3000),
+                requested: Uint128::one(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_borrow_cap_near_uint128_max() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        // Total debt close to Uint128::MAX with a little liquidity left
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::MAX;
+        state.total_debt_scaled = Uint128::MAX - Uint128::new(10);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.borrow_cap = Some(Uint128::MAX - Uint128::new(10));
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            message_info(&user1, &[]),
+            Uint128::new(5),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExceedsBorrowCap {
+                cap: Uint128::MAX - Uint128::new(10),
+                current: Uint128::MAX - Uint128::new(10),
+                requested: Uint128::new(5),
+            }
+        );
+    }
+
+    #[test]
+    fn test_borrow_below_min_borrow_amount() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.min_borrow_amount = Uint128::new(500);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let env = mock_env_at_time(0);
+        let info = message_info(&user1, &[]);
+
+        let err = execute_borrow(deps.as_mut(), env, info, Uint128::new(499), None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BorrowAmountTooSmall {
+                min: Uint128::new(500),
+                provided: Uint128::new(499),
+            }
+        );
+    }
+
+    #[test]
+    fn test_borrow_at_min_borrow_amount() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.min_borrow_amount = Uint128::new(500);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let env = mock_env_at_time(0);
+        let info = message_info(&user1, &[]);
+
+        execute_borrow(deps.as_mut(), env, info, Uint128::new(500), None).unwrap();
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(500));
     }
 
     #[test]
@@ -398,4 +1027,134 @@ mod tests {
         // Should fail with address validation error
         assert!(matches!(err, ContractError::Std(_)));
     }
+
+    /// Reserve 6000 of the 10000 available liquidity for `protocol`.
+    fn reserve_for_protocol(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            MockQuerier,
+        >,
+    ) {
+        let api = MockApi::default();
+        crate::execute::execute_set_privileged_borrower(
+            deps.as_mut(),
+            message_info(&api.addr_make("curator"), &[]),
+            api.addr_make("protocol").to_string(),
+            Uint128::new(6000),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_borrow_non_privileged_cannot_use_reserve() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        reserve_for_protocol(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        // Within LTV but beyond the 4000 general pool
+        let info = message_info(&user1, &[]);
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info.clone(),
+            Uint128::new(4001),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientLiquidity {
+                available: "4000".to_string(),
+                requested: "4001".to_string(),
+            }
+        );
+
+        let res = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(4000),
+            None,
+        )
+        .unwrap();
+        assert!(!res.attributes.iter().any(|a| a.key == "reserve_used"));
+        assert_eq!(
+            PRIVILEGED_SUPPLY.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(6000)
+        );
+    }
+
+    #[test]
+    fn test_borrow_privileged_draws_from_reserve() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        reserve_for_protocol(&mut deps);
+
+        let protocol = MockApi::default().addr_make("protocol");
+        COLLATERAL
+            .save(
+                deps.as_mut().storage,
+                protocol.as_str(),
+                &Uint128::new(1000),
+            )
+            .unwrap();
+
+        // 4000 from the general pool, 3000 from the reserve
+        let info = message_info(&protocol, &[]);
+        let res = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(7000),
+            None,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "reserve_used" && a.value == "3000"));
+
+        let limit = PRIVILEGED_BORROWERS
+            .load(deps.as_ref().storage, protocol.as_str())
+            .unwrap();
+        assert_eq!(limit, Uint128::new(3000));
+        assert_eq!(
+            PRIVILEGED_SUPPLY.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(3000)
+        );
+    }
+
+    #[test]
+    fn test_borrow_privileged_limited_to_own_reserve() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+        reserve_for_protocol(&mut deps);
+
+        let protocol = MockApi::default().addr_make("protocol");
+        COLLATERAL
+            .save(
+                deps.as_mut().storage,
+                protocol.as_str(),
+                &Uint128::new(2000),
+            )
+            .unwrap();
+
+        // Enough collateral for 16000, but only 4000 + 6000 is accessible
+        let info = message_info(&protocol, &[]);
+        let err = execute_borrow(
+            deps.as_mut(),
+            mock_env_at_time(0),
+            info,
+            Uint128::new(10001),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientLiquidity { .. }));
+    }
 }
@@ -1,24 +1,30 @@
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
 
 use crate::error::ContractError;
-use crate::health::check_withdrawal_allowed;
+use crate::execute::{ensure_not_paused, execute_borrow};
+use crate::health::{check_withdrawal_allowed, query_price};
 use crate::interest::apply_accumulated_interest;
-use crate::state::{COLLATERAL, CONFIG, PARAMS, STATE};
+use crate::state::{COLLATERAL, COLLATERAL_WHITELIST, CONFIG, PARAMS, STATE};
+use stone_types::{MarketConfig, MarketParams};
 
 /// Supply collateral asset to enable borrowing.
 pub fn execute_supply_collateral(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient: Option<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let params = PARAMS.load(deps.storage)?;
 
+    ensure_not_paused(deps.storage)?;
+
     if !params.enabled {
         return Err(ContractError::MarketDisabled);
     }
 
+    check_collateral_whitelist(deps.storage, &params, info.sender.as_str())?;
+
     // Check for wrong denom first
     if info.funds.len() > 1
         || (info.funds.len() == 1 && info.funds[0].denom != config.collateral_denom)
@@ -52,7 +58,8 @@ pub fn execute_supply_collateral(
         None => info.sender.clone(),
     };
 
-    // Update user's collateral position (collateral is NOT scaled)
+    // Update user's collateral position (collateral is NOT scaled, so amounts
+    // are tracked exactly and withdrawals never lose a rounding remainder)
     let current = COLLATERAL
         .may_load(deps.storage, recipient_addr.as_str())?
         .unwrap_or_default();
@@ -64,11 +71,109 @@ pub fn execute_supply_collateral(
     state.total_collateral = state.total_collateral.checked_add(amount)?;
     STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_attribute("action", "supply_collateral")
         .add_attribute("supplier", info.sender)
         .add_attribute("recipient", recipient_addr)
-        .add_attribute("amount", amount))
+        .add_attribute("amount", amount)
+        .add_attribute("total_collateral", state.total_collateral);
+    if let Some(value) =
+        total_collateral_value(deps.as_ref(), &env, &config, state.total_collateral)
+    {
+        response = response.add_attribute("total_collateral_value", value);
+    }
+
+    Ok(response)
+}
+
+/// Reject collateral deposits from `depositor` if the market restricts
+/// depositors and it isn't on the whitelist.
+pub(crate) fn check_collateral_whitelist(
+    storage: &dyn Storage,
+    params: &MarketParams,
+    depositor: &str,
+) -> Result<(), ContractError> {
+    if params.collateral_whitelist_enabled && !COLLATERAL_WHITELIST.has(storage, depositor) {
+        return Err(ContractError::NotWhitelisted {
+            address: depositor.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Deposit collateral and borrow against it in one transaction (margin-trading entry).
+/// Exactly `collateral_amount` of the collateral denom must be sent. The single LTV
+/// check runs in the borrow step, against the position including the new collateral.
+pub fn execute_supply_collateral_and_borrow(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collateral_amount: Uint128,
+    borrow_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    ensure_not_paused(deps.storage)?;
+
+    if !params.enabled {
+        return Err(ContractError::MarketDisabled);
+    }
+
+    if collateral_amount.is_zero() || borrow_amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    // Check for wrong denom first
+    if info.funds.len() > 1
+        || (info.funds.len() == 1 && info.funds[0].denom != config.collateral_denom)
+    {
+        let sent_denom = info
+            .funds
+            .first()
+            .map(|c| c.denom.as_str())
+            .unwrap_or("none");
+        return Err(ContractError::WrongDenom {
+            expected: config.collateral_denom.clone(),
+            got: sent_denom.to_string(),
+        });
+    }
+
+    let sent = info.funds.first().map(|c| c.amount).unwrap_or_default();
+    if sent != collateral_amount {
+        return Err(ContractError::FundsMismatch {
+            expected: collateral_amount.to_string(),
+            sent: sent.to_string(),
+        });
+    }
+
+    let supply_res = execute_supply_collateral(deps.branch(), env.clone(), info.clone(), None)?;
+
+    let borrow_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: vec![],
+    };
+    let borrow_res = execute_borrow(deps, env, borrow_info, borrow_amount, None)?;
+
+    Ok(Response::new()
+        .add_submessages(supply_res.messages)
+        .add_submessages(borrow_res.messages)
+        .add_attribute("action", "supply_collateral_and_borrow")
+        .add_attribute("borrower", info.sender.as_str())
+        .add_attribute("collateral_amount", collateral_amount)
+        .add_attribute("borrow_amount", borrow_amount)
+        .add_attributes(
+            supply_res
+                .attributes
+                .into_iter()
+                .chain(borrow_res.attributes)
+                .filter(|a| {
+                    !matches!(
+                        a.key.as_str(),
+                        "action" | "supplier" | "borrower" | "recipient" | "amount"
+                    )
+                }),
+        ))
 }
 
 /// Withdraw collateral (must maintain LTV if debt exists).
@@ -129,6 +234,9 @@ pub fn execute_withdraw_collateral(
         None => info.sender.clone(),
     };
 
+    let collateral_value =
+        total_collateral_value(deps.as_ref(), &env, &config, state.total_collateral);
+
     // Create transfer message
     let transfer_msg = BankMsg::Send {
         to_address: recipient_addr.to_string(),
@@ -138,21 +246,45 @@ pub fn execute_withdraw_collateral(
         }],
     };
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_message(transfer_msg)
         .add_attribute("action", "withdraw_collateral")
         .add_attribute("user", info.sender)
         .add_attribute("recipient", recipient_addr.as_str())
-        .add_attribute("amount", withdraw_amount))
+        .add_attribute("amount", withdraw_amount)
+        .add_attribute("total_collateral", state.total_collateral);
+    if let Some(value) = collateral_value {
+        response = response.add_attribute("total_collateral_value", value);
+    }
+
+    Ok(response)
+}
+
+/// Oracle value of the market's total collateral, or None if the price is
+/// unavailable. Only used for event attributes, so oracle failures never
+/// block collateral operations.
+fn total_collateral_value(
+    deps: Deps,
+    env: &Env,
+    config: &MarketConfig,
+    total_collateral: Uint128,
+) -> Option<Uint128> {
+    let price = query_price(deps, env, &config.oracle_config, &config.collateral_denom).ok()?;
+    total_collateral.checked_mul_floor(price).ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::DEBTS;
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::{coins, Decimal};
+    use cosmwasm_std::{
+        coins, from_json, to_json_binary, ContractResult as CwContractResult, Decimal,
+        SystemResult, WasmQuery,
+    };
     use stone_types::{
-        InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleType,
+        InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleQueryMsg,
+        OracleType, PriceResponse,
     };
 
     fn setup_market(
@@ -177,6 +309,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -192,6 +326,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -202,6 +346,41 @@ mod tests {
         STATE.save(deps.as_mut().storage, &state).unwrap();
     }
 
+    /// Market with 10000 debt liquidity and an oracle pricing ATOM at $10, USDC at $1.
+    fn setup_market_with_oracle(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        setup_market(deps);
+
+        let updated_at = mock_env().block.time.seconds();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(10000);
+        state.last_update = updated_at;
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = if denom == "uatom" {
+                    Decimal::from_ratio(10u128, 1u128)
+                } else {
+                    Decimal::one()
+                };
+                let response = PriceResponse {
+                    denom,
+                    price,
+                    updated_at,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
     #[test]
     fn test_supply_collateral_success() {
         let mut deps = mock_dependencies();
@@ -214,7 +393,16 @@ mod tests {
 
         let res = execute_supply_collateral(deps.as_mut(), env, info, None).unwrap();
 
-        assert_eq!(res.attributes.len(), 4);
+        // No oracle is configured, so the collateral value is skipped
+        assert_eq!(res.attributes.len(), 5);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral" && a.value == "1000"));
+        assert!(!res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral_value"));
 
         // Check user's collateral was recorded
         let collateral = COLLATERAL
@@ -227,6 +415,44 @@ mod tests {
         assert_eq!(state.total_collateral, Uint128::new(1000));
     }
 
+    #[test]
+    fn test_supply_collateral_emits_total_collateral_value() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+        let res = execute_supply_collateral(deps.as_mut(), mock_env(), info, None).unwrap();
+
+        // 1000 ATOM at $10
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral" && a.value == "1000"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral_value" && a.value == "10000"));
+
+        let info = message_info(&user1, &[]);
+        let res = execute_withdraw_collateral(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Some(Uint128::new(400)),
+            None,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral" && a.value == "600"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral_value" && a.value == "6000"));
+    }
+
     #[test]
     fn test_supply_collateral_with_recipient() {
         let mut deps = mock_dependencies();
@@ -315,6 +541,10 @@ mod tests {
                 .unwrap();
 
         assert!(!res.messages.is_empty());
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral" && a.value == "500"));
 
         let remaining = COLLATERAL
             .load(deps.as_ref().storage, user1.as_str())
@@ -359,6 +589,48 @@ mod tests {
         assert_eq!(collateral, Uint128::new(1500));
     }
 
+    #[test]
+    fn test_collateral_round_trips_leave_no_remainder() {
+        // Collateral is stored unscaled, so amounts that don't divide evenly
+        // must still come back to the user exactly.
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let env = mock_env();
+
+        let mut supplied = Uint128::zero();
+        let mut withdrawn = Uint128::zero();
+        for _ in 0..10 {
+            let info = message_info(&user1, &coins(1_000_001, "uatom"));
+            execute_supply_collateral(deps.as_mut(), env.clone(), info, None).unwrap();
+            supplied += Uint128::new(1_000_001);
+
+            for amount in [
+                Some(Uint128::new(333_333)),
+                Some(Uint128::new(333_333)),
+                None,
+            ] {
+                let info = message_info(&user1, &[]);
+                let res =
+                    execute_withdraw_collateral(deps.as_mut(), env.clone(), info, amount, None)
+                        .unwrap();
+                match &res.messages[0].msg {
+                    cosmwasm_std::CosmosMsg::Bank(BankMsg::Send { amount, .. }) => {
+                        withdrawn += amount[0].amount;
+                    }
+                    other => panic!("unexpected message: {other:?}"),
+                }
+            }
+        }
+
+        assert_eq!(supplied, withdrawn);
+        assert!(!COLLATERAL.has(deps.as_ref().storage, user1.as_str()));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert!(state.total_collateral.is_zero());
+    }
+
     #[test]
     fn test_supply_collateral_blocked_when_disabled() {
         // C4 Fix: Supply collateral must be blocked when market is disabled
@@ -379,6 +651,44 @@ mod tests {
         assert!(matches!(err, ContractError::MarketDisabled));
     }
 
+    #[test]
+    fn test_supply_collateral_whitelist() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let allowed = api.addr_make("allowed");
+        let blocked = api.addr_make("blocked");
+        COLLATERAL_WHITELIST
+            .save(deps.as_mut().storage, allowed.as_str(), &true)
+            .unwrap();
+
+        // Whitelist disabled: anyone can deposit
+        let info = message_info(&blocked, &coins(1000, "uatom"));
+        execute_supply_collateral(deps.as_mut(), mock_env(), info, None).unwrap();
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.collateral_whitelist_enabled = true;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let info = message_info(&blocked, &coins(1000, "uatom"));
+        let err = execute_supply_collateral(deps.as_mut(), mock_env(), info, None).unwrap_err();
+        assert!(
+            matches!(err, ContractError::NotWhitelisted { ref address } if address == blocked.as_str())
+        );
+
+        let info = message_info(&allowed, &coins(1000, "uatom"));
+        execute_supply_collateral(deps.as_mut(), mock_env(), info, None).unwrap();
+        let collateral = COLLATERAL
+            .load(deps.as_ref().storage, allowed.as_str())
+            .unwrap();
+        assert_eq!(collateral, Uint128::new(1000));
+
+        // Existing positions can still be withdrawn
+        let info = message_info(&blocked, &[]);
+        execute_withdraw_collateral(deps.as_mut(), mock_env(), info, None, None).unwrap();
+    }
+
     #[test]
     fn test_withdraw_collateral_works_when_disabled() {
         // C4 Fix: Withdraw collateral must ALWAYS work regardless of market status
@@ -453,4 +763,84 @@ mod tests {
         // Should fail with address validation error
         assert!(matches!(err, ContractError::Std(_)));
     }
+
+    #[test]
+    fn test_supply_collateral_and_borrow_success() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        // 1000 ATOM ($10,000) supports up to $8000 of debt at 80% LTV
+        let res = execute_supply_collateral_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(8000),
+        )
+        .unwrap();
+
+        let collateral = COLLATERAL
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(collateral, Uint128::new(1000));
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(8000));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_collateral, Uint128::new(1000));
+
+        assert_eq!(res.messages.len(), 1);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "supply_collateral_and_borrow"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "collateral_amount" && a.value == "1000"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "borrow_amount" && a.value == "8000"));
+    }
+
+    #[test]
+    fn test_supply_collateral_and_borrow_fails_health_check() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        let err = execute_supply_collateral_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(8001),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ExceedsLtv { .. }));
+    }
+
+    #[test]
+    fn test_supply_collateral_and_borrow_funds_mismatch() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1200, "uatom"));
+
+        let err = execute_supply_collateral_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FundsMismatch { .. }));
+    }
 }
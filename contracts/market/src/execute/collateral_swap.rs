@@ -0,0 +1,442 @@
+use cosmwasm_std::{
+    Binary, Coin, DepsMut, Env, MessageInfo, Reply, Response, Storage, SubMsg, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::execute::ensure_not_paused;
+use crate::health::check_withdrawal_allowed;
+use crate::interest::apply_accumulated_interest;
+use crate::state::{
+    PendingCollateralSwap, COLLATERAL, CONFIG, PARAMS, PENDING_COLLATERAL_REPAY,
+    PENDING_COLLATERAL_SWAP, STATE,
+};
+
+/// Reply ID for the DEX router call made by `execute_collateral_swap`.
+pub const COLLATERAL_SWAP_REPLY_ID: u64 = 1;
+
+/// Reject any execute message while a router call is in flight.
+///
+/// Proceeds are measured as a balance delta, so a router that re-enters the
+/// market (e.g. to supply collateral or repay on someone's behalf) could
+/// otherwise inflate what is credited. Pending state only exists between
+/// dispatch and reply within one transaction.
+pub fn ensure_no_pending_swap(storage: &dyn Storage) -> Result<(), ContractError> {
+    if PENDING_COLLATERAL_SWAP.exists(storage) {
+        return Err(ContractError::CollateralSwapInProgress);
+    }
+    if PENDING_COLLATERAL_REPAY.exists(storage) {
+        return Err(ContractError::CollateralRepayInProgress);
+    }
+    Ok(())
+}
+
+/// Swap the sender's collateral through a DEX router in a single transaction.
+///
+/// The collateral is removed from the position and sent to the router with `swap_msg`.
+/// The router's proceeds are credited back in `handle_collateral_swap_reply`.
+/// Isolated markets hold a single collateral denom, so proceeds must be returned
+/// in `config.collateral_denom`; the denom itself is never changed.
+pub fn execute_collateral_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    router: String,
+    swap_msg: Binary,
+    min_receive: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    if !config.allow_collateral_swap {
+        return Err(ContractError::CollateralSwapDisabled);
+    }
+
+    ensure_not_paused(deps.storage)?;
+
+    if !params.enabled {
+        return Err(ContractError::MarketDisabled);
+    }
+
+    if PENDING_COLLATERAL_SWAP.exists(deps.storage) {
+        return Err(ContractError::CollateralSwapInProgress);
+    }
+
+    if min_receive.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let router_addr = deps.api.addr_validate(&router)?;
+
+    // Apply accumulated interest (needed for accurate post-swap health check)
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let user = info.sender.as_str();
+    let amount_in = COLLATERAL.may_load(deps.storage, user)?.unwrap_or_default();
+
+    if amount_in.is_zero() {
+        return Err(ContractError::NoCollateral);
+    }
+
+    // Remove the collateral from the position while it is with the router
+    COLLATERAL.remove(deps.storage, user);
+
+    let mut state = STATE.load(deps.storage)?;
+    state.total_collateral = state.total_collateral.saturating_sub(amount_in);
+    STATE.save(deps.storage, &state)?;
+
+    // Balance after the collateral leaves, used to measure what the router returns
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.collateral_denom)?;
+    let balance_before = balance.amount.checked_sub(amount_in)?;
+
+    PENDING_COLLATERAL_SWAP.save(
+        deps.storage,
+        &PendingCollateralSwap {
+            user: info.sender.clone(),
+            amount_in,
+            min_receive,
+            balance_before,
+        },
+    )?;
+
+    let swap = WasmMsg::Execute {
+        contract_addr: router_addr.to_string(),
+        msg: swap_msg,
+        funds: vec![Coin {
+            denom: config.collateral_denom,
+            amount: amount_in,
+        }],
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(swap, COLLATERAL_SWAP_REPLY_ID))
+        .add_attribute("action", "collateral_swap")
+        .add_attribute("user", info.sender)
+        .add_attribute("router", router_addr)
+        .add_attribute("amount_in", amount_in)
+        .add_attribute("min_receive", min_receive))
+}
+
+/// Credit the router's proceeds back to the swapping user.
+///
+/// Received collateral is measured from the contract balance rather than trusted
+/// from the router response. Fails (reverting the whole swap) if less than
+/// `min_receive` came back or the resulting position would exceed LTV.
+pub fn handle_collateral_swap_reply(
+    deps: DepsMut,
+    env: Env,
+    _msg: Reply,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending = PENDING_COLLATERAL_SWAP.load(deps.storage)?;
+    PENDING_COLLATERAL_SWAP.remove(deps.storage);
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.collateral_denom)?;
+    let received = balance.amount.saturating_sub(pending.balance_before);
+
+    if received < pending.min_receive {
+        return Err(ContractError::SwapSlippageExceeded {
+            min_receive: pending.min_receive.to_string(),
+            received: received.to_string(),
+        });
+    }
+
+    let user = pending.user.as_str();
+    let existing = COLLATERAL.may_load(deps.storage, user)?.unwrap_or_default();
+    COLLATERAL.save(deps.storage, user, &existing.checked_add(received)?)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    state.total_collateral = state.total_collateral.checked_add(received)?;
+    STATE.save(deps.storage, &state)?;
+
+    // Post-swap position must still satisfy LTV
+    check_withdrawal_allowed(deps.as_ref(), &env, user, Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "collateral_swap_complete")
+        .add_attribute("user", pending.user)
+        .add_attribute("amount_in", pending.amount_in)
+        .add_attribute("amount_out", received))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{coins, to_json_binary, SubMsgResult};
+    use stone_testing::default_stored_market_params;
+    use stone_types::{MarketConfig, MarketState, OracleConfig, OracleType};
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        allow_collateral_swap: bool,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let mut state = MarketState::new(1000);
+        state.total_collateral = Uint128::new(1000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let user1 = api.addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let env = mock_env();
+        deps.querier
+            .bank
+            .update_balance(env.contract.address, coins(1000, "uatom"));
+    }
+
+    fn swap_reply() -> Reply {
+        #[allow(deprecated)]
+        Reply {
+            id: COLLATERAL_SWAP_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 0,
+            result: SubMsgResult::Ok(cosmwasm_std::SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_collateral_swap_disabled() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, false);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("user1"), &[]);
+        let err = execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CollateralSwapDisabled));
+    }
+
+    #[test]
+    fn test_collateral_swap_no_collateral() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("user2"), &[]);
+        let err = execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoCollateral));
+    }
+
+    #[test]
+    fn test_collateral_swap_dispatches_router_call() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let router = api.addr_make("router");
+        let swap_msg = to_json_binary(&"swap").unwrap();
+        let info = message_info(&user1, &[]);
+
+        let res = execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            router.to_string(),
+            swap_msg.clone(),
+            Uint128::new(900),
+        )
+        .unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, COLLATERAL_SWAP_REPLY_ID);
+        assert_eq!(
+            res.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: router.to_string(),
+                msg: swap_msg,
+                funds: coins(1000, "uatom"),
+            }
+            .into()
+        );
+
+        // Collateral is held out of the position until the reply
+        assert!(!COLLATERAL.has(deps.as_ref().storage, user1.as_str()));
+        let pending = PENDING_COLLATERAL_SWAP.load(deps.as_ref().storage).unwrap();
+        assert_eq!(pending.amount_in, Uint128::new(1000));
+        assert_eq!(pending.balance_before, Uint128::zero());
+    }
+
+    #[test]
+    fn test_collateral_swap_reply_slippage_exceeded() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("user1"), &[]);
+        execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap();
+
+        // Router returned only 850
+        let env = mock_env();
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(850, "uatom"));
+
+        let err = handle_collateral_swap_reply(deps.as_mut(), env, swap_reply()).unwrap_err();
+        assert!(matches!(err, ContractError::SwapSlippageExceeded { .. }));
+    }
+
+    #[test]
+    fn test_collateral_swap_reply_credits_received() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let info = message_info(&user1, &[]);
+        execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap();
+
+        let env = mock_env();
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(950, "uatom"));
+
+        // No debt, so the post-swap health check needs no oracle
+        let res = handle_collateral_swap_reply(deps.as_mut(), env, swap_reply()).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "amount_out" && a.value == "950"));
+
+        assert_eq!(
+            COLLATERAL
+                .load(deps.as_ref().storage, user1.as_str())
+                .unwrap(),
+            Uint128::new(950)
+        );
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_collateral, Uint128::new(950));
+        assert!(!PENDING_COLLATERAL_SWAP.exists(deps.as_ref().storage));
+    }
+
+    #[test]
+    fn test_collateral_swap_blocks_reentrant_execute() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&api.addr_make("user1"), &[]),
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap();
+
+        // Every execute entry point is rejected until the reply clears the swap,
+        // so the router cannot re-enter to inflate the balance delta
+        let err = ensure_no_pending_swap(deps.as_ref().storage).unwrap_err();
+        assert!(matches!(err, ContractError::CollateralSwapInProgress));
+
+        let env = mock_env();
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(950, "uatom"));
+        handle_collateral_swap_reply(deps.as_mut(), env, swap_reply()).unwrap();
+        ensure_no_pending_swap(deps.as_ref().storage).unwrap();
+    }
+
+    #[test]
+    fn test_collateral_swap_reply_adds_to_existing_collateral() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, true);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        execute_collateral_swap(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            api.addr_make("router").to_string(),
+            Binary::default(),
+            Uint128::new(900),
+        )
+        .unwrap();
+
+        // Position credited while the swap was pending is kept
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(100))
+            .unwrap();
+        let env = mock_env();
+        deps.querier
+            .bank
+            .update_balance(env.contract.address.clone(), coins(950, "uatom"));
+
+        handle_collateral_swap_reply(deps.as_mut(), env, swap_reply()).unwrap();
+        assert_eq!(
+            COLLATERAL
+                .load(deps.as_ref().storage, user1.as_str())
+                .unwrap(),
+            Uint128::new(1050)
+        );
+    }
+}
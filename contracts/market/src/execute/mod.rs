@@ -1,15 +1,23 @@
 mod admin;
 mod borrow;
 mod collateral;
+mod collateral_swap;
 mod liquidate;
+mod nonce;
+mod receipt_nft;
 mod repay;
+mod repay_with_collateral;
 mod supply;
 mod withdraw;
 
 pub use admin::*;
 pub use borrow::*;
 pub use collateral::*;
+pub use collateral_swap::*;
 pub use liquidate::*;
+pub use nonce::*;
+pub use receipt_nft::*;
 pub use repay::*;
+pub use repay_with_collateral::*;
 pub use supply::*;
 pub use withdraw::*;
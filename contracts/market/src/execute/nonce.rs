@@ -0,0 +1,144 @@
+use cosmwasm_std::{Addr, Env, Order, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::state::USED_NONCES;
+
+/// How long a used nonce is remembered (24 hours)
+pub const NONCE_EXPIRY_SECONDS: u64 = 86_400;
+
+/// How many of the sender's stored nonces each use looks at for pruning
+pub const NONCE_PRUNE_SCAN: usize = 10;
+
+/// Record `nonce` as used by `sender`, rejecting it if they already used it in
+/// the last `NONCE_EXPIRY_SECONDS`. This is best-effort protection against
+/// front-end retries double-submitting a supply or withdrawal; `None` skips it.
+///
+/// Each use also removes expired entries among the sender's first
+/// `NONCE_PRUNE_SCAN` stored nonces, so a sender's `USED_NONCES` entries are
+/// cleaned up by their own later uses rather than kept forever.
+pub fn use_nonce(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    nonce: Option<u64>,
+) -> Result<(), ContractError> {
+    let Some(nonce) = nonce else {
+        return Ok(());
+    };
+
+    let now = env.block.time.seconds();
+
+    let expired = USED_NONCES
+        .prefix(sender.as_str())
+        .range(storage, None, None, Order::Ascending)
+        .take(NONCE_PRUNE_SCAN)
+        .filter(|item| match item {
+            Ok((_, used_at)) => now >= used_at.saturating_add(NONCE_EXPIRY_SECONDS),
+            // Keep errors so they are surfaced by the collect
+            Err(_) => true,
+        })
+        .map(|item| item.map(|(nonce, _)| nonce))
+        .collect::<StdResult<Vec<u64>>>()?;
+    for expired_nonce in expired {
+        USED_NONCES.remove(storage, (sender.as_str(), expired_nonce));
+    }
+
+    let key = (sender.as_str(), nonce);
+    if let Some(used_at) = USED_NONCES.may_load(storage, key)? {
+        if now < used_at.saturating_add(NONCE_EXPIRY_SECONDS) {
+            return Err(ContractError::DuplicateNonce { nonce });
+        }
+    }
+    USED_NONCES.save(storage, key, &now)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+
+    #[test]
+    fn test_duplicate_nonce_rejected() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let api = MockApi::default();
+        let user = api.addr_make("user");
+
+        use_nonce(deps.as_mut().storage, &env, &user, Some(7)).unwrap();
+        let err = use_nonce(deps.as_mut().storage, &env, &user, Some(7)).unwrap_err();
+        assert_eq!(err, ContractError::DuplicateNonce { nonce: 7 });
+
+        // Other nonces and other senders are unaffected
+        use_nonce(deps.as_mut().storage, &env, &user, Some(8)).unwrap();
+        use_nonce(
+            deps.as_mut().storage,
+            &env,
+            &api.addr_make("other"),
+            Some(7),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_nonce_expires_after_24_hours() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let user = MockApi::default().addr_make("user");
+
+        use_nonce(deps.as_mut().storage, &env, &user, Some(1)).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(NONCE_EXPIRY_SECONDS - 1);
+        let err = use_nonce(deps.as_mut().storage, &env, &user, Some(1)).unwrap_err();
+        assert_eq!(err, ContractError::DuplicateNonce { nonce: 1 });
+
+        env.block.time = env.block.time.plus_seconds(1);
+        use_nonce(deps.as_mut().storage, &env, &user, Some(1)).unwrap();
+
+        // Reusing it restarts the window
+        let err = use_nonce(deps.as_mut().storage, &env, &user, Some(1)).unwrap_err();
+        assert_eq!(err, ContractError::DuplicateNonce { nonce: 1 });
+    }
+
+    #[test]
+    fn test_expired_nonces_are_pruned() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let user = MockApi::default().addr_make("user");
+
+        for nonce in 1..=3 {
+            use_nonce(deps.as_mut().storage, &env, &user, Some(nonce)).unwrap();
+        }
+
+        env.block.time = env.block.time.plus_seconds(NONCE_EXPIRY_SECONDS);
+        use_nonce(deps.as_mut().storage, &env, &user, Some(10)).unwrap();
+
+        let stored: Vec<u64> = USED_NONCES
+            .prefix(user.as_str())
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        assert_eq!(stored, vec![10]);
+    }
+
+    #[test]
+    fn test_no_nonce_is_never_recorded() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let user = MockApi::default().addr_make("user");
+
+        use_nonce(deps.as_mut().storage, &env, &user, None).unwrap();
+        use_nonce(deps.as_mut().storage, &env, &user, None).unwrap();
+        assert!(USED_NONCES
+            .prefix(user.as_str())
+            .keys(
+                deps.as_ref().storage,
+                None,
+                None,
+                cosmwasm_std::Order::Ascending
+            )
+            .next()
+            .is_none());
+    }
+}
@@ -0,0 +1,181 @@
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, DepsMut, Env, Order, Reply, Response, StdResult, Storage,
+    SubMsg, SubMsgResult, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::state::{NEXT_RECEIPT_ID, SUPPLY_RECEIPTS};
+use stone_types::{DepositInfo, MintMsg, ReceiptNftExecuteMsg};
+
+/// Reply ID for receipt NFT mints and burns. Only failures are replied to.
+pub const RECEIPT_NFT_REPLY_ID: u64 = 3;
+
+/// Most receipts burned by a single withdrawal, so a position with many
+/// receipts can't make withdrawing run out of gas. Receipts left over are
+/// burned by the next withdrawal that empties the position.
+pub const MAX_RECEIPT_BURNS: usize = 30;
+
+/// Token ID for the next supply receipt: the market address and a sequence
+/// number, so markets sharing an NFT contract never collide.
+pub fn generate_token_id(storage: &mut dyn Storage, env: &Env) -> StdResult<String> {
+    let id = NEXT_RECEIPT_ID.may_load(storage)?.unwrap_or_default();
+    NEXT_RECEIPT_ID.save(storage, &(id + 1))?;
+    Ok(format!("{}-{}", env.contract.address, id))
+}
+
+/// Mint a receipt for a supply of `amount` (`scaled` units) to `owner`.
+/// A failed mint is caught in `handle_receipt_nft_reply` and never reverts the supply.
+pub(crate) fn mint_receipt_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    nft_contract: &Addr,
+    owner: &Addr,
+    amount: Uint128,
+    scaled: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let token_id = generate_token_id(storage, env)?;
+    SUPPLY_RECEIPTS.save(storage, (owner.as_str(), &token_id), &())?;
+
+    let mint = WasmMsg::Execute {
+        contract_addr: nft_contract.to_string(),
+        msg: to_json_binary(&ReceiptNftExecuteMsg::Mint(MintMsg {
+            token_id: token_id.clone(),
+            owner: owner.to_string(),
+            extension: DepositInfo {
+                amount,
+                scaled,
+                timestamp: env.block.time.seconds(),
+            },
+        }))?,
+        funds: vec![],
+    };
+    Ok(SubMsg::reply_on_error(mint, RECEIPT_NFT_REPLY_ID)
+        .with_payload(to_json_binary(&(owner.as_str(), token_id))?))
+}
+
+/// Burn up to `MAX_RECEIPT_BURNS` of `owner`'s receipts once their supply
+/// position is empty. Failed burns are ignored like failed mints.
+pub(crate) fn burn_receipt_msgs(
+    storage: &mut dyn Storage,
+    nft_contract: &Addr,
+    owner: &Addr,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let token_ids = SUPPLY_RECEIPTS
+        .prefix(owner.as_str())
+        .keys(storage, None, None, Order::Ascending)
+        .take(MAX_RECEIPT_BURNS)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    token_ids
+        .into_iter()
+        .map(|token_id| {
+            SUPPLY_RECEIPTS.remove(storage, (owner.as_str(), &token_id));
+            let burn = WasmMsg::Execute {
+                contract_addr: nft_contract.to_string(),
+                msg: to_json_binary(&ReceiptNftExecuteMsg::Burn { token_id })?,
+                funds: vec![],
+            };
+            Ok(SubMsg::reply_on_error(burn, RECEIPT_NFT_REPLY_ID))
+        })
+        .collect()
+}
+
+/// Swallow a failed receipt mint or burn so the supply or withdrawal goes
+/// through. A receipt that failed to mint is forgotten so it is never burned.
+pub fn handle_receipt_nft_reply(
+    deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => String::new(),
+    };
+
+    if !msg.payload.is_empty() {
+        let (owner, token_id): (String, String) = from_json(&msg.payload)?;
+        SUPPLY_RECEIPTS.remove(deps.storage, (&owner, &token_id));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "receipt_nft_failed")
+        .add_attribute("error", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{Binary, CosmosMsg};
+
+    #[test]
+    fn test_generate_token_id_is_sequential() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let first = generate_token_id(deps.as_mut().storage, &env).unwrap();
+        let second = generate_token_id(deps.as_mut().storage, &env).unwrap();
+        assert_eq!(first, format!("{}-0", env.contract.address));
+        assert_eq!(second, format!("{}-1", env.contract.address));
+    }
+
+    #[test]
+    fn test_burn_receipts_after_failed_mint() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let api = MockApi::default();
+        let nft = api.addr_make("receipt_nft");
+        let user = api.addr_make("user");
+
+        let minted = mint_receipt_msg(
+            deps.as_mut().storage,
+            &env,
+            &nft,
+            &user,
+            Uint128::new(100),
+            Uint128::new(100),
+        )
+        .unwrap();
+        let failed = mint_receipt_msg(
+            deps.as_mut().storage,
+            &env,
+            &nft,
+            &user,
+            Uint128::new(50),
+            Uint128::new(50),
+        )
+        .unwrap();
+        assert_eq!(minted.id, RECEIPT_NFT_REPLY_ID);
+
+        // The second mint fails and is forgotten
+        #[allow(deprecated)]
+        let reply = Reply {
+            id: RECEIPT_NFT_REPLY_ID,
+            payload: failed.payload,
+            gas_used: 0,
+            result: SubMsgResult::Err("mint failed".to_string()),
+        };
+        let res = handle_receipt_nft_reply(deps.as_mut(), env.clone(), reply).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "error" && a.value == "mint failed"));
+
+        // Only the minted receipt is burned
+        let burns = burn_receipt_msgs(deps.as_mut().storage, &nft, &user).unwrap();
+        assert_eq!(burns.len(), 1);
+        assert_eq!(burns[0].payload, Binary::default());
+        match &burns[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                from_json::<ReceiptNftExecuteMsg>(msg).unwrap(),
+                ReceiptNftExecuteMsg::Burn {
+                    token_id: format!("{}-0", env.contract.address)
+                }
+            ),
+            other => panic!("unexpected message: {other:?}"),
+        }
+        assert!(burn_receipt_msgs(deps.as_mut().storage, &nft, &user)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,5 +1,6 @@
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
 
+use super::liquidate::socialize_loss;
 use crate::error::ContractError;
 use crate::interest::{apply_accumulated_interest, get_user_debt};
 use crate::state::{CONFIG, DEBTS, PARAMS, STATE};
@@ -12,7 +13,7 @@ pub fn execute_repay(
     on_behalf_of: Option<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let _params = PARAMS.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
 
     // NOTE: Repay is ALWAYS allowed regardless of market status
     // so users can always reduce their debt positions.
@@ -62,25 +63,23 @@ pub fn execute_repay(
     let repay_amount = amount_sent.min(current_debt);
     let refund_amount = amount_sent.saturating_sub(repay_amount);
 
-    let state = STATE.load(deps.storage)?;
-
-    // Calculate scaled debt decrease
-    let scaled_decrease = stone_types::amount_to_scaled(repay_amount, state.borrow_index)?;
+    let scaled_decrease = apply_repayment(deps.storage, &borrower, repay_amount)?;
 
-    // Update borrower's debt position
-    let current_scaled = DEBTS.may_load(deps.storage, &borrower)?.unwrap_or_default();
-    let new_scaled = current_scaled.saturating_sub(scaled_decrease);
+    // Borrower's remaining debt after repayment
+    let mut final_debt = get_user_debt(deps.storage, &borrower)?;
 
-    if new_scaled.is_zero() {
-        DEBTS.remove(deps.storage, &borrower);
+    // Forgive leftover dust so it can't linger as an unliquidatable micro-position
+    let dust_cleared = if params.auto_clear_dust
+        && !final_debt.is_zero()
+        && final_debt <= params.dust_debt_threshold
+    {
+        final_debt = Uint128::zero();
+        clear_debt(deps.storage, &borrower)?
     } else {
-        DEBTS.save(deps.storage, &borrower, &new_scaled)?;
-    }
+        Uint128::zero()
+    };
 
-    // Update market totals
-    let mut state = STATE.load(deps.storage)?;
-    state.total_debt_scaled = state.total_debt_scaled.saturating_sub(scaled_decrease);
-    STATE.save(deps.storage, &state)?;
+    let state = STATE.load(deps.storage)?;
 
     // Calculate unscaled totals for event
     let total_supply = state.total_supply();
@@ -97,6 +96,7 @@ pub fn execute_repay(
         .add_attribute("borrower", &borrower)
         .add_attribute("amount", repay_amount)
         .add_attribute("scaled_decrease", scaled_decrease)
+        .add_attribute("final_debt", final_debt)
         .add_attribute("borrow_index", state.borrow_index.to_string())
         .add_attribute("liquidity_index", state.liquidity_index.to_string())
         .add_attribute("borrow_rate", borrow_rate.to_string())
@@ -105,26 +105,99 @@ pub fn execute_repay(
         .add_attribute("total_debt", total_debt)
         .add_attribute("utilization", utilization.to_string());
 
+    if !dust_cleared.is_zero() {
+        response = response.add_attribute("dust_cleared", dust_cleared);
+    }
+
     // Refund excess if any
     if !refund_amount.is_zero() {
         let refund_msg = BankMsg::Send {
             to_address: info.sender.to_string(),
             amount: vec![Coin {
-                denom: config.debt_denom,
+                denom: config.debt_denom.clone(),
                 amount: refund_amount,
             }],
         };
         response = response
             .add_message(refund_msg)
-            .add_attribute("refund", refund_amount);
+            // `refund` is kept alongside `refunded` for existing indexers
+            .add_attribute("refund", refund_amount)
+            .add_attribute("refunded", refund_amount)
+            .add_attribute("refund_denom", config.debt_denom);
     }
 
     Ok(response)
 }
 
+/// Repay the sender's entire debt without knowing it up front. The funds sent are
+/// capped at the outstanding debt and the excess is refunded, as with `Repay`;
+/// the response is marked with `repay_max`.
+pub fn execute_repay_max(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    Ok(execute_repay(deps, env, info, None)?.add_attribute("repay_max", "true"))
+}
+
+/// Reduce `borrower`'s debt and the market's total debt by `repay_amount`.
+/// Interest must already be applied and `repay_amount` capped at the current debt.
+/// Returns the scaled debt decrease, failing if it would round to zero.
+pub(crate) fn apply_repayment(
+    storage: &mut dyn Storage,
+    borrower: &str,
+    repay_amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let mut state = STATE.load(storage)?;
+
+    // Calculate scaled debt decrease
+    let scaled_decrease = stone_types::amount_to_scaled(repay_amount, state.borrow_index)?;
+
+    // Dust repayments round to zero scaled debt and would be absorbed without
+    // reducing the position
+    if scaled_decrease.is_zero() {
+        return Err(ContractError::RepayAmountTooSmall {
+            minimum_implied: state.borrow_index.to_uint_ceil(),
+        });
+    }
+
+    // Update borrower's debt position
+    let current_scaled = DEBTS.may_load(storage, borrower)?.unwrap_or_default();
+    let new_scaled = current_scaled.saturating_sub(scaled_decrease);
+
+    if new_scaled.is_zero() {
+        DEBTS.remove(storage, borrower);
+    } else {
+        DEBTS.save(storage, borrower, &new_scaled)?;
+    }
+
+    // Update market totals
+    state.total_debt_scaled = state.total_debt_scaled.saturating_sub(scaled_decrease);
+    STATE.save(storage, &state)?;
+
+    Ok(scaled_decrease)
+}
+
+/// Remove `borrower`'s remaining debt without repayment. The debt is written
+/// off from the market's total debt and socialized like bad debt, so suppliers
+/// absorb it and it is counted in `SOCIALIZED_LOSSES`. Returns the amount
+/// forgiven.
+fn clear_debt(storage: &mut dyn Storage, borrower: &str) -> Result<Uint128, ContractError> {
+    let scaled_debt = DEBTS.may_load(storage, borrower)?.unwrap_or_default();
+    DEBTS.remove(storage, borrower);
+
+    let mut state = STATE.load(storage)?;
+    state.total_debt_scaled = state.total_debt_scaled.saturating_sub(scaled_debt);
+    let forgiven = socialize_loss(storage, &mut state, scaled_debt)?;
+    STATE.save(storage, &state)?;
+
+    Ok(forgiven)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::SOCIALIZED_LOSSES;
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
     use cosmwasm_std::{coins, Decimal, Uint128};
     use stone_types::{
@@ -154,6 +227,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -169,6 +244,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -187,6 +272,65 @@ mod tests {
         user1
     }
 
+    #[test]
+    fn test_repay_max_exact_amount() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+
+        let info = message_info(&user1, &coins(5000, "uusdc"));
+        let res = execute_repay_max(deps.as_mut(), mock_env(), info).unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "repay_max" && a.value == "true"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "amount" && a.value == "5000"));
+        assert!(res.messages.is_empty());
+        assert!(!DEBTS.has(deps.as_ref().storage, user1.as_str()));
+    }
+
+    #[test]
+    fn test_repay_max_refunds_overpayment() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+
+        let info = message_info(&user1, &coins(1_000_000, "uusdc"));
+        let res = execute_repay_max(deps.as_mut(), mock_env(), info).unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "amount" && a.value == "5000"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "refunded" && a.value == "995000"));
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: user1.to_string(),
+                amount: coins(995_000, "uusdc"),
+            })
+        );
+        assert!(!DEBTS.has(deps.as_ref().storage, user1.as_str()));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert!(state.total_debt_scaled.is_zero());
+    }
+
+    #[test]
+    fn test_repay_max_no_debt() {
+        let mut deps = mock_dependencies();
+        setup_market_with_debt(&mut deps);
+        let user2 = MockApi::default().addr_make("user2");
+
+        let info = message_info(&user2, &coins(1000, "uusdc"));
+        let err = execute_repay_max(deps.as_mut(), mock_env(), info).unwrap_err();
+        assert_eq!(err, ContractError::NoDebt);
+    }
+
     #[test]
     fn test_repay_partial() {
         let mut deps = mock_dependencies();
@@ -249,6 +393,37 @@ mod tests {
             .attributes
             .iter()
             .any(|a| a.key == "refund" && a.value == "2000"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "refunded" && a.value == "2000"));
+    }
+
+    #[test]
+    fn test_repay_over_repay_attributes() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+
+        let env = mock_env();
+        let info = message_info(&user1, &coins(5100, "uusdc")); // 100 more than debt
+
+        let res = execute_repay(deps.as_mut(), env, info, None).unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "refunded" && a.value == "100"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "refund_denom" && a.value == "uusdc"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_debt" && a.value == "0"));
+
+        // Position is fully repaid
+        assert!(!DEBTS.has(deps.as_ref().storage, user1.as_str()));
     }
 
     #[test]
@@ -329,6 +504,115 @@ mod tests {
         assert_eq!(state.total_debt_scaled, Uint128::new(3000));
     }
 
+    fn enable_auto_clear_dust(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.auto_clear_dust = true;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+    }
+
+    #[test]
+    fn test_repay_clears_dust_debt() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+        enable_auto_clear_dust(&mut deps);
+
+        // Leaves 50, below the dust threshold of 100
+        let info = message_info(&user1, &coins(4950, "uusdc"));
+        let res = execute_repay(deps.as_mut(), mock_env(), info, None).unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "dust_cleared" && a.value == "50"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "final_debt" && a.value == "0"));
+        assert!(!DEBTS.has(deps.as_ref().storage, user1.as_str()));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert!(state.total_debt_scaled.is_zero());
+        assert_eq!(
+            SOCIALIZED_LOSSES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(50)
+        );
+    }
+
+    #[test]
+    fn test_repay_dust_cleared_across_borrow_repay_cycles_is_socialized() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+        enable_auto_clear_dust(&mut deps);
+
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(100_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        for cycle in 0..3 {
+            if cycle > 0 {
+                // Borrow 5000 again
+                DEBTS
+                    .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(5000))
+                    .unwrap();
+                let mut state = STATE.load(deps.as_ref().storage).unwrap();
+                state.total_debt_scaled += Uint128::new(5000);
+                STATE.save(deps.as_mut().storage, &state).unwrap();
+            }
+
+            // Leaves 50 of dust each time
+            let info = message_info(&user1, &coins(4950, "uusdc"));
+            let res = execute_repay(deps.as_mut(), mock_env(), info, None).unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|a| a.key == "dust_cleared" && a.value == "50"));
+        }
+
+        // Every forgiven unit is recorded and taken out of the supply (up to
+        // index rounding)
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert!(state.total_debt_scaled.is_zero());
+        assert_eq!(
+            SOCIALIZED_LOSSES.load(deps.as_ref().storage).unwrap(),
+            Uint128::new(150)
+        );
+        assert!(state.total_supply().abs_diff(Uint128::new(99_850)) <= Uint128::new(2));
+    }
+
+    #[test]
+    fn test_repay_keeps_debt_above_dust_threshold() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+        enable_auto_clear_dust(&mut deps);
+
+        let info = message_info(&user1, &coins(4800, "uusdc"));
+        let res = execute_repay(deps.as_mut(), mock_env(), info, None).unwrap();
+
+        assert!(!res.attributes.iter().any(|a| a.key == "dust_cleared"));
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(200));
+    }
+
+    #[test]
+    fn test_repay_keeps_dust_when_auto_clear_disabled() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+
+        let info = message_info(&user1, &coins(4950, "uusdc"));
+        let res = execute_repay(deps.as_mut(), mock_env(), info, None).unwrap();
+
+        assert!(!res.attributes.iter().any(|a| a.key == "dust_cleared"));
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(50));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_debt_scaled, Uint128::new(50));
+    }
+
     #[test]
     fn test_repay_works_when_disabled() {
         // C4 Fix: Repay must ALWAYS work regardless of market status
@@ -353,4 +637,29 @@ mod tests {
         let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
         assert_eq!(debt, Uint128::new(3000));
     }
+
+    #[test]
+    fn test_repay_below_borrow_index_rejected() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_debt(&mut deps);
+
+        let env = mock_env();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.borrow_index = Decimal::from_ratio(5u128, 2u128);
+        state.last_update = env.block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        // 2 / 2.5 rounds down to zero scaled debt
+        let info = message_info(&user1, &coins(2, "uusdc"));
+        let err = execute_repay(deps.as_mut(), env, info, None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RepayAmountTooSmall {
+                minimum_implied: Uint128::new(3),
+            }
+        );
+
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(5000));
+    }
 }
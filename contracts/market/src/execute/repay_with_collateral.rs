@@ -0,0 +1,325 @@
+use cosmwasm_std::{
+    BankMsg, Binary, Coin, DepsMut, Env, MessageInfo, Reply, Response, SubMsg, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::execute::repay::apply_repayment;
+use crate::health::check_withdrawal_allowed;
+use crate::interest::{apply_accumulated_interest, get_user_debt};
+use crate::state::{PendingCollateralRepay, COLLATERAL, CONFIG, PENDING_COLLATERAL_REPAY, STATE};
+
+/// Reply ID for the DEX router call made by `execute_repay_with_collateral`.
+pub const REPAY_WITH_COLLATERAL_REPLY_ID: u64 = 2;
+
+/// Repay the sender's debt by swapping part of their collateral through a DEX router.
+///
+/// `collateral_to_use` is removed from the position and sent to the router with
+/// `route_msg`. The debt asset the router returns is applied as a repayment in
+/// `handle_repay_with_collateral_reply`.
+pub fn execute_repay_with_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collateral_to_use: Uint128,
+    max_debt_repaid: Uint128,
+    router: String,
+    route_msg: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // NOTE: Like Repay, this is allowed regardless of market status
+    // so users can always reduce their debt positions.
+
+    if PENDING_COLLATERAL_REPAY.exists(deps.storage) {
+        return Err(ContractError::CollateralRepayInProgress);
+    }
+
+    if collateral_to_use.is_zero() || max_debt_repaid.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let router_addr = deps.api.addr_validate(&router)?;
+
+    // Apply accumulated interest (needed for accurate debt and post-repay health check)
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let user = info.sender.as_str();
+    if get_user_debt(deps.storage, user)?.is_zero() {
+        return Err(ContractError::NoDebt);
+    }
+
+    let collateral = COLLATERAL.may_load(deps.storage, user)?.unwrap_or_default();
+    if collateral_to_use > collateral {
+        return Err(ContractError::InsufficientCollateralBalance {
+            available: collateral.to_string(),
+            requested: collateral_to_use.to_string(),
+        });
+    }
+
+    // Remove the collateral from the position before it goes to the router
+    let remaining = collateral - collateral_to_use;
+    if remaining.is_zero() {
+        COLLATERAL.remove(deps.storage, user);
+    } else {
+        COLLATERAL.save(deps.storage, user, &remaining)?;
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.total_collateral = state.total_collateral.saturating_sub(collateral_to_use);
+    STATE.save(deps.storage, &state)?;
+
+    // Balance before the router call, used to measure the debt asset it returns
+    let debt_balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &config.debt_denom)?
+        .amount;
+
+    PENDING_COLLATERAL_REPAY.save(
+        deps.storage,
+        &PendingCollateralRepay {
+            user: info.sender.clone(),
+            collateral_used: collateral_to_use,
+            max_debt_repaid,
+            debt_balance_before,
+        },
+    )?;
+
+    let swap = WasmMsg::Execute {
+        contract_addr: router_addr.to_string(),
+        msg: route_msg,
+        funds: vec![Coin {
+            denom: config.collateral_denom,
+            amount: collateral_to_use,
+        }],
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            swap,
+            REPAY_WITH_COLLATERAL_REPLY_ID,
+        ))
+        .add_attribute("action", "repay_with_collateral")
+        .add_attribute("borrower", info.sender)
+        .add_attribute("router", router_addr)
+        .add_attribute("collateral_used", collateral_to_use)
+        .add_attribute("max_debt_repaid", max_debt_repaid))
+}
+
+/// Apply the router's proceeds as a repayment of the borrower's debt.
+///
+/// Received debt asset is measured from the contract balance rather than trusted
+/// from the router response. Repays up to `max_debt_repaid` (capped at the current
+/// debt) and refunds the rest. Fails, reverting the whole operation, if nothing
+/// came back or the remaining position would exceed LTV.
+pub fn handle_repay_with_collateral_reply(
+    deps: DepsMut,
+    env: Env,
+    _msg: Reply,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending = PENDING_COLLATERAL_REPAY.load(deps.storage)?;
+    PENDING_COLLATERAL_REPAY.remove(deps.storage);
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.debt_denom)?;
+    let received = balance.amount.saturating_sub(pending.debt_balance_before);
+
+    if received.is_zero() {
+        return Err(ContractError::NoRepayProceeds {
+            denom: config.debt_denom,
+        });
+    }
+
+    let borrower = pending.user.as_str();
+    let current_debt = get_user_debt(deps.storage, borrower)?;
+    let repay_amount = received.min(pending.max_debt_repaid).min(current_debt);
+    let refund_amount = received - repay_amount;
+
+    let scaled_decrease = apply_repayment(deps.storage, borrower, repay_amount)?;
+    let final_debt = get_user_debt(deps.storage, borrower)?;
+
+    // Remaining position must still satisfy LTV
+    check_withdrawal_allowed(deps.as_ref(), &env, borrower, Uint128::zero())?;
+
+    let state = STATE.load(deps.storage)?;
+    let (borrow_rate, liquidity_rate) = crate::interest::calculate_current_rates(deps.storage)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "repay_with_collateral_complete")
+        .add_attribute("borrower", &pending.user)
+        .add_attribute("collateral_used", pending.collateral_used)
+        .add_attribute("debt_received", received)
+        .add_attribute("amount", repay_amount)
+        .add_attribute("scaled_decrease", scaled_decrease)
+        .add_attribute("final_debt", final_debt)
+        .add_attribute("borrow_index", state.borrow_index.to_string())
+        .add_attribute("liquidity_index", state.liquidity_index.to_string())
+        .add_attribute("borrow_rate", borrow_rate.to_string())
+        .add_attribute("liquidity_rate", liquidity_rate.to_string())
+        .add_attribute("total_supply", state.total_supply())
+        .add_attribute("total_debt", state.total_debt())
+        .add_attribute("utilization", state.utilization().to_string());
+
+    // Proceeds beyond the repaid debt go back to the borrower
+    if !refund_amount.is_zero() {
+        response = response
+            .add_message(BankMsg::Send {
+                to_address: pending.user.to_string(),
+                amount: vec![Coin {
+                    denom: config.debt_denom.clone(),
+                    amount: refund_amount,
+                }],
+            })
+            .add_attribute("refunded", refund_amount)
+            .add_attribute("refund_denom", config.debt_denom);
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DEBTS, PARAMS};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::SubMsgResult;
+    use stone_testing::default_stored_market_params;
+    use stone_types::{MarketConfig, MarketState, OracleConfig, OracleType};
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let env = mock_env();
+        let mut state = MarketState::new(env.block.time.seconds());
+        state.total_collateral = Uint128::new(1000);
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(4000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let user1 = api.addr_make("user1");
+        COLLATERAL
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+        DEBTS
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(4000))
+            .unwrap();
+    }
+
+    fn repay(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        collateral_to_use: u128,
+    ) -> Result<Response, ContractError> {
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("user1"), &[]);
+        execute_repay_with_collateral(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(collateral_to_use),
+            Uint128::new(5000),
+            api.addr_make("router").to_string(),
+            Binary::default(),
+        )
+    }
+
+    #[test]
+    fn test_repay_with_collateral_dispatches_router_call() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let res = repay(&mut deps, 300).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, REPAY_WITH_COLLATERAL_REPLY_ID);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let collateral = COLLATERAL
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(collateral, Uint128::new(700));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_collateral, Uint128::new(700));
+
+        let pending = PENDING_COLLATERAL_REPAY
+            .load(deps.as_ref().storage)
+            .unwrap();
+        assert_eq!(pending.collateral_used, Uint128::new(300));
+    }
+
+    #[test]
+    fn test_repay_with_collateral_rejects_over_collateral_usage() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let err = repay(&mut deps, 1001).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientCollateralBalance { .. }
+        ));
+        assert!(!PENDING_COLLATERAL_REPAY.exists(deps.as_ref().storage));
+    }
+
+    #[test]
+    fn test_repay_with_collateral_blocks_reentrant_execute() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        repay(&mut deps, 300).unwrap();
+
+        // A router re-entering with Supply or Repay { on_behalf_of } mid-call would
+        // inflate the measured proceeds, so every execute is rejected until the reply
+        let err = crate::execute::ensure_no_pending_swap(deps.as_ref().storage).unwrap_err();
+        assert!(matches!(err, ContractError::CollateralRepayInProgress));
+    }
+
+    #[test]
+    fn test_repay_with_collateral_reply_without_proceeds_fails() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        repay(&mut deps, 300).unwrap();
+
+        #[allow(deprecated)]
+        let reply = Reply {
+            id: REPAY_WITH_COLLATERAL_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 0,
+            result: SubMsgResult::Ok(cosmwasm_std::SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+        };
+        let err = handle_repay_with_collateral_reply(deps.as_mut(), mock_env(), reply).unwrap_err();
+        assert!(matches!(err, ContractError::NoRepayProceeds { .. }));
+    }
+}
@@ -1,19 +1,38 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    to_json_binary, Addr, Attribute, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response,
+    Storage, SubMsg, Uint128,
+};
 
 use crate::error::ContractError;
-use crate::interest::apply_accumulated_interest;
-use crate::state::{CONFIG, PARAMS, STATE, SUPPLIES};
+use crate::execute::{
+    burn_receipt_msgs, ensure_not_paused, execute_supply_collateral_and_borrow, mint_receipt_msg,
+};
+use crate::interest::{
+    apply_accumulated_interest, apply_accumulated_interest_with_params,
+    calculate_current_rates_with_params, calculate_rates, simulate_accumulated_interest,
+};
+use crate::state::{
+    CONFIG, LAST_SUPPLY, LAST_SUPPLY_BLOCK, PARAMS, REFERRAL_VOLUMES, STATE, SUPPLIES,
+};
+use stone_types::{MarketConfig, MarketParams, MarketState, SupplyPreviewResponse};
 
-/// Supply debt asset to earn interest.
+/// Supply debt asset to earn interest. The supplied amount is added to
+/// `referrer`'s referral volume when one is given. Unless `force` is set, a
+/// supply matching the sender's previous one in both block and amount is
+/// rejected as a likely double submission.
 pub fn execute_supply(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: Option<String>,
+    referrer: Option<String>,
+    force: bool,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let params = PARAMS.load(deps.storage)?;
 
+    ensure_not_paused(deps.storage)?;
+
     // Check market is enabled
     if !params.enabled {
         return Err(ContractError::MarketDisabled);
@@ -44,21 +63,14 @@ pub fn execute_supply(
         return Err(ContractError::ZeroAmount);
     }
 
+    check_duplicate_supply(deps.storage, &env, &info.sender, amount, force)?;
+
     // Apply accumulated interest (fees are accrued to state, not sent immediately)
-    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+    apply_accumulated_interest_with_params(deps.storage, &params, env.block.time.seconds())?;
 
     // Check supply cap
     let state = STATE.load(deps.storage)?;
-    let current_supply = state.total_supply();
-    if let Some(cap) = params.supply_cap {
-        let would_be = current_supply.checked_add(amount)?;
-        if would_be > cap {
-            return Err(ContractError::SupplyCapExceeded {
-                cap: cap.to_string(),
-                would_be: would_be.to_string(),
-            });
-        }
-    }
+    check_supply_cap(&params, &state, amount)?;
 
     // Calculate scaled amount: scaled = amount / index
     let scaled_amount = stone_types::amount_to_scaled(amount, state.liquidity_index)?;
@@ -69,27 +81,52 @@ pub fn execute_supply(
         None => info.sender.clone(),
     };
 
+    let referrer_addr = referrer
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    // Self-referral would let a user inflate their own volume by cycling supplies
+    if let Some(referrer_addr) = &referrer_addr {
+        if *referrer_addr == info.sender || *referrer_addr == recipient_addr {
+            return Err(ContractError::SelfReferral);
+        }
+    }
+
     // Update user's supply position
-    let current_scaled = SUPPLIES
-        .may_load(deps.storage, recipient_addr.as_str())?
-        .unwrap_or_default();
-    let new_scaled = current_scaled.checked_add(scaled_amount)?;
-    SUPPLIES.save(deps.storage, recipient_addr.as_str(), &new_scaled)?;
+    let receipt_mint = credit_supply(
+        deps.storage,
+        &env,
+        &config,
+        &info.sender,
+        &recipient_addr,
+        amount,
+        scaled_amount,
+    )?;
 
     // Update market totals
     let mut state = STATE.load(deps.storage)?;
     state.total_supply_scaled = state.total_supply_scaled.checked_add(scaled_amount)?;
     STATE.save(deps.storage, &state)?;
 
+    if let Some(referrer_addr) = &referrer_addr {
+        REFERRAL_VOLUMES.update(
+            deps.storage,
+            referrer_addr.as_str(),
+            |volume| -> Result<_, ContractError> {
+                Ok(volume.unwrap_or_default().checked_add(amount)?)
+            },
+        )?;
+    }
+
     // Calculate unscaled totals for event
     let total_supply = state.total_supply();
     let total_debt = state.total_debt();
     let utilization = state.utilization();
 
     // Calculate current rates based on post-transaction state
-    let (borrow_rate, liquidity_rate) = crate::interest::calculate_current_rates(deps.storage)?;
+    let (borrow_rate, liquidity_rate) = calculate_current_rates_with_params(deps.storage, &params)?;
 
-    Ok(Response::new()
+    let mut response = Response::new()
+        .add_submessages(receipt_mint)
         .add_attribute("action", "supply")
         .add_attribute("supplier", info.sender)
         .add_attribute("recipient", recipient_addr)
@@ -101,16 +138,405 @@ pub fn execute_supply(
         .add_attribute("liquidity_rate", liquidity_rate.to_string())
         .add_attribute("total_supply", total_supply)
         .add_attribute("total_debt", total_debt)
-        .add_attribute("utilization", utilization.to_string()))
+        .add_attribute("utilization", utilization.to_string());
+
+    if let Some(referrer_addr) = referrer_addr {
+        response = response.add_attribute("referrer", referrer_addr);
+    }
+
+    Ok(response)
+}
+
+/// Reject a supply matching the sender's previous one in both block and amount
+/// unless `force` is set, then record it as the sender's latest supply.
+fn check_duplicate_supply(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    amount: Uint128,
+    force: bool,
+) -> Result<(), ContractError> {
+    let this_supply = (env.block.height, amount);
+    if !force && LAST_SUPPLY.may_load(storage, sender.as_str())? == Some(this_supply) {
+        return Err(ContractError::PossibleDuplicate {
+            block_height: env.block.height,
+            amount,
+        });
+    }
+    LAST_SUPPLY.save(storage, sender.as_str(), &this_supply)?;
+    Ok(())
+}
+
+/// Credit `scaled_amount` shares (worth `amount`) to `recipient`. Returns the
+/// receipt NFT mint when the market has a receipt contract. Market totals are
+/// left to the caller.
+fn credit_supply(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &MarketConfig,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+    scaled_amount: Uint128,
+) -> Result<Option<SubMsg>, ContractError> {
+    SUPPLIES.update(
+        storage,
+        recipient.as_str(),
+        |current| -> Result<_, ContractError> {
+            Ok(current.unwrap_or_default().checked_add(scaled_amount)?)
+        },
+    )?;
+
+    // Only a supply to one's own position starts the withdraw cooldown, so nobody
+    // can lock another user's withdrawals by supplying dust on their behalf
+    if recipient == sender {
+        LAST_SUPPLY_BLOCK.save(storage, recipient.as_str(), &env.block.height)?;
+    }
+
+    config
+        .receipt_nft_contract
+        .as_ref()
+        .map(|nft_contract| {
+            mint_receipt_msg(storage, env, nft_contract, recipient, amount, scaled_amount)
+        })
+        .transpose()
+}
+
+/// Supply debt asset on behalf of several recipients in one transaction (for
+/// aggregators). The funds sent must equal the sum of the amounts; each recipient
+/// is credited with the shares for its own amount (and a receipt NFT, if the
+/// market mints them). The supply cap and the duplicate-supply check apply to
+/// the batch total.
+pub fn execute_supply_on_behalf_many(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, Uint128)>,
+    force: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    ensure_not_paused(deps.storage)?;
+
+    if !params.enabled {
+        return Err(ContractError::MarketDisabled);
+    }
+
+    // Check for wrong denom first
+    if info.funds.len() > 1 || (info.funds.len() == 1 && info.funds[0].denom != config.debt_denom) {
+        let sent_denom = info
+            .funds
+            .first()
+            .map(|c| c.denom.as_str())
+            .unwrap_or("none");
+        return Err(ContractError::WrongDenom {
+            expected: config.debt_denom.clone(),
+            got: sent_denom.to_string(),
+        });
+    }
+
+    let recipients = recipients
+        .into_iter()
+        .map(|(addr, amount)| {
+            if amount.is_zero() {
+                return Err(ContractError::ZeroAmount);
+            }
+            Ok((deps.api.addr_validate(&addr)?, amount))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let total_amount = recipients
+        .iter()
+        .try_fold(Uint128::zero(), |total, (_, amount)| {
+            total.checked_add(*amount)
+        })?;
+    if total_amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let sent = info.funds.first().map(|c| c.amount).unwrap_or_default();
+    if sent != total_amount {
+        return Err(ContractError::AmountMismatch {
+            expected: total_amount.to_string(),
+            sent: sent.to_string(),
+        });
+    }
+
+    check_duplicate_supply(deps.storage, &env, &info.sender, total_amount, force)?;
+
+    // Apply accumulated interest (fees are accrued to state, not sent immediately)
+    apply_accumulated_interest_with_params(deps.storage, &params, env.block.time.seconds())?;
+
+    // Check supply cap once against the batch total
+    let mut state = STATE.load(deps.storage)?;
+    check_supply_cap(&params, &state, total_amount)?;
+
+    let mut total_scaled = Uint128::zero();
+    let mut receipt_mints = Vec::new();
+    for (recipient_addr, amount) in &recipients {
+        let scaled_amount = stone_types::amount_to_scaled(*amount, state.liquidity_index)?;
+        receipt_mints.extend(credit_supply(
+            deps.storage,
+            &env,
+            &config,
+            &info.sender,
+            recipient_addr,
+            *amount,
+            scaled_amount,
+        )?);
+        total_scaled = total_scaled.checked_add(scaled_amount)?;
+    }
+
+    state.total_supply_scaled = state.total_supply_scaled.checked_add(total_scaled)?;
+    STATE.save(deps.storage, &state)?;
+
+    let (borrow_rate, liquidity_rate) = calculate_current_rates_with_params(deps.storage, &params)?;
+
+    Ok(Response::new()
+        .add_submessages(receipt_mints)
+        .add_attribute("action", "supply_on_behalf_many")
+        .add_attribute("supplier", info.sender)
+        .add_attribute("recipients", recipients.len().to_string())
+        .add_attribute("amount", total_amount)
+        .add_attribute("scaled_amount", total_scaled)
+        .add_attribute("borrow_index", state.borrow_index.to_string())
+        .add_attribute("liquidity_index", state.liquidity_index.to_string())
+        .add_attribute("borrow_rate", borrow_rate.to_string())
+        .add_attribute("liquidity_rate", liquidity_rate.to_string())
+        .add_attribute("total_supply", state.total_supply())
+        .add_attribute("total_debt", state.total_debt())
+        .add_attribute("utilization", state.utilization().to_string()))
+}
+
+/// Preview the market after supplying `amount`, without applying it. Checks the
+/// same conditions as `Supply` (market enabled, non-zero amount, supply cap) and
+/// returns the `SupplyPreviewResponse` as the response data. Nothing is written,
+/// not even accrued interest.
+pub fn execute_supply_preview(
+    deps: DepsMut,
+    env: Env,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let preview = preview_supply(deps.as_ref(), &env, amount)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&preview)?)
+        .add_attribute("action", "supply_preview")
+        .add_attribute("amount", amount)
+        .add_attribute("new_total_supply", preview.new_total_supply)
+        .add_attribute("new_utilization", preview.new_utilization.to_string())
+        .add_attribute("borrow_rate", preview.borrow_rate.to_string())
+        .add_attribute("supply_rate", preview.supply_rate.to_string()))
+}
+
+/// Totals and rates the market would have right after a supply of `amount` in
+/// the current block, with interest accrued up to it.
+fn preview_supply(
+    deps: Deps,
+    env: &Env,
+    amount: Uint128,
+) -> Result<SupplyPreviewResponse, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+
+    ensure_not_paused(deps.storage)?;
+
+    if !params.enabled {
+        return Err(ContractError::MarketDisabled);
+    }
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let mut state = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?.state;
+    check_supply_cap(&params, &state, amount)?;
+
+    let scaled_amount = stone_types::amount_to_scaled(amount, state.liquidity_index)?;
+    state.total_supply_scaled = state.total_supply_scaled.checked_add(scaled_amount)?;
+
+    let new_utilization = state.utilization();
+    let (borrow_rate, supply_rate) = calculate_rates(&params, new_utilization)?;
+
+    Ok(SupplyPreviewResponse {
+        new_total_supply: state.total_supply(),
+        new_utilization,
+        borrow_rate,
+        supply_rate,
+    })
+}
+
+/// Reject a supply of `amount` that would take total supply above the cap.
+fn check_supply_cap(
+    params: &MarketParams,
+    state: &MarketState,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if let Some(cap) = params.supply_cap {
+        let would_be = state.total_supply().checked_add(amount)?;
+        if would_be > cap {
+            return Err(ContractError::SupplyCapExceeded {
+                cap: cap.to_string(),
+                would_be: would_be.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Move `amount` scaled supply units from the sender to `recipient`. The
+/// recipient inherits the sender's withdraw cooldown so shares can't be moved
+/// to another address to skip `min_supply_blocks`. Like a withdrawal, a
+/// transfer that empties the sender's position burns their supply receipts.
+pub fn execute_transfer_supply_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let sender = info.sender.as_str();
+
+    let sender_shares = SUPPLIES.may_load(deps.storage, sender)?.unwrap_or_default();
+    if sender_shares < amount {
+        return Err(ContractError::InsufficientShares {
+            available: sender_shares.to_string(),
+            requested: amount.to_string(),
+        });
+    }
+
+    // Accrue so the reported value reflects the current liquidity index
+    apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
+    let state = STATE.load(deps.storage)?;
+    let value = stone_types::scaled_to_amount(amount, state.liquidity_index);
+
+    let mut receipt_burns = vec![];
+    if recipient_addr != info.sender {
+        let remaining = sender_shares - amount;
+        if remaining.is_zero() {
+            SUPPLIES.remove(deps.storage, sender);
+            if let Some(nft_contract) = &CONFIG.load(deps.storage)?.receipt_nft_contract {
+                receipt_burns = burn_receipt_msgs(deps.storage, nft_contract, &info.sender)?;
+            }
+        } else {
+            SUPPLIES.save(deps.storage, sender, &remaining)?;
+        }
+        let recipient_shares = SUPPLIES
+            .may_load(deps.storage, recipient_addr.as_str())?
+            .unwrap_or_default();
+        SUPPLIES.save(
+            deps.storage,
+            recipient_addr.as_str(),
+            &recipient_shares.checked_add(amount)?,
+        )?;
+
+        if let Some(last_supply) = LAST_SUPPLY_BLOCK.may_load(deps.storage, sender)? {
+            let recipient_last = LAST_SUPPLY_BLOCK
+                .may_load(deps.storage, recipient_addr.as_str())?
+                .unwrap_or_default();
+            LAST_SUPPLY_BLOCK.save(
+                deps.storage,
+                recipient_addr.as_str(),
+                &last_supply.max(recipient_last),
+            )?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_submessages(receipt_burns)
+        .add_attribute("action", "transfer_supply_shares")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient_addr)
+        .add_attribute("shares", amount)
+        .add_attribute("value", value))
+}
+
+/// Supply collateral and borrow against it in one transaction (one-step leverage).
+/// Collateral sent beyond `supply_amount` is refunded; the rest is handled by
+/// `execute_supply_collateral_and_borrow` with exactly `supply_amount` attached.
+pub fn execute_supply_and_borrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    supply_amount: Uint128,
+    borrow_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Check for wrong denom first
+    if info.funds.len() > 1
+        || (info.funds.len() == 1 && info.funds[0].denom != config.collateral_denom)
+    {
+        let sent_denom = info
+            .funds
+            .first()
+            .map(|c| c.denom.as_str())
+            .unwrap_or("none");
+        return Err(ContractError::WrongDenom {
+            expected: config.collateral_denom.clone(),
+            got: sent_denom.to_string(),
+        });
+    }
+
+    let sent = info.funds.first().map(|c| c.amount).unwrap_or_default();
+    if sent < supply_amount {
+        return Err(ContractError::InsufficientFundsSent {
+            required: supply_amount.to_string(),
+            sent: sent.to_string(),
+        });
+    }
+    let refund = sent - supply_amount;
+
+    let supply_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: vec![Coin {
+            denom: config.collateral_denom.clone(),
+            amount: supply_amount,
+        }],
+    };
+    let res =
+        execute_supply_collateral_and_borrow(deps, env, supply_info, supply_amount, borrow_amount)?;
+
+    // Keep this message's own action and amount keys for indexers
+    let mut response = Response::new()
+        .add_submessages(res.messages)
+        .add_attributes(res.attributes.into_iter().map(|a| match a.key.as_str() {
+            "action" => Attribute::new("action", "supply_and_borrow"),
+            "collateral_amount" => Attribute::new("supply_amount", a.value),
+            _ => a,
+        }));
+
+    if !refund.is_zero() {
+        response = response
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: config.collateral_denom,
+                    amount: refund,
+                }],
+            })
+            .add_attribute("refund", refund);
+    }
+
+    Ok(response)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::{COLLATERAL, DEBTS};
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::{coins, Decimal, Uint128};
+    use cosmwasm_std::{
+        coins, from_json, to_json_binary, ContractResult as CwContractResult, CosmosMsg, Decimal,
+        SystemResult, WasmQuery,
+    };
     use stone_types::{
-        InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleType,
+        InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleQueryMsg,
+        OracleType, PriceResponse,
     };
 
     fn setup_market(
@@ -135,6 +561,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -150,6 +578,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -160,6 +598,42 @@ mod tests {
         STATE.save(deps.as_mut().storage, &state).unwrap();
     }
 
+    /// Market with 10000 debt liquidity and an oracle pricing ATOM at $10, USDC at $1.
+    fn setup_market_with_oracle(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        setup_market(deps);
+
+        // Interest is up to date as of the mock block time
+        let updated_at = mock_env().block.time.seconds();
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(10000);
+        state.last_update = updated_at;
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = if denom == "uatom" {
+                    Decimal::from_ratio(10u128, 1u128)
+                } else {
+                    Decimal::one()
+                };
+                let response = PriceResponse {
+                    denom,
+                    price,
+                    updated_at,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
     #[test]
     fn test_supply_success() {
         let mut deps = mock_dependencies();
@@ -170,7 +644,7 @@ mod tests {
         let env = mock_env();
         let info = message_info(&user1, &coins(1000, "uusdc"));
 
-        let res = execute_supply(deps.as_mut(), env, info, None).unwrap();
+        let res = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap();
 
         assert_eq!(res.attributes.len(), 12); // Updated to include borrow_rate and liquidity_rate
 
@@ -194,9 +668,18 @@ mod tests {
         let user1 = api.addr_make("user1");
         let user2 = api.addr_make("user2");
         let env = mock_env();
+        let height = env.block.height;
         let info = message_info(&user1, &coins(1000, "uusdc"));
 
-        let res = execute_supply(deps.as_mut(), env, info, Some(user2.to_string())).unwrap();
+        let res = execute_supply(
+            deps.as_mut(),
+            env,
+            info,
+            Some(user2.to_string()),
+            None,
+            false,
+        )
+        .unwrap();
 
         assert!(res
             .attributes
@@ -208,6 +691,275 @@ mod tests {
             .load(deps.as_ref().storage, user2.as_str())
             .unwrap();
         assert_eq!(supply, Uint128::new(1000));
+
+        // Supplying on someone's behalf does not start their withdraw cooldown
+        assert!(!LAST_SUPPLY_BLOCK.has(deps.as_ref().storage, user2.as_str()));
+        assert!(!LAST_SUPPLY_BLOCK.has(deps.as_ref().storage, user1.as_str()));
+
+        // Supplying to one's own position does
+        let info = message_info(&user2, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), mock_env(), info, None, None, false).unwrap();
+        assert_eq!(
+            LAST_SUPPLY_BLOCK
+                .load(deps.as_ref().storage, user2.as_str())
+                .unwrap(),
+            height
+        );
+    }
+
+    #[test]
+    fn test_supply_with_referrer() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let referrer = api.addr_make("referrer");
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        let res = execute_supply(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            None,
+            Some(referrer.to_string()),
+            false,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "referrer" && a.value == referrer.as_str()));
+
+        let info = message_info(&user1, &coins(500, "uusdc"));
+        execute_supply(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            None,
+            Some(referrer.to_string()),
+            false,
+        )
+        .unwrap();
+
+        let volume = crate::query::referral_volume(deps.as_ref(), referrer.to_string()).unwrap();
+        assert_eq!(volume, Uint128::new(1500));
+    }
+
+    #[test]
+    fn test_supply_without_referrer() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        let res = execute_supply(deps.as_mut(), mock_env(), info, None, None, false).unwrap();
+
+        assert!(!res.attributes.iter().any(|a| a.key == "referrer"));
+        assert!(REFERRAL_VOLUMES.is_empty(deps.as_ref().storage));
+    }
+
+    #[test]
+    fn test_supply_invalid_referrer() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        let err = execute_supply(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            None,
+            Some("not-an-address".to_string()),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+        assert!(!SUPPLIES.has(deps.as_ref().storage, user1.as_str()));
+    }
+
+    #[test]
+    fn test_supply_self_referral_rejected() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let user2 = api.addr_make("user2");
+
+        // Neither the sender nor the recipient can be the referrer
+        for (recipient, referrer) in [(None, &user1), (Some(user2.to_string()), &user2)] {
+            let info = message_info(&user1, &coins(1000, "uusdc"));
+            let err = execute_supply(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                recipient,
+                Some(referrer.to_string()),
+                false,
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::SelfReferral));
+        }
+        assert!(!REFERRAL_VOLUMES.has(deps.as_ref().storage, user1.as_str()));
+        assert!(!REFERRAL_VOLUMES.has(deps.as_ref().storage, user2.as_str()));
+    }
+
+    #[test]
+    fn test_supply_on_behalf_many() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let carol = api.addr_make("carol");
+        let info = message_info(&api.addr_make("vault"), &coins(1000, "uusdc"));
+
+        let res = execute_supply_on_behalf_many(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![
+                (alice.to_string(), Uint128::new(500)),
+                (bob.to_string(), Uint128::new(300)),
+                (carol.to_string(), Uint128::new(200)),
+            ],
+            false,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "recipients" && a.value == "3"));
+
+        for (user, expected) in [(&alice, 500u128), (&bob, 300), (&carol, 200)] {
+            let supply = SUPPLIES.load(deps.as_ref().storage, user.as_str()).unwrap();
+            assert_eq!(supply, Uint128::new(expected));
+            // Supplied on their behalf, so no withdraw cooldown
+            assert!(!LAST_SUPPLY_BLOCK.has(deps.as_ref().storage, user.as_str()));
+        }
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_supply_scaled, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_supply_on_behalf_many_amount_mismatch() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("vault"), &coins(1000, "uusdc"));
+        let err = execute_supply_on_behalf_many(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![
+                (api.addr_make("alice").to_string(), Uint128::new(500)),
+                (api.addr_make("bob").to_string(), Uint128::new(400)),
+            ],
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AmountMismatch {
+                expected: "900".to_string(),
+                sent: "1000".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_supply_on_behalf_many_duplicate_guard() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let vault = api.addr_make("vault");
+        let recipients = vec![
+            (api.addr_make("alice").to_string(), Uint128::new(600)),
+            (api.addr_make("bob").to_string(), Uint128::new(400)),
+        ];
+
+        // A repeat of the same batch total in the same block needs `force`
+        for (force, expect_duplicate) in [(false, false), (false, true), (true, false)] {
+            let res = execute_supply_on_behalf_many(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&vault, &coins(1000, "uusdc")),
+                recipients.clone(),
+                force,
+            );
+            if expect_duplicate {
+                assert_eq!(
+                    res.unwrap_err(),
+                    ContractError::PossibleDuplicate {
+                        block_height: mock_env().block.height,
+                        amount: Uint128::new(1000),
+                    }
+                );
+            } else {
+                res.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_supply_on_behalf_many_mints_receipts() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let api = MockApi::default();
+        let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+        config.receipt_nft_contract = Some(api.addr_make("receipt_nft"));
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let res = execute_supply_on_behalf_many(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&api.addr_make("vault"), &coins(1000, "uusdc")),
+            vec![
+                (api.addr_make("alice").to_string(), Uint128::new(600)),
+                (api.addr_make("bob").to_string(), Uint128::new(400)),
+            ],
+            false,
+        )
+        .unwrap();
+
+        // One receipt per recipient
+        assert_eq!(res.messages.len(), 2);
+        assert!(res
+            .messages
+            .iter()
+            .all(|m| m.id == crate::execute::RECEIPT_NFT_REPLY_ID));
+    }
+
+    #[test]
+    fn test_supply_on_behalf_many_cap_applies_to_total() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.supply_cap = Some(Uint128::new(900));
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        // Each amount fits under the cap, the total does not
+        let api = MockApi::default();
+        let info = message_info(&api.addr_make("vault"), &coins(1000, "uusdc"));
+        let err = execute_supply_on_behalf_many(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![
+                (api.addr_make("alice").to_string(), Uint128::new(500)),
+                (api.addr_make("bob").to_string(), Uint128::new(500)),
+            ],
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SupplyCapExceeded { .. }));
     }
 
     #[test]
@@ -220,7 +972,7 @@ mod tests {
         let env = mock_env();
         let info = message_info(&user1, &[]);
 
-        let err = execute_supply(deps.as_mut(), env, info, None).unwrap_err();
+        let err = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap_err();
         assert!(matches!(err, ContractError::ZeroAmount));
     }
 
@@ -234,7 +986,7 @@ mod tests {
         let env = mock_env();
         let info = message_info(&user1, &coins(1000, "uatom")); // Wrong denom
 
-        let err = execute_supply(deps.as_mut(), env, info, None).unwrap_err();
+        let err = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap_err();
         assert!(matches!(err, ContractError::WrongDenom { .. }));
     }
 
@@ -253,7 +1005,7 @@ mod tests {
         let env = mock_env();
         let info = message_info(&user1, &coins(1000, "uusdc"));
 
-        let err = execute_supply(deps.as_mut(), env, info, None).unwrap_err();
+        let err = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap_err();
         assert!(matches!(err, ContractError::MarketDisabled));
     }
 
@@ -272,10 +1024,70 @@ mod tests {
         let env = mock_env();
         let info = message_info(&user1, &coins(1000, "uusdc"));
 
-        let err = execute_supply(deps.as_mut(), env, info, None).unwrap_err();
+        let err = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap_err();
         assert!(matches!(err, ContractError::SupplyCapExceeded { .. }));
     }
 
+    #[test]
+    fn test_supply_preview_matches_supply() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        // 10000 supplied, 5000 borrowed
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(5000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let api = MockApi::default();
+        let user1 = api.addr_make("user1");
+        let env = mock_env();
+
+        let res = execute_supply_preview(deps.as_mut(), env.clone(), Uint128::new(10000)).unwrap();
+        let preview: SupplyPreviewResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(preview.new_total_supply, Uint128::new(20000));
+        assert_eq!(preview.new_utilization, Decimal::percent(25));
+
+        // The preview leaves state untouched
+        assert_eq!(STATE.load(deps.as_ref().storage).unwrap(), state);
+
+        let info = message_info(&user1, &coins(10000, "uusdc"));
+        let res = execute_supply(deps.as_mut(), env, info, None, None, false).unwrap();
+        let attr = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+                .unwrap()
+        };
+        assert_eq!(attr("total_supply"), preview.new_total_supply.to_string());
+        assert_eq!(attr("utilization"), preview.new_utilization.to_string());
+        assert_eq!(attr("borrow_rate"), preview.borrow_rate.to_string());
+        assert_eq!(attr("liquidity_rate"), preview.supply_rate.to_string());
+    }
+
+    #[test]
+    fn test_supply_preview_validates_amount_and_cap() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let err = execute_supply_preview(deps.as_mut(), mock_env(), Uint128::zero()).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount));
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.supply_cap = Some(Uint128::new(500));
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let err =
+            execute_supply_preview(deps.as_mut(), mock_env(), Uint128::new(1000)).unwrap_err();
+        assert!(matches!(err, ContractError::SupplyCapExceeded { .. }));
+        execute_supply_preview(deps.as_mut(), mock_env(), Uint128::new(500)).unwrap();
+
+        params.enabled = false;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+        let err = execute_supply_preview(deps.as_mut(), mock_env(), Uint128::new(500)).unwrap_err();
+        assert!(matches!(err, ContractError::MarketDisabled));
+    }
+
     #[test]
     fn test_supply_with_existing_position() {
         let mut deps = mock_dependencies();
@@ -287,11 +1099,11 @@ mod tests {
 
         // First supply
         let info = message_info(&user1, &coins(1000, "uusdc"));
-        execute_supply(deps.as_mut(), env.clone(), info, None).unwrap();
+        execute_supply(deps.as_mut(), env.clone(), info, None, None, false).unwrap();
 
-        // Second supply
+        // Second supply in the same block: a different amount is not a duplicate
         let info = message_info(&user1, &coins(500, "uusdc"));
-        execute_supply(deps.as_mut(), env, info, None).unwrap();
+        execute_supply(deps.as_mut(), env, info, None, None, false).unwrap();
 
         // Check accumulated supply
         let supply = SUPPLIES
@@ -299,4 +1111,486 @@ mod tests {
             .unwrap();
         assert_eq!(supply, Uint128::new(1500));
     }
+
+    #[test]
+    fn test_supply_rejects_same_block_duplicate() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let env = mock_env();
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), env.clone(), info, None, None, false).unwrap();
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        let err = execute_supply(deps.as_mut(), env.clone(), info, None, None, false).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PossibleDuplicate {
+                block_height: env.block.height,
+                amount: Uint128::new(1000),
+            }
+        );
+
+        // Only the first supply was credited
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_supply_force_allows_duplicate() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let env = mock_env();
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), env.clone(), info, None, None, false).unwrap();
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), env, info, None, None, true).unwrap();
+
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(2000));
+    }
+
+    #[test]
+    fn test_supply_same_amount_in_next_block_allowed() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let mut env = mock_env();
+
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), env.clone(), info, None, None, false).unwrap();
+
+        env.block.height += 1;
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+        execute_supply(deps.as_mut(), env, info, None, None, false).unwrap();
+
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(2000));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_success() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        // 1000 ATOM ($10,000) supports up to $8000 of debt at 80% LTV
+        let res = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap();
+
+        let collateral = COLLATERAL
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(collateral, Uint128::new(1000));
+        let debt = DEBTS.load(deps.as_ref().storage, user1.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(5000));
+
+        // Only the borrowed funds are sent out
+        assert_eq!(res.messages.len(), 1);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "supply_and_borrow"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "supply_amount" && a.value == "1000"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "borrow_amount" && a.value == "5000"));
+        assert!(res.attributes.iter().any(|a| a.key == "utilization"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "total_collateral" && a.value == "1000"));
+        assert!(!res.attributes.iter().any(|a| a.key == "refund"));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_exceeds_ltv() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        let err = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(8001),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ExceedsLtv { .. }));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_cap_exceeded() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.borrow_cap = Some(Uint128::new(1000));
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        let err = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(1001),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ExceedsBorrowCap { .. }));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_refunds_excess_collateral() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1200, "uatom"));
+
+        let res = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap();
+
+        let collateral = COLLATERAL
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(collateral, Uint128::new(1000));
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_collateral, Uint128::new(1000));
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "refund" && a.value == "200"));
+        assert!(res.messages.iter().any(|m| matches!(
+            &m.msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount })
+                if to_address == user1.as_str() && amount == &coins(200, "uatom")
+        )));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_insufficient_funds() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(999, "uatom"));
+
+        let err = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFundsSent { .. }));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_wrong_denom() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uusdc"));
+
+        let err = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::WrongDenom { .. }));
+    }
+
+    #[test]
+    fn test_supply_and_borrow_disabled_market() {
+        let mut deps = mock_dependencies();
+        setup_market_with_oracle(&mut deps);
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.enabled = false;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &coins(1000, "uatom"));
+
+        let err = execute_supply_and_borrow(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Uint128::new(1000),
+            Uint128::new(5000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MarketDisabled));
+    }
+
+    /// Market where user1 holds 1000 shares at a liquidity index of 1.5.
+    fn setup_market_with_shares(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) -> cosmwasm_std::Addr {
+        setup_market(deps);
+        let user1 = MockApi::default().addr_make("user1");
+
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(1000);
+        state.liquidity_index = Decimal::percent(150);
+        state.last_update = mock_env().block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+        SUPPLIES
+            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
+            .unwrap();
+        user1
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_full() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+        let user2 = MockApi::default().addr_make("user2");
+
+        let res = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        let attr = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+        };
+        assert_eq!(attr("action").as_deref(), Some("transfer_supply_shares"));
+        assert_eq!(attr("from"), Some(user1.to_string()));
+        assert_eq!(attr("to"), Some(user2.to_string()));
+        assert_eq!(attr("shares").as_deref(), Some("1000"));
+        assert_eq!(attr("value").as_deref(), Some("1500"));
+
+        assert!(!SUPPLIES.has(deps.as_ref().storage, user1.as_str()));
+        assert_eq!(
+            SUPPLIES
+                .load(deps.as_ref().storage, user2.as_str())
+                .unwrap(),
+            Uint128::new(1000)
+        );
+        // Market totals are unchanged
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.total_supply_scaled, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_burns_emptied_receipts() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+        let api = MockApi::default();
+        let user2 = api.addr_make("user2");
+
+        let nft_contract = api.addr_make("receipt_nft");
+        let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+        config.receipt_nft_contract = Some(nft_contract.clone());
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        mint_receipt_msg(
+            deps.as_mut().storage,
+            &mock_env(),
+            &nft_contract,
+            &user1,
+            Uint128::new(1500),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        // A partial transfer keeps the receipt
+        let res = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(400),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+
+        // Emptying the position burns it
+        let res = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(600),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, crate::execute::RECEIPT_NFT_REPLY_ID);
+        assert!(crate::state::SUPPLY_RECEIPTS
+            .prefix(user1.as_str())
+            .keys(
+                deps.as_ref().storage,
+                None,
+                None,
+                cosmwasm_std::Order::Ascending
+            )
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_partial() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+        let user2 = MockApi::default().addr_make("user2");
+        SUPPLIES
+            .save(deps.as_mut().storage, user2.as_str(), &Uint128::new(50))
+            .unwrap();
+
+        execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(400),
+        )
+        .unwrap();
+
+        assert_eq!(
+            SUPPLIES
+                .load(deps.as_ref().storage, user1.as_str())
+                .unwrap(),
+            Uint128::new(600)
+        );
+        assert_eq!(
+            SUPPLIES
+                .load(deps.as_ref().storage, user2.as_str())
+                .unwrap(),
+            Uint128::new(450)
+        );
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_insufficient() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+        let user2 = MockApi::default().addr_make("user2");
+
+        let err = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(1001),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientShares { .. }));
+
+        // An address with no position can't transfer either
+        let err = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user2, &[]),
+            user1.to_string(),
+            Uint128::new(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientShares { .. }));
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_to_self_is_noop() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+
+        let res = execute_transfer_supply_shares(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            user1.to_string(),
+            Uint128::new(400),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "transfer_supply_shares"));
+        assert_eq!(
+            SUPPLIES
+                .load(deps.as_ref().storage, user1.as_str())
+                .unwrap(),
+            Uint128::new(1000)
+        );
+    }
+
+    #[test]
+    fn test_transfer_supply_shares_carries_withdraw_cooldown() {
+        let mut deps = mock_dependencies();
+        let user1 = setup_market_with_shares(&mut deps);
+        let user2 = MockApi::default().addr_make("user2");
+        let env = mock_env();
+        LAST_SUPPLY_BLOCK
+            .save(deps.as_mut().storage, user1.as_str(), &env.block.height)
+            .unwrap();
+
+        execute_transfer_supply_shares(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&user1, &[]),
+            user2.to_string(),
+            Uint128::new(400),
+        )
+        .unwrap();
+
+        assert_eq!(
+            LAST_SUPPLY_BLOCK
+                .load(deps.as_ref().storage, user2.as_str())
+                .unwrap(),
+            env.block.height
+        );
+    }
 }
@@ -1,23 +1,43 @@
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response, Uint128};
 
 use crate::error::ContractError;
+use crate::execute::burn_receipt_msgs;
 use crate::interest::{apply_accumulated_interest, get_user_supply};
-use crate::state::{CONFIG, PARAMS, STATE, SUPPLIES};
+use crate::state::{ACCRUED_CURATOR_FEES, CONFIG, LAST_SUPPLY_BLOCK, PARAMS, STATE, SUPPLIES};
 
 /// Withdraw previously supplied debt asset.
+///
+/// With `max_withdraw` the user's full supply position is withdrawn, failing
+/// if available liquidity cannot cover it rather than withdrawing part of it.
 pub fn execute_withdraw(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     amount: Option<Uint128>,
     recipient: Option<String>,
+    max_withdraw: bool,
 ) -> Result<Response, ContractError> {
+    if max_withdraw && amount.is_some() {
+        return Err(ContractError::ConflictingWithdrawParams);
+    }
+
     let config = CONFIG.load(deps.storage)?;
-    let _params = PARAMS.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
 
     // NOTE: Withdraw is ALWAYS allowed regardless of market status
     // so users can always access their supplied funds.
 
+    // Block withdrawals in the same window as a supply so liquidity cannot be
+    // supplied and pulled around another transaction
+    if params.min_supply_blocks > 0 {
+        if let Some(last_supply) = LAST_SUPPLY_BLOCK.may_load(deps.storage, info.sender.as_str())? {
+            let available_at_block = last_supply.saturating_add(params.min_supply_blocks);
+            if env.block.height < available_at_block {
+                return Err(ContractError::WithdrawCooldownActive { available_at_block });
+            }
+        }
+    }
+
     // Apply accumulated interest (fees are accrued to state, not sent immediately)
     apply_accumulated_interest(deps.storage, env.block.time.seconds())?;
 
@@ -31,7 +51,7 @@ pub fn execute_withdraw(
         return Err(ContractError::NoSupply);
     }
 
-    // Determine withdraw amount
+    // Determine withdraw amount (max_withdraw implies amount is None)
     let withdraw_amount = match amount {
         Some(amt) => {
             if amt.is_zero() {
@@ -51,6 +71,20 @@ pub fn execute_withdraw(
         });
     }
 
+    // Keep at least `min_reserve_ratio` of the remaining supply available
+    if !params.min_reserve_ratio.is_zero() {
+        let remaining_supply = state.total_supply().saturating_sub(withdraw_amount);
+        if !remaining_supply.is_zero() {
+            let reserve_ratio = Decimal::from_ratio(available - withdraw_amount, remaining_supply);
+            if reserve_ratio < params.min_reserve_ratio {
+                return Err(ContractError::InsufficientReserve {
+                    reserve_ratio,
+                    min_required: params.min_reserve_ratio,
+                });
+            }
+        }
+    }
+
     // Calculate scaled amount to remove: scaled = amount / index
     let scaled_decrease = stone_types::amount_to_scaled(withdraw_amount, state.liquidity_index)?;
 
@@ -58,8 +92,13 @@ pub fn execute_withdraw(
     let current_scaled = SUPPLIES.may_load(deps.storage, user)?.unwrap_or_default();
     let new_scaled = current_scaled.saturating_sub(scaled_decrease);
 
+    let mut receipt_burns = vec![];
     if new_scaled.is_zero() {
         SUPPLIES.remove(deps.storage, user);
+        // The position is closed, so its supply receipts are burned
+        if let Some(nft_contract) = &config.receipt_nft_contract {
+            receipt_burns = burn_receipt_msgs(deps.storage, nft_contract, &info.sender)?;
+        }
     } else {
         SUPPLIES.save(deps.storage, user, &new_scaled)?;
     }
@@ -83,21 +122,32 @@ pub fn execute_withdraw(
         None => info.sender.clone(),
     };
 
+    // Keep the withdraw fee in the market as an accrued curator fee
+    let withdraw_fee = withdraw_amount.mul_floor(params.withdraw_fee);
+    if !withdraw_fee.is_zero() {
+        let accrued = ACCRUED_CURATOR_FEES
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        ACCRUED_CURATOR_FEES.save(deps.storage, &accrued.checked_add(withdraw_fee)?)?;
+    }
+
     // Create transfer message
     let transfer_msg = BankMsg::Send {
         to_address: recipient_addr.to_string(),
         amount: vec![Coin {
             denom: config.debt_denom,
-            amount: withdraw_amount,
+            amount: withdraw_amount - withdraw_fee,
         }],
     };
 
     Ok(Response::new()
         .add_message(transfer_msg)
+        .add_submessages(receipt_burns)
         .add_attribute("action", "withdraw")
         .add_attribute("withdrawer", info.sender)
         .add_attribute("recipient", recipient_addr.as_str())
         .add_attribute("amount", withdraw_amount)
+        .add_attribute("withdraw_fee_charged", withdraw_fee)
         .add_attribute("scaled_decrease", scaled_decrease)
         .add_attribute("borrow_index", state.borrow_index.to_string())
         .add_attribute("liquidity_index", state.liquidity_index.to_string())
@@ -139,6 +189,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -154,6 +206,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -180,8 +242,15 @@ mod tests {
         let user1 = MockApi::default().addr_make("user1");
         let info = message_info(&user1, &[]);
 
-        let res =
-            execute_withdraw(deps.as_mut(), env, info, Some(Uint128::new(500)), None).unwrap();
+        let res = execute_withdraw(
+            deps.as_mut(),
+            env,
+            info,
+            Some(Uint128::new(500)),
+            None,
+            false,
+        )
+        .unwrap();
 
         // Should have transfer message
         assert!(!res.messages.is_empty());
@@ -202,7 +271,7 @@ mod tests {
         let user1 = MockApi::default().addr_make("user1");
         let info = message_info(&user1, &[]);
 
-        let res = execute_withdraw(deps.as_mut(), env, info, None, None).unwrap();
+        let res = execute_withdraw(deps.as_mut(), env, info, None, None, false).unwrap();
 
         assert!(!res.messages.is_empty());
 
@@ -227,6 +296,7 @@ mod tests {
             info,
             Some(Uint128::new(500)),
             Some(user2.to_string()),
+            false,
         )
         .unwrap();
 
@@ -245,7 +315,7 @@ mod tests {
         let user2 = MockApi::default().addr_make("user2"); // User with no supply
         let info = message_info(&user2, &[]);
 
-        let err = execute_withdraw(deps.as_mut(), env, info, None, None).unwrap_err();
+        let err = execute_withdraw(deps.as_mut(), env, info, None, None, false).unwrap_err();
         assert!(matches!(err, ContractError::NoSupply));
     }
 
@@ -258,8 +328,8 @@ mod tests {
         let user1 = MockApi::default().addr_make("user1");
         let info = message_info(&user1, &[]);
 
-        let err =
-            execute_withdraw(deps.as_mut(), env, info, Some(Uint128::zero()), None).unwrap_err();
+        let err = execute_withdraw(deps.as_mut(), env, info, Some(Uint128::zero()), None, false)
+            .unwrap_err();
         assert!(matches!(err, ContractError::ZeroAmount));
     }
 
@@ -277,8 +347,15 @@ mod tests {
         let user1 = MockApi::default().addr_make("user1");
         let info = message_info(&user1, &[]);
 
-        let err =
-            execute_withdraw(deps.as_mut(), env, info, Some(Uint128::new(1000)), None).unwrap_err();
+        let err = execute_withdraw(
+            deps.as_mut(),
+            env,
+            info,
+            Some(Uint128::new(1000)),
+            None,
+            false,
+        )
+        .unwrap_err();
         assert!(matches!(err, ContractError::InsufficientLiquidity { .. }));
     }
 
@@ -298,6 +375,7 @@ mod tests {
             info,
             Some(Uint128::new(5000)), // More than user has
             None,
+            false,
         )
         .unwrap();
 
@@ -323,8 +401,15 @@ mod tests {
         let info = message_info(&user1, &[]);
 
         // Withdraw should succeed even when market is disabled
-        let res =
-            execute_withdraw(deps.as_mut(), env, info, Some(Uint128::new(500)), None).unwrap();
+        let res = execute_withdraw(
+            deps.as_mut(),
+            env,
+            info,
+            Some(Uint128::new(500)),
+            None,
+            false,
+        )
+        .unwrap();
 
         assert!(!res.messages.is_empty());
         assert!(res
@@ -356,10 +441,304 @@ mod tests {
             info,
             Some(Uint128::new(500)),
             Some("invalid_address".to_string()),
+            false,
         )
         .unwrap_err();
 
         // Should fail with address validation error
         assert!(matches!(err, ContractError::Std(_)));
     }
+
+    #[test]
+    fn test_withdraw_max() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+
+        let env = mock_env();
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        let res = execute_withdraw(deps.as_mut(), env, info, None, None, true).unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "amount" && a.value == "1000"));
+        assert!(!SUPPLIES.has(deps.as_ref().storage, user1.as_str()));
+    }
+
+    #[test]
+    fn test_withdraw_max_insufficient_liquidity() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+
+        // Only 500 available, user has 1000 supplied
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(9500);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let env = mock_env();
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        let err = execute_withdraw(deps.as_mut(), env, info, None, None, true).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientLiquidity { available, requested }
+                if available == "500" && requested == "1000"
+        ));
+
+        // Position is untouched
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_withdraw_max_with_amount_conflicts() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+
+        let env = mock_env();
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        let err = execute_withdraw(
+            deps.as_mut(),
+            env,
+            info,
+            Some(Uint128::new(500)),
+            None,
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ConflictingWithdrawParams));
+    }
+
+    #[test]
+    fn test_withdraw_blocked_below_min_reserve_ratio() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+
+        // 10000 supplied, 8000 borrowed: 2000 available (20% reserve). No time
+        // elapses so interest does not move the indices.
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(8000);
+        state.last_update = mock_env().block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.min_reserve_ratio = Decimal::percent(15);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        // Withdrawing 1000 leaves 1000 / 9000 = 11.1% in reserve
+        let err = execute_withdraw(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Some(Uint128::new(1000)),
+            None,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientReserve {
+                reserve_ratio: Decimal::from_ratio(1000u128, 9000u128),
+                min_required: Decimal::percent(15),
+            }
+        );
+
+        // Supply is untouched
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(1000));
+    }
+
+    #[test]
+    fn test_withdraw_allowed_above_min_reserve_ratio() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+
+        // 10000 supplied, 8000 borrowed: 2000 available (20% reserve). No time
+        // elapses so interest does not move the indices.
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(8000);
+        state.last_update = mock_env().block.time.seconds();
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.min_reserve_ratio = Decimal::percent(15);
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+
+        // Withdrawing 500 leaves 1500 / 9500 = 15.8% in reserve
+        execute_withdraw(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            Some(Uint128::new(500)),
+            None,
+            false,
+        )
+        .unwrap();
+
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(500));
+    }
+
+    /// Set a cooldown of `min_supply_blocks` and record user1's last supply at `supplied_at`.
+    /// Withdraw 500 of user1's supply under `withdraw_fee`, returning the
+    /// amount sent and the fee charged.
+    fn withdraw_with_fee(withdraw_fee: Decimal) -> (Uint128, Uint128) {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.withdraw_fee = withdraw_fee;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        let res = execute_withdraw(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user1, &[]),
+            Some(Uint128::new(500)),
+            None,
+            false,
+        )
+        .unwrap();
+
+        // The full amount leaves the user's position either way
+        let supply = SUPPLIES
+            .load(deps.as_ref().storage, user1.as_str())
+            .unwrap();
+        assert_eq!(supply, Uint128::new(500));
+
+        let sent = match &res.messages[0].msg {
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount[0].amount,
+            other => panic!("unexpected message: {other:?}"),
+        };
+        let charged = res
+            .attributes
+            .iter()
+            .find(|a| a.key == "withdraw_fee_charged")
+            .map(|a| a.value.clone())
+            .unwrap();
+        let accrued = ACCRUED_CURATOR_FEES
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .unwrap_or_default();
+        assert_eq!(charged, accrued.to_string());
+        (sent, accrued)
+    }
+
+    #[test]
+    fn test_withdraw_without_fee() {
+        assert_eq!(
+            withdraw_with_fee(Decimal::zero()),
+            (Uint128::new(500), Uint128::zero())
+        );
+    }
+
+    #[test]
+    fn test_withdraw_charges_fee() {
+        assert_eq!(
+            withdraw_with_fee(Decimal::percent(1)),
+            (Uint128::new(495), Uint128::new(5))
+        );
+    }
+
+    #[test]
+    fn test_withdraw_charges_max_fee() {
+        assert_eq!(
+            withdraw_with_fee(stone_types::MAX_WITHDRAW_FEE),
+            (Uint128::new(490), Uint128::new(10))
+        );
+    }
+
+    fn setup_supply_cooldown(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        min_supply_blocks: u64,
+        supplied_at: u64,
+    ) {
+        let mut params = PARAMS.load(deps.as_ref().storage).unwrap();
+        params.min_supply_blocks = min_supply_blocks;
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let user1 = MockApi::default().addr_make("user1");
+        LAST_SUPPLY_BLOCK
+            .save(deps.as_mut().storage, user1.as_str(), &supplied_at)
+            .unwrap();
+    }
+
+    fn withdraw_at_height(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let mut env = mock_env();
+        env.block.height = height;
+        let user1 = MockApi::default().addr_make("user1");
+        let info = message_info(&user1, &[]);
+        execute_withdraw(
+            deps.as_mut(),
+            env,
+            info,
+            Some(Uint128::new(500)),
+            None,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_withdraw_blocked_during_supply_cooldown() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+        setup_supply_cooldown(&mut deps, 10, 100);
+
+        for height in [100, 109] {
+            let err = withdraw_at_height(&mut deps, height).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::WithdrawCooldownActive {
+                    available_at_block: 110
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_withdraw_allowed_after_supply_cooldown() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+        setup_supply_cooldown(&mut deps, 10, 100);
+
+        withdraw_at_height(&mut deps, 110).unwrap();
+    }
+
+    #[test]
+    fn test_withdraw_without_supply_cooldown() {
+        let mut deps = mock_dependencies();
+        setup_market_with_supply(&mut deps);
+        // Default params have no cooldown, so a same-block withdraw goes through
+        setup_supply_cooldown(&mut deps, 0, 100);
+
+        withdraw_at_height(&mut deps, 100).unwrap();
+    }
 }
@@ -2,11 +2,11 @@ use cosmwasm_std::{Decimal, Decimal256, Deps, Env, Uint128};
 
 use crate::error::ContractError;
 use crate::interest::{get_user_collateral, get_user_debt};
-use crate::math256::{
-    decimal256_to_decimal, decimal_to_decimal256, u128_to_decimal256, uint256_to_uint128,
-};
+use crate::math256::{u128_to_decimal256, uint256_to_uint128};
 use crate::state::{CONFIG, PARAMS};
-use stone_types::{MarketConfig, MarketParams, OracleConfig, OracleQueryMsg, PriceResponse};
+use stone_types::{
+    Decimal256Ext, MarketConfig, MarketParams, OracleConfig, OracleQueryMsg, PriceResponse,
+};
 
 /// Query price from oracle for a denom.
 /// Validates that the price is not stale and not zero.
@@ -16,6 +16,16 @@ pub fn query_price(
     oracle_config: &OracleConfig,
     denom: &str,
 ) -> Result<Decimal, ContractError> {
+    Ok(query_price_response(deps, env, oracle_config, denom)?.price)
+}
+
+/// Like `query_price`, but returns the full oracle response.
+pub fn query_price_response(
+    deps: Deps,
+    env: &Env,
+    oracle_config: &OracleConfig,
+    denom: &str,
+) -> Result<PriceResponse, ContractError> {
     let response: PriceResponse = deps
         .querier
         .query_wasm_smart(
@@ -29,27 +39,8 @@ pub fn query_price(
             reason: e.to_string(),
         })?;
 
-    // Validate timestamp is not in the future (clock skew check)
-    let current_time = env.block.time.seconds();
-    if response.updated_at > current_time {
-        return Err(ContractError::OraclePriceFuture {
-            denom: denom.to_string(),
-            updated_at: response.updated_at,
-            current: current_time,
-        });
-    }
-
-    // Validate staleness
-    let max_staleness = oracle_config.oracle_type.max_staleness_secs();
-    let age_seconds = current_time.saturating_sub(response.updated_at);
-
-    if age_seconds > max_staleness {
-        return Err(ContractError::OraclePriceStale {
-            denom: denom.to_string(),
-            age_seconds,
-            max_staleness,
-        });
-    }
+    // Reject future timestamps (clock skew) and stale prices
+    oracle_config.validate_fresh(&response, env.block.time.seconds())?;
 
     // Validate non-zero price
     if response.price.is_zero() {
@@ -58,7 +49,7 @@ pub fn query_price(
         });
     }
 
-    Ok(response.price)
+    Ok(response)
 }
 
 // ============================================================================
@@ -99,10 +90,10 @@ impl PositionHealth {
 
         let health_factor = self
             .collateral_value
-            .checked_mul(decimal_to_decimal256(self.liquidation_threshold))?
+            .checked_mul(Decimal256::from_decimal(self.liquidation_threshold))?
             .checked_div(self.debt_value)?;
 
-        Ok(Some(decimal256_to_decimal(health_factor)?))
+        Ok(Some(health_factor.to_decimal_checked()?))
     }
 
     /// Check if this position is liquidatable.
@@ -119,7 +110,7 @@ impl PositionHealth {
     pub fn max_borrow_value(&self) -> Result<Decimal256, ContractError> {
         Ok(self
             .collateral_value
-            .checked_mul(decimal_to_decimal256(self.loan_to_value))?)
+            .checked_mul(Decimal256::from_decimal(self.loan_to_value))?)
     }
 
     /// Calculate the maximum amount that can be borrowed in debt tokens.
@@ -135,7 +126,7 @@ impl PositionHealth {
 
         // Convert value back to debt tokens
         let max_borrow =
-            remaining_borrow_value.checked_div(decimal_to_decimal256(self.debt_price))?;
+            remaining_borrow_value.checked_div(Decimal256::from_decimal(self.debt_price))?;
 
         // Convert Decimal256 to Uint128 (truncate), capping at Uint128::MAX
         let max_borrow_u256 = max_borrow.to_uint_floor();
@@ -154,10 +145,36 @@ impl PositionHealth {
         }
 
         let denominator = u128_to_decimal256(self.collateral_amount)
-            .checked_mul(decimal_to_decimal256(self.liquidation_threshold))?;
+            .checked_mul(Decimal256::from_decimal(self.liquidation_threshold))?;
         let liquidation_price = self.debt_value.checked_div(denominator)?;
 
-        Ok(Some(decimal256_to_decimal(liquidation_price)?))
+        Ok(Some(liquidation_price.to_decimal_checked()?))
+    }
+
+    /// Calculate the maximum amount of collateral that can be withdrawn without exceeding LTV.
+    /// max_withdraw = collateral_amount - ceil(debt_value / (collateral_price * LTV))
+    pub fn max_withdraw_amount(&self) -> Result<Uint128, ContractError> {
+        if self.debt_amount.is_zero() {
+            return Ok(self.collateral_amount);
+        }
+
+        let denominator = Decimal256::from_decimal(self.collateral_price)
+            .checked_mul(Decimal256::from_decimal(self.loan_to_value))?;
+        let required_collateral =
+            uint256_to_uint128(self.debt_value.checked_div(denominator)?.to_uint_ceil())?;
+
+        Ok(self.collateral_amount.saturating_sub(required_collateral))
+    }
+
+    /// Calculate the ratio of debt value to collateral value.
+    /// debt_ratio = debt_value / collateral_value
+    pub fn debt_ratio(&self) -> Result<Decimal, ContractError> {
+        if self.debt_amount.is_zero() {
+            return Ok(Decimal::zero());
+        }
+
+        let debt_ratio = self.debt_value.checked_div(self.collateral_value)?;
+        Ok(debt_ratio.to_decimal_checked()?)
     }
 
     /// Create a modified position with additional debt.
@@ -165,7 +182,7 @@ impl PositionHealth {
     pub fn with_additional_debt(&self, additional_debt: Uint128) -> Result<Self, ContractError> {
         let new_debt_amount = self.debt_amount.checked_add(additional_debt)?;
         let new_debt_value = u128_to_decimal256(new_debt_amount)
-            .checked_mul(decimal_to_decimal256(self.debt_price))?;
+            .checked_mul(Decimal256::from_decimal(self.debt_price))?;
 
         Ok(Self {
             collateral_amount: self.collateral_amount,
@@ -188,7 +205,7 @@ impl PositionHealth {
 
         let new_collateral_amount = self.collateral_amount.checked_sub(withdraw_amount)?;
         let new_collateral_value = u128_to_decimal256(new_collateral_amount)
-            .checked_mul(decimal_to_decimal256(self.collateral_price))?;
+            .checked_mul(Decimal256::from_decimal(self.collateral_price))?;
 
         Ok(Self {
             collateral_amount: new_collateral_amount,
@@ -210,14 +227,35 @@ impl PositionHealth {
 
         if position_after.debt_value > max_borrow_value {
             return Err(ContractError::ExceedsLtv {
-                max_borrow: decimal256_to_decimal(max_borrow_value)?.to_string(),
-                requested: decimal256_to_decimal(position_after.debt_value)?.to_string(),
+                max_borrow: max_borrow_value.to_decimal_checked()?.to_string(),
+                requested: position_after.debt_value.to_decimal_checked()?.to_string(),
             });
         }
 
         Ok(())
     }
 
+    /// Check that the health factor after borrowing `borrow_amount` stays at or
+    /// above `min_health_factor`.
+    pub fn check_post_borrow_health_factor(
+        &self,
+        borrow_amount: Uint128,
+        min_health_factor: Decimal,
+    ) -> Result<(), ContractError> {
+        let position_after = self.with_additional_debt(borrow_amount)?;
+
+        if let Some(health_factor) = position_after.health_factor()? {
+            if health_factor < min_health_factor {
+                return Err(ContractError::HealthFactorTooLow {
+                    required: min_health_factor,
+                    actual: health_factor,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if withdrawing collateral would make the position unhealthy.
     /// Uses LTV for withdrawal check (more conservative than liquidation threshold).
     /// Returns Ok(()) if the withdrawal is allowed, Err otherwise.
@@ -268,21 +306,20 @@ pub fn calculate_position_health_with_config(
     let collateral_amount = get_user_collateral(deps.storage, user)?;
     let debt_amount = get_user_debt(deps.storage, user)?;
 
-    let collateral_price = query_price(deps, env, &config.oracle_config, &config.collateral_denom)?;
-    let debt_price = query_price(deps, env, &config.oracle_config, &config.debt_denom)?;
+    let collateral_price =
+        query_price_response(deps, env, &config.oracle_config, &config.collateral_denom)?;
+    let debt_price = query_price_response(deps, env, &config.oracle_config, &config.debt_denom)?;
 
-    let collateral_value = u128_to_decimal256(collateral_amount)
-        .checked_mul(decimal_to_decimal256(collateral_price))?;
-    let debt_value =
-        u128_to_decimal256(debt_amount).checked_mul(decimal_to_decimal256(debt_price))?;
+    let collateral_value = collateral_price.collateral_value(collateral_amount)?;
+    let debt_value = debt_price.debt_value(debt_amount)?;
 
     Ok(PositionHealth {
         collateral_amount,
         debt_amount,
         collateral_value,
         debt_value,
-        collateral_price,
-        debt_price,
+        collateral_price: collateral_price.price,
+        debt_price: debt_price.price,
         loan_to_value: params.loan_to_value,
         liquidation_threshold: params.liquidation_threshold,
     })
@@ -319,7 +356,8 @@ pub fn calculate_max_borrow(deps: Deps, env: &Env, user: &str) -> Result<Uint128
     position.max_borrow_amount()
 }
 
-/// Check if a borrow would exceed LTV.
+/// Check if a borrow would exceed LTV or drop below the market's minimum
+/// post-borrow health factor.
 /// Uses Decimal256 internally to prevent overflow with large token amounts.
 pub fn check_borrow_allowed(
     deps: Deps,
@@ -327,8 +365,30 @@ pub fn check_borrow_allowed(
     user: &str,
     borrow_amount: Uint128,
 ) -> Result<(), ContractError> {
-    let position = calculate_position_health(deps, env, user)?;
-    position.check_borrow_allowed(borrow_amount)
+    let config = CONFIG.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    check_borrow_allowed_with_config(deps, env, user, borrow_amount, &config, &params)
+}
+
+/// Check if a borrow is allowed with provided config and params.
+/// Useful when caller already has config/params loaded.
+pub fn check_borrow_allowed_with_config(
+    deps: Deps,
+    env: &Env,
+    user: &str,
+    borrow_amount: Uint128,
+    config: &MarketConfig,
+    params: &MarketParams,
+) -> Result<(), ContractError> {
+    let position = calculate_position_health_with_config(deps, env, user, config, params)?;
+    position.check_borrow_allowed(borrow_amount)?;
+
+    if let Some(min_health_factor) = params.min_post_borrow_health_factor {
+        position.check_post_borrow_health_factor(borrow_amount, min_health_factor)?;
+    }
+
+    Ok(())
 }
 
 /// Check if a collateral withdrawal would make the position unhealthy.
@@ -370,7 +430,8 @@ mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, MockQuerier};
     use cosmwasm_std::{from_json, to_json_binary, Addr, ContractResult, QuerierResult, WasmQuery};
     use stone_types::{
-        InterestRateModel, MarketConfig, MarketParams, MarketState, OracleConfig, OracleType,
+        errors::TypesError, InterestRateModel, MarketConfig, MarketParams, MarketState,
+        OracleConfig, OracleType,
     };
 
     // Base timestamp for tests (~Nov 2023)
@@ -413,6 +474,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: Addr::unchecked("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -429,6 +492,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -844,11 +917,11 @@ mod tests {
         assert!(
             matches!(
                 &result,
-                Err(ContractError::OraclePriceStale {
+                Err(ContractError::Types(TypesError::OraclePriceStale {
                     denom,
                     age_seconds: 301,
                     max_staleness: 300
-                }) if denom == "uatom"
+                })) if denom == "uatom"
             ),
             "Expected OraclePriceStale error, got {:?}",
             result
@@ -972,11 +1045,11 @@ mod tests {
         assert!(
             matches!(
                 &result,
-                Err(ContractError::OraclePriceFuture {
+                Err(ContractError::Types(TypesError::OraclePriceFuture {
                     denom,
                     updated_at,
                     current
-                }) if denom == "uatom" && *updated_at == BASE_TIMESTAMP + 100 && *current == BASE_TIMESTAMP
+                })) if denom == "uatom" && *updated_at == BASE_TIMESTAMP + 100 && *current == BASE_TIMESTAMP
             ),
             "Expected OraclePriceFuture error, got {:?}",
             result
@@ -1,55 +1,147 @@
 use cosmwasm_std::{Decimal, Storage, Uint128};
+use stone_types::{InterestAccrualEvent, InterestRateModel, MarketParams, MarketState};
 
 use crate::error::ContractError;
-use crate::state::{ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, PARAMS, STATE};
+use crate::state::{
+    record_interest_accrual, ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, PARAMS, STATE,
+};
 
 /// Seconds per year for interest calculations
 pub const SECONDS_PER_YEAR: u64 = 31_536_000;
 
+/// Result of accruing interest up to a point in time, without persisting it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterestAccrual {
+    /// Market state with updated indices, rates, and last_update
+    pub state: MarketState,
+    /// Protocol fees accrued during the elapsed period
+    pub protocol_fee_amount: Uint128,
+    /// Curator fees accrued during the elapsed period
+    pub curator_fee_amount: Uint128,
+}
+
 /// Apply accumulated interest to the market state.
 /// This updates borrow_index, liquidity_index, and accrues fees.
 /// Fees are stored as claimable balances rather than being sent immediately,
 /// since interest is virtual (index-based) and tokens don't exist until borrowers repay.
+/// Accruals that move the indices are appended to `INTEREST_ACCRUAL_EVENTS`.
 pub fn apply_accumulated_interest(
     storage: &mut dyn Storage,
     current_time: u64,
 ) -> Result<(), ContractError> {
-    let mut state = STATE.load(storage)?;
     let params = PARAMS.load(storage)?;
+    apply_accumulated_interest_with_params(storage, &params, current_time)
+}
+
+/// Apply accumulated interest with provided params.
+/// Useful when caller already has params loaded.
+pub fn apply_accumulated_interest_with_params(
+    storage: &mut dyn Storage,
+    params: &MarketParams,
+    current_time: u64,
+) -> Result<(), ContractError> {
+    let previous = STATE.load(storage)?;
+    let accrual = project_interest(previous.clone(), params, current_time)?;
+
+    STATE.save(storage, &accrual.state)?;
+
+    // Only accruals that actually moved the indices are worth keeping
+    if accrual.state.borrow_index != previous.borrow_index
+        || accrual.state.liquidity_index != previous.liquidity_index
+    {
+        record_interest_accrual(
+            storage,
+            &InterestAccrualEvent {
+                borrow_index: accrual.state.borrow_index,
+                liquidity_index: accrual.state.liquidity_index,
+                borrow_rate: accrual.state.borrow_rate,
+                liquidity_rate: accrual.state.liquidity_rate,
+                timestamp: current_time,
+                elapsed_secs: current_time - previous.last_update,
+            },
+        )?;
+    }
+
+    // Accrue fees to claimable balances (instead of sending immediately)
+    // This fixes C-2: Fees are virtual (index-based) and tokens don't exist until borrowers repay
+    if !accrual.protocol_fee_amount.is_zero() {
+        let current = ACCRUED_PROTOCOL_FEES.may_load(storage)?.unwrap_or_default();
+        let new = current.checked_add(accrual.protocol_fee_amount)?;
+        ACCRUED_PROTOCOL_FEES.save(storage, &new)?;
+    }
+
+    if !accrual.curator_fee_amount.is_zero() {
+        let current = ACCRUED_CURATOR_FEES.may_load(storage)?.unwrap_or_default();
+        let new = current.checked_add(accrual.curator_fee_amount)?;
+        ACCRUED_CURATOR_FEES.save(storage, &new)?;
+    }
+
+    Ok(())
+}
+
+/// Calculate the market state as it would be after applying accumulated interest,
+/// without writing anything to storage. Used by queries that need current-as-of-block values.
+pub fn simulate_accumulated_interest(
+    storage: &dyn Storage,
+    current_time: u64,
+) -> Result<InterestAccrual, ContractError> {
+    let state = STATE.load(storage)?;
+    let params = PARAMS.load(storage)?;
+
+    project_interest(state, &params, current_time)
+}
+
+/// Project the utilization at `target_time`, assuming no new supplies, borrows,
+/// repayments, or withdrawals in between. Debt and supply grow exactly as a single
+/// `apply_accumulated_interest` call at `target_time` would grow them.
+/// Useful for off-chain APY calculations over a specific window.
+pub fn get_utilization_at_time(
+    state: &MarketState,
+    params: &MarketParams,
+    target_time: u64,
+) -> Result<Decimal, ContractError> {
+    if target_time < state.last_update {
+        return Err(ContractError::TargetTimeBeforeLastUpdate {
+            target_time,
+            last_update: state.last_update,
+        });
+    }
 
+    let accrual = project_interest(state.clone(), params, target_time)?;
+    Ok(accrual.state.utilization())
+}
+
+/// Accrue interest on `state` up to `current_time` as a pure function of state and params.
+fn project_interest(
+    mut state: MarketState,
+    params: &MarketParams,
+    current_time: u64,
+) -> Result<InterestAccrual, ContractError> {
     let time_elapsed = current_time.saturating_sub(state.last_update);
 
     // Calculate current utilization and rates (even if no time elapsed, rates should reflect current utilization)
-    let utilization = state.utilization();
-    let borrow_rate = params
-        .interest_rate_model
-        .calculate_borrow_rate(utilization);
-    let liquidity_rate = if utilization.is_zero() {
-        Decimal::zero()
-    } else {
-        let fee_share = Decimal::one()
-            .checked_sub(params.protocol_fee)?
-            .checked_sub(params.curator_fee)?;
-        borrow_rate
-            .checked_mul(utilization)?
-            .checked_mul(fee_share)?
-    };
+    let (borrow_rate, liquidity_rate) = calculate_rates(params, state.utilization())?;
 
-    // If no time elapsed, just update rates and return (no interest accrual or index changes)
+    state.borrow_rate = borrow_rate;
+    state.liquidity_rate = liquidity_rate;
+
+    // If no time elapsed, just update rates (no interest accrual or index changes)
     if time_elapsed == 0 {
-        state.borrow_rate = borrow_rate;
-        state.liquidity_rate = liquidity_rate;
-        STATE.save(storage, &state)?;
-        return Ok(());
+        return Ok(InterestAccrual {
+            state,
+            protocol_fee_amount: Uint128::zero(),
+            curator_fee_amount: Uint128::zero(),
+        });
     }
 
     // If no debt, update timestamp and rates
     if state.total_debt_scaled.is_zero() {
         state.last_update = current_time;
-        state.borrow_rate = borrow_rate;
-        state.liquidity_rate = liquidity_rate;
-        STATE.save(storage, &state)?;
-        return Ok(());
+        return Ok(InterestAccrual {
+            state,
+            protocol_fee_amount: Uint128::zero(),
+            curator_fee_amount: Uint128::zero(),
+        });
     }
 
     // Calculate borrow index increase
@@ -88,30 +180,16 @@ pub fn apply_accumulated_interest(
         }
     };
 
-    // Update state (rates were already calculated above)
+    // Update state (rates were already set above)
     state.borrow_index = new_borrow_index;
     state.liquidity_index = new_liquidity_index;
-    state.borrow_rate = borrow_rate;
-    state.liquidity_rate = liquidity_rate;
     state.last_update = current_time;
 
-    STATE.save(storage, &state)?;
-
-    // Accrue fees to claimable balances (instead of sending immediately)
-    // This fixes C-2: Fees are virtual (index-based) and tokens don't exist until borrowers repay
-    if !protocol_fee_amount.is_zero() {
-        let current = ACCRUED_PROTOCOL_FEES.may_load(storage)?.unwrap_or_default();
-        let new = current.checked_add(protocol_fee_amount)?;
-        ACCRUED_PROTOCOL_FEES.save(storage, &new)?;
-    }
-
-    if !curator_fee_amount.is_zero() {
-        let current = ACCRUED_CURATOR_FEES.may_load(storage)?.unwrap_or_default();
-        let new = current.checked_add(curator_fee_amount)?;
-        ACCRUED_CURATOR_FEES.save(storage, &new)?;
-    }
-
-    Ok(())
+    Ok(InterestAccrual {
+        state,
+        protocol_fee_amount,
+        curator_fee_amount,
+    })
 }
 
 /// Get current user supply amount (unscaled).
@@ -145,29 +223,39 @@ pub fn get_user_collateral(storage: &dyn Storage, user: &str) -> Result<Uint128,
 
 /// Calculate current rates based on utilization and interest rate model.
 /// This should be called after state updates to get accurate rates for events.
-pub fn calculate_current_rates(
-    storage: &dyn Storage,
-) -> Result<(cosmwasm_std::Decimal, cosmwasm_std::Decimal), ContractError> {
-    use cosmwasm_std::Decimal;
+pub fn calculate_current_rates(storage: &dyn Storage) -> Result<(Decimal, Decimal), ContractError> {
+    let params = PARAMS.load(storage)?;
 
+    calculate_current_rates_with_params(storage, &params)
+}
+
+/// Calculate current rates with provided params.
+/// Useful when caller already has params loaded.
+pub fn calculate_current_rates_with_params(
+    storage: &dyn Storage,
+    params: &MarketParams,
+) -> Result<(Decimal, Decimal), ContractError> {
     let state = STATE.load(storage)?;
-    let params = PARAMS.load(storage)?;
 
-    let utilization = state.utilization();
+    calculate_rates(params, state.utilization())
+}
+
+/// Borrow and liquidity rates for a given utilization. The liquidity rate is the
+/// borrow rate scaled by utilization, net of protocol and curator fees.
+pub fn calculate_rates(
+    params: &MarketParams,
+    utilization: Decimal,
+) -> Result<(Decimal, Decimal), ContractError> {
     let borrow_rate = params
         .interest_rate_model
         .calculate_borrow_rate(utilization);
 
-    let liquidity_rate = if utilization.is_zero() {
-        Decimal::zero()
-    } else {
-        let fee_share = Decimal::one()
-            .checked_sub(params.protocol_fee)?
-            .checked_sub(params.curator_fee)?;
-        borrow_rate
-            .checked_mul(utilization)?
-            .checked_mul(fee_share)?
-    };
+    let liquidity_rate = InterestRateModel::calculate_liquidity_rate(
+        borrow_rate,
+        utilization,
+        params.protocol_fee,
+        params.curator_fee,
+    )?;
 
     Ok((borrow_rate, liquidity_rate))
 }
@@ -175,7 +263,9 @@ pub fn calculate_current_rates(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::{ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, CONFIG, STATE};
+    use crate::state::{
+        ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, CONFIG, INTEREST_ACCRUAL_EVENTS, STATE,
+    };
     use cosmwasm_std::testing::mock_dependencies;
     use cosmwasm_std::Addr;
     use stone_types::{
@@ -203,6 +293,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: Addr::unchecked("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -223,6 +315,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -331,6 +433,79 @@ mod tests {
         assert!(!curator_fees.is_zero());
     }
 
+    #[test]
+    fn test_interest_accrual_records_history() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        // No debt: the timestamp moves but the indices don't, so nothing is recorded
+        apply_accumulated_interest(deps.as_mut().storage, 2000).unwrap();
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS.len(deps.as_ref().storage).unwrap(),
+            0
+        );
+
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(5000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        apply_accumulated_interest(deps.as_mut().storage, 2000 + SECONDS_PER_YEAR).unwrap();
+        // Same block again: no elapsed time, no new entry
+        apply_accumulated_interest(deps.as_mut().storage, 2000 + SECONDS_PER_YEAR).unwrap();
+
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS.len(deps.as_ref().storage).unwrap(),
+            1
+        );
+        let event = INTEREST_ACCRUAL_EVENTS
+            .back(deps.as_ref().storage)
+            .unwrap()
+            .unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(event.borrow_index, state.borrow_index);
+        assert_eq!(event.liquidity_index, state.liquidity_index);
+        // Rates in effect over the period (50% utilization)
+        assert_eq!(event.borrow_rate, Decimal::from_ratio(25u128, 1000u128));
+        assert!(!event.liquidity_rate.is_zero());
+        assert_eq!(event.timestamp, 2000 + SECONDS_PER_YEAR);
+        assert_eq!(event.elapsed_secs, SECONDS_PER_YEAR);
+    }
+
+    #[test]
+    fn test_simulate_interest_matches_apply_without_writing() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(5000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let accrual =
+            simulate_accumulated_interest(deps.as_ref().storage, 1000 + SECONDS_PER_YEAR).unwrap();
+
+        // Nothing persisted by the simulation
+        assert_eq!(STATE.load(deps.as_ref().storage).unwrap(), state);
+        assert!(ACCRUED_PROTOCOL_FEES
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .unwrap_or_default()
+            .is_zero());
+
+        apply_accumulated_interest(deps.as_mut().storage, 1000 + SECONDS_PER_YEAR).unwrap();
+
+        assert_eq!(STATE.load(deps.as_ref().storage).unwrap(), accrual.state);
+        assert_eq!(
+            ACCRUED_PROTOCOL_FEES.load(deps.as_ref().storage).unwrap(),
+            accrual.protocol_fee_amount
+        );
+        assert_eq!(
+            ACCRUED_CURATOR_FEES.load(deps.as_ref().storage).unwrap(),
+            accrual.curator_fee_amount
+        );
+    }
+
     #[test]
     fn test_fee_accrual_at_100_percent_utilization() {
         // This test verifies the fix for M-3: Market Freeze at 100% Utilization
@@ -425,4 +600,53 @@ mod tests {
         let collateral = get_user_collateral(deps.as_ref().storage, "user1").unwrap();
         assert_eq!(collateral, Uint128::new(1000));
     }
+
+    #[test]
+    fn test_utilization_at_time_matches_applied_interest() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(5000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+
+        for elapsed in [0, 3600, SECONDS_PER_YEAR / 2, SECONDS_PER_YEAR] {
+            let target_time = 1000 + elapsed;
+            let projected = get_utilization_at_time(&state, &params, target_time).unwrap();
+
+            let mut applied_deps = mock_dependencies();
+            setup_market(&mut applied_deps);
+            STATE.save(applied_deps.as_mut().storage, &state).unwrap();
+            apply_accumulated_interest(applied_deps.as_mut().storage, target_time).unwrap();
+            let applied = STATE.load(applied_deps.as_ref().storage).unwrap();
+
+            assert_eq!(projected, applied.utilization(), "elapsed = {}", elapsed);
+        }
+
+        // Utilization rises over time as debt interest outpaces supplier interest
+        let now = get_utilization_at_time(&state, &params, 1000).unwrap();
+        let later = get_utilization_at_time(&state, &params, 1000 + SECONDS_PER_YEAR).unwrap();
+        assert_eq!(now, Decimal::percent(50));
+        assert!(later > now);
+    }
+
+    #[test]
+    fn test_utilization_at_time_rejects_past_target() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        let params = PARAMS.load(deps.as_ref().storage).unwrap();
+
+        let err = get_utilization_at_time(&state, &params, 999).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::TargetTimeBeforeLastUpdate {
+                target_time: 999,
+                last_update: 1000
+            }
+        ));
+    }
 }
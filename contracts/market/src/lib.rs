@@ -9,4 +9,5 @@ mod query;
 mod state;
 
 pub use error::ContractError;
+pub use interest::get_utilization_at_time;
 pub use state::*;
@@ -1,6 +1,7 @@
 use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint256};
 
 use crate::error::ContractError;
+use stone_types::Decimal256Ext;
 
 /// Convert Uint128 to Decimal256 for intermediate calculations that need wider range.
 /// This prevents overflow when multiplying large token amounts by prices.
@@ -8,17 +9,15 @@ pub fn u128_to_decimal256(amount: Uint128) -> Decimal256 {
     Decimal256::from_ratio(Uint256::from(amount), Uint256::one())
 }
 
-/// Convert Decimal to Decimal256 using native cosmwasm-std `From` trait.
-pub fn decimal_to_decimal256(decimal: Decimal) -> Decimal256 {
-    Decimal256::from(decimal)
-}
-
-/// Convert Decimal256 back to Decimal using native cosmwasm-std `TryFrom` trait.
-/// Returns an error if the value exceeds the Decimal range.
-pub fn decimal256_to_decimal(value: Decimal256) -> Result<Decimal, ContractError> {
-    Decimal::try_from(value).map_err(|_| ContractError::MathOverflow {
-        reason: "Decimal256 value too large for Decimal".to_string(),
-    })
+/// Value of supply shares at the given liquidity index, rounded down.
+/// Computed in Decimal256 so large share balances cannot overflow the product.
+pub fn shares_to_amount(
+    shares: Uint128,
+    liquidity_index: Decimal,
+) -> Result<Uint128, ContractError> {
+    let value =
+        u128_to_decimal256(shares).checked_mul(Decimal256::from_decimal(liquidity_index))?;
+    uint256_to_uint128(value.to_uint_floor())
 }
 
 /// Convert Uint256 to Uint128 using native cosmwasm-std `TryFrom` trait.
@@ -48,32 +47,6 @@ mod tests {
         assert!(result > Decimal256::zero());
     }
 
-    #[test]
-    fn test_decimal_to_decimal256_native() {
-        let decimal = Decimal::percent(85);
-        let result = decimal_to_decimal256(decimal);
-        assert_eq!(result, Decimal256::percent(85));
-    }
-
-    #[test]
-    fn test_decimal256_to_decimal_native() {
-        let value = Decimal256::percent(170);
-        let result = decimal256_to_decimal(value).unwrap();
-        assert_eq!(result, Decimal::percent(170));
-    }
-
-    #[test]
-    fn test_decimal256_to_decimal_overflow() {
-        // Decimal256 can represent values much larger than Decimal
-        // Decimal max ≈ 340282366920938463463.374607431768211455
-        // Create a value that exceeds Decimal range
-        let huge = Decimal256::from_ratio(Uint256::from(u128::MAX), Uint256::one())
-            .checked_mul(Decimal256::from_ratio(1000u128, 1u128))
-            .unwrap();
-        let result = decimal256_to_decimal(huge);
-        assert!(result.is_err());
-    }
-
     #[test]
     fn test_uint256_to_uint128_success() {
         let value = Uint256::from(42u128);
@@ -0,0 +1,155 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::error::ContractResult;
+use crate::execute::claimable_fees;
+use crate::interest::simulate_accumulated_interest;
+use crate::state::{ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, CONFIG};
+use stone_types::ClaimableFeesPreviewResponse;
+
+/// What each fee recipient would get from `ClaimFees` at the current block.
+/// Fees are accrued up to the current block first, as `ClaimFees` does. If the
+/// curator is also the fee collector, one claim takes both amounts.
+pub fn claimable_fees_preview(
+    deps: Deps,
+    env: Env,
+) -> ContractResult<ClaimableFeesPreviewResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let accrual = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let accrued_protocol = ACCRUED_PROTOCOL_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(accrual.protocol_fee_amount)?;
+    let accrued_curator = ACCRUED_CURATOR_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(accrual.curator_fee_amount)?;
+    let available_liquidity = accrual.state.available_liquidity();
+
+    let same_recipient = config.protocol_fee_collector == config.curator;
+    let (protocol_claimable, _) = claimable_fees(
+        available_liquidity,
+        accrued_protocol,
+        accrued_curator,
+        true,
+        same_recipient,
+    );
+    let (_, curator_claimable) = claimable_fees(
+        available_liquidity,
+        accrued_protocol,
+        accrued_curator,
+        same_recipient,
+        true,
+    );
+
+    Ok(ClaimableFeesPreviewResponse {
+        protocol_claimable,
+        curator_claimable,
+        available_liquidity,
+        fee_coverage_partial: available_liquidity
+            < accrued_protocol.checked_add(accrued_curator)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{PARAMS, STATE};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{Timestamp, Uint128};
+    use stone_testing::default_stored_market_params;
+    use stone_types::{MarketConfig, MarketState, OracleConfig, OracleType};
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        total_debt_scaled: u128,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(total_debt_scaled);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        ACCRUED_PROTOCOL_FEES
+            .save(deps.as_mut().storage, &Uint128::new(1000))
+            .unwrap();
+        ACCRUED_CURATOR_FEES
+            .save(deps.as_mut().storage, &Uint128::new(500))
+            .unwrap();
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_claimable_fees_preview_full_coverage() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, 5000);
+
+        let preview = claimable_fees_preview(deps.as_ref(), env_at(BASE_TIMESTAMP)).unwrap();
+        assert_eq!(preview.protocol_claimable, Uint128::new(1000));
+        assert_eq!(preview.curator_claimable, Uint128::new(500));
+        assert_eq!(preview.available_liquidity, Uint128::new(5000));
+        assert!(!preview.fee_coverage_partial);
+    }
+
+    #[test]
+    fn test_claimable_fees_preview_partial_coverage() {
+        let mut deps = mock_dependencies();
+        // 800 available against 1500 accrued
+        setup_market(&mut deps, 9200);
+
+        let preview = claimable_fees_preview(deps.as_ref(), env_at(BASE_TIMESTAMP)).unwrap();
+        assert_eq!(preview.protocol_claimable, Uint128::new(800));
+        assert_eq!(preview.curator_claimable, Uint128::new(500));
+        assert_eq!(preview.available_liquidity, Uint128::new(800));
+        assert!(preview.fee_coverage_partial);
+    }
+
+    #[test]
+    fn test_claimable_fees_preview_shared_recipient() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, 9200);
+
+        // The curator also collects protocol fees: one claim, protocol fees first
+        let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+        config.protocol_fee_collector = config.curator.clone();
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let preview = claimable_fees_preview(deps.as_ref(), env_at(BASE_TIMESTAMP)).unwrap();
+        assert_eq!(preview.protocol_claimable, Uint128::new(800));
+        assert_eq!(preview.curator_claimable, Uint128::zero());
+        assert!(preview.fee_coverage_partial);
+    }
+}
@@ -0,0 +1,223 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::error::{ContractError, ContractResult};
+use crate::health::calculate_position_health;
+use stone_types::{HealthMetric, HealthMetrics};
+
+/// Complete risk profile for a user, computed from a single position health load.
+/// A metric that fails to calculate reports its error instead of failing the query,
+/// so partially-invalid positions still return the metrics that can be computed.
+pub fn health_metrics(deps: Deps, env: Env, user: String) -> ContractResult<HealthMetrics> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let position = calculate_position_health(deps, &env, user_addr.as_str())?;
+
+    Ok(HealthMetrics {
+        collateral_amount: position.collateral_amount,
+        debt_amount: position.debt_amount,
+        health_factor: to_metric(position.health_factor()),
+        liquidation_price: to_metric(position.liquidation_price()),
+        max_borrow: to_metric(position.max_borrow_amount().map(Some)),
+        max_withdraw: to_metric(position.max_withdraw_amount().map(Some)),
+        debt_ratio: to_metric(position.debt_ratio().map(Some)),
+        is_liquidatable: to_metric(position.is_liquidatable().map(Some)),
+    })
+}
+
+fn to_metric<T>(result: Result<Option<T>, ContractError>) -> HealthMetric<T> {
+    match result {
+        Ok(value) => HealthMetric {
+            value,
+            calculation_error: None,
+        },
+        Err(e) => HealthMetric {
+            value: None,
+            calculation_error: Some(e.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{COLLATERAL, CONFIG, DEBTS, PARAMS, STATE};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{
+        from_json, to_json_binary, ContractResult as CwContractResult, Decimal, SystemResult,
+        Timestamp, Uint128, WasmQuery,
+    };
+    use stone_testing::default_stored_market_params;
+    use stone_types::{
+        MarketConfig, MarketState, OracleConfig, OracleQueryMsg, OracleType, PriceResponse,
+    };
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+        STATE
+            .save(deps.as_mut().storage, &MarketState::new(BASE_TIMESTAMP))
+            .unwrap();
+
+        // Collateral at $10, debt at $1
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = if denom == "uatom" {
+                    Decimal::from_ratio(10u128, 1u128)
+                } else {
+                    Decimal::one()
+                };
+                let response = PriceResponse {
+                    denom,
+                    price,
+                    updated_at: BASE_TIMESTAMP,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_health_metrics_healthy_position() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        // 1000 collateral ($10,000), 4000 debt ($4,000)
+        COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+        DEBTS
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(4000))
+            .unwrap();
+
+        let metrics =
+            health_metrics(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+
+        // HF = 10000 * 0.85 / 4000 = 2.125
+        assert_eq!(
+            metrics.health_factor.value,
+            Some(Decimal::from_ratio(2125u128, 1000u128))
+        );
+        // LP = 4000 / (1000 * 0.85)
+        assert_eq!(
+            metrics.liquidation_price.value,
+            Some(Decimal::from_ratio(4000u128, 850u128))
+        );
+        // Max borrow = 10000 * 0.80 - 4000 = 4000
+        assert_eq!(metrics.max_borrow.value, Some(Uint128::new(4000)));
+        // Required collateral = 4000 / (10 * 0.80) = 500
+        assert_eq!(metrics.max_withdraw.value, Some(Uint128::new(500)));
+        assert_eq!(metrics.debt_ratio.value, Some(Decimal::percent(40)));
+        assert_eq!(metrics.is_liquidatable.value, Some(false));
+        assert!(metrics.health_factor.calculation_error.is_none());
+    }
+
+    #[test]
+    fn test_health_metrics_no_debt() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let metrics =
+            health_metrics(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+
+        assert_eq!(metrics.health_factor.value, None);
+        assert!(metrics.health_factor.calculation_error.is_none());
+        assert_eq!(metrics.liquidation_price.value, None);
+        assert_eq!(metrics.max_borrow.value, Some(Uint128::new(8000)));
+        assert_eq!(metrics.max_withdraw.value, Some(Uint128::new(1000)));
+        assert_eq!(metrics.debt_ratio.value, Some(Decimal::zero()));
+        assert_eq!(metrics.is_liquidatable.value, Some(false));
+    }
+
+    #[test]
+    fn test_health_metrics_reports_per_field_errors() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        // Extreme debt against dust collateral overflows the Decimal range for
+        // liquidation price and debt ratio, but the other metrics still compute
+        COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1))
+            .unwrap();
+        DEBTS
+            .save(
+                deps.as_mut().storage,
+                user.as_str(),
+                &Uint128::new(u128::MAX / 10),
+            )
+            .unwrap();
+
+        let metrics =
+            health_metrics(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+
+        assert_eq!(metrics.liquidation_price.value, None);
+        assert!(metrics.liquidation_price.calculation_error.is_some());
+        assert_eq!(metrics.debt_ratio.value, None);
+        assert!(metrics.debt_ratio.calculation_error.is_some());
+
+        assert_eq!(metrics.max_borrow.value, Some(Uint128::zero()));
+        assert_eq!(metrics.max_withdraw.value, Some(Uint128::zero()));
+        assert_eq!(metrics.is_liquidatable.value, Some(true));
+        assert!(metrics.is_liquidatable.calculation_error.is_none());
+    }
+
+    #[test]
+    fn test_health_metrics_stale_price_fails_query() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        let result = health_metrics(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP + 301),
+            user.to_string(),
+        );
+        assert!(matches!(
+            result,
+            Err(ContractError::Types(
+                stone_types::errors::TypesError::OraclePriceStale { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+use cosmwasm_std::Deps;
+
+use crate::error::ContractResult;
+use crate::state::{INTEREST_ACCRUAL_EVENTS, MAX_INTEREST_HISTORY};
+use stone_types::InterestHistoryResponse;
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Most recent index-moving interest accruals, newest first.
+pub fn interest_history(deps: Deps, limit: Option<u32>) -> ContractResult<InterestHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_INTEREST_HISTORY) as usize;
+
+    let events = INTEREST_ACCRUAL_EVENTS
+        .iter(deps.storage)?
+        .rev()
+        .take(limit)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(InterestHistoryResponse { events })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::record_interest_accrual;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::Decimal;
+    use stone_types::InterestAccrualEvent;
+
+    #[test]
+    fn test_interest_history_newest_first_and_limited() {
+        let mut deps = mock_dependencies();
+        for i in 0..250u64 {
+            let event = InterestAccrualEvent {
+                borrow_index: Decimal::one(),
+                liquidity_index: Decimal::one(),
+                borrow_rate: Decimal::percent(5),
+                liquidity_rate: Decimal::percent(2),
+                timestamp: 1000 + i,
+                elapsed_secs: 1,
+            };
+            record_interest_accrual(deps.as_mut().storage, &event).unwrap();
+        }
+
+        let res = interest_history(deps.as_ref(), None).unwrap();
+        assert_eq!(res.events.len(), DEFAULT_LIMIT as usize);
+        assert_eq!(res.events[0].timestamp, 1249);
+        assert_eq!(res.events[1].timestamp, 1248);
+
+        let res = interest_history(deps.as_ref(), Some(500)).unwrap();
+        assert_eq!(res.events.len(), MAX_INTEREST_HISTORY as usize);
+        assert_eq!(res.events.last().unwrap().timestamp, 1050);
+    }
+}
@@ -0,0 +1,76 @@
+use cosmwasm_std::Deps;
+
+use crate::error::ContractResult;
+use crate::state::{LIQUIDATION_HISTORY, MAX_LIQUIDATION_HISTORY};
+use stone_types::LiquidationHistoryResponse;
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Most recent liquidations from the audit trail, newest first.
+pub fn liquidation_history(
+    deps: Deps,
+    limit: Option<u32>,
+) -> ContractResult<LiquidationHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIQUIDATION_HISTORY) as usize;
+
+    let liquidations = LIQUIDATION_HISTORY
+        .iter(deps.storage)?
+        .rev()
+        .take(limit)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(LiquidationHistoryResponse { liquidations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::record_liquidation;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{Decimal, Uint128};
+    use stone_types::LiquidationEvent;
+
+    fn record(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        count: u64,
+    ) {
+        for i in 0..count {
+            let event = LiquidationEvent {
+                borrower: format!("borrower{i}"),
+                debt_repaid: Uint128::new(100),
+                collateral_seized: Uint128::new(10),
+                health_factor_before: Decimal::percent(95),
+                timestamp: 1000 + i,
+            };
+            record_liquidation(deps.as_mut().storage, &event).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_liquidation_history_newest_first() {
+        let mut deps = mock_dependencies();
+        record(&mut deps, 3);
+
+        let res = liquidation_history(deps.as_ref(), None).unwrap();
+        let timestamps: Vec<u64> = res.liquidations.iter().map(|l| l.timestamp).collect();
+        assert_eq!(timestamps, vec![1002, 1001, 1000]);
+    }
+
+    #[test]
+    fn test_liquidation_history_limit() {
+        let mut deps = mock_dependencies();
+        record(&mut deps, 60);
+
+        let res = liquidation_history(deps.as_ref(), None).unwrap();
+        assert_eq!(res.liquidations.len(), DEFAULT_LIMIT as usize);
+        assert_eq!(res.liquidations[0].timestamp, 1059);
+
+        let res = liquidation_history(deps.as_ref(), Some(100)).unwrap();
+        assert_eq!(res.liquidations.len(), MAX_LIQUIDATION_HISTORY as usize);
+        assert_eq!(res.liquidations.last().unwrap().timestamp, 1010);
+    }
+}
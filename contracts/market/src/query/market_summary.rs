@@ -0,0 +1,198 @@
+use cosmwasm_std::{Decimal, Decimal256, Deps, Env};
+
+use crate::error::ContractResult;
+use crate::interest::simulate_accumulated_interest;
+use crate::math256::u128_to_decimal256;
+use crate::state::{ACCRUED_CURATOR_FEES, ACCRUED_PROTOCOL_FEES, CONFIG};
+use stone_types::{
+    Decimal256Ext, MarketSummaryResponse, OracleConfig, OracleQueryMsg, PriceResponse,
+};
+
+/// Maximum age of the debt asset price used for `total_supply_usd`.
+pub const SUMMARY_PRICE_MAX_AGE_SECS: u64 = 300;
+
+/// Market-wide snapshot with interest accrued up to the current block.
+/// Interest is simulated rather than applied, so the query never writes state.
+pub fn market_summary(deps: Deps, env: Env) -> ContractResult<MarketSummaryResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let current_time = env.block.time.seconds();
+
+    let accrual = simulate_accumulated_interest(deps.storage, current_time)?;
+    let state = accrual.state;
+
+    let accrued_protocol_fees = ACCRUED_PROTOCOL_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(accrual.protocol_fee_amount)?;
+    let accrued_curator_fees = ACCRUED_CURATOR_FEES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(accrual.curator_fee_amount)?;
+    let total_accrued_fees = accrued_protocol_fees.checked_add(accrued_curator_fees)?;
+
+    let total_supply = state.total_supply();
+
+    // USD value is best-effort: a missing, stale, or zero price yields None
+    // instead of failing the whole summary
+    let total_supply_usd = match recent_price(
+        deps,
+        &config.oracle_config,
+        current_time,
+        &config.debt_denom,
+    ) {
+        Some(price) => Some(
+            u128_to_decimal256(total_supply)
+                .checked_mul(Decimal256::from_decimal(price))?
+                .to_decimal_checked()?,
+        ),
+        None => None,
+    };
+
+    Ok(MarketSummaryResponse {
+        total_supply,
+        total_debt: state.total_debt(),
+        total_collateral: state.total_collateral,
+        utilization: state.utilization(),
+        borrow_rate: state.borrow_rate,
+        supply_rate: state.liquidity_rate,
+        total_accrued_fees,
+        total_supply_usd,
+        last_update: state.last_update,
+    })
+}
+
+/// Query the oracle for a price updated within `SUMMARY_PRICE_MAX_AGE_SECS`.
+fn recent_price(
+    deps: Deps,
+    oracle_config: &OracleConfig,
+    current_time: u64,
+    denom: &str,
+) -> Option<Decimal> {
+    let response: PriceResponse = deps
+        .querier
+        .query_wasm_smart(
+            oracle_config.address.as_str(),
+            &OracleQueryMsg::Price {
+                denom: denom.to_string(),
+            },
+        )
+        .ok()?;
+
+    let fresh = response.updated_at <= current_time
+        && current_time - response.updated_at <= SUMMARY_PRICE_MAX_AGE_SECS;
+    if !fresh || response.price.is_zero() {
+        return None;
+    }
+
+    Some(response.price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interest::SECONDS_PER_YEAR;
+    use crate::state::{PARAMS, STATE};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{
+        from_json, to_json_binary, ContractResult as CwContractResult, SystemResult, Timestamp,
+        Uint128, WasmQuery,
+    };
+    use stone_testing::default_stored_market_params;
+    use stone_types::{MarketConfig, MarketState, OracleType};
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        price_updated_at: u64,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let mut state = MarketState::new(1000);
+        state.total_supply_scaled = Uint128::new(10000);
+        state.total_debt_scaled = Uint128::new(5000);
+        state.total_collateral = Uint128::new(2000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let response = PriceResponse {
+                    denom,
+                    price: Decimal::percent(200),
+                    updated_at: price_updated_at,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_market_summary_simulates_interest() {
+        let mut deps = mock_dependencies();
+        let now = 1000 + SECONDS_PER_YEAR;
+        setup_market(&mut deps, now);
+
+        let summary = market_summary(deps.as_ref(), env_at(now)).unwrap();
+
+        // Stored state is untouched but the summary includes a year of interest
+        let stored = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(stored.last_update, 1000);
+        assert!(summary.total_debt > Uint128::new(5000));
+        assert!(summary.total_supply > Uint128::new(10000));
+        assert!(!summary.total_accrued_fees.is_zero());
+        assert_eq!(summary.total_collateral, Uint128::new(2000));
+        assert_eq!(summary.last_update, now);
+        assert!(summary.borrow_rate > summary.supply_rate);
+
+        // Debt asset priced at $2
+        assert_eq!(
+            summary.total_supply_usd,
+            Some(Decimal::from_ratio(summary.total_supply.u128() * 2, 1u128))
+        );
+    }
+
+    #[test]
+    fn test_market_summary_stale_price_omits_usd() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, 1000);
+
+        let summary =
+            market_summary(deps.as_ref(), env_at(1000 + SUMMARY_PRICE_MAX_AGE_SECS + 1)).unwrap();
+        assert_eq!(summary.total_supply_usd, None);
+
+        let summary =
+            market_summary(deps.as_ref(), env_at(1000 + SUMMARY_PRICE_MAX_AGE_SECS)).unwrap();
+        assert!(summary.total_supply_usd.is_some());
+    }
+}
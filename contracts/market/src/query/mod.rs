@@ -1,4 +1,26 @@
-use cosmwasm_std::{Decimal, Deps, Env};
+mod claimable_fees;
+mod health_metrics;
+mod interest_history;
+mod liquidation_history;
+mod market_summary;
+mod preview_borrow;
+mod rates;
+mod shares;
+mod simulate_liquidation;
+mod user_supply;
+
+pub use claimable_fees::*;
+pub use health_metrics::*;
+pub use interest_history::*;
+pub use liquidation_history::*;
+pub use market_summary::*;
+pub use preview_borrow::*;
+pub use rates::*;
+pub use shares::*;
+pub use simulate_liquidation::*;
+pub use user_supply::*;
+
+use cosmwasm_std::{Decimal, Decimal256, Deps, Env, Uint128};
 
 use crate::error::ContractResult;
 use crate::health::{
@@ -6,11 +28,11 @@ use crate::health::{
     query_price,
 };
 use crate::interest::{get_user_collateral, get_user_debt, get_user_supply};
-use crate::math256::{decimal256_to_decimal, decimal_to_decimal256, u128_to_decimal256};
-use crate::state::{CONFIG, PARAMS, STATE};
+use crate::math256::u128_to_decimal256;
+use crate::state::{CONFIG, PARAMS, REFERRAL_VOLUMES, SOCIALIZED_LOSSES, STATE};
 use stone_types::{
-    IsLiquidatableResponse, MarketConfigResponse, MarketParamsResponse, MarketStateResponse,
-    UserBalanceResponse, UserPositionResponse,
+    Decimal256Ext, IsLiquidatableResponse, MarketConfigResponse, MarketParams,
+    MarketParamsResponse, MarketStateResponse, UserBalanceResponse, UserPositionResponse,
 };
 
 pub fn config(deps: Deps) -> ContractResult<MarketConfigResponse> {
@@ -24,26 +46,65 @@ pub fn config(deps: Deps) -> ContractResult<MarketConfigResponse> {
         debt_denom: config.debt_denom,
         protocol_fee_collector: config.protocol_fee_collector.to_string(),
         salt: config.salt,
+        allow_collateral_swap: config.allow_collateral_swap,
+        receipt_nft_contract: config.receipt_nft_contract.map(|addr| addr.to_string()),
     })
 }
 
+/// Every stored market parameter. `MarketParams` is destructured without `..`
+/// so a new field fails to compile until it is exposed here.
 pub fn params(deps: Deps) -> ContractResult<MarketParamsResponse> {
-    let params = PARAMS.load(deps.storage)?;
+    let MarketParams {
+        loan_to_value,
+        liquidation_threshold,
+        liquidation_bonus,
+        liquidation_protocol_fee,
+        close_factor,
+        dust_debt_threshold,
+        interest_rate_model,
+        protocol_fee,
+        curator_fee,
+        supply_cap,
+        borrow_cap,
+        min_borrow_amount,
+        min_reserve_ratio,
+        min_supply_blocks,
+        max_liquidation_value_usd,
+        collateral_whitelist_enabled,
+        min_post_borrow_health_factor,
+        protocol_borrow_fee_flat,
+        auto_clear_dust,
+        liquidation_bonus_by_health_factor,
+        withdraw_fee,
+        enabled,
+        is_mutable,
+        ltv_last_update,
+    } = PARAMS.load(deps.storage)?;
     Ok(MarketParamsResponse {
-        loan_to_value: params.loan_to_value,
-        liquidation_threshold: params.liquidation_threshold,
-        liquidation_bonus: params.liquidation_bonus,
-        liquidation_protocol_fee: params.liquidation_protocol_fee,
-        close_factor: params.close_factor,
-        dust_debt_threshold: params.dust_debt_threshold,
-        interest_rate_model: params.interest_rate_model,
-        protocol_fee: params.protocol_fee,
-        curator_fee: params.curator_fee,
-        supply_cap: params.supply_cap,
-        borrow_cap: params.borrow_cap,
-        enabled: params.enabled,
-        is_mutable: params.is_mutable,
-        ltv_last_update: params.ltv_last_update,
+        loan_to_value,
+        liquidation_threshold,
+        liquidation_bonus,
+        liquidation_protocol_fee,
+        close_factor,
+        dust_debt_threshold,
+        interest_rate_model,
+        protocol_fee,
+        curator_fee,
+        supply_cap,
+        borrow_cap,
+        min_borrow_amount,
+        min_reserve_ratio,
+        min_supply_blocks,
+        max_liquidation_value_usd,
+        collateral_whitelist_enabled,
+        min_post_borrow_health_factor,
+        protocol_borrow_fee_flat,
+        auto_clear_dust,
+        liquidation_bonus_by_health_factor,
+        withdraw_fee,
+        enabled,
+        is_mutable,
+        ltv_last_update,
     })
 }
 
@@ -86,16 +147,15 @@ pub fn user_position(deps: Deps, env: Env, user: String) -> ContractResult<UserP
     let debt_price = query_price(deps, &env, &config.oracle_config, &config.debt_denom)?;
 
     // Calculate values using Decimal256 to prevent overflow with large amounts
-    let collateral_value = decimal256_to_decimal(
-        u128_to_decimal256(collateral_amount)
-            .checked_mul(decimal_to_decimal256(collateral_price))?,
-    )?;
-    let supply_value = decimal256_to_decimal(
-        u128_to_decimal256(supply_amount).checked_mul(decimal_to_decimal256(debt_price))?,
-    )?;
-    let debt_value = decimal256_to_decimal(
-        u128_to_decimal256(debt_amount).checked_mul(decimal_to_decimal256(debt_price))?,
-    )?;
+    let collateral_value = u128_to_decimal256(collateral_amount)
+        .checked_mul(Decimal256::from_decimal(collateral_price))?
+        .to_decimal_checked()?;
+    let supply_value = u128_to_decimal256(supply_amount)
+        .checked_mul(Decimal256::from_decimal(debt_price))?
+        .to_decimal_checked()?;
+    let debt_value = u128_to_decimal256(debt_amount)
+        .checked_mul(Decimal256::from_decimal(debt_price))?
+        .to_decimal_checked()?;
 
     // Calculate health factor
     let health_factor = calculate_health_factor(deps, &env, user_str)
@@ -104,9 +164,9 @@ pub fn user_position(deps: Deps, env: Env, user: String) -> ContractResult<UserP
     // Calculate max borrow
     let max_borrow = calculate_max_borrow(deps, &env, user_str)
         .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
-    let max_borrow_value = decimal256_to_decimal(
-        u128_to_decimal256(max_borrow).checked_mul(decimal_to_decimal256(debt_price))?,
-    )?;
+    let max_borrow_value = u128_to_decimal256(max_borrow)
+        .checked_mul(Decimal256::from_decimal(debt_price))?
+        .to_decimal_checked()?;
 
     // Calculate liquidation price
     let liquidation_price = calculate_liquidation_price(deps, &env, user_str)
@@ -125,30 +185,6 @@ pub fn user_position(deps: Deps, env: Env, user: String) -> ContractResult<UserP
     })
 }
 
-pub fn user_supply(deps: Deps, env: Env, user: String) -> ContractResult<UserBalanceResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
-
-    let user_addr = deps.api.addr_validate(&user)?;
-
-    let scaled = crate::state::SUPPLIES
-        .may_load(deps.storage, user_addr.as_str())?
-        .unwrap_or_default();
-    let amount = stone_types::scaled_to_amount(scaled, state.liquidity_index);
-
-    let debt_price = query_price(deps, &env, &config.oracle_config, &config.debt_denom)
-        .unwrap_or(Decimal::zero());
-    let value = decimal256_to_decimal(
-        u128_to_decimal256(amount).checked_mul(decimal_to_decimal256(debt_price))?,
-    )?;
-
-    Ok(UserBalanceResponse {
-        scaled,
-        amount,
-        value,
-    })
-}
-
 pub fn user_collateral(deps: Deps, env: Env, user: String) -> ContractResult<UserBalanceResponse> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -160,9 +196,9 @@ pub fn user_collateral(deps: Deps, env: Env, user: String) -> ContractResult<Use
 
     let collateral_price = query_price(deps, &env, &config.oracle_config, &config.collateral_denom)
         .unwrap_or(Decimal::zero());
-    let value = decimal256_to_decimal(
-        u128_to_decimal256(amount).checked_mul(decimal_to_decimal256(collateral_price))?,
-    )?;
+    let value = u128_to_decimal256(amount)
+        .checked_mul(Decimal256::from_decimal(collateral_price))?
+        .to_decimal_checked()?;
 
     Ok(UserBalanceResponse {
         scaled: amount, // Collateral is not scaled
@@ -185,9 +221,9 @@ pub fn user_debt(deps: Deps, env: Env, user: String) -> ContractResult<UserBalan
 
     let debt_price = query_price(deps, &env, &config.oracle_config, &config.debt_denom)
         .unwrap_or(Decimal::zero());
-    let value = decimal256_to_decimal(
-        u128_to_decimal256(amount).checked_mul(decimal_to_decimal256(debt_price))?,
-    )?;
+    let value = u128_to_decimal256(amount)
+        .checked_mul(Decimal256::from_decimal(debt_price))?
+        .to_decimal_checked()?;
 
     Ok(UserBalanceResponse {
         scaled,
@@ -242,6 +278,21 @@ pub fn accrued_fees(deps: Deps) -> ContractResult<stone_types::AccruedFeesRespon
     })
 }
 
+/// Total debt asset supplied under `referrer`. Zero if it never referred a supply.
+pub fn referral_volume(deps: Deps, referrer: String) -> ContractResult<Uint128> {
+    let referrer_addr = deps.api.addr_validate(&referrer)?;
+    Ok(REFERRAL_VOLUMES
+        .may_load(deps.storage, referrer_addr.as_str())?
+        .unwrap_or_default())
+}
+
+/// Total bad debt written off across all liquidations. Zero if none occurred.
+pub fn socialized_losses(deps: Deps) -> ContractResult<Uint128> {
+    Ok(SOCIALIZED_LOSSES
+        .may_load(deps.storage)?
+        .unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,6 +337,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: api.addr_make("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
 
@@ -301,6 +354,16 @@ mod tests {
             curator_fee: Decimal::percent(5),
             supply_cap: None,
             borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
             enabled: true,
             is_mutable: false,
             ltv_last_update: 0,
@@ -364,6 +427,89 @@ mod tests {
         assert!(result.enabled);
     }
 
+    #[test]
+    fn test_query_params_returns_every_field() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        // Non-default values everywhere so a swapped or dropped field shows up
+        let stored = MarketParams {
+            loan_to_value: Decimal::percent(70),
+            liquidation_threshold: Decimal::percent(75),
+            liquidation_bonus: Decimal::percent(6),
+            liquidation_protocol_fee: Decimal::percent(3),
+            close_factor: Decimal::percent(40),
+            dust_debt_threshold: Uint128::new(250),
+            interest_rate_model: InterestRateModel::Linear {
+                optimal_utilization: Decimal::percent(90),
+                base_rate: Decimal::percent(1),
+                slope_1: Decimal::percent(5),
+                slope_2: Decimal::percent(200),
+            },
+            protocol_fee: Decimal::percent(12),
+            curator_fee: Decimal::percent(4),
+            supply_cap: Some(Uint128::new(1_000_000)),
+            borrow_cap: Some(Uint128::new(500_000)),
+            min_borrow_amount: Uint128::new(10),
+            min_reserve_ratio: Decimal::percent(5),
+            min_supply_blocks: 3,
+            max_liquidation_value_usd: Some(Decimal::from_ratio(50_000u128, 1u128)),
+            collateral_whitelist_enabled: true,
+            min_post_borrow_health_factor: Some(Decimal::percent(110)),
+            protocol_borrow_fee_flat: Uint128::new(7),
+            auto_clear_dust: true,
+            liquidation_bonus_by_health_factor: vec![(Decimal::percent(95), Decimal::percent(8))],
+            withdraw_fee: Decimal::zero(),
+            enabled: false,
+            is_mutable: true,
+            ltv_last_update: 1_700_000_123,
+        };
+        PARAMS.save(deps.as_mut().storage, &stored).unwrap();
+
+        let result = params(deps.as_ref()).unwrap();
+        assert_eq!(result.loan_to_value, stored.loan_to_value);
+        assert_eq!(result.liquidation_threshold, stored.liquidation_threshold);
+        assert_eq!(result.liquidation_bonus, stored.liquidation_bonus);
+        assert_eq!(
+            result.liquidation_protocol_fee,
+            stored.liquidation_protocol_fee
+        );
+        assert_eq!(result.close_factor, stored.close_factor);
+        assert_eq!(result.dust_debt_threshold, stored.dust_debt_threshold);
+        assert_eq!(result.interest_rate_model, stored.interest_rate_model);
+        assert_eq!(result.protocol_fee, stored.protocol_fee);
+        assert_eq!(result.curator_fee, stored.curator_fee);
+        assert_eq!(result.supply_cap, stored.supply_cap);
+        assert_eq!(result.borrow_cap, stored.borrow_cap);
+        assert_eq!(result.min_borrow_amount, stored.min_borrow_amount);
+        assert_eq!(result.min_reserve_ratio, stored.min_reserve_ratio);
+        assert_eq!(result.min_supply_blocks, stored.min_supply_blocks);
+        assert_eq!(
+            result.max_liquidation_value_usd,
+            stored.max_liquidation_value_usd
+        );
+        assert_eq!(
+            result.collateral_whitelist_enabled,
+            stored.collateral_whitelist_enabled
+        );
+        assert_eq!(
+            result.min_post_borrow_health_factor,
+            stored.min_post_borrow_health_factor
+        );
+        assert_eq!(
+            result.protocol_borrow_fee_flat,
+            stored.protocol_borrow_fee_flat
+        );
+        assert_eq!(result.auto_clear_dust, stored.auto_clear_dust);
+        assert_eq!(
+            result.liquidation_bonus_by_health_factor,
+            stored.liquidation_bonus_by_health_factor
+        );
+        assert_eq!(result.enabled, stored.enabled);
+        assert_eq!(result.is_mutable, stored.is_mutable);
+        assert_eq!(result.ltv_last_update, stored.ltv_last_update);
+    }
+
     #[test]
     fn test_query_state() {
         let mut deps = mock_dependencies();
@@ -376,24 +522,6 @@ mod tests {
         assert_eq!(result.total_collateral, Uint128::new(2000));
     }
 
-    #[test]
-    fn test_query_user_supply() {
-        let mut deps = mock_dependencies();
-        let _curator = setup_market(&mut deps);
-
-        let api = MockApi::default();
-        let user1 = api.addr_make("user1");
-
-        crate::state::SUPPLIES
-            .save(deps.as_mut().storage, user1.as_str(), &Uint128::new(1000))
-            .unwrap();
-
-        let env = mock_env_at_time(BASE_TIMESTAMP);
-        let result = user_supply(deps.as_ref(), env, user1.to_string()).unwrap();
-        assert_eq!(result.scaled, Uint128::new(1000));
-        assert_eq!(result.amount, Uint128::new(1000)); // index = 1
-    }
-
     #[test]
     fn test_query_user_collateral() {
         let mut deps = mock_dependencies();
@@ -0,0 +1,242 @@
+use cosmwasm_std::{Deps, Env, Uint128};
+
+use crate::error::{ContractError, ContractResult};
+use crate::execute::{borrowable_liquidity, check_borrow_limits};
+use crate::health::calculate_position_health;
+use crate::state::{PARAMS, STATE};
+use stone_types::PreviewBorrowResponse;
+
+/// Simulate a borrow of `amount` by `user` against the current position and
+/// market limits. Never fails: anything that would make the borrow fail is
+/// reported with `allowed = false` and a description in `error`.
+pub fn preview_borrow(
+    deps: Deps,
+    env: Env,
+    user: String,
+    amount: Uint128,
+) -> ContractResult<PreviewBorrowResponse> {
+    Ok(
+        simulate_borrow(deps, &env, &user, amount).unwrap_or_else(|e| PreviewBorrowResponse {
+            allowed: false,
+            health_factor_after: None,
+            max_allowed_amount: Uint128::zero(),
+            error: Some(e.to_string()),
+        }),
+    )
+}
+
+fn simulate_borrow(
+    deps: Deps,
+    env: &Env,
+    user: &str,
+    amount: Uint128,
+) -> Result<PreviewBorrowResponse, ContractError> {
+    let user_addr = deps.api.addr_validate(user)?;
+    let params = PARAMS.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    let liquidity = borrowable_liquidity(deps.storage, &state, user_addr.as_str())?.total();
+
+    let position = calculate_position_health(deps, env, user_addr.as_str())?;
+    let max_allowed_amount = position.max_borrow_amount()?.min(liquidity);
+    let health_factor_after = position.with_additional_debt(amount)?.health_factor()?;
+
+    let check = check_borrow_limits(deps.storage, &params, &state, amount, liquidity)
+        .and_then(|_| position.check_borrow_allowed(amount))
+        .and_then(|_| match params.min_post_borrow_health_factor {
+            Some(min_health_factor) => {
+                position.check_post_borrow_health_factor(amount, min_health_factor)
+            }
+            None => Ok(()),
+        });
+
+    Ok(PreviewBorrowResponse {
+        allowed: check.is_ok(),
+        health_factor_after,
+        max_allowed_amount,
+        error: check.err().map(|e| e.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{COLLATERAL, CONFIG, DEBTS};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{
+        from_json, to_json_binary, ContractResult as CwContractResult, Decimal, SystemResult,
+        Timestamp, WasmQuery,
+    };
+    use stone_testing::default_stored_market_params;
+    use stone_types::{
+        MarketConfig, MarketState, OracleConfig, OracleQueryMsg, OracleType, PriceResponse,
+    };
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        // 100_000 available liquidity
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.total_supply_scaled = Uint128::new(100_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        // Collateral at $10, debt at $1
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = if denom == "uatom" {
+                    Decimal::from_ratio(10u128, 1u128)
+                } else {
+                    Decimal::one()
+                };
+                let response = PriceResponse {
+                    denom,
+                    price,
+                    updated_at: BASE_TIMESTAMP,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_preview_borrow_allowed() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        // 1000 collateral ($10,000), 4000 debt ($4,000)
+        COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+        DEBTS
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(4000))
+            .unwrap();
+
+        let preview = preview_borrow(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP),
+            user.to_string(),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        assert!(preview.allowed);
+        assert_eq!(preview.error, None);
+        // HF = 10000 * 0.85 / 5000 = 1.7
+        assert_eq!(
+            preview.health_factor_after,
+            Some(Decimal::from_ratio(17u128, 10u128))
+        );
+        // Max borrow = 10000 * 0.80 - 4000 = 4000
+        assert_eq!(preview.max_allowed_amount, Uint128::new(4000));
+
+        // Preview does not touch the position
+        let debt = DEBTS.load(deps.as_ref().storage, user.as_str()).unwrap();
+        assert_eq!(debt, Uint128::new(4000));
+    }
+
+    #[test]
+    fn test_preview_borrow_exceeds_ltv() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        COLLATERAL
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let preview = preview_borrow(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP),
+            user.to_string(),
+            Uint128::new(9000),
+        )
+        .unwrap();
+
+        assert!(!preview.allowed);
+        assert!(preview.error.unwrap().contains("exceed LTV limit"));
+        // HF = 10000 * 0.85 / 9000
+        assert_eq!(
+            preview.health_factor_after,
+            Some(Decimal::from_ratio(8500u128, 9000u128))
+        );
+        assert_eq!(preview.max_allowed_amount, Uint128::new(8000));
+    }
+
+    #[test]
+    fn test_preview_borrow_zero_collateral() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        let preview = preview_borrow(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP),
+            user.to_string(),
+            Uint128::new(100),
+        )
+        .unwrap();
+
+        assert!(!preview.allowed);
+        assert!(preview.error.is_some());
+        assert_eq!(preview.health_factor_after, Some(Decimal::zero()));
+        assert_eq!(preview.max_allowed_amount, Uint128::zero());
+    }
+
+    #[test]
+    fn test_preview_borrow_reports_oracle_failure() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let user = MockApi::default().addr_make("user1");
+
+        // Stale price makes the position health calculation fail
+        let preview = preview_borrow(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP + 301),
+            user.to_string(),
+            Uint128::new(100),
+        )
+        .unwrap();
+
+        assert!(!preview.allowed);
+        assert!(preview.error.unwrap().contains("stale"));
+        assert_eq!(preview.health_factor_after, None);
+    }
+}
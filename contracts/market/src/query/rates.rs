@@ -0,0 +1,99 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::error::ContractResult;
+use crate::interest::{calculate_rates, simulate_accumulated_interest};
+use crate::state::PARAMS;
+use stone_types::CurrentRatesResponse;
+
+/// Borrow and supply rates as of the current block. Interest since the last
+/// interaction is simulated first, so the rates reflect the utilization the
+/// next `apply_accumulated_interest` would leave behind rather than the stale
+/// rates stored in state.
+pub fn current_rates(deps: Deps, env: Env) -> ContractResult<CurrentRatesResponse> {
+    let params = PARAMS.load(deps.storage)?;
+    let current_time = env.block.time.seconds();
+
+    let accrual = simulate_accumulated_interest(deps.storage, current_time)?;
+    let utilization = accrual.state.utilization();
+    let (borrow_rate, supply_rate) = calculate_rates(&params, utilization)?;
+
+    Ok(CurrentRatesResponse {
+        borrow_rate,
+        supply_rate,
+        utilization,
+        timestamp: current_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interest::{apply_accumulated_interest, calculate_current_rates, SECONDS_PER_YEAR};
+    use crate::state::STATE;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Decimal, Timestamp, Uint128};
+    use stone_testing::default_stored_market_params;
+    use stone_types::MarketState;
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+    ) {
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        // 50% utilization
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.total_supply_scaled = Uint128::new(1_000_000);
+        state.total_debt_scaled = Uint128::new(500_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_current_rates_without_elapsed_time() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+
+        let rates = current_rates(deps.as_ref(), env_at(BASE_TIMESTAMP)).unwrap();
+        let (borrow_rate, supply_rate) = calculate_current_rates(deps.as_ref().storage).unwrap();
+
+        assert_eq!(rates.utilization, Decimal::percent(50));
+        assert_eq!(rates.borrow_rate, borrow_rate);
+        assert_eq!(rates.supply_rate, supply_rate);
+        assert_eq!(rates.timestamp, BASE_TIMESTAMP);
+    }
+
+    #[test]
+    fn test_current_rates_match_post_accrual_rates() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps);
+        let now = BASE_TIMESTAMP + SECONDS_PER_YEAR;
+
+        let (stale_borrow_rate, _) = calculate_current_rates(deps.as_ref().storage).unwrap();
+        let rates = current_rates(deps.as_ref(), env_at(now)).unwrap();
+
+        // Debt grows faster than supply, so utilization and the borrow rate rise
+        assert!(rates.utilization > Decimal::percent(50));
+        assert!(rates.borrow_rate > stale_borrow_rate);
+        assert_eq!(rates.timestamp, now);
+
+        apply_accumulated_interest(deps.as_mut().storage, now).unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        let (borrow_rate, supply_rate) = calculate_current_rates(deps.as_ref().storage).unwrap();
+
+        assert_eq!(rates.utilization, state.utilization());
+        assert_eq!(rates.borrow_rate, borrow_rate);
+        assert_eq!(rates.supply_rate, supply_rate);
+    }
+}
@@ -0,0 +1,170 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::error::ContractResult;
+use crate::interest::simulate_accumulated_interest;
+use crate::math256::shares_to_amount;
+use crate::state::{STATE, SUPPLIES};
+use stone_types::{UserShareBalanceResponse, UserShareValueResponse};
+
+/// Raw supply share balance of a user alongside the total outstanding shares.
+pub fn user_share_balance(deps: Deps, user: String) -> ContractResult<UserShareBalanceResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let state = STATE.load(deps.storage)?;
+
+    let shares = SUPPLIES
+        .may_load(deps.storage, user_addr.as_str())?
+        .unwrap_or_default();
+
+    Ok(UserShareBalanceResponse {
+        shares,
+        total_shares: state.total_supply_scaled,
+    })
+}
+
+/// Value of a user's supply shares at the liquidity index projected to the
+/// current block. Interest is simulated rather than applied, so the query
+/// never writes state.
+pub fn user_share_value(
+    deps: Deps,
+    env: Env,
+    user: String,
+) -> ContractResult<UserShareValueResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let accrual = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?;
+
+    let shares = SUPPLIES
+        .may_load(deps.storage, user_addr.as_str())?
+        .unwrap_or_default();
+    let value = shares_to_amount(shares, accrual.state.liquidity_index)?;
+
+    Ok(UserShareValueResponse { shares, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interest::SECONDS_PER_YEAR;
+    use crate::state::PARAMS;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{Decimal, Timestamp, Uint128};
+    use stone_testing::default_stored_market_params;
+    use stone_types::{amount_to_scaled, MarketState};
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        liquidity_index: Decimal,
+    ) {
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.liquidity_index = liquidity_index;
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+    }
+
+    /// Record a supply the same way `execute_supply` does.
+    fn supply(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        user: &str,
+        amount: Uint128,
+    ) -> Uint128 {
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        let shares = amount_to_scaled(amount, state.liquidity_index).unwrap();
+        SUPPLIES.save(deps.as_mut().storage, user, &shares).unwrap();
+        state.total_supply_scaled += shares;
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+        shares
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_share_round_trip_at_unit_index() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::one());
+        let user = MockApi::default().addr_make("user1");
+
+        let shares = supply(&mut deps, user.as_str(), Uint128::new(1_000_000));
+
+        let balance = user_share_balance(deps.as_ref(), user.to_string()).unwrap();
+        assert_eq!(balance.shares, shares);
+        assert_eq!(balance.total_shares, shares);
+
+        let value =
+            user_share_value(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        assert_eq!(value.value, Uint128::new(1_000_000));
+    }
+
+    #[test]
+    fn test_share_round_trip_never_overstates_value() {
+        let mut deps = mock_dependencies();
+        // Index that does not divide evenly into the supplied amount
+        setup_market(&mut deps, Decimal::from_ratio(3u128, 2u128));
+        let user = MockApi::default().addr_make("user1");
+
+        let amount = Uint128::new(1_000_001);
+        let shares = supply(&mut deps, user.as_str(), amount);
+        assert_eq!(shares, Uint128::new(666_667));
+
+        let value =
+            user_share_value(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        // Rounding loses at most one unit and always in the protocol's favor
+        assert!(value.value <= amount);
+        assert!(amount - value.value <= Uint128::one());
+    }
+
+    #[test]
+    fn test_share_value_large_balance_does_not_overflow() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::percent(150));
+        let user = MockApi::default().addr_make("user1");
+
+        let shares = Uint128::new(u128::MAX / 2);
+        SUPPLIES
+            .save(deps.as_mut().storage, user.as_str(), &shares)
+            .unwrap();
+
+        let value =
+            user_share_value(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        assert_eq!(value.value, Uint128::new(shares.u128() + shares.u128() / 2));
+    }
+
+    #[test]
+    fn test_share_value_includes_unaccrued_interest() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::one());
+        let user = MockApi::default().addr_make("user1");
+        supply(&mut deps, user.as_str(), Uint128::new(1_000_000));
+
+        // 50% utilization so suppliers earn interest
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(500_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let later = user_share_value(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP + SECONDS_PER_YEAR),
+            user.to_string(),
+        )
+        .unwrap();
+        assert!(later.value > Uint128::new(1_000_000));
+
+        // Shares themselves are unchanged by interest
+        let balance = user_share_balance(deps.as_ref(), user.to_string()).unwrap();
+        assert_eq!(balance.shares, later.shares);
+    }
+}
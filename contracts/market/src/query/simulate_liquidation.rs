@@ -0,0 +1,386 @@
+use cosmwasm_std::{Decimal, Decimal256, Deps, Env, Uint128};
+
+use crate::error::{ContractError, ContractResult};
+use crate::execute::{calculate_liquidation_amounts, LiquidationAmounts};
+use crate::health::{query_price, PositionHealth};
+use crate::interest::{get_user_collateral, simulate_accumulated_interest};
+use crate::math256::u128_to_decimal256;
+use crate::state::{CONFIG, DEBTS, PARAMS};
+use stone_types::{
+    amount_to_scaled, scaled_to_amount_ceil, Decimal256Ext, LiquidationSimulationResponse,
+};
+
+/// Outcome of liquidating `borrower` with `debt_amount` at the current block.
+/// Interest is simulated up to the current block and the same math as
+/// `Liquidate` is applied, so the result matches a liquidation executed in
+/// this block. Fails with the error the liquidation itself would return.
+pub fn simulate_liquidation(
+    deps: Deps,
+    env: Env,
+    borrower: String,
+    debt_amount: Uint128,
+) -> ContractResult<LiquidationSimulationResponse> {
+    let borrower_addr = deps.api.addr_validate(&borrower)?;
+    let config = CONFIG.load(deps.storage)?;
+    let params = PARAMS.load(deps.storage)?;
+
+    if debt_amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let accrual = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?;
+    let borrow_index = accrual.state.borrow_index;
+
+    let debt_scaled = DEBTS
+        .may_load(deps.storage, borrower_addr.as_str())?
+        .unwrap_or_default();
+    let borrower_debt = scaled_to_amount_ceil(debt_scaled, borrow_index);
+    let borrower_collateral = get_user_collateral(deps.storage, borrower_addr.as_str())?;
+
+    let collateral_price =
+        query_price(deps, &env, &config.oracle_config, &config.collateral_denom)?;
+    let debt_price = query_price(deps, &env, &config.oracle_config, &config.debt_denom)?;
+
+    let position = |collateral_amount: Uint128, debt_amount: Uint128| -> ContractResult<_> {
+        Ok(PositionHealth {
+            collateral_amount,
+            debt_amount,
+            collateral_value: u128_to_decimal256(collateral_amount)
+                .checked_mul(Decimal256::from_decimal(collateral_price))?,
+            debt_value: u128_to_decimal256(debt_amount)
+                .checked_mul(Decimal256::from_decimal(debt_price))?,
+            collateral_price,
+            debt_price,
+            loan_to_value: params.loan_to_value,
+            liquidation_threshold: params.liquidation_threshold,
+        })
+    };
+
+    let health_factor = match position(borrower_collateral, borrower_debt)?.health_factor()? {
+        None => {
+            return Err(ContractError::NotLiquidatable {
+                health_factor: "infinite (no debt)".to_string(),
+            });
+        }
+        Some(hf) if hf >= Decimal::one() => {
+            return Err(ContractError::NotLiquidatable {
+                health_factor: hf.to_string(),
+            });
+        }
+        Some(hf) => hf,
+    };
+
+    let LiquidationAmounts {
+        debt_repaid,
+        collateral_seized,
+        protocol_fee,
+        liquidator_collateral,
+        ..
+    } = calculate_liquidation_amounts(
+        &params,
+        params.liquidation_bonus_for(health_factor),
+        debt_amount,
+        borrower_debt,
+        borrower_collateral,
+        collateral_price,
+        debt_price,
+    )?;
+
+    // Remaining debt is rounded exactly as the scaled balance update in `Liquidate`,
+    // and written off like there when no collateral is left behind it
+    let remaining_collateral = borrower_collateral.saturating_sub(collateral_seized);
+    let remaining_debt = if remaining_collateral.is_zero() {
+        Uint128::zero()
+    } else {
+        let remaining_debt_scaled =
+            debt_scaled.saturating_sub(amount_to_scaled(debt_repaid, borrow_index)?);
+        scaled_to_amount_ceil(remaining_debt_scaled, borrow_index)
+    };
+    let health_factor_after = position(remaining_collateral, remaining_debt)?.health_factor()?;
+
+    Ok(LiquidationSimulationResponse {
+        debt_repaid,
+        collateral_received: liquidator_collateral,
+        protocol_fee_collateral: protocol_fee,
+        refund: debt_amount.saturating_sub(debt_repaid),
+        health_factor_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execute::execute_liquidate;
+    use crate::state::{COLLATERAL, STATE};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{
+        coins, from_json, to_json_binary, BankMsg, ContractResult as CwContractResult, CosmosMsg,
+        Response, SystemResult, Timestamp, WasmQuery,
+    };
+    use std::str::FromStr;
+    use stone_testing::default_stored_market_params;
+    use stone_types::{
+        MarketConfig, MarketParams, MarketState, OracleConfig, OracleQueryMsg, OracleType,
+        PriceResponse,
+    };
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    /// Borrower with 1000 collateral at $5.80 against 5000 debt at $1
+    /// (health factor 0.986), in a market at 50% utilization.
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        max_liquidation_value_usd: Option<Decimal>,
+    ) {
+        let api = MockApi::default();
+        let config = MarketConfig {
+            factory: api.addr_make("factory"),
+            curator: api.addr_make("curator"),
+            oracle_config: OracleConfig {
+                address: api.addr_make("oracle"),
+                oracle_type: OracleType::Generic {
+                    expected_code_id: None,
+                    max_staleness_secs: 300,
+                },
+            },
+            collateral_denom: "uatom".to_string(),
+            debt_denom: "uusdc".to_string(),
+            protocol_fee_collector: api.addr_make("collector"),
+            salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+        let params = MarketParams {
+            max_liquidation_value_usd,
+            ..default_stored_market_params()
+        };
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let borrower = api.addr_make("borrower");
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.total_supply_scaled = Uint128::new(10_000);
+        state.total_debt_scaled = Uint128::new(5_000);
+        state.total_collateral = Uint128::new(1_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+        COLLATERAL
+            .save(
+                deps.as_mut().storage,
+                borrower.as_str(),
+                &Uint128::new(1_000),
+            )
+            .unwrap();
+        DEBTS
+            .save(
+                deps.as_mut().storage,
+                borrower.as_str(),
+                &Uint128::new(5_000),
+            )
+            .unwrap();
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = if denom == "uatom" {
+                    Decimal::from_ratio(58u128, 10u128)
+                } else {
+                    Decimal::one()
+                };
+                let response = PriceResponse {
+                    denom,
+                    price,
+                    updated_at: BASE_TIMESTAMP,
+                };
+                SystemResult::Ok(CwContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    fn attr(res: &Response, key: &str) -> String {
+        res.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+            .unwrap()
+    }
+
+    fn sent_to(res: &Response, recipient: &str, denom: &str) -> Uint128 {
+        res.messages
+            .iter()
+            .filter_map(|m| match &m.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount })
+                    if to_address == recipient =>
+                {
+                    amount.iter().find(|c| c.denom == denom).map(|c| c.amount)
+                }
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Simulate, then execute the same liquidation in the same block and
+    /// compare every field against the actual outcome.
+    fn assert_simulation_matches_execution(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        time: u64,
+        debt_amount: u128,
+    ) -> LiquidationSimulationResponse {
+        let api = MockApi::default();
+        let borrower = api.addr_make("borrower");
+        let liquidator = api.addr_make("liquidator");
+
+        let simulation = simulate_liquidation(
+            deps.as_ref(),
+            env_at(time),
+            borrower.to_string(),
+            Uint128::new(debt_amount),
+        )
+        .unwrap();
+
+        let info = message_info(&liquidator, &coins(debt_amount, "uusdc"));
+        let res =
+            execute_liquidate(deps.as_mut(), env_at(time), info, borrower.to_string()).unwrap();
+
+        assert_eq!(
+            simulation.debt_repaid.to_string(),
+            attr(&res, "debt_repaid")
+        );
+        assert_eq!(
+            simulation.collateral_received,
+            sent_to(&res, liquidator.as_str(), "uatom")
+        );
+        assert_eq!(
+            simulation.protocol_fee_collateral,
+            sent_to(&res, api.addr_make("collector").as_str(), "uatom")
+        );
+        assert_eq!(
+            simulation.refund,
+            sent_to(&res, liquidator.as_str(), "uusdc")
+        );
+        assert_eq!(
+            simulation.health_factor_after,
+            Some(Decimal::from_str(&attr(&res, "health_factor_after")).unwrap())
+        );
+
+        simulation
+    }
+
+    #[test]
+    fn test_simulate_liquidation_matches_execution() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, None);
+
+        let simulation = assert_simulation_matches_execution(&mut deps, BASE_TIMESTAMP, 2_500);
+
+        // 2500 debt buys 431 collateral, plus 21 bonus and 8 protocol fee
+        assert_eq!(simulation.debt_repaid, Uint128::new(2_500));
+        assert_eq!(simulation.collateral_received, Uint128::new(452));
+        assert_eq!(simulation.protocol_fee_collateral, Uint128::new(8));
+        assert_eq!(simulation.refund, Uint128::zero());
+    }
+
+    #[test]
+    fn test_simulate_liquidation_includes_unaccrued_interest() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, None);
+        let later = BASE_TIMESTAMP + 200;
+
+        // Sending the full debt is capped by the close factor on the accrued debt
+        let simulation = assert_simulation_matches_execution(&mut deps, later, 5_000);
+        assert!(simulation.refund > Uint128::zero());
+
+        // The liquidation accrued interest, so the simulation had to project it
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert!(state.borrow_index > Decimal::one());
+    }
+
+    #[test]
+    fn test_simulate_liquidation_value_capped() {
+        let mut deps = mock_dependencies();
+        // At most $1,160 of collateral (200 uatom) per liquidation
+        setup_market(&mut deps, Some(Decimal::from_ratio(1_160u128, 1u128)));
+
+        let simulation = assert_simulation_matches_execution(&mut deps, BASE_TIMESTAMP, 2_500);
+        assert_eq!(
+            simulation.collateral_received + simulation.protocol_fee_collateral,
+            Uint128::new(200)
+        );
+        assert!(simulation.debt_repaid < Uint128::new(2_500));
+        assert_eq!(
+            simulation.refund,
+            Uint128::new(2_500) - simulation.debt_repaid
+        );
+    }
+
+    #[test]
+    fn test_simulate_liquidation_does_not_write_state() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, None);
+        let borrower = MockApi::default().addr_make("borrower");
+
+        simulate_liquidation(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP + 200),
+            borrower.to_string(),
+            Uint128::new(2_500),
+        )
+        .unwrap();
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.last_update, BASE_TIMESTAMP);
+        assert_eq!(
+            COLLATERAL
+                .load(deps.as_ref().storage, borrower.as_str())
+                .unwrap(),
+            Uint128::new(1_000)
+        );
+    }
+
+    #[test]
+    fn test_simulate_liquidation_healthy_position() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, None);
+        let borrower = MockApi::default().addr_make("borrower");
+        DEBTS
+            .save(
+                deps.as_mut().storage,
+                borrower.as_str(),
+                &Uint128::new(1_000),
+            )
+            .unwrap();
+
+        let err = simulate_liquidation(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP),
+            borrower.to_string(),
+            Uint128::new(500),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotLiquidatable { .. }));
+
+        let err = simulate_liquidation(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP),
+            borrower.to_string(),
+            Uint128::zero(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroAmount);
+    }
+}
@@ -0,0 +1,139 @@
+use cosmwasm_std::{Deps, Env};
+
+use crate::error::ContractResult;
+use crate::interest::simulate_accumulated_interest;
+use crate::math256::shares_to_amount;
+use crate::state::SUPPLIES;
+use stone_types::UserSupplyResponse;
+
+/// A user's scaled supply and its unscaled amount at the liquidity index
+/// projected to the current block, so the balance includes interest accrued
+/// since the last state update.
+pub fn user_supply(deps: Deps, env: Env, user: String) -> ContractResult<UserSupplyResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let accrual = simulate_accumulated_interest(deps.storage, env.block.time.seconds())?;
+    let liquidity_index = accrual.state.liquidity_index;
+
+    let scaled_supply = SUPPLIES
+        .may_load(deps.storage, user_addr.as_str())?
+        .unwrap_or_default();
+    let actual_supply = shares_to_amount(scaled_supply, liquidity_index)?;
+
+    Ok(UserSupplyResponse {
+        user: user_addr.to_string(),
+        scaled_supply,
+        actual_supply,
+        liquidity_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interest::SECONDS_PER_YEAR;
+    use crate::state::{PARAMS, STATE};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{Decimal, Timestamp, Uint128};
+    use stone_testing::default_stored_market_params;
+    use stone_types::MarketState;
+
+    const BASE_TIMESTAMP: u64 = 1_700_000_000;
+
+    fn setup_market(
+        deps: &mut cosmwasm_std::OwnedDeps<
+            cosmwasm_std::MemoryStorage,
+            cosmwasm_std::testing::MockApi,
+            cosmwasm_std::testing::MockQuerier,
+        >,
+        liquidity_index: Decimal,
+    ) {
+        let params = default_stored_market_params();
+        PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let mut state = MarketState::new(BASE_TIMESTAMP);
+        state.liquidity_index = liquidity_index;
+        state.total_supply_scaled = Uint128::new(1_000_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+    }
+
+    fn env_at(time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    #[test]
+    fn test_user_supply_at_unit_index() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::one());
+        let user = MockApi::default().addr_make("user1");
+        SUPPLIES
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let res = user_supply(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        assert_eq!(res.user, user.to_string());
+        assert_eq!(res.scaled_supply, Uint128::new(1000));
+        assert_eq!(res.actual_supply, Uint128::new(1000));
+        assert_eq!(res.liquidity_index, Decimal::one());
+    }
+
+    #[test]
+    fn test_user_supply_uses_stored_index() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::percent(125));
+        let user = MockApi::default().addr_make("user1");
+        SUPPLIES
+            .save(deps.as_mut().storage, user.as_str(), &Uint128::new(1000))
+            .unwrap();
+
+        let res = user_supply(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        assert_eq!(res.scaled_supply, Uint128::new(1000));
+        assert_eq!(res.actual_supply, Uint128::new(1250));
+        assert_eq!(res.liquidity_index, Decimal::percent(125));
+    }
+
+    #[test]
+    fn test_user_supply_includes_unaccrued_interest() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::one());
+        let user = MockApi::default().addr_make("user1");
+        SUPPLIES
+            .save(
+                deps.as_mut().storage,
+                user.as_str(),
+                &Uint128::new(1_000_000),
+            )
+            .unwrap();
+
+        // 50% utilization so suppliers earn interest
+        let mut state = STATE.load(deps.as_ref().storage).unwrap();
+        state.total_debt_scaled = Uint128::new(500_000);
+        STATE.save(deps.as_mut().storage, &state).unwrap();
+
+        let res = user_supply(
+            deps.as_ref(),
+            env_at(BASE_TIMESTAMP + SECONDS_PER_YEAR),
+            user.to_string(),
+        )
+        .unwrap();
+        assert!(res.liquidity_index > Decimal::one());
+        assert_eq!(res.scaled_supply, Uint128::new(1_000_000));
+        assert_eq!(
+            res.actual_supply,
+            Uint128::new(1_000_000).mul_floor(res.liquidity_index)
+        );
+        assert!(res.actual_supply > Uint128::new(1_000_000));
+    }
+
+    #[test]
+    fn test_user_supply_without_position() {
+        let mut deps = mock_dependencies();
+        setup_market(&mut deps, Decimal::percent(125));
+        let user = MockApi::default().addr_make("user1");
+
+        let res = user_supply(deps.as_ref(), env_at(BASE_TIMESTAMP), user.to_string()).unwrap();
+        assert_eq!(res.scaled_supply, Uint128::zero());
+        assert_eq!(res.actual_supply, Uint128::zero());
+    }
+}
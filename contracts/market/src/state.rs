@@ -1,6 +1,9 @@
-use cosmwasm_std::Uint128;
-use cw_storage_plus::{Item, Map};
-use stone_types::{MarketConfig, MarketParams, MarketState};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
+use cw_storage_plus::{Deque, Item, Map};
+use stone_types::{
+    InterestAccrualEvent, LiquidationEvent, MarketConfig, MarketParams, MarketState,
+};
 
 #[cfg(test)]
 use stone_types::{OracleConfig, OracleType};
@@ -8,7 +11,7 @@ use stone_types::{OracleConfig, OracleType};
 pub const CONTRACT_NAME: &str = "crates.io:stone-market";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Market configuration (immutable after instantiation)
+/// Market configuration (only the factory can change it after instantiation)
 pub const CONFIG: Item<MarketConfig> = Item::new("config");
 
 /// Market parameters (some updatable by curator)
@@ -18,9 +21,38 @@ pub const PARAMS: Item<MarketParams> = Item::new("params");
 pub const STATE: Item<MarketState> = Item::new("state");
 
 /// User supply positions (scaled amounts)
+/// Scaled amounts are the supplier's shares of total liquidity: they only change
+/// on supply, withdraw and share transfers, and are redeemed at the current
+/// liquidity index.
 /// Key: user address
 pub const SUPPLIES: Map<&str, Uint128> = Map::new("supplies");
 
+/// Block height of each user's most recent supply to their own position, for the
+/// withdraw cooldown. Supplies made on a user's behalf do not update it.
+/// Key: user address
+pub const LAST_SUPPLY_BLOCK: Map<&str, u64> = Map::new("last_supply_block");
+
+/// Block height and amount of each sender's most recent supply, to reject an
+/// identical supply submitted twice in the same block.
+/// Key: sender address
+pub const LAST_SUPPLY: Map<&str, (u64, Uint128)> = Map::new("last_supply");
+
+/// Idempotency nonces used for supplies and withdrawals, with the block time
+/// they were used at. Expired entries are pruned by the sender's later nonce
+/// uses (see `use_nonce`). Key: (sender address, nonce)
+pub const USED_NONCES: Map<(&str, u64), u64> = Map::new("used_nonces");
+
+/// Sequence number for the next supply receipt NFT token ID
+pub const NEXT_RECEIPT_ID: Item<u64> = Item::new("next_receipt_id");
+
+/// Supply receipt NFTs minted for each position and not yet burned
+/// Key: (position owner address, token ID)
+pub const SUPPLY_RECEIPTS: Map<(&str, &str), ()> = Map::new("supply_receipts");
+
+/// Cumulative debt asset supplied under each referrer, for off-chain rewards
+/// Key: referrer address
+pub const REFERRAL_VOLUMES: Map<&str, Uint128> = Map::new("referral_volumes");
+
 /// User collateral positions (unscaled amounts)
 /// Key: user address
 pub const COLLATERAL: Map<&str, Uint128> = Map::new("collateral");
@@ -29,6 +61,19 @@ pub const COLLATERAL: Map<&str, Uint128> = Map::new("collateral");
 /// Key: user address
 pub const DEBTS: Map<&str, Uint128> = Map::new("debts");
 
+/// Privileged borrowers and the reserved liquidity each may still draw
+/// beyond the general pool. Drawing from the reserve consumes the limit.
+/// Key: borrower address
+pub const PRIVILEGED_BORROWERS: Map<&str, Uint128> = Map::new("privileged_borrowers");
+
+/// Liquidity reserved for privileged borrowers (sum of their remaining limits).
+/// General borrowers can only draw on available liquidity above this amount.
+pub const PRIVILEGED_SUPPLY: Item<Uint128> = Item::new("privileged_supply");
+
+/// Addresses allowed to deposit collateral while `collateral_whitelist_enabled` is set.
+/// Key: depositor address
+pub const COLLATERAL_WHITELIST: Map<&str, bool> = Map::new("collateral_whitelist");
+
 /// Accrued protocol fees (claimable by protocol fee collector)
 /// These are accumulated from interest accrual but not yet claimed.
 /// Fees are virtual until borrowers repay and tokens are available.
@@ -39,6 +84,85 @@ pub const ACCRUED_PROTOCOL_FEES: Item<Uint128> = Item::new("accrued_protocol_fee
 /// Fees are virtual until borrowers repay and tokens are available.
 pub const ACCRUED_CURATOR_FEES: Item<Uint128> = Item::new("accrued_curator_fees");
 
+/// Cumulative debt written off after liquidations that left a borrower with
+/// no collateral, or cleared as dust by `SweepDust` or on repay (in debt
+/// token). Each loss is absorbed by suppliers through the liquidity index.
+pub const SOCIALIZED_LOSSES: Item<Uint128> = Item::new("socialized_losses");
+
+/// Collateral swap awaiting the router reply.
+#[cw_serde]
+pub struct PendingCollateralSwap {
+    /// Borrower whose collateral is being swapped
+    pub user: Addr,
+    /// Collateral sent to the router
+    pub amount_in: Uint128,
+    /// Minimum collateral the router must return
+    pub min_receive: Uint128,
+    /// Contract collateral balance after sending to the router
+    pub balance_before: Uint128,
+}
+
+/// Emergency pause set by the factory. Blocks the same operations as a disabled
+/// market, but only the factory can lift it, so `UpdateParams` cannot undo it.
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+/// In-flight collateral swap (set on dispatch, cleared in the reply)
+pub const PENDING_COLLATERAL_SWAP: Item<PendingCollateralSwap> =
+    Item::new("pending_collateral_swap");
+
+/// Repay-with-collateral awaiting the router reply.
+#[cw_serde]
+pub struct PendingCollateralRepay {
+    /// Borrower repaying with collateral
+    pub user: Addr,
+    /// Collateral sent to the router
+    pub collateral_used: Uint128,
+    /// Maximum debt to repay from the router proceeds
+    pub max_debt_repaid: Uint128,
+    /// Contract debt asset balance before the router call
+    pub debt_balance_before: Uint128,
+}
+
+/// In-flight repay-with-collateral (set on dispatch, cleared in the reply)
+pub const PENDING_COLLATERAL_REPAY: Item<PendingCollateralRepay> =
+    Item::new("pending_collateral_repay");
+
+/// Maximum number of liquidations kept in `LIQUIDATION_HISTORY`
+pub const MAX_LIQUIDATION_HISTORY: u32 = 50;
+
+/// Most recent liquidations, oldest at the front (audit trail)
+pub const LIQUIDATION_HISTORY: Deque<LiquidationEvent> = Deque::new("liquidation_history");
+
+/// Append a liquidation to the history, dropping the oldest entries beyond
+/// `MAX_LIQUIDATION_HISTORY`.
+pub fn record_liquidation(storage: &mut dyn Storage, event: &LiquidationEvent) -> StdResult<()> {
+    LIQUIDATION_HISTORY.push_back(storage, event)?;
+    while LIQUIDATION_HISTORY.len(storage)? > MAX_LIQUIDATION_HISTORY {
+        LIQUIDATION_HISTORY.pop_front(storage)?;
+    }
+    Ok(())
+}
+
+/// Maximum number of accruals kept in `INTEREST_ACCRUAL_EVENTS`
+pub const MAX_INTEREST_HISTORY: u32 = 200;
+
+/// Most recent index-moving interest accruals, oldest at the front
+pub const INTEREST_ACCRUAL_EVENTS: Deque<InterestAccrualEvent> =
+    Deque::new("interest_accrual_events");
+
+/// Append an interest accrual to the history, dropping the oldest entries beyond
+/// `MAX_INTEREST_HISTORY`.
+pub fn record_interest_accrual(
+    storage: &mut dyn Storage,
+    event: &InterestAccrualEvent,
+) -> StdResult<()> {
+    INTEREST_ACCRUAL_EVENTS.push_back(storage, event)?;
+    while INTEREST_ACCRUAL_EVENTS.len(storage)? > MAX_INTEREST_HISTORY {
+        INTEREST_ACCRUAL_EVENTS.pop_front(storage)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +187,8 @@ mod tests {
             debt_denom: "uusdc".to_string(),
             protocol_fee_collector: Addr::unchecked("collector"),
             salt: None,
+            allow_collateral_swap: false,
+            receipt_nft_contract: None,
         };
 
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
@@ -111,4 +237,87 @@ mod tests {
             Uint128::new(200)
         );
     }
+
+    fn liquidation(index: u64) -> LiquidationEvent {
+        LiquidationEvent {
+            borrower: format!("borrower{index}"),
+            debt_repaid: Uint128::new(100),
+            collateral_seized: Uint128::new(10),
+            health_factor_before: Decimal::percent(95),
+            timestamp: 1000 + index,
+        }
+    }
+
+    #[test]
+    fn test_liquidation_history_is_capped() {
+        let mut deps = mock_dependencies();
+
+        for i in 0..u64::from(MAX_LIQUIDATION_HISTORY) {
+            record_liquidation(deps.as_mut().storage, &liquidation(i)).unwrap();
+        }
+        assert_eq!(
+            LIQUIDATION_HISTORY.len(deps.as_ref().storage).unwrap(),
+            MAX_LIQUIDATION_HISTORY
+        );
+
+        // The 51st entry evicts the first
+        let newest = liquidation(u64::from(MAX_LIQUIDATION_HISTORY));
+        record_liquidation(deps.as_mut().storage, &newest).unwrap();
+
+        assert_eq!(
+            LIQUIDATION_HISTORY.len(deps.as_ref().storage).unwrap(),
+            MAX_LIQUIDATION_HISTORY
+        );
+        assert_eq!(
+            LIQUIDATION_HISTORY.front(deps.as_ref().storage).unwrap(),
+            Some(liquidation(1))
+        );
+        assert_eq!(
+            LIQUIDATION_HISTORY.back(deps.as_ref().storage).unwrap(),
+            Some(newest)
+        );
+    }
+
+    fn interest_accrual(index: u64) -> InterestAccrualEvent {
+        InterestAccrualEvent {
+            borrow_index: Decimal::one(),
+            liquidity_index: Decimal::one(),
+            borrow_rate: Decimal::percent(5),
+            liquidity_rate: Decimal::percent(2),
+            timestamp: 1000 + index,
+            elapsed_secs: 1,
+        }
+    }
+
+    #[test]
+    fn test_interest_history_is_capped() {
+        let mut deps = mock_dependencies();
+
+        for i in 0..u64::from(MAX_INTEREST_HISTORY) {
+            record_interest_accrual(deps.as_mut().storage, &interest_accrual(i)).unwrap();
+        }
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS.len(deps.as_ref().storage).unwrap(),
+            MAX_INTEREST_HISTORY
+        );
+
+        // The 201st entry evicts the first
+        let newest = interest_accrual(u64::from(MAX_INTEREST_HISTORY));
+        record_interest_accrual(deps.as_mut().storage, &newest).unwrap();
+
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS.len(deps.as_ref().storage).unwrap(),
+            MAX_INTEREST_HISTORY
+        );
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS
+                .front(deps.as_ref().storage)
+                .unwrap(),
+            Some(interest_accrual(1))
+        );
+        assert_eq!(
+            INTEREST_ACCRUAL_EVENTS.back(deps.as_ref().storage).unwrap(),
+            Some(newest)
+        );
+    }
 }
@@ -0,0 +1,863 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, from_json, to_json_binary, Addr, Decimal, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+use stone_market::contract as market_contract;
+use stone_testing::{
+    default_market_params, mock_oracle_contract, mock_receipt_nft_contract, mock_router_contract,
+    MockOracleExecuteMsg, MockOracleInstantiateMsg, MockReceiptNftInstantiateMsg,
+    MockReceiptNftQueryMsg, MockRouterExecuteMsg, MockRouterInstantiateMsg, COLLATERAL_DENOM,
+    DEBT_DENOM,
+};
+use stone_types::{
+    CurrentRatesResponse, HealthMetrics, IsLiquidatableResponse, LiquidationHistoryResponse,
+    MarketExecuteMsg, MarketInstantiateMsg, MarketQueryMsg, MarketStateResponse,
+    OracleConfigUnchecked, OracleType, SupplyPreviewResponse, UserBalanceResponse,
+    UserSupplyResponse,
+};
+
+fn market_wrapper() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        market_contract::execute,
+        market_contract::instantiate,
+        market_contract::query,
+    )
+    .with_reply(market_contract::reply);
+    Box::new(contract)
+}
+
+struct TestEnv {
+    app: App,
+    user: Addr,
+    market_addr: Addr,
+    router_addr: Addr,
+    oracle_addr: Addr,
+}
+
+fn setup_env(allow_collateral_swap: bool) -> TestEnv {
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let curator = api.addr_make("curator");
+    let collector = api.addr_make("fee_collector");
+    let user = api.addr_make("user");
+
+    let mut app = AppBuilder::new().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &user, vec![coin(10_000, COLLATERAL_DENOM)])
+            .unwrap();
+        router
+            .bank
+            .init_balance(
+                storage,
+                &owner,
+                vec![coin(100_000, COLLATERAL_DENOM), coin(100_000, DEBT_DENOM)],
+            )
+            .unwrap();
+    });
+
+    let market_id = app.store_code(market_wrapper());
+    let oracle_id = app.store_code(Box::new(mock_oracle_contract()));
+    let router_id = app.store_code(Box::new(mock_router_contract()));
+
+    let oracle_addr = app
+        .instantiate_contract(
+            oracle_id,
+            owner.clone(),
+            &MockOracleInstantiateMsg {
+                prices: vec![
+                    (
+                        COLLATERAL_DENOM.to_string(),
+                        Decimal::from_ratio(10u128, 1u128),
+                    ),
+                    (DEBT_DENOM.to_string(), Decimal::one()),
+                ],
+            },
+            &[],
+            "mock-oracle",
+            None,
+        )
+        .unwrap();
+
+    let router_addr = app
+        .instantiate_contract(
+            router_id,
+            owner.clone(),
+            &MockRouterInstantiateMsg {},
+            &[coin(100_000, COLLATERAL_DENOM)],
+            "mock-router",
+            None,
+        )
+        .unwrap();
+
+    let market_addr = app
+        .instantiate_contract(
+            market_id,
+            owner.clone(),
+            &MarketInstantiateMsg {
+                curator: curator.to_string(),
+                oracle_config: OracleConfigUnchecked {
+                    address: oracle_addr.to_string(),
+                    oracle_type: OracleType::Generic {
+                        expected_code_id: None,
+                        max_staleness_secs: 300,
+                    },
+                },
+                collateral_denom: COLLATERAL_DENOM.to_string(),
+                debt_denom: DEBT_DENOM.to_string(),
+                protocol_fee_collector: collector.to_string(),
+                params: default_market_params(),
+                allow_collateral_swap,
+                receipt_nft_contract: None,
+            },
+            &[],
+            "market",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        user.clone(),
+        market_addr.clone(),
+        &MarketExecuteMsg::SupplyCollateral { recipient: None },
+        &[coin(1_000, COLLATERAL_DENOM)],
+    )
+    .unwrap();
+
+    TestEnv {
+        app,
+        user,
+        market_addr,
+        router_addr,
+        oracle_addr,
+    }
+}
+
+fn swap_msg(amount_out: u128, min_receive: u128, router: &Addr) -> MarketExecuteMsg {
+    MarketExecuteMsg::CollateralSwap {
+        router: router.to_string(),
+        swap_msg: to_json_binary(&MockRouterExecuteMsg::Swap {
+            denom_out: COLLATERAL_DENOM.to_string(),
+            amount_out: Uint128::new(amount_out),
+        })
+        .unwrap(),
+        min_receive: Uint128::new(min_receive),
+    }
+}
+
+fn user_collateral(env: &TestEnv) -> Uint128 {
+    let balance: UserBalanceResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::UserCollateral {
+                user: env.user.to_string(),
+            },
+        )
+        .unwrap();
+    balance.amount
+}
+
+#[test]
+fn collateral_swap_credits_router_proceeds() {
+    let mut env = setup_env(true);
+
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &swap_msg(980, 950, &env.router_addr),
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(user_collateral(&env), Uint128::new(980));
+
+    let state: MarketStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.market_addr.clone(), &MarketQueryMsg::State {})
+        .unwrap();
+    assert_eq!(state.total_collateral, Uint128::new(980));
+
+    let market_balance = env
+        .app
+        .wrap()
+        .query_balance(env.market_addr.clone(), COLLATERAL_DENOM)
+        .unwrap();
+    assert_eq!(market_balance.amount, Uint128::new(980));
+}
+
+#[test]
+fn collateral_swap_reverts_on_slippage() {
+    let mut env = setup_env(true);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &swap_msg(900, 950, &env.router_addr),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.root_cause().to_string().contains("slippage exceeded"),
+        "{err:?}"
+    );
+
+    // Whole transaction reverted: position untouched
+    assert_eq!(user_collateral(&env), Uint128::new(1_000));
+}
+
+#[test]
+fn collateral_swap_requires_enabled_market() {
+    let mut env = setup_env(false);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &swap_msg(980, 950, &env.router_addr),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.root_cause().to_string().contains("not enabled"),
+        "{err:?}"
+    );
+}
+
+fn health_metrics(env: &TestEnv) -> HealthMetrics {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::HealthMetrics {
+                user: env.user.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+/// Owner supplies debt liquidity and the user borrows against 1_000 collateral ($10,000).
+fn setup_borrow_env(borrow: u128) -> TestEnv {
+    let mut env = setup_env(false);
+    let owner = MockApi::default().addr_make("owner");
+
+    env.app
+        .execute_contract(
+            owner,
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(50_000, DEBT_DENOM)],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: Uint128::new(borrow),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    env
+}
+
+#[test]
+fn health_metrics_matches_individual_queries() {
+    let env = setup_borrow_env(4_000);
+    let metrics = health_metrics(&env);
+
+    assert_eq!(metrics.collateral_amount, Uint128::new(1_000));
+    assert_eq!(metrics.debt_amount, Uint128::new(4_000));
+
+    let liquidatable: IsLiquidatableResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::IsLiquidatable {
+                user: env.user.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(metrics.health_factor.value, liquidatable.health_factor);
+    assert_eq!(
+        metrics.is_liquidatable.value,
+        Some(liquidatable.is_liquidatable)
+    );
+
+    // Debt ratio is the inverse of the health factor scaled by the liquidation threshold
+    let hf = metrics.health_factor.value.unwrap();
+    let debt_ratio = metrics.debt_ratio.value.unwrap();
+    assert_eq!(debt_ratio, Decimal::percent(40));
+    assert_eq!(hf * debt_ratio, Decimal::percent(85));
+
+    // At the liquidation price, collateral_amount * price * 0.85 equals the debt value
+    assert_eq!(
+        metrics.liquidation_price.value,
+        Some(Decimal::from_ratio(4_000u128, 1_000u128 * 85 / 100))
+    );
+
+    for metric_error in [
+        &metrics.health_factor.calculation_error,
+        &metrics.liquidation_price.calculation_error,
+        &metrics.max_borrow.calculation_error,
+        &metrics.max_withdraw.calculation_error,
+        &metrics.debt_ratio.calculation_error,
+        &metrics.is_liquidatable.calculation_error,
+    ] {
+        assert!(metric_error.is_none(), "{metric_error:?}");
+    }
+}
+
+#[test]
+fn health_metrics_max_borrow_and_withdraw_are_exact_limits() {
+    let mut env = setup_borrow_env(4_000);
+    let metrics = health_metrics(&env);
+    let max_borrow = metrics.max_borrow.value.unwrap();
+    let max_withdraw = metrics.max_withdraw.value.unwrap();
+    assert_eq!(max_borrow, Uint128::new(4_000));
+    assert_eq!(max_withdraw, Uint128::new(500));
+
+    // One token past the reported limit is rejected
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: max_borrow + Uint128::one(),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::WithdrawCollateral {
+                amount: Some(max_withdraw + Uint128::one()),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    // Withdrawing exactly the reported maximum leaves no further headroom
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::WithdrawCollateral {
+                amount: Some(max_withdraw),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let metrics = health_metrics(&env);
+    assert_eq!(metrics.max_borrow.value, Some(Uint128::zero()));
+    assert_eq!(metrics.max_withdraw.value, Some(Uint128::zero()));
+}
+
+/// Borrow 4_000 against 1_000 collateral and fund the router with debt tokens.
+fn setup_repay_with_collateral_env() -> TestEnv {
+    let mut env = setup_borrow_env(4_000);
+    let owner = MockApi::default().addr_make("owner");
+
+    env.app
+        .send_tokens(owner, env.router_addr.clone(), &[coin(20_000, DEBT_DENOM)])
+        .unwrap();
+
+    env
+}
+
+fn repay_with_collateral_msg(
+    collateral_to_use: u128,
+    max_debt_repaid: u128,
+    debt_out: u128,
+    router: &Addr,
+) -> MarketExecuteMsg {
+    MarketExecuteMsg::RepayWithCollateral {
+        collateral_to_use: Uint128::new(collateral_to_use),
+        max_debt_repaid: Uint128::new(max_debt_repaid),
+        router: router.to_string(),
+        route_msg: to_json_binary(&MockRouterExecuteMsg::Swap {
+            denom_out: DEBT_DENOM.to_string(),
+            amount_out: Uint128::new(debt_out),
+        })
+        .unwrap(),
+    }
+}
+
+fn user_debt(env: &TestEnv) -> Uint128 {
+    let balance: UserBalanceResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::UserDebt {
+                user: env.user.to_string(),
+            },
+        )
+        .unwrap();
+    balance.amount
+}
+
+#[test]
+fn repay_with_collateral_repays_from_router_proceeds() {
+    let mut env = setup_repay_with_collateral_env();
+
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &repay_with_collateral_msg(300, 5_000, 3_000, &env.router_addr),
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(user_debt(&env), Uint128::new(1_000));
+    assert_eq!(user_collateral(&env), Uint128::new(700));
+
+    let state: MarketStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.market_addr.clone(), &MarketQueryMsg::State {})
+        .unwrap();
+    assert_eq!(state.total_collateral, Uint128::new(700));
+    assert_eq!(state.total_debt, Uint128::new(1_000));
+}
+
+#[test]
+fn repay_with_collateral_refunds_proceeds_above_max_debt_repaid() {
+    let mut env = setup_repay_with_collateral_env();
+
+    env.app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &repay_with_collateral_msg(300, 2_500, 3_000, &env.router_addr),
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(user_debt(&env), Uint128::new(1_500));
+    // Borrowed 4_000, plus 500 refunded from the router proceeds
+    let user_balance = env
+        .app
+        .wrap()
+        .query_balance(env.user.clone(), DEBT_DENOM)
+        .unwrap();
+    assert_eq!(user_balance.amount, Uint128::new(4_500));
+}
+
+#[test]
+fn repay_with_collateral_rejects_over_collateral_usage() {
+    let mut env = setup_repay_with_collateral_env();
+
+    let err = env
+        .app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &repay_with_collateral_msg(1_001, 5_000, 4_000, &env.router_addr),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.root_cause()
+            .to_string()
+            .contains("Insufficient collateral: available 1000, requested 1001"),
+        "{err:?}"
+    );
+
+    assert_eq!(user_debt(&env), Uint128::new(4_000));
+    assert_eq!(user_collateral(&env), Uint128::new(1_000));
+}
+
+#[test]
+fn repay_with_collateral_reverts_when_remaining_position_is_unhealthy() {
+    let mut env = setup_repay_with_collateral_env();
+
+    // 600 collateral ($6,000) swapped for only 100 debt: 400 collateral ($3,200 max borrow)
+    // cannot back the remaining 3_900 debt
+    let err = env
+        .app
+        .execute_contract(
+            env.user.clone(),
+            env.market_addr.clone(),
+            &repay_with_collateral_msg(600, 5_000, 100, &env.router_addr),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.root_cause()
+            .to_string()
+            .contains("Insufficient collateral for withdrawal"),
+        "{err:?}"
+    );
+
+    // Whole transaction reverted: position untouched
+    assert_eq!(user_debt(&env), Uint128::new(4_000));
+    assert_eq!(user_collateral(&env), Uint128::new(1_000));
+}
+
+fn liquidate(env: &mut TestEnv, debt: u128) {
+    let liquidator = MockApi::default().addr_make("owner");
+    env.app
+        .execute_contract(
+            liquidator,
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Liquidate {
+                borrower: env.user.to_string(),
+            },
+            &[coin(debt, DEBT_DENOM)],
+        )
+        .unwrap();
+}
+
+#[test]
+fn liquidation_history_records_liquidations() {
+    let mut env = setup_borrow_env(4_000);
+    let owner = MockApi::default().addr_make("owner");
+
+    let history: LiquidationHistoryResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::LiquidationHistory { limit: None },
+        )
+        .unwrap();
+    assert!(history.liquidations.is_empty());
+
+    // Collateral drops to $4: HF = 1_000 * 4 * 0.85 / 4_000 = 0.85
+    env.app
+        .execute_contract(
+            owner,
+            env.oracle_addr.clone(),
+            &MockOracleExecuteMsg::SetPrice {
+                denom: COLLATERAL_DENOM.to_string(),
+                price: Decimal::from_ratio(4u128, 1u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    liquidate(&mut env, 1_000);
+    let collateral_after_first = user_collateral(&env);
+    liquidate(&mut env, 1_000);
+
+    let history: LiquidationHistoryResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::LiquidationHistory { limit: None },
+        )
+        .unwrap();
+    assert_eq!(history.liquidations.len(), 2);
+
+    // Newest first
+    let (second, first) = (&history.liquidations[0], &history.liquidations[1]);
+    assert_eq!(first.borrower, env.user.to_string());
+    assert_eq!(first.debt_repaid, Uint128::new(1_000));
+    assert_eq!(
+        first.collateral_seized,
+        Uint128::new(1_000) - collateral_after_first
+    );
+    assert_eq!(first.health_factor_before, Decimal::percent(85));
+    assert_eq!(first.timestamp, env.app.block_info().time.seconds());
+
+    assert_eq!(second.debt_repaid, Uint128::new(1_000));
+    assert_eq!(
+        second.collateral_seized,
+        collateral_after_first - user_collateral(&env)
+    );
+    assert!(second.health_factor_before < first.health_factor_before);
+
+    let history: LiquidationHistoryResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::LiquidationHistory { limit: Some(1) },
+        )
+        .unwrap();
+    assert_eq!(history.liquidations, vec![second.clone()]);
+}
+
+#[test]
+fn supply_preview_matches_rates_after_supply() {
+    let mut env = setup_borrow_env(4_000);
+    let owner = MockApi::default().addr_make("owner");
+
+    // Let interest accrue so the preview has to account for it
+    env.app.update_block(|block| {
+        block.time = block.time.plus_seconds(86_400);
+        block.height += 1;
+    });
+
+    let res = env
+        .app
+        .execute_contract(
+            owner.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::SupplyPreview {
+                amount: Uint128::new(20_000),
+            },
+            &[],
+        )
+        .unwrap();
+    let preview: SupplyPreviewResponse = from_json(res.data.unwrap()).unwrap();
+
+    env.app
+        .execute_contract(
+            owner,
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: false,
+                nonce: None,
+            },
+            &[coin(20_000, DEBT_DENOM)],
+        )
+        .unwrap();
+
+    let state: MarketStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.market_addr.clone(), &MarketQueryMsg::State {})
+        .unwrap();
+    let rates: CurrentRatesResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.market_addr.clone(), &MarketQueryMsg::CurrentRates {})
+        .unwrap();
+
+    assert_eq!(preview.new_total_supply, state.total_supply);
+    assert_eq!(preview.new_utilization, state.utilization);
+    assert_eq!(preview.new_utilization, rates.utilization);
+    assert_eq!(preview.borrow_rate, rates.borrow_rate);
+    assert_eq!(preview.supply_rate, rates.supply_rate);
+}
+
+struct ReceiptEnv {
+    app: App,
+    supplier: Addr,
+    market_addr: Addr,
+    nft_addr: Addr,
+}
+
+fn setup_receipt_env(fail_mint: bool) -> ReceiptEnv {
+    let api = MockApi::default();
+    let owner = api.addr_make("owner");
+    let supplier = api.addr_make("supplier");
+
+    let mut app = AppBuilder::new().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &supplier, vec![coin(10_000, DEBT_DENOM)])
+            .unwrap();
+    });
+
+    let market_id = app.store_code(market_wrapper());
+    let oracle_id = app.store_code(Box::new(mock_oracle_contract()));
+    let nft_id = app.store_code(Box::new(mock_receipt_nft_contract()));
+
+    let oracle_addr = app
+        .instantiate_contract(
+            oracle_id,
+            owner.clone(),
+            &MockOracleInstantiateMsg {
+                prices: vec![
+                    (
+                        COLLATERAL_DENOM.to_string(),
+                        Decimal::from_ratio(10u128, 1u128),
+                    ),
+                    (DEBT_DENOM.to_string(), Decimal::one()),
+                ],
+            },
+            &[],
+            "mock-oracle",
+            None,
+        )
+        .unwrap();
+
+    let nft_addr = app
+        .instantiate_contract(
+            nft_id,
+            owner.clone(),
+            &MockReceiptNftInstantiateMsg { fail_mint },
+            &[],
+            "mock-receipt-nft",
+            None,
+        )
+        .unwrap();
+
+    let market_addr = app
+        .instantiate_contract(
+            market_id,
+            owner.clone(),
+            &MarketInstantiateMsg {
+                curator: api.addr_make("curator").to_string(),
+                oracle_config: OracleConfigUnchecked {
+                    address: oracle_addr.to_string(),
+                    oracle_type: OracleType::Generic {
+                        expected_code_id: None,
+                        max_staleness_secs: 300,
+                    },
+                },
+                collateral_denom: COLLATERAL_DENOM.to_string(),
+                debt_denom: DEBT_DENOM.to_string(),
+                protocol_fee_collector: api.addr_make("fee_collector").to_string(),
+                params: default_market_params(),
+                allow_collateral_swap: false,
+                receipt_nft_contract: Some(nft_addr.to_string()),
+            },
+            &[],
+            "market",
+            None,
+        )
+        .unwrap();
+
+    ReceiptEnv {
+        app,
+        supplier,
+        market_addr,
+        nft_addr,
+    }
+}
+
+fn supply_debt(env: &mut ReceiptEnv, amount: u128) {
+    env.app
+        .execute_contract(
+            env.supplier.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Supply {
+                recipient: None,
+                referrer: None,
+                force: true,
+                nonce: None,
+            },
+            &[coin(amount, DEBT_DENOM)],
+        )
+        .unwrap();
+}
+
+fn receipt_tokens(env: &ReceiptEnv) -> Vec<String> {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            env.nft_addr.clone(),
+            &MockReceiptNftQueryMsg::Tokens {
+                owner: env.supplier.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn supply_mints_receipts_and_full_withdraw_burns_them() {
+    let mut env = setup_receipt_env(false);
+
+    supply_debt(&mut env, 1_000);
+    supply_debt(&mut env, 500);
+    assert_eq!(
+        receipt_tokens(&env),
+        vec![
+            format!("{}-0", env.market_addr),
+            format!("{}-1", env.market_addr)
+        ]
+    );
+
+    env.app.update_block(|block| {
+        block.time = block.time.plus_seconds(60);
+        block.height += 10;
+    });
+
+    // A partial withdrawal keeps the receipts
+    env.app
+        .execute_contract(
+            env.supplier.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Withdraw {
+                amount: Some(Uint128::new(400)),
+                recipient: None,
+                max_withdraw: false,
+                nonce: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(receipt_tokens(&env).len(), 2);
+
+    env.app
+        .execute_contract(
+            env.supplier.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Withdraw {
+                amount: None,
+                recipient: None,
+                max_withdraw: true,
+                nonce: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(receipt_tokens(&env).is_empty());
+}
+
+#[test]
+fn failing_receipt_mint_does_not_block_supply_or_withdraw() {
+    let mut env = setup_receipt_env(true);
+
+    supply_debt(&mut env, 1_000);
+    assert!(receipt_tokens(&env).is_empty());
+
+    let supply: UserSupplyResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.market_addr.clone(),
+            &MarketQueryMsg::UserSupply {
+                user: env.supplier.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(supply.actual_supply, Uint128::new(1_000));
+
+    env.app.update_block(|block| {
+        block.time = block.time.plus_seconds(60);
+        block.height += 10;
+    });
+
+    env.app
+        .execute_contract(
+            env.supplier.clone(),
+            env.market_addr.clone(),
+            &MarketExecuteMsg::Withdraw {
+                amount: None,
+                recipient: None,
+                max_withdraw: true,
+                nonce: None,
+            },
+            &[],
+        )
+        .unwrap();
+}
@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult,
+    StdResult, Storage,
 };
 use cw_storage_plus::Map;
 use stone_types::{OracleQueryMsg, PriceResponse};
@@ -21,7 +21,16 @@ pub struct PriceInit {
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    SetPrice { denom: String, price: String },
+    SetPrice {
+        denom: String,
+        price: String,
+    },
+    /// Set several prices at once. Also accepts the `set_prices` message used by
+    /// the mock oracle in `stone-testing`.
+    #[serde(alias = "set_prices")]
+    BulkSetPrice {
+        prices: Vec<PriceInit>,
+    },
 }
 
 #[entry_point]
@@ -46,14 +55,46 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::SetPrice { denom, price } => {
-            let price: Decimal = price.parse().unwrap_or(Decimal::zero());
-            PRICES.save(deps.storage, &denom, &price)?;
-            Ok(Response::new()
-                .add_attribute("action", "set_price")
-                .add_attribute("denom", denom))
+        ExecuteMsg::SetPrice { denom, price } => execute_set_price(deps, denom, price),
+        ExecuteMsg::BulkSetPrice { prices } => execute_bulk_set_price(deps, prices),
+    }
+}
+
+/// Store a price, skipping the write if it is unchanged.
+/// Prices are compared after parsing, so "1.50" matches a stored "1.5".
+pub fn execute_set_price(deps: DepsMut, denom: String, price: String) -> StdResult<Response> {
+    let price: Decimal = price.parse().unwrap_or(Decimal::zero());
+    if !update_price(deps.storage, &denom, price)? {
+        return Ok(Response::new().add_attribute("action", "no_op"));
+    }
+    Ok(Response::new()
+        .add_attribute("action", "set_price")
+        .add_attribute("denom", denom))
+}
+
+pub fn execute_bulk_set_price(deps: DepsMut, prices: Vec<PriceInit>) -> StdResult<Response> {
+    let mut updated = 0u32;
+    for price_init in prices {
+        let price: Decimal = price_init.price.parse().unwrap_or(Decimal::zero());
+        if update_price(deps.storage, &price_init.denom, price)? {
+            updated += 1;
         }
     }
+    if updated == 0 {
+        return Ok(Response::new().add_attribute("action", "no_op"));
+    }
+    Ok(Response::new()
+        .add_attribute("action", "bulk_set_price")
+        .add_attribute("updated", updated.to_string()))
+}
+
+/// Save `price` for `denom` unless it is already stored. Returns whether it was written.
+fn update_price(storage: &mut dyn Storage, denom: &str, price: Decimal) -> StdResult<bool> {
+    if PRICES.may_load(storage, denom)? == Some(price) {
+        return Ok(false);
+    }
+    PRICES.save(storage, denom, &price)?;
+    Ok(true)
 }
 
 #[entry_point]
@@ -69,3 +110,65 @@ pub fn query(deps: Deps, env: Env, msg: OracleQueryMsg) -> StdResult<Binary> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_json;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    fn setup(deps: DepsMut) {
+        PRICES
+            .save(deps.storage, "uatom", &Decimal::percent(150))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_set_price_same_value_is_no_op() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        // Equal after parsing even though the string differs from the stored "1.5"
+        let res =
+            execute_set_price(deps.as_mut(), "uatom".to_string(), "1.50".to_string()).unwrap();
+        assert_eq!(res.attributes.len(), 1);
+        assert_eq!(res.attributes[0].value, "no_op");
+
+        let res = execute_set_price(deps.as_mut(), "uatom".to_string(), "2".to_string()).unwrap();
+        assert_eq!(res.attributes[0].value, "set_price");
+        assert_eq!(
+            PRICES.load(&deps.storage, "uatom").unwrap(),
+            Decimal::percent(200)
+        );
+    }
+
+    #[test]
+    fn test_bulk_set_price_skips_unchanged_prices() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let prices = vec![
+            PriceInit {
+                denom: "uatom".to_string(),
+                price: "1.5".to_string(),
+            },
+            PriceInit {
+                denom: "uusdc".to_string(),
+                price: "1".to_string(),
+            },
+        ];
+        let res = execute_bulk_set_price(deps.as_mut(), prices.clone()).unwrap();
+        assert_eq!(res.attributes[0].value, "bulk_set_price");
+        assert_eq!(res.attributes[1].value, "1");
+
+        let res = execute_bulk_set_price(deps.as_mut(), prices).unwrap();
+        assert_eq!(res.attributes[0].value, "no_op");
+    }
+
+    #[test]
+    fn test_bulk_set_price_accepts_set_prices_alias() {
+        let msg: ExecuteMsg =
+            from_json(br#"{"set_prices":{"prices":[{"denom":"uatom","price":"2"}]}}"#).unwrap();
+        assert!(matches!(msg, ExecuteMsg::BulkSetPrice { .. }));
+    }
+}
@@ -6,13 +6,16 @@
 //!
 //! The contract stores price feeds keyed by their 64-character hex feed ID
 //! and responds to `PriceFeed { id }` queries with `PriceFeedResponse`.
+//! `PriceFeedNoOlderThan { id, age_secs }` adds the staleness check of Pyth's
+//! `get_price_no_older_than` against the block time.
 //!
 //! Supports both single and batch price updates to simulate the real
 //! Pyth pull model where prices are submitted externally.
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Uint128,
 };
 use cw_storage_plus::Map;
 use schemars::JsonSchema;
@@ -211,6 +214,30 @@ pub enum ExecuteMsg {
         /// List of price feed updates.
         feeds: Vec<PriceFeedUpdate>,
     },
+    /// Activate or deactivate a feed (simulates a feed going offline).
+    /// Queries for an inactive feed fail until it is reactivated.
+    SetFeedActive {
+        /// Feed ID (64-character hex).
+        id: String,
+        /// Whether the feed should serve prices.
+        active: bool,
+    },
+    /// Multiply the price and EMA price of every stored feed by `factor`
+    /// (simulates a market-wide price shock). The exponent is left unchanged.
+    ScaleAllPrices {
+        /// Multiplier applied to every price; must be greater than zero.
+        factor: Decimal,
+        /// Publish timestamp to set on every feed.
+        new_publish_time: i64,
+    },
+    /// Change the exponent of an existing feed, leaving its price, confidence,
+    /// and publish time untouched.
+    SetExpo {
+        /// Feed ID (64-character hex).
+        id: String,
+        /// New exponent.
+        expo: i32,
+    },
 }
 
 /// Query messages for the Pyth contract.
@@ -220,6 +247,34 @@ pub enum QueryMsg {
     /// Query a single price feed by ID.
     #[returns(PriceFeedResponse)]
     PriceFeed { id: PriceIdentifier },
+    /// Query several price feeds at once. Feeds that don't exist or are
+    /// inactive are reported in `missing` instead of failing the query.
+    #[returns(MultiPriceFeedResponse)]
+    MultiPriceFeed { ids: Vec<PriceIdentifier> },
+    /// Query only a feed's publish time, for staleness checks in tests.
+    /// Answered for inactive feeds too.
+    #[returns(PublishTimeResponse)]
+    PublishTime { id: PriceIdentifier },
+    /// Query a single price feed, failing with "price too old" if it was
+    /// published more than `age_secs` before the current block time (like
+    /// Pyth's `get_price_no_older_than`).
+    #[returns(PriceFeedResponse)]
+    PriceFeedNoOlderThan { id: PriceIdentifier, age_secs: u64 },
+}
+
+/// Response from a publish time query.
+#[cw_serde]
+pub struct PublishTimeResponse {
+    pub publish_time: i64,
+}
+
+/// Response from a batched price feed query.
+#[cw_serde]
+pub struct MultiPriceFeedResponse {
+    /// Feeds that were found, in the order they were requested.
+    pub feeds: Vec<PriceFeedResponse>,
+    /// Requested IDs with no servable feed, in the order they were requested.
+    pub missing: Vec<PriceIdentifier>,
 }
 
 /// Stored feed data.
@@ -231,6 +286,14 @@ pub struct StoredFeed {
     pub publish_time: i64,
     pub ema_price: i64,
     pub ema_conf: u64,
+    /// Whether the feed is serving prices (defaults to true for feeds stored
+    /// before this field existed).
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
 }
 
 /// Storage: feed_id (hex string) → stored feed data.
@@ -255,6 +318,7 @@ pub fn instantiate(
                 publish_time: feed.publish_time,
                 ema_price: feed.ema_price.unwrap_or(feed.price),
                 ema_conf: feed.ema_conf.unwrap_or(feed.conf),
+                active: true,
             },
         )?;
     }
@@ -294,20 +358,7 @@ pub fn execute(
 
             // Validate all feed IDs before processing
             for update in &feeds {
-                if update.id.len() != 64 {
-                    return Err(cosmwasm_std::StdError::generic_err(format!(
-                        "Invalid feed ID length: expected 64 characters, got {} for feed ID: {}",
-                        update.id.len(),
-                        &update.id[..update.id.len().min(16)]
-                    )));
-                }
-                // Validate hex characters
-                if !update.id.chars().all(|c| c.is_ascii_hexdigit()) {
-                    return Err(cosmwasm_std::StdError::generic_err(format!(
-                        "Invalid feed ID format: must be hex string, got invalid characters in: {}",
-                        &update.id[..update.id.len().min(16)]
-                    )));
-                }
+                validate_feed_id(&update.id)?;
             }
 
             for update in feeds {
@@ -338,6 +389,7 @@ pub fn execute(
                             publish_time: update.publish_time,
                             ema_price: update.ema_price.unwrap_or(update.price),
                             ema_conf: update.ema_conf.unwrap_or(update.conf),
+                            active: true,
                         };
                         FEEDS.save(deps.storage, &update.id, &feed)?;
                         created_count += 1;
@@ -350,37 +402,152 @@ pub fn execute(
                 .add_attribute("updated", updated_count.to_string())
                 .add_attribute("created", created_count.to_string()))
         }
+        ExecuteMsg::SetFeedActive { id, active } => {
+            FEEDS.update(deps.storage, &id, |existing| -> StdResult<_> {
+                let mut feed = existing.ok_or_else(|| cosmwasm_std::StdError::not_found("feed"))?;
+                feed.active = active;
+                Ok(feed)
+            })?;
+            Ok(Response::new()
+                .add_attribute("action", "set_feed_active")
+                .add_attribute("feed_id", id)
+                .add_attribute("active", active.to_string()))
+        }
+        ExecuteMsg::ScaleAllPrices {
+            factor,
+            new_publish_time,
+        } => {
+            if factor.is_zero() {
+                return Err(StdError::generic_err("factor must be greater than zero"));
+            }
+
+            let feeds = FEEDS
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            let scaled_count = feeds.len();
+
+            for (id, mut feed) in feeds {
+                feed.price = scale_price(feed.price, factor)?;
+                feed.ema_price = scale_price(feed.ema_price, factor)?;
+                feed.publish_time = new_publish_time;
+                FEEDS.save(deps.storage, &id, &feed)?;
+            }
+
+            Ok(Response::new()
+                .add_attribute("action", "scale_all_prices")
+                .add_attribute("factor", factor.to_string())
+                .add_attribute("scaled", scaled_count.to_string()))
+        }
+        ExecuteMsg::SetExpo { id, expo } => {
+            validate_feed_id(&id)?;
+            FEEDS.update(deps.storage, &id, |existing| -> StdResult<_> {
+                let mut feed = existing.ok_or_else(|| cosmwasm_std::StdError::not_found("feed"))?;
+                feed.expo = expo;
+                Ok(feed)
+            })?;
+            Ok(Response::new()
+                .add_attribute("action", "set_expo")
+                .add_attribute("feed_id", id)
+                .add_attribute("expo", expo.to_string()))
+        }
+    }
+}
+
+/// Check that a feed ID is a 64-character hex string.
+fn validate_feed_id(id: &str) -> StdResult<()> {
+    if id.len() != 64 {
+        return Err(cosmwasm_std::StdError::generic_err(format!(
+            "Invalid feed ID length: expected 64 characters, got {} for feed ID: {}",
+            id.len(),
+            &id[..id.len().min(16)]
+        )));
+    }
+    // Validate hex characters
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(cosmwasm_std::StdError::generic_err(format!(
+            "Invalid feed ID format: must be hex string, got invalid characters in: {}",
+            &id[..id.len().min(16)]
+        )));
     }
+    Ok(())
+}
+
+/// Multiply a raw Pyth price by `factor`, rounding toward zero.
+fn scale_price(price: i64, factor: Decimal) -> StdResult<i64> {
+    let scaled = Uint128::from(price.unsigned_abs()).mul_floor(factor);
+    let scaled = i64::try_from(scaled.u128())
+        .map_err(|_| StdError::generic_err(format!("scaled price {scaled} overflows i64")))?;
+    Ok(if price < 0 { -scaled } else { scaled })
 }
 
 /// Query entry point.
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::PriceFeed { id } => {
             let id_hex = hex::encode(id.0);
             let feed = FEEDS.load(deps.storage, &id_hex)?;
+            if !feed.active {
+                return Err(cosmwasm_std::StdError::generic_err("feed is inactive"));
+            }
 
-            let response = PriceFeedResponse {
-                price_feed: PriceFeed {
-                    id,
-                    price: Price {
-                        price: feed.price,
-                        conf: feed.conf,
-                        expo: feed.expo,
-                        publish_time: feed.publish_time,
-                    },
-                    ema_price: Price {
-                        price: feed.ema_price,
-                        conf: feed.ema_conf,
-                        expo: feed.expo,
-                        publish_time: feed.publish_time,
-                    },
-                },
+            to_json_binary(&price_feed_response(id, &feed))
+        }
+        QueryMsg::MultiPriceFeed { ids } => {
+            let mut response = MultiPriceFeedResponse {
+                feeds: vec![],
+                missing: vec![],
             };
+            for id in ids {
+                match FEEDS.may_load(deps.storage, &id.to_hex())? {
+                    Some(feed) if feed.active => {
+                        response.feeds.push(price_feed_response(id, &feed))
+                    }
+                    _ => response.missing.push(id),
+                }
+            }
 
             to_json_binary(&response)
         }
+        QueryMsg::PublishTime { id } => {
+            let feed = FEEDS.load(deps.storage, &id.to_hex())?;
+            to_json_binary(&PublishTimeResponse {
+                publish_time: feed.publish_time,
+            })
+        }
+        QueryMsg::PriceFeedNoOlderThan { id, age_secs } => {
+            let feed = FEEDS.load(deps.storage, &id.to_hex())?;
+            if !feed.active {
+                return Err(cosmwasm_std::StdError::generic_err("feed is inactive"));
+            }
+            // Prices published after the block time count as fresh
+            let age = i128::from(env.block.time.seconds()) - i128::from(feed.publish_time);
+            if age > i128::from(age_secs) {
+                return Err(StdError::generic_err("price too old"));
+            }
+
+            to_json_binary(&price_feed_response(id, &feed))
+        }
+    }
+}
+
+fn price_feed_response(id: PriceIdentifier, feed: &StoredFeed) -> PriceFeedResponse {
+    PriceFeedResponse {
+        price_feed: PriceFeed {
+            id,
+            price: Price {
+                price: feed.price,
+                conf: feed.conf,
+                expo: feed.expo,
+                publish_time: feed.publish_time,
+            },
+            ema_price: Price {
+                price: feed.ema_price,
+                conf: feed.ema_conf,
+                expo: feed.expo,
+                publish_time: feed.publish_time,
+            },
+        },
     }
 }
 
@@ -530,6 +697,195 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_publish_time_query() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = message_info(&test_addr(), &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![PriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let query_msg = QueryMsg::PublishTime {
+            id: PriceIdentifier(atom_feed_id_bytes()),
+        };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let response: PublishTimeResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert_eq!(response.publish_time, 1_700_000_000i64);
+
+        // Unknown feeds are an error
+        let query_msg = QueryMsg::PublishTime {
+            id: PriceIdentifier(usdc_feed_id_bytes()),
+        };
+        assert!(query(deps.as_ref(), env, query_msg).is_err());
+    }
+
+    #[test]
+    fn test_price_feed_no_older_than() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&test_addr(), &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![PriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_at = |block_time: u64, age_secs: u64| {
+            let mut env = mock_env();
+            env.block.time = cosmwasm_std::Timestamp::from_seconds(block_time);
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::PriceFeedNoOlderThan {
+                    id: PriceIdentifier(atom_feed_id_bytes()),
+                    age_secs,
+                },
+            )
+        };
+
+        // Published in the same block
+        let res = query_at(1_700_000_000, 0).unwrap();
+        let response: PriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert_eq!(response.price_feed.price.price, 1_052_000_000i64);
+
+        // Exactly at the age limit is still fresh
+        assert!(query_at(1_700_000_060, 60).is_ok());
+
+        // One second past the limit is too old
+        let err = query_at(1_700_000_061, 60).unwrap_err();
+        assert!(err.to_string().contains("price too old"));
+
+        // A large limit accepts an old price, a zero limit rejects it
+        assert!(query_at(1_700_086_400, 86_400).is_ok());
+        assert!(query_at(1_700_000_001, 0).is_err());
+
+        // A publish time ahead of the block time is fresh
+        assert!(query_at(1_699_999_000, 0).is_ok());
+
+        // Unknown feeds are an error
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(1_700_000_000);
+        let query_msg = QueryMsg::PriceFeedNoOlderThan {
+            id: PriceIdentifier(usdc_feed_id_bytes()),
+            age_secs: 60,
+        };
+        assert!(query(deps.as_ref(), env, query_msg).is_err());
+    }
+
+    #[test]
+    fn test_multi_price_feed_reports_missing() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let feed = |id: String, price: i64| PriceFeedInit {
+            id,
+            price,
+            conf: 1_000_000u64,
+            expo: -8,
+            publish_time: 1_700_000_000i64,
+            ema_price: None,
+            ema_conf: None,
+        };
+        let msg = InstantiateMsg {
+            feeds: vec![
+                feed(atom_feed_id(), 1_052_000_000i64),
+                feed(usdc_feed_id(), 100_000_000i64),
+            ],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The STONE feed was never created
+        let query_msg = QueryMsg::MultiPriceFeed {
+            ids: vec![
+                PriceIdentifier(usdc_feed_id_bytes()),
+                PriceIdentifier(stone_feed_id_bytes()),
+                PriceIdentifier(atom_feed_id_bytes()),
+            ],
+        };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let response: MultiPriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+
+        // Found feeds keep the requested order
+        assert_eq!(response.feeds.len(), 2);
+        assert_eq!(
+            response.feeds[0].price_feed.id,
+            PriceIdentifier(usdc_feed_id_bytes())
+        );
+        assert_eq!(response.feeds[0].price_feed.price.price, 100_000_000i64);
+        assert_eq!(
+            response.feeds[1].price_feed.id,
+            PriceIdentifier(atom_feed_id_bytes())
+        );
+        assert_eq!(response.feeds[1].price_feed.price.price, 1_052_000_000i64);
+
+        assert_eq!(
+            response.missing,
+            vec![PriceIdentifier(stone_feed_id_bytes())]
+        );
+    }
+
+    #[test]
+    fn test_multi_price_feed_treats_inactive_as_missing() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![PriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SetFeedActive {
+                id: atom_feed_id(),
+                active: false,
+            },
+        )
+        .unwrap();
+
+        let query_msg = QueryMsg::MultiPriceFeed {
+            ids: vec![PriceIdentifier(atom_feed_id_bytes())],
+        };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let response: MultiPriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert!(response.feeds.is_empty());
+        assert_eq!(
+            response.missing,
+            vec![PriceIdentifier(atom_feed_id_bytes())]
+        );
+    }
+
     // ==========================================================================
     // Tests for UpdatePriceFeeds (batch update)
     // ==========================================================================
@@ -917,4 +1273,342 @@ mod tests {
         let err_msg = format!("{}", res.unwrap_err());
         assert!(err_msg.contains("Invalid feed ID format"));
     }
+
+    #[test]
+    fn test_set_feed_active() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![PriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let query_msg = QueryMsg::PriceFeed {
+            id: PriceIdentifier(atom_feed_id_bytes()),
+        };
+
+        // Deactivate the feed
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetFeedActive {
+                id: atom_feed_id(),
+                active: false,
+            },
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "set_feed_active"));
+
+        // Query fails while inactive
+        let err = query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("feed is inactive"));
+
+        // Reactivate and query succeeds again
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SetFeedActive {
+                id: atom_feed_id(),
+                active: true,
+            },
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let response: PriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert_eq!(response.price_feed.price.price, 1_052_000_000i64);
+    }
+
+    #[test]
+    fn test_set_feed_active_unknown_feed() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg { feeds: vec![] };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::SetFeedActive {
+                id: atom_feed_id(),
+                active: false,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_set_expo() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![PriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SetExpo {
+                id: atom_feed_id(),
+                expo: -5,
+            },
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "expo" && a.value == "-5"));
+
+        let query_msg = QueryMsg::PriceFeed {
+            id: PriceIdentifier(atom_feed_id_bytes()),
+        };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let response: PriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert_eq!(response.price_feed.price.expo, -5);
+        assert_eq!(response.price_feed.ema_price.expo, -5);
+        // Everything else is unchanged
+        assert_eq!(response.price_feed.price.price, 1_052_000_000i64);
+        assert_eq!(response.price_feed.price.conf, 1_000_000u64);
+        assert_eq!(response.price_feed.price.publish_time, 1_700_000_000i64);
+    }
+
+    #[test]
+    fn test_set_expo_rejects_invalid_or_unknown_feed() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg { feeds: vec![] };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetExpo {
+                id: "abc123".to_string(),
+                expo: -5,
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid feed ID length"));
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::SetExpo {
+                id: atom_feed_id(),
+                expo: -5,
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_scale_all_prices() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg {
+            feeds: vec![
+                PriceFeedInit {
+                    id: atom_feed_id(),
+                    price: 1_052_000_000i64,
+                    conf: 1_000_000u64,
+                    expo: -8,
+                    publish_time: 1_700_000_000i64,
+                    ema_price: Some(1_050_000_000i64),
+                    ema_conf: None,
+                },
+                PriceFeedInit {
+                    id: usdc_feed_id(),
+                    price: 100_000_001i64,
+                    conf: 50_000u64,
+                    expo: -8,
+                    publish_time: 1_700_000_000i64,
+                    ema_price: None,
+                    ema_conf: None,
+                },
+            ],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::ScaleAllPrices {
+                factor: Decimal::percent(50),
+                new_publish_time: 1_700_000_100i64,
+            },
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "scaled" && a.value == "2"));
+
+        let query_msg = QueryMsg::PriceFeed {
+            id: PriceIdentifier(atom_feed_id_bytes()),
+        };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let atom: PriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        assert_eq!(atom.price_feed.price.price, 526_000_000i64);
+        assert_eq!(atom.price_feed.ema_price.price, 525_000_000i64);
+        assert_eq!(atom.price_feed.price.expo, -8);
+        assert_eq!(atom.price_feed.price.conf, 1_000_000u64);
+        assert_eq!(atom.price_feed.price.publish_time, 1_700_000_100i64);
+
+        let query_msg = QueryMsg::PriceFeed {
+            id: PriceIdentifier(usdc_feed_id_bytes()),
+        };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let usdc: PriceFeedResponse = cosmwasm_std::from_json(&res).unwrap();
+        // Rounds toward zero
+        assert_eq!(usdc.price_feed.price.price, 50_000_000i64);
+        assert_eq!(usdc.price_feed.ema_price.price, 50_000_000i64);
+        assert_eq!(usdc.price_feed.ema_price.publish_time, 1_700_000_100i64);
+    }
+
+    #[test]
+    fn test_scale_all_prices_rejects_zero_factor() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addr();
+        let info = message_info(&creator, &[]);
+
+        let msg = InstantiateMsg { feeds: vec![] };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::ScaleAllPrices {
+                factor: Decimal::zero(),
+                new_publish_time: 1_700_000_100i64,
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("factor must be greater than zero"));
+    }
+
+    fn numbered_feed_id(i: u64) -> String {
+        format!("{:064x}", i + 1)
+    }
+
+    fn query_numbered_feed(deps: Deps, i: u64) -> PriceFeedResponse {
+        let id = PriceIdentifier::from_hex(&numbered_feed_id(i)).unwrap();
+        let res = query(deps, mock_env(), QueryMsg::PriceFeed { id }).unwrap();
+        cosmwasm_std::from_json(&res).unwrap()
+    }
+
+    /// 100 feeds, instantiated and then batch-updated in one message each.
+    /// Each feed is a single FEEDS write of ~200 bytes, so gas grows linearly
+    /// with the batch; at SDK KV costs (2000 flat + 30 per byte written) 100
+    /// feeds come to well under 1M gas, far below typical block gas limits.
+    #[test]
+    fn test_hundred_feed_batches() {
+        const FEED_COUNT: u64 = 100;
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = message_info(&test_addr(), &[]);
+
+        let feeds = (0..FEED_COUNT)
+            .map(|i| PriceFeedInit {
+                id: numbered_feed_id(i),
+                price: 1_000_000 + i as i64,
+                conf: 1_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            })
+            .collect();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg { feeds },
+        )
+        .unwrap();
+
+        for i in 0..FEED_COUNT {
+            let response = query_numbered_feed(deps.as_ref(), i);
+            assert_eq!(response.price_feed.price.price, 1_000_000 + i as i64);
+            assert_eq!(response.price_feed.ema_price.price, 1_000_000 + i as i64);
+        }
+
+        let feeds = (0..FEED_COUNT)
+            .map(|i| PriceFeedUpdate {
+                id: numbered_feed_id(i),
+                price: 2_000_000 + i as i64,
+                conf: 2_000u64,
+                expo: -8,
+                publish_time: 1_700_000_100i64,
+                ema_price: Some(1_500_000 + i as i64),
+                ema_conf: None,
+            })
+            .collect();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::UpdatePriceFeeds { feeds },
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "updated" && a.value == "100"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "created" && a.value == "0"));
+
+        for i in 0..FEED_COUNT {
+            let response = query_numbered_feed(deps.as_ref(), i);
+            assert_eq!(response.price_feed.price.price, 2_000_000 + i as i64);
+            assert_eq!(response.price_feed.price.conf, 2_000u64);
+            assert_eq!(response.price_feed.price.publish_time, 1_700_000_100i64);
+            assert_eq!(response.price_feed.ema_price.price, 1_500_000 + i as i64);
+        }
+    }
 }
@@ -1,7 +1,7 @@
 //! Contract entry points for the Pyth oracle adapter.
 //!
 //! This module contains the main contract logic including:
-//! - Entry points (`instantiate`, `execute`, `query`)
+//! - Entry points (`instantiate`, `execute`, `query`, `migrate`)
 //! - Execute handlers for admin operations
 //! - Query handlers for price and configuration queries
 //!
@@ -24,13 +24,19 @@
 use std::collections::HashSet;
 
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    entry_point, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Response,
+    StdResult, Storage, Uint128,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, PriceFeedConfig, PriceFeedMetadata, QueryMsg,
+};
 use crate::pyth_types::{PriceFeedResponse, PriceIdentifier, PythQueryMsg};
-use crate::state::{Config, CONFIG, CONTRACT_NAME, CONTRACT_VERSION, PENDING_OWNER, PRICE_FEEDS};
+use crate::state::{
+    migrate_config, CachedPrice, Config, CACHED_PRICES, CONFIG, CONTRACT_NAME, CONTRACT_VERSION,
+    PENDING_OWNER, PRICE_FEEDS, PRICE_FEED_METADATA,
+};
 
 /// Contract entry point for instantiation.
 ///
@@ -85,11 +91,25 @@ pub fn instantiate(
         });
     }
 
+    // A zero minimum is the same as no minimum
+    let min_confidence_ratio = msg.min_confidence_ratio.filter(|ratio| !ratio.is_zero());
+    if let Some(ratio) = min_confidence_ratio {
+        validate_min_confidence_ratio(ratio, msg.max_confidence_ratio)?;
+    }
+
+    if let Some(denom) = &msg.pyth_fee_denom {
+        validate_pyth_fee_denom(denom)?;
+    }
+
     // Store Config in state
     let config = Config {
         owner,
         pyth_contract_addr,
         max_confidence_ratio: msg.max_confidence_ratio,
+        min_confidence_ratio,
+        pyth_fee_denom: msg.pyth_fee_denom,
+        pyth_fee_amount: msg.pyth_fee_amount,
+        min_update_interval_secs: 0,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -113,6 +133,7 @@ pub fn instantiate(
         })?;
         // Store mapping: denom → PriceIdentifier
         PRICE_FEEDS.save(deps.storage, &price_feed.denom, &feed_id)?;
+        save_price_feed_metadata(deps.storage, &price_feed.denom, price_feed.metadata)?;
     }
 
     // Set contract version for migration tracking
@@ -142,25 +163,56 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::SetPriceFeed { denom, feed_id } => {
-            execute_set_price_feed(deps, env, info, denom, feed_id)
+        ExecuteMsg::SetPriceFeed {
+            denom,
+            feed_id,
+            metadata,
+        } => execute_set_price_feed(deps, env, info, denom, feed_id, metadata),
+        ExecuteMsg::SetPriceFeedWithValidation { denom, feed_id } => {
+            execute_set_price_feed_with_validation(deps, env, info, denom, feed_id)
+        }
+        ExecuteMsg::BatchSetPriceFeeds { feeds } => {
+            execute_batch_set_price_feeds(deps, env, info, feeds)
         }
         ExecuteMsg::RemovePriceFeed { denom } => execute_remove_price_feed(deps, env, info, denom),
         ExecuteMsg::UpdateConfig {
             pyth_contract_addr,
             max_confidence_ratio,
-        } => execute_update_config(deps, env, info, pyth_contract_addr, max_confidence_ratio),
+            min_confidence_ratio,
+            pyth_fee_denom,
+            pyth_fee_amount,
+            min_update_interval_secs,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            pyth_contract_addr,
+            max_confidence_ratio,
+            min_confidence_ratio,
+            pyth_fee_denom,
+            pyth_fee_amount,
+            min_update_interval_secs,
+        ),
+        ExecuteMsg::RefreshPriceCache { denom } => execute_refresh_price_cache(deps, env, denom),
+        ExecuteMsg::FetchPriceForLog { denom } => execute_fetch_price_for_log(deps, env, denom),
         ExecuteMsg::TransferOwnership { new_owner } => {
             execute_transfer_ownership(deps, env, info, new_owner)
         }
         ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        #[cfg(all(
+            feature = "unsafe-single-step-owner-transfer",
+            not(feature = "library")
+        ))]
+        ExecuteMsg::ForceSetOwner { new_owner } => {
+            execute_force_set_owner(deps, env, info, new_owner)
+        }
     }
 }
 
 /// Set or update a price feed mapping.
 ///
 /// Associates a denom with a Pyth feed ID. If the denom already exists,
-/// its feed ID is updated to the new value.
+/// its feed ID and metadata are replaced with the new values.
 ///
 /// # Authorization
 ///
@@ -176,6 +228,7 @@ fn execute_set_price_feed(
     info: MessageInfo,
     denom: String,
     feed_id: String,
+    metadata: Option<PriceFeedMetadata>,
 ) -> Result<Response, ContractError> {
     // Require sender == owner
     let config = CONFIG.load(deps.storage)?;
@@ -189,8 +242,8 @@ fn execute_set_price_feed(
             feed_id: feed_id.clone(),
         })?;
 
-    // Save to PRICE_FEEDS map
-    PRICE_FEEDS.save(deps.storage, &denom, &feed_id)?;
+    store_price_feed(deps.storage, &denom, &feed_id)?;
+    save_price_feed_metadata(deps.storage, &denom, metadata)?;
 
     Ok(Response::new()
         .add_attribute("action", "set_price_feed")
@@ -198,6 +251,142 @@ fn execute_set_price_feed(
         .add_attribute("feed_id", feed_id.to_hex()))
 }
 
+/// Set or update a price feed mapping once Pyth has answered for the feed.
+///
+/// Issues a `PriceFeed` query to the Pyth contract before storing anything.
+/// The price itself is not checked (sign, confidence), only that Pyth serves
+/// the feed. Metadata already stored for the denom is kept.
+///
+/// # Authorization
+///
+/// Requires the caller to be the contract owner.
+///
+/// # Errors
+///
+/// * `Unauthorized` - Caller is not the owner
+/// * `InvalidFeedId` - Feed ID is not a valid 64-character hex string
+/// * `FeedNotLiveInPyth` - The Pyth query for the feed failed
+fn execute_set_price_feed_with_validation(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+    feed_id: String,
+) -> Result<Response, ContractError> {
+    // Require sender == owner
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let feed_id =
+        PriceIdentifier::from_hex(&feed_id).map_err(|_| ContractError::InvalidFeedId {
+            feed_id: feed_id.clone(),
+        })?;
+
+    deps.querier
+        .query_wasm_smart::<PriceFeedResponse>(
+            config.pyth_contract_addr.as_str(),
+            &PythQueryMsg::PriceFeed { id: feed_id },
+        )
+        .map_err(|_| ContractError::FeedNotLiveInPyth {
+            feed_id: feed_id.to_hex(),
+        })?;
+
+    store_price_feed(deps.storage, &denom, &feed_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_price_feed_with_validation")
+        .add_attribute("denom", denom)
+        .add_attribute("feed_id", feed_id.to_hex()))
+}
+
+/// Set multiple price feed mappings in one call.
+///
+/// Every feed ID is validated and duplicate denoms are rejected before
+/// anything is written, so the batch is applied all-or-nothing. Denoms
+/// that already have a feed are updated, as with `SetPriceFeed`.
+///
+/// # Authorization
+///
+/// Requires the caller to be the contract owner.
+///
+/// # Errors
+///
+/// * `Unauthorized` - Caller is not the owner
+/// * `DuplicateDenom` - The same denom appears more than once in the batch
+/// * `InvalidFeedId` - A feed ID is not a valid 64-character hex string
+fn execute_batch_set_price_feeds(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    feeds: Vec<PriceFeedConfig>,
+) -> Result<Response, ContractError> {
+    // Require sender == owner
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    // Validate the whole batch before writing anything
+    let mut seen_denoms: HashSet<String> = HashSet::new();
+    let mut parsed = Vec::with_capacity(feeds.len());
+    for price_feed in feeds {
+        if !seen_denoms.insert(price_feed.denom.clone()) {
+            return Err(ContractError::DuplicateDenom {
+                denom: price_feed.denom,
+            });
+        }
+        let feed_id = PriceIdentifier::from_hex(&price_feed.feed_id).map_err(|_| {
+            ContractError::InvalidFeedId {
+                feed_id: price_feed.feed_id.clone(),
+            }
+        })?;
+        parsed.push((price_feed.denom, feed_id, price_feed.metadata));
+    }
+
+    // Save to PRICE_FEEDS map
+    let set_count = parsed.len();
+    for (denom, feed_id, metadata) in parsed {
+        store_price_feed(deps.storage, &denom, &feed_id)?;
+        save_price_feed_metadata(deps.storage, &denom, metadata)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "batch_set_price_feeds")
+        .add_attribute("set_count", set_count.to_string()))
+}
+
+/// Point `denom` at `feed_id`. A price cached for the denom may come from its
+/// previous feed, so it is dropped.
+fn store_price_feed(
+    storage: &mut dyn Storage,
+    denom: &str,
+    feed_id: &PriceIdentifier,
+) -> StdResult<()> {
+    PRICE_FEEDS.save(storage, denom, feed_id)?;
+    CACHED_PRICES.remove(storage, denom);
+    Ok(())
+}
+
+/// Store or clear the metadata for a denom's price feed.
+///
+/// Metadata mirrors the latest `SetPriceFeed` configuration, so `None`
+/// removes any metadata left over from a previous configuration.
+fn save_price_feed_metadata(
+    storage: &mut dyn Storage,
+    denom: &str,
+    metadata: Option<PriceFeedMetadata>,
+) -> StdResult<()> {
+    match metadata {
+        Some(metadata) => PRICE_FEED_METADATA.save(storage, denom, &metadata),
+        None => {
+            PRICE_FEED_METADATA.remove(storage, denom);
+            Ok(())
+        }
+    }
+}
+
 /// Remove a price feed mapping.
 ///
 /// Removes the association between a denom and its Pyth feed ID.
@@ -229,6 +418,8 @@ fn execute_remove_price_feed(
         });
     }
     PRICE_FEEDS.remove(deps.storage, &denom);
+    PRICE_FEED_METADATA.remove(deps.storage, &denom);
+    CACHED_PRICES.remove(deps.storage, &denom);
 
     Ok(Response::new()
         .add_attribute("action", "remove_price_feed")
@@ -247,13 +438,20 @@ fn execute_remove_price_feed(
 /// # Errors
 ///
 /// * `Unauthorized` - Caller is not the owner
-/// * `InvalidConfidenceRatio` - New ratio is 0 or > 1
+/// * `InvalidConfidenceRatio` - New ratio is 0 or > 1, or the minimum is not
+///   below the maximum
+/// * `InvalidPythFeeDenom` - New fee denom is empty
+#[allow(clippy::too_many_arguments)]
 fn execute_update_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     pyth_contract_addr: Option<String>,
     max_confidence_ratio: Option<Decimal>,
+    min_confidence_ratio: Option<Decimal>,
+    pyth_fee_denom: Option<String>,
+    pyth_fee_amount: Option<Uint128>,
+    min_update_interval_secs: Option<u64>,
 ) -> Result<Response, ContractError> {
     // Require sender == owner
     let mut config = CONFIG.load(deps.storage)?;
@@ -262,6 +460,7 @@ fn execute_update_config(
     }
 
     let mut attributes = vec![("action", "update_config".to_string())];
+    let previous = config.clone();
 
     // Partial update — only update fields that are Some
     if let Some(addr) = pyth_contract_addr {
@@ -291,9 +490,45 @@ fn execute_update_config(
         ));
     }
 
+    if let Some(ratio) = min_confidence_ratio {
+        // Zero removes the minimum
+        config.min_confidence_ratio = (!ratio.is_zero()).then_some(ratio);
+        attributes.push(("min_confidence_ratio", ratio.to_string()));
+    }
+
+    // Checked after both updates so a lower maximum can't undercut the minimum
+    if let Some(ratio) = config.min_confidence_ratio {
+        validate_min_confidence_ratio(ratio, config.max_confidence_ratio)?;
+    }
+
+    if let Some(denom) = pyth_fee_denom {
+        validate_pyth_fee_denom(&denom)?;
+        attributes.push(("pyth_fee_denom", denom.clone()));
+        config.pyth_fee_denom = Some(denom);
+    }
+
+    if let Some(amount) = pyth_fee_amount {
+        config.pyth_fee_amount = Some(amount);
+        attributes.push(("pyth_fee_amount", amount.to_string()));
+    }
+
+    if let Some(interval) = min_update_interval_secs {
+        config.min_update_interval_secs = interval;
+        attributes.push(("min_update_interval_secs", interval.to_string()));
+    }
+
     // Save updated Config
     CONFIG.save(deps.storage, &config)?;
 
+    // Cached prices were read from the old Pyth contract or validated against
+    // the old confidence bounds, so none of them can be served any more
+    if config.pyth_contract_addr != previous.pyth_contract_addr
+        || config.max_confidence_ratio != previous.max_confidence_ratio
+        || config.min_confidence_ratio != previous.min_confidence_ratio
+    {
+        CACHED_PRICES.clear(deps.storage);
+    }
+
     let mut resp = Response::new();
     for (key, value) in attributes {
         resp = resp.add_attribute(key, value);
@@ -302,6 +537,94 @@ fn execute_update_config(
     Ok(resp)
 }
 
+/// Check that a minimum confidence ratio leaves room for valid prices below
+/// `max_confidence_ratio`.
+fn validate_min_confidence_ratio(
+    min_confidence_ratio: Decimal,
+    max_confidence_ratio: Decimal,
+) -> Result<(), ContractError> {
+    if min_confidence_ratio >= max_confidence_ratio {
+        return Err(ContractError::InvalidConfidenceRatio {
+            value: min_confidence_ratio,
+            reason: format!(
+                "minimum must be less than max_confidence_ratio {max_confidence_ratio}"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Read a denom's price from Pyth and store it in the price cache.
+///
+/// Anyone may call this; the price is validated exactly like the `Price`
+/// query, so only prices a market would accept are cached.
+///
+/// # Errors
+///
+/// Same as `query_price`.
+fn execute_refresh_price_cache(
+    deps: DepsMut,
+    _env: Env,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let price = fetch_pyth_price(deps.as_ref(), &config, denom)?;
+
+    CACHED_PRICES.save(
+        deps.storage,
+        &price.denom,
+        &CachedPrice {
+            price: price.price,
+            updated_at: price.updated_at,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(emit_price_queried_event(&price))
+        .add_attribute("action", "refresh_price_cache")
+        .add_attribute("denom", price.denom)
+        .add_attribute("price", price.price.to_string())
+        .add_attribute("updated_at", price.updated_at.to_string()))
+}
+
+/// Read a denom's price through the `Price` query path and log it.
+///
+/// Stores nothing; the price only ends up in the transaction's events.
+fn execute_fetch_price_for_log(
+    deps: DepsMut,
+    env: Env,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let price = query_price(deps.as_ref(), env, denom)?;
+
+    Ok(Response::new()
+        .add_event(emit_price_queried_event(&price))
+        .add_attribute("action", "fetch_price_for_log")
+        .add_attribute("denom", price.denom))
+}
+
+/// Event type recording a price read by an execute message.
+pub const PRICE_QUERIED_EVENT: &str = "stone.pyth_adapter.price_queried";
+
+/// Typed event for a price read during an execute message.
+///
+/// Queries cannot emit events, so this is only attached by execute handlers
+/// that read a price (`RefreshPriceCache`, `FetchPriceForLog`).
+fn emit_price_queried_event(price: &stone_types::PriceResponse) -> Event {
+    Event::new(PRICE_QUERIED_EVENT)
+        .add_attribute("denom", &price.denom)
+        .add_attribute("price", price.price.to_string())
+        .add_attribute("updated_at", price.updated_at.to_string())
+}
+
+/// Reject an empty Pyth fee denom.
+fn validate_pyth_fee_denom(denom: &str) -> Result<(), ContractError> {
+    if denom.trim().is_empty() {
+        return Err(ContractError::InvalidPythFeeDenom);
+    }
+    Ok(())
+}
+
 /// Initiate ownership transfer.
 ///
 /// Starts a two-step ownership transfer by setting the pending owner.
@@ -377,6 +700,60 @@ fn execute_accept_ownership(
         .add_attribute("new_owner", config.owner.to_string()))
 }
 
+/// Immediately replace the owner (test deployments only).
+///
+/// Skips the pending-owner step of `TransferOwnership` / `AcceptOwnership`
+/// and clears any pending transfer. Only compiled with the
+/// `unsafe-single-step-owner-transfer` feature.
+///
+/// # Authorization
+///
+/// Requires the caller to be the current contract owner.
+///
+/// # Errors
+///
+/// * `Unauthorized` - Caller is not the owner
+#[cfg(all(
+    feature = "unsafe-single-step-owner-transfer",
+    not(feature = "library")
+))]
+fn execute_force_set_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    config.owner = deps.api.addr_validate(&new_owner)?;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "force_set_owner")
+        .add_attribute("previous_owner", info.sender.to_string())
+        .add_attribute("new_owner", config.owner.to_string()))
+}
+
+/// Contract entry point for migration.
+///
+/// Converts a config stored in the V1 layout before recording the new
+/// contract version, so no handler of the new code ever loads a V1 config.
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let config_migrated = migrate_config(deps.storage)?;
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_attribute("config_migrated", config_migrated.to_string()))
+}
+
 /// Contract entry point for query messages.
 ///
 /// Dispatches query messages to their respective handlers. Queries are
@@ -390,6 +767,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::AllPriceFeeds { start_after, limit } => {
             to_json_binary(&query_all_price_feeds(deps, start_after, limit)?)?
         }
+        QueryMsg::ValidateFeeds {} => to_json_binary(&query_validate_feeds(deps)?)?,
+        QueryMsg::PriceUpdateFeeHint {} => {
+            to_json_binary(&CONFIG.load(deps.storage)?.price_update_fee())?
+        }
+        QueryMsg::PriceFeedMetadata { denom } => {
+            to_json_binary(&PRICE_FEED_METADATA.may_load(deps.storage, &denom)?)?
+        }
     };
 
     Ok(result)
@@ -402,33 +786,57 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
 ///
 /// # Flow
 ///
-/// 1. Look up the Pyth feed ID for the denom
-/// 2. Query the Pyth contract for the latest price
-/// 3. Validate the price is positive
-/// 4. Validate the confidence ratio is within bounds
-/// 5. Convert the price to a Decimal
-/// 6. Return the PriceResponse
+/// 1. Serve the cached price if it is younger than `min_update_interval_secs`
+/// 2. Otherwise look up the Pyth feed ID for the denom
+/// 3. Query the Pyth contract for the latest price
+/// 4. Validate the price is positive
+/// 5. Validate the confidence ratio is within bounds
+/// 6. Convert the price to a Decimal
+/// 7. Return the PriceResponse
 ///
 /// # Note on Staleness
 ///
-/// The `_env` parameter is intentionally unused. Staleness checking is
-/// handled by the market layer (`stone_types::OraclePriceStale`), not
-/// by this adapter. This design allows multiple markets to share the
-/// same adapter with different staleness requirements.
+/// Staleness checking is handled by the market layer
+/// (`stone_types::OraclePriceStale`), not by this adapter. This design allows
+/// multiple markets to share the same adapter with different staleness
+/// requirements. Cached prices keep their Pyth publish time as `updated_at`,
+/// so the market's staleness check still applies to them.
 ///
 /// # Errors
 ///
 /// * `PriceFeedNotConfigured` - No feed ID configured for the denom
+/// * `PythContractError` - The Pyth `PriceFeed` query returned an error
 /// * `NegativeOrZeroPrice` - Pyth returned price <= 0
 /// * `ConfidenceTooHigh` - Confidence ratio exceeds max_confidence_ratio
+/// * `ConfidenceTooLow` - Confidence ratio is below min_confidence_ratio
 /// * `InvalidTimestamp` - Pyth returned negative publish_time
 fn query_price(
     deps: Deps,
-    _env: Env,
+    env: Env,
     denom: String,
 ) -> Result<stone_types::PriceResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
+    if let Some(cached) = CACHED_PRICES.may_load(deps.storage, &denom)? {
+        let age = env.block.time.seconds().saturating_sub(cached.updated_at);
+        if age < config.min_update_interval_secs {
+            return Ok(stone_types::PriceResponse {
+                denom,
+                price: cached.price,
+                updated_at: cached.updated_at,
+            });
+        }
+    }
+
+    fetch_pyth_price(deps, &config, denom)
+}
+
+/// Query Pyth for a denom's latest price and validate it.
+fn fetch_pyth_price(
+    deps: Deps,
+    config: &Config,
+    denom: String,
+) -> Result<stone_types::PriceResponse, ContractError> {
     // 1. Look up feed ID
     let feed_id = PRICE_FEEDS.load(deps.storage, &denom).map_err(|_| {
         ContractError::PriceFeedNotConfigured {
@@ -437,10 +845,15 @@ fn query_price(
     })?;
 
     // 2. Query Pyth contract
-    let pyth_response: PriceFeedResponse = deps.querier.query_wasm_smart(
-        config.pyth_contract_addr.as_str(),
-        &PythQueryMsg::PriceFeed { id: feed_id },
-    )?;
+    let pyth_response: PriceFeedResponse = deps
+        .querier
+        .query_wasm_smart(
+            config.pyth_contract_addr.as_str(),
+            &PythQueryMsg::PriceFeed { id: feed_id },
+        )
+        .map_err(|e| ContractError::PythContractError {
+            reason: e.to_string(),
+        })?;
 
     let pyth_price = &pyth_response.price_feed.price;
 
@@ -450,13 +863,21 @@ fn query_price(
     }
 
     // 4. Confidence check
-    if pyth_price.conf > 0 {
-        let conf_ratio = Decimal::from_ratio(pyth_price.conf as u128, pyth_price.price as u128);
-        if conf_ratio > config.max_confidence_ratio {
-            return Err(ContractError::ConfidenceTooHigh {
+    let conf_ratio = Decimal::from_ratio(pyth_price.conf as u128, pyth_price.price as u128);
+    if conf_ratio > config.max_confidence_ratio {
+        return Err(ContractError::ConfidenceTooHigh {
+            denom,
+            confidence_ratio: conf_ratio,
+            max_allowed: config.max_confidence_ratio,
+        });
+    }
+    // A suspiciously tight interval points to a stale or manipulated feed
+    if let Some(min_confidence_ratio) = config.min_confidence_ratio {
+        if conf_ratio < min_confidence_ratio {
+            return Err(ContractError::ConfidenceTooLow {
                 denom,
                 confidence_ratio: conf_ratio,
-                max_allowed: config.max_confidence_ratio,
+                min_allowed: min_confidence_ratio,
             });
         }
     }
@@ -488,6 +909,10 @@ fn query_config(deps: Deps) -> Result<crate::msg::ConfigResponse, ContractError>
         owner: config.owner.to_string(),
         pyth_contract_addr: config.pyth_contract_addr.to_string(),
         max_confidence_ratio: config.max_confidence_ratio,
+        min_confidence_ratio: config.min_confidence_ratio,
+        pyth_fee_denom: config.pyth_fee_denom,
+        pyth_fee_amount: config.pyth_fee_amount,
+        min_update_interval_secs: config.min_update_interval_secs,
     })
 }
 
@@ -505,9 +930,11 @@ fn query_price_feed(deps: Deps, denom: String) -> Result<crate::msg::PriceFeedIn
             denom: denom.clone(),
         }
     })?;
+    let metadata = PRICE_FEED_METADATA.may_load(deps.storage, &denom)?;
     Ok(crate::msg::PriceFeedInfo {
         denom,
         feed_id: feed_id.to_hex(),
+        metadata,
     })
 }
 
@@ -538,9 +965,11 @@ fn query_all_price_feeds(
         .take(limit)
         .map(|item| {
             let (denom, feed_id) = item?;
+            let metadata = PRICE_FEED_METADATA.may_load(deps.storage, &denom)?;
             Ok(crate::msg::PriceFeedInfo {
                 denom,
                 feed_id: feed_id.to_hex(),
+                metadata,
             })
         })
         .collect();
@@ -548,6 +977,39 @@ fn query_all_price_feeds(
     feeds
 }
 
+/// Validate every configured price feed against the Pyth contract.
+///
+/// Iterates all entries in `PRICE_FEEDS` and issues a `PriceFeed` query
+/// to Pyth for each. Query failures are collected rather than propagated,
+/// so a single missing feed does not hide the status of the others.
+fn query_validate_feeds(deps: Deps) -> Result<crate::msg::ValidateFeedsResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let feeds = PRICE_FEEDS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut valid = vec![];
+    let mut invalid = vec![];
+
+    for (denom, feed_id) in feeds {
+        let result: Result<PriceFeedResponse, String> = deps
+            .querier
+            .query_wasm_smart(
+                config.pyth_contract_addr.as_str(),
+                &PythQueryMsg::PriceFeed { id: feed_id },
+            )
+            .map_err(|e| e.to_string());
+
+        match result {
+            Ok(_) => valid.push(denom),
+            Err(reason) => invalid.push(crate::msg::FeedValidationError { denom, reason }),
+        }
+    }
+
+    Ok(crate::msg::ValidateFeedsResponse { valid, invalid })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -657,6 +1119,10 @@ mod tests {
                         owner: MockApi::default().addr_make("owner"),
                         pyth_contract_addr: cosmwasm_std::Addr::unchecked(pyth_bech32),
                         max_confidence_ratio,
+                        min_confidence_ratio: None,
+                        pyth_fee_denom: None,
+                        pyth_fee_amount: None,
+                        min_update_interval_secs: 0,
                     },
                 )
                 .unwrap();
@@ -826,6 +1292,59 @@ mod tests {
             assert_eq!(result.denom, "uatom");
         }
 
+        #[test]
+        fn test_query_price_confidence_too_low() {
+            // conf=0 with min=0.001 → ConfidenceTooLow
+            let pyth_addr = "pyth";
+            let feed_id = "b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819";
+            let mut deps = setup_with_pyth(
+                pyth_addr,
+                feed_id,
+                "uatom",
+                1052000000i64,
+                0u64, // zero confidence
+                -8i32,
+                1700000000i64,
+                Decimal::percent(1),
+            );
+            let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+            config.min_confidence_ratio = Some(Decimal::permille(1));
+            CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+            let err = query_price(deps.as_ref(), mock_env(), "uatom".to_string()).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::ConfidenceTooLow {
+                    denom: "uatom".to_string(),
+                    confidence_ratio: Decimal::zero(),
+                    min_allowed: Decimal::permille(1),
+                }
+            );
+        }
+
+        #[test]
+        fn test_query_price_confidence_at_min() {
+            // conf/price=0.001, min=0.001 → success, since the check is `conf_ratio < min`
+            let pyth_addr = "pyth";
+            let feed_id = "b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819";
+            let mut deps = setup_with_pyth(
+                pyth_addr,
+                feed_id,
+                "uatom",
+                10000i64,
+                10u64, // conf → 10/10000 = 0.001
+                -8i32,
+                1700000000i64,
+                Decimal::percent(1),
+            );
+            let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+            config.min_confidence_ratio = Some(Decimal::permille(1));
+            CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+            let result = query_price(deps.as_ref(), mock_env(), "uatom".to_string()).unwrap();
+            assert_eq!(result.denom, "uatom");
+        }
+
         #[test]
         fn test_query_price_negative_timestamp() {
             // publish_time=-1 → InvalidTimestamp
@@ -909,6 +1428,10 @@ mod tests {
                         owner: MockApi::default().addr_make("owner"),
                         pyth_contract_addr: cosmwasm_std::Addr::unchecked(pyth_bech32),
                         max_confidence_ratio: Decimal::percent(1),
+                        min_confidence_ratio: None,
+                        pyth_fee_denom: None,
+                        pyth_fee_amount: None,
+                        min_update_interval_secs: 0,
                     },
                 )
                 .unwrap();
@@ -922,10 +1445,10 @@ mod tests {
             // Query should propagate the error from Pyth
             let result = query_price(deps.as_ref(), mock_env(), "uatom".to_string());
             assert!(result.is_err(), "Expected error when Pyth is unreachable");
-            // Verify it's a StdError (querier error propagates as StdError)
+            // Querier errors are wrapped as PythContractError
             assert!(
-                matches!(result.unwrap_err(), ContractError::Std(_)),
-                "Expected StdError for Pyth unreachable"
+                matches!(result.unwrap_err(), ContractError::PythContractError { .. }),
+                "Expected PythContractError for Pyth unreachable"
             );
         }
 
@@ -1020,35 +1543,256 @@ mod tests {
                 "Expected ConfidenceTooHigh when ratio exceeds max by a small amount"
             );
         }
-    }
-
-    /// Create test addresses for use in tests.
-    fn test_addrs() -> (cosmwasm_std::Addr, cosmwasm_std::Addr, cosmwasm_std::Addr) {
-        let api = MockApi::default();
-        (
-            api.addr_make("owner"),
-            api.addr_make("pyth"),
-            api.addr_make("new_owner"),
-        )
-    }
-
-    /// Returns a valid Pyth feed ID for use in tests.
-    fn valid_feed_id() -> String {
-        "b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819".to_string()
-    }
 
-    #[test]
-    fn test_instantiate_valid() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let (owner, pyth, _) = test_addrs();
-        let info = message_info(&owner, &[]);
+        const CACHE_FEED_ID: &str =
+            "b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819";
 
-        let msg = InstantiateMsg {
-            owner: owner.to_string(),
+        /// ATOM at $10.52 from Pyth, published at 1_700_000_000, with the
+        /// cache enabled for `interval` seconds.
+        fn setup_with_cache(
+            interval: u64,
+        ) -> OwnedDeps<MockStorage, MockApi, cosmwasm_std::testing::MockQuerier> {
+            let mut deps = setup_with_pyth(
+                "pyth",
+                CACHE_FEED_ID,
+                "uatom",
+                1052000000i64,
+                1000u64,
+                -8i32,
+                1700000000i64,
+                Decimal::percent(1),
+            );
+            CONFIG
+                .update(deps.as_mut().storage, |mut config| -> StdResult<_> {
+                    config.min_update_interval_secs = interval;
+                    Ok(config)
+                })
+                .unwrap();
+            deps
+        }
+
+        fn cache_price(
+            deps: &mut OwnedDeps<MockStorage, MockApi, cosmwasm_std::testing::MockQuerier>,
+            price: Decimal,
+            updated_at: u64,
+        ) {
+            CACHED_PRICES
+                .save(
+                    deps.as_mut().storage,
+                    "uatom",
+                    &CachedPrice { price, updated_at },
+                )
+                .unwrap();
+        }
+
+        fn env_at(seconds: u64) -> Env {
+            let mut env = mock_env();
+            env.block.time = cosmwasm_std::Timestamp::from_seconds(seconds);
+            env
+        }
+
+        #[test]
+        fn test_query_price_cache_hit() {
+            let mut deps = setup_with_cache(60);
+            let cached = Decimal::from_atomics(9u128, 0).unwrap();
+            cache_price(&mut deps, cached, 1700000000);
+
+            // 59s old: served from the cache without querying Pyth
+            let result =
+                query_price(deps.as_ref(), env_at(1700000059), "uatom".to_string()).unwrap();
+            assert_eq!(result.price, cached);
+            assert_eq!(result.updated_at, 1700000000);
+        }
+
+        #[test]
+        fn test_query_price_cache_miss() {
+            let mut deps = setup_with_cache(60);
+            let pyth_price = Decimal::from_atomics(1052u128, 2).unwrap();
+            cache_price(
+                &mut deps,
+                Decimal::from_atomics(9u128, 0).unwrap(),
+                1699999990,
+            );
+
+            // 70s old: Pyth is queried instead
+            let result =
+                query_price(deps.as_ref(), env_at(1700000060), "uatom".to_string()).unwrap();
+            assert_eq!(result.price, pyth_price);
+            assert_eq!(result.updated_at, 1700000000);
+
+            // Caching disabled: a fresh cache entry is ignored
+            let mut deps = setup_with_cache(0);
+            cache_price(
+                &mut deps,
+                Decimal::from_atomics(9u128, 0).unwrap(),
+                1700000000,
+            );
+            let result =
+                query_price(deps.as_ref(), env_at(1700000000), "uatom".to_string()).unwrap();
+            assert_eq!(result.price, pyth_price);
+        }
+
+        #[test]
+        fn test_refresh_price_cache() {
+            let mut deps = setup_with_cache(60);
+
+            let res =
+                execute_refresh_price_cache(deps.as_mut(), env_at(1700000010), "uatom".to_string())
+                    .unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|a| a.key == "action" && a.value == "refresh_price_cache"));
+
+            let cached = CACHED_PRICES.load(deps.as_ref().storage, "uatom").unwrap();
+            assert_eq!(cached.price, Decimal::from_atomics(1052u128, 2).unwrap());
+            assert_eq!(cached.updated_at, 1700000000);
+
+            // Replacing the feed drops the cached price
+            let owner = MockApi::default().addr_make("owner");
+            execute_set_price_feed(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&owner, &[]),
+                "uatom".to_string(),
+                CACHE_FEED_ID.to_string(),
+                None,
+            )
+            .unwrap();
+            assert!(!CACHED_PRICES.has(deps.as_ref().storage, "uatom"));
+        }
+
+        #[test]
+        fn test_update_config_clears_price_cache() {
+            let mut deps = setup_with_cache(60);
+            fn update(
+                deps: &mut OwnedDeps<MockStorage, MockApi, cosmwasm_std::testing::MockQuerier>,
+                pyth_contract_addr: Option<String>,
+                max_confidence_ratio: Option<Decimal>,
+                min_confidence_ratio: Option<Decimal>,
+                min_update_interval_secs: Option<u64>,
+            ) {
+                let owner = MockApi::default().addr_make("owner");
+                execute_update_config(
+                    deps.as_mut(),
+                    mock_env(),
+                    message_info(&owner, &[]),
+                    pyth_contract_addr,
+                    max_confidence_ratio,
+                    min_confidence_ratio,
+                    None,
+                    None,
+                    min_update_interval_secs,
+                )
+                .unwrap();
+            }
+            let cached = Decimal::from_atomics(9u128, 0).unwrap();
+
+            // Changing only the cache interval keeps cached prices
+            cache_price(&mut deps, cached, 1700000000);
+            update(&mut deps, None, None, None, Some(120));
+            assert!(CACHED_PRICES.has(deps.as_ref().storage, "uatom"));
+
+            // Changing either confidence bound drops them
+            update(&mut deps, None, Some(Decimal::percent(2)), None, None);
+            assert!(!CACHED_PRICES.has(deps.as_ref().storage, "uatom"));
+
+            cache_price(&mut deps, cached, 1700000000);
+            update(&mut deps, None, None, Some(Decimal::permille(1)), None);
+            assert!(!CACHED_PRICES.has(deps.as_ref().storage, "uatom"));
+
+            // Pointing at another Pyth contract drops them too
+            cache_price(&mut deps, cached, 1700000000);
+            let new_pyth = MockApi::default().addr_make("new_pyth").to_string();
+            update(&mut deps, Some(new_pyth), None, None, None);
+            assert!(!CACHED_PRICES.has(deps.as_ref().storage, "uatom"));
+        }
+
+        #[test]
+        fn test_refresh_price_cache_emits_price_queried_event() {
+            let mut deps = setup_with_cache(60);
+
+            let res =
+                execute_refresh_price_cache(deps.as_mut(), env_at(1700000010), "uatom".to_string())
+                    .unwrap();
+            assert_eq!(
+                res.events,
+                vec![Event::new(PRICE_QUERIED_EVENT)
+                    .add_attribute("denom", "uatom")
+                    .add_attribute("price", "10.52")
+                    .add_attribute("updated_at", "1700000000")]
+            );
+        }
+
+        #[test]
+        fn test_fetch_price_for_log() {
+            let mut deps = setup_with_cache(60);
+            let cached = Decimal::from_atomics(9u128, 0).unwrap();
+            cache_price(&mut deps, cached, 1700000000);
+
+            // Served like the `Price` query: from the cache while it is fresh
+            let res =
+                execute_fetch_price_for_log(deps.as_mut(), env_at(1700000030), "uatom".to_string())
+                    .unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|a| a.key == "action" && a.value == "fetch_price_for_log"));
+            assert_eq!(
+                res.events,
+                vec![Event::new(PRICE_QUERIED_EVENT)
+                    .add_attribute("denom", "uatom")
+                    .add_attribute("price", "9")
+                    .add_attribute("updated_at", "1700000000")]
+            );
+
+            // Nothing is written back to the cache
+            let stored = CACHED_PRICES.load(deps.as_ref().storage, "uatom").unwrap();
+            assert_eq!(stored.price, cached);
+        }
+
+        #[test]
+        fn test_fetch_price_for_log_unknown_denom() {
+            let mut deps = setup_with_cache(0);
+
+            let err =
+                execute_fetch_price_for_log(deps.as_mut(), env_at(1700000000), "uosmo".to_string())
+                    .unwrap_err();
+            assert!(matches!(err, ContractError::PriceFeedNotConfigured { .. }));
+            assert!(!CACHED_PRICES.has(deps.as_ref().storage, "uosmo"));
+        }
+    }
+
+    /// Create test addresses for use in tests.
+    fn test_addrs() -> (cosmwasm_std::Addr, cosmwasm_std::Addr, cosmwasm_std::Addr) {
+        let api = MockApi::default();
+        (
+            api.addr_make("owner"),
+            api.addr_make("pyth"),
+            api.addr_make("new_owner"),
+        )
+    }
+
+    /// Returns a valid Pyth feed ID for use in tests.
+    fn valid_feed_id() -> String {
+        "b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819".to_string()
+    }
+
+    #[test]
+    fn test_instantiate_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
@@ -1068,6 +1812,63 @@ mod tests {
         assert_eq!(config.max_confidence_ratio, Decimal::percent(1));
     }
 
+    #[test]
+    fn test_migrate_converts_v1_config() {
+        let mut deps = mock_dependencies();
+        let (owner, pyth, _) = test_addrs();
+
+        // Config as written by the V1 contract: no fee or cache fields
+        let v1 = format!(
+            r#"{{"owner":"{owner}","pyth_contract_addr":"{pyth}","max_confidence_ratio":"0.01"}}"#
+        );
+        deps.storage.set(b"config", v1.as_bytes());
+        assert!(CONFIG.load(deps.as_ref().storage).is_err());
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "config_migrated" && a.value == "true"));
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.owner, owner);
+        assert_eq!(config.pyth_contract_addr, pyth);
+        assert_eq!(config.max_confidence_ratio, Decimal::percent(1));
+        assert_eq!(config.pyth_fee_denom, None);
+        assert_eq!(config.pyth_fee_amount, None);
+        assert_eq!(config.min_update_interval_secs, 0);
+
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_keeps_current_config() {
+        let mut deps = mock_dependencies();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: Some("untrn".to_string()),
+            pyth_fee_amount: Some(Uint128::new(1)),
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let before = CONFIG.load(deps.as_ref().storage).unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "config_migrated" && a.value == "false"));
+        assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap(), before);
+    }
+
     #[test]
     fn test_instantiate_invalid_owner() {
         let mut deps = mock_dependencies();
@@ -1079,7 +1880,10 @@ mod tests {
             owner: "invalid_address".to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg);
@@ -1097,7 +1901,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: "invalid_address".to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg);
@@ -1115,7 +1922,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::zero(),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg);
@@ -1133,13 +1943,46 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(101), // > 1.0
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg);
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_instantiate_min_confidence_ratio_must_be_below_max() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: Some(Decimal::percent(1)),
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        let err = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidConfidenceRatio { .. }));
+
+        let msg = InstantiateMsg {
+            min_confidence_ratio: Some(Decimal::permille(1)),
+            ..msg
+        };
+        instantiate(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().min_confidence_ratio,
+            Some(Decimal::permille(1))
+        );
+    }
+
     #[test]
     fn test_instantiate_with_price_feeds() {
         let mut deps = mock_dependencies();
@@ -1152,10 +1995,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: feed_id.clone(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
@@ -1180,10 +2027,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: "invalid_feed_id".to_string(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg);
@@ -1202,7 +2053,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1214,6 +2068,7 @@ mod tests {
             info,
             "uatom".to_string(),
             feed_id.clone(),
+            None,
         )
         .unwrap();
 
@@ -1245,16 +2100,276 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Try to set price feed as non-owner
         let info = message_info(&not_owner, &[]);
         let feed_id = valid_feed_id();
-        let res = execute_set_price_feed(deps.as_mut(), env, info, "uatom".to_string(), feed_id);
+        let res =
+            execute_set_price_feed(deps.as_mut(), env, info, "uatom".to_string(), feed_id, None);
+
+        assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
+    }
+
+    fn atom_metadata() -> PriceFeedMetadata {
+        PriceFeedMetadata {
+            description: "ATOM/USD".to_string(),
+            decimals: 6,
+            asset_class: "crypto".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_set_price_feed_with_metadata() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        execute_set_price_feed(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "uatom".to_string(),
+            valid_feed_id(),
+            Some(atom_metadata()),
+        )
+        .unwrap();
+
+        let metadata: Option<PriceFeedMetadata> = cosmwasm_std::from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::PriceFeedMetadata {
+                    denom: "uatom".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata, Some(atom_metadata()));
+
+        let price_feed = query_price_feed(deps.as_ref(), "uatom".to_string()).unwrap();
+        assert_eq!(price_feed.metadata, Some(atom_metadata()));
+
+        // Setting the feed again without metadata clears it
+        execute_set_price_feed(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            "uatom".to_string(),
+            valid_feed_id(),
+            None,
+        )
+        .unwrap();
+
+        let metadata: Option<PriceFeedMetadata> = cosmwasm_std::from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::PriceFeedMetadata {
+                    denom: "uatom".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata, None);
+    }
+
+    #[test]
+    fn test_remove_price_feed_clears_metadata() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![PriceFeedConfig {
+                denom: "uatom".to_string(),
+                feed_id: valid_feed_id(),
+                metadata: Some(atom_metadata()),
+            }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            PRICE_FEED_METADATA
+                .may_load(deps.as_ref().storage, "uatom")
+                .unwrap(),
+            Some(atom_metadata())
+        );
+
+        execute_remove_price_feed(deps.as_mut(), env, info, "uatom".to_string()).unwrap();
+
+        assert!(!PRICE_FEED_METADATA.has(deps.as_ref().storage, "uatom"));
+    }
+
+    #[test]
+    fn test_batch_set_price_feeds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let usdc_feed_id = "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::BatchSetPriceFeeds {
+                feeds: vec![
+                    PriceFeedConfig {
+                        denom: "uatom".to_string(),
+                        feed_id: valid_feed_id(),
+                        metadata: None,
+                    },
+                    PriceFeedConfig {
+                        denom: "uusdc".to_string(),
+                        feed_id: usdc_feed_id.to_string(),
+                        metadata: None,
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "batch_set_price_feeds"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "set_count" && a.value == "2"));
+
+        let atom_feed = PRICE_FEEDS.load(deps.as_ref().storage, "uatom").unwrap();
+        assert_eq!(atom_feed.to_hex(), valid_feed_id());
+        let usdc_feed = PRICE_FEEDS.load(deps.as_ref().storage, "uusdc").unwrap();
+        assert_eq!(usdc_feed.to_hex(), usdc_feed_id);
+    }
+
+    #[test]
+    fn test_batch_set_price_feeds_invalid_feed_saves_nothing() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // First feed is valid, second is not
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::BatchSetPriceFeeds {
+                feeds: vec![
+                    PriceFeedConfig {
+                        denom: "uatom".to_string(),
+                        feed_id: valid_feed_id(),
+                        metadata: None,
+                    },
+                    PriceFeedConfig {
+                        denom: "uusdc".to_string(),
+                        feed_id: "not_hex".to_string(),
+                        metadata: None,
+                    },
+                ],
+            },
+        );
+
+        assert!(
+            matches!(res.unwrap_err(), ContractError::InvalidFeedId { feed_id } if feed_id == "not_hex")
+        );
+        assert!(!PRICE_FEEDS.has(deps.as_ref().storage, "uatom"));
+        assert!(!PRICE_FEEDS.has(deps.as_ref().storage, "uusdc"));
+    }
+
+    #[test]
+    fn test_batch_set_price_feeds_duplicate_denom() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::BatchSetPriceFeeds {
+                feeds: vec![
+                    PriceFeedConfig {
+                        denom: "uatom".to_string(),
+                        feed_id: valid_feed_id(),
+                        metadata: None,
+                    },
+                    PriceFeedConfig {
+                        denom: "uatom".to_string(),
+                        feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
+                            .to_string(),
+                        metadata: None,
+                    },
+                ],
+            },
+        );
 
-        assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
+        assert!(
+            matches!(res.unwrap_err(), ContractError::DuplicateDenom { denom } if denom == "uatom")
+        );
+        assert!(!PRICE_FEEDS.has(deps.as_ref().storage, "uatom"));
     }
 
     #[test]
@@ -1270,10 +2385,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: feed_id.clone(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1303,7 +2422,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -1330,7 +2452,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1341,6 +2466,10 @@ mod tests {
             info.clone(),
             Some(new_pyth.to_string()),
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .unwrap();
 
@@ -1365,6 +2494,10 @@ mod tests {
             info,
             None,
             Some(Decimal::percent(2)),
+            None,
+            None,
+            None,
+            None,
         )
         .unwrap();
 
@@ -1393,13 +2526,26 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Try to update config as non-owner
         let info = message_info(&not_owner, &[]);
-        let res = execute_update_config(deps.as_mut(), env, info, None, Some(Decimal::percent(2)));
+        let res = execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            None,
+            Some(Decimal::percent(2)),
+            None,
+            None,
+            None,
+            None,
+        );
 
         assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
     }
@@ -1416,7 +2562,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1478,7 +2627,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1510,10 +2662,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: feed_id.clone(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -1539,13 +2695,26 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Try to update pyth_contract_addr as non-owner
         let info = message_info(&not_owner, &[]);
-        let res = execute_update_config(deps.as_mut(), env, info, Some(new_pyth.to_string()), None);
+        let res = execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            Some(new_pyth.to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
         assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
     }
 
@@ -1564,13 +2733,26 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Try to update max_confidence_ratio as non-owner
         let info = message_info(&not_owner, &[]);
-        let res = execute_update_config(deps.as_mut(), env, info, None, Some(Decimal::percent(2)));
+        let res = execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            None,
+            Some(Decimal::percent(2)),
+            None,
+            None,
+            None,
+            None,
+        );
         assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
     }
 
@@ -1589,7 +2771,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -1614,7 +2799,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -1627,6 +2815,69 @@ mod tests {
         ));
     }
 
+    #[cfg(all(
+        feature = "unsafe-single-step-owner-transfer",
+        not(feature = "library")
+    ))]
+    #[test]
+    fn test_force_set_owner_skips_pending_step() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, new_owner) = test_addrs();
+        let api = MockApi::default();
+        let not_owner = api.addr_make("not_owner");
+
+        let info = message_info(&owner, &[]);
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Non-owner cannot force the owner
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&not_owner, &[]),
+            ExecuteMsg::ForceSetOwner {
+                new_owner: not_owner.to_string(),
+            },
+        );
+        assert!(matches!(res.unwrap_err(), ContractError::Unauthorized));
+
+        // A pending transfer is discarded by the forced owner change
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::TransferOwnership {
+                new_owner: not_owner.to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            message_info(&owner, &[]),
+            ExecuteMsg::ForceSetOwner {
+                new_owner: new_owner.to_string(),
+            },
+        )
+        .unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.owner, new_owner);
+        assert!(PENDING_OWNER
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn test_owner_auth_for_all_execute_messages() {
         // Issue #87: Comprehensive test that owner auth is required for all execute messages
@@ -1644,10 +2895,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: feed_id.clone(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -1662,6 +2917,7 @@ mod tests {
                 denom: "uosmo".to_string(),
                 feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
                     .to_string(),
+                metadata: None,
             },
         );
         assert!(
@@ -1669,6 +2925,25 @@ mod tests {
             "SetPriceFeed should require owner"
         );
 
+        // Test BatchSetPriceFeeds
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info_not_owner.clone(),
+            ExecuteMsg::BatchSetPriceFeeds {
+                feeds: vec![crate::msg::PriceFeedConfig {
+                    denom: "uosmo".to_string(),
+                    feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
+                        .to_string(),
+                    metadata: None,
+                }],
+            },
+        );
+        assert!(
+            matches!(res.unwrap_err(), ContractError::Unauthorized),
+            "BatchSetPriceFeeds should require owner"
+        );
+
         // Test RemovePriceFeed
         let res = execute(
             deps.as_mut(),
@@ -1691,6 +2966,10 @@ mod tests {
             ExecuteMsg::UpdateConfig {
                 pyth_contract_addr: None,
                 max_confidence_ratio: Some(Decimal::percent(2)),
+                min_confidence_ratio: None,
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
+                min_update_interval_secs: None,
             },
         );
         assert!(
@@ -1729,7 +3008,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1740,6 +3022,235 @@ mod tests {
         assert_eq!(config.max_confidence_ratio, Decimal::percent(1));
     }
 
+    fn query_fee_hint(deps: Deps) -> Option<cosmwasm_std::Coin> {
+        cosmwasm_std::from_json(query(deps, mock_env(), QueryMsg::PriceUpdateFeeHint {}).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_instantiate_with_pyth_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: Some("untrn".to_string()),
+            pyth_fee_amount: Some(Uint128::new(1)),
+        };
+        instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.pyth_fee_denom, Some("untrn".to_string()));
+        assert_eq!(config.pyth_fee_amount, Some(Uint128::new(1)));
+        assert_eq!(
+            query_fee_hint(deps.as_ref()),
+            Some(cosmwasm_std::coin(1, "untrn"))
+        );
+    }
+
+    #[test]
+    fn test_update_config_pyth_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(query_fee_hint(deps.as_ref()), None);
+
+        let res = execute_update_config(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            None,
+            None,
+            None,
+            Some("untrn".to_string()),
+            Some(Uint128::new(5)),
+            None,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "pyth_fee_denom" && a.value == "untrn"));
+        assert_eq!(
+            query_fee_hint(deps.as_ref()),
+            Some(cosmwasm_std::coin(5, "untrn"))
+        );
+
+        // Zero amount disables the fee but keeps the denom
+        execute_update_config(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            None,
+            None,
+            None,
+            None,
+            Some(Uint128::zero()),
+            None,
+        )
+        .unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.pyth_fee_denom, Some("untrn".to_string()));
+        assert_eq!(query_fee_hint(deps.as_ref()), None);
+
+        let err = execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            None,
+            None,
+            None,
+            Some(String::new()),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPythFeeDenom));
+    }
+
+    #[test]
+    fn test_update_config_min_confidence_ratio() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().min_confidence_ratio,
+            None
+        );
+
+        let res = execute_update_config(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            None,
+            None,
+            Some(Decimal::permille(1)),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "min_confidence_ratio" && a.value == "0.001"));
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().min_confidence_ratio,
+            Some(Decimal::permille(1))
+        );
+
+        // A maximum at or below the minimum is rejected
+        let err = execute_update_config(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            None,
+            Some(Decimal::permille(1)),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidConfidenceRatio { .. }));
+
+        // Zero removes the minimum
+        execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            None,
+            None,
+            Some(Decimal::zero()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().min_confidence_ratio,
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_config_min_update_interval() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let (owner, pyth, _) = test_addrs();
+        let info = message_info(&owner, &[]);
+
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            pyth_contract_addr: pyth.to_string(),
+            max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            query_config(deps.as_ref())
+                .unwrap()
+                .min_update_interval_secs,
+            0
+        );
+
+        let res = execute_update_config(
+            deps.as_mut(),
+            env,
+            info,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(30),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "min_update_interval_secs" && a.value == "30"));
+        assert_eq!(
+            query_config(deps.as_ref())
+                .unwrap()
+                .min_update_interval_secs,
+            30
+        );
+    }
+
     #[test]
     fn test_query_price_feed() {
         let mut deps = mock_dependencies();
@@ -1752,10 +3263,14 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![crate::msg::PriceFeedConfig {
                 denom: "uatom".to_string(),
                 feed_id: feed_id.clone(),
+                metadata: None,
             }],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1776,7 +3291,10 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1804,20 +3322,26 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![
                 crate::msg::PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: feed_id1.clone(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uosmo".to_string(),
                     feed_id: feed_id2.clone(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uusdc".to_string(),
                     feed_id: feed_id3.clone(),
+                    metadata: None,
                 },
             ],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1842,32 +3366,40 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![
                 crate::msg::PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: valid_feed_id(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "ubtc".to_string(),
                     feed_id: "a00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "ueth".to_string(),
                     feed_id: "b00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fa"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uosmo".to_string(),
                     feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fb"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uusdc".to_string(),
                     feed_id: "d00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fc"
                         .to_string(),
+                    metadata: None,
                 },
             ],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1903,32 +3435,40 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![
                 crate::msg::PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: valid_feed_id(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "ubtc".to_string(),
                     feed_id: "a00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "ueth".to_string(),
                     feed_id: "b00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fa"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uosmo".to_string(),
                     feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fb"
                         .to_string(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uusdc".to_string(),
                     feed_id: "d00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493fc"
                         .to_string(),
+                    metadata: None,
                 },
             ],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
         instantiate(deps.as_mut(), env, info, msg).unwrap();
 
@@ -1954,17 +3494,22 @@ mod tests {
             owner: owner.to_string(),
             pyth_contract_addr: pyth.to_string(),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
             price_feeds: vec![
                 crate::msg::PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: feed_id.clone(),
+                    metadata: None,
                 },
                 crate::msg::PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: "c00b60f88b03a6a625a8d1c048c3f45ef9e88f1ffb3f1032faea4f0ce7b493f9"
                         .to_string(),
+                    metadata: None,
                 },
             ],
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
         };
 
         let result = instantiate(deps.as_mut(), env, info, msg);
@@ -22,7 +22,7 @@ pub enum ContractError {
 
     /// Wrapper for Stone types errors.
     #[error("{0}")]
-    Types(#[from] stone_types::ContractError),
+    Types(#[from] stone_types::errors::TypesError),
 
     // =========================================================================
     // Price Data Errors
@@ -83,6 +83,18 @@ pub enum ContractError {
         max_allowed: Decimal,
     },
 
+    /// Confidence interval too low relative to price.
+    ///
+    /// Occurs when `min_confidence_ratio` is set and the confidence/price
+    /// ratio is below it. A near-zero confidence on a volatile asset
+    /// suggests a stale or manipulated feed rather than a precise price.
+    #[error("Confidence too low for {denom}: ratio {confidence_ratio} below min {min_allowed}")]
+    ConfidenceTooLow {
+        denom: String,
+        confidence_ratio: Decimal,
+        min_allowed: Decimal,
+    },
+
     /// Invalid timestamp from Pyth.
     ///
     /// Occurs when Pyth returns a negative publish_time, which cannot
@@ -98,6 +110,13 @@ pub enum ContractError {
     #[error("Pyth query failed for {denom}: {reason}")]
     PythQueryFailed { denom: String, reason: String },
 
+    /// Pyth contract returned an error for a price feed sub-query.
+    ///
+    /// Wraps the error from the `PriceFeed` query made while fetching a
+    /// price, e.g. when the feed ID is unknown to the Pyth contract.
+    #[error("Pyth contract error: {reason}")]
+    PythContractError { reason: String },
+
     // =========================================================================
     // Validation Errors
     // =========================================================================
@@ -115,11 +134,18 @@ pub enum ContractError {
 
     /// Duplicate denom in price feeds list.
     ///
-    /// Occurs during instantiation if the same denom appears multiple
-    /// times in the price_feeds list.
+    /// Occurs during instantiation or `BatchSetPriceFeeds` if the same
+    /// denom appears multiple times in the price feeds list.
     #[error("Duplicate denom in price feeds: {denom}")]
     DuplicateDenom { denom: String },
 
+    /// Feed not served by the Pyth contract.
+    ///
+    /// Occurs during `SetPriceFeedWithValidation` when the test query for
+    /// the feed fails, e.g. because the feed ID doesn't exist in Pyth.
+    #[error("Feed {feed_id} is not live in the Pyth contract")]
+    FeedNotLiveInPyth { feed_id: String },
+
     /// Invalid confidence ratio value.
     ///
     /// Occurs when max_confidence_ratio is set to 0 or greater than 1.
@@ -133,13 +159,27 @@ pub enum ContractError {
     #[error("Invalid confidence ratio: {value} - {reason}")]
     InvalidConfidenceRatio { value: Decimal, reason: String },
 
+    /// Invalid Pyth fee denom.
+    ///
+    /// Occurs when `pyth_fee_denom` is set to an empty string at
+    /// instantiation or via `UpdateConfig`.
+    #[error("Invalid Pyth fee denom: must not be empty")]
+    InvalidPythFeeDenom,
+
     /// Exponent out of supported range.
     ///
-    /// Occurs when Pyth returns a price with |exponent| > 18.
+    /// Occurs when Pyth returns a price with exponent < -18.
     /// Decimal only supports up to 18 decimal places.
     #[error("Exponent out of range: {expo}")]
     ExponentOutOfRange { expo: i32 },
 
+    /// Positive exponent too large.
+    ///
+    /// Occurs when Pyth returns a price with exponent > 18, which is
+    /// beyond `Decimal`'s precision.
+    #[error("Price exponent too large: {expo}")]
+    PriceExponentTooLarge { expo: i32 },
+
     // =========================================================================
     // Arithmetic Errors
     // =========================================================================
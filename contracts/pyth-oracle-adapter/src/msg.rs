@@ -5,7 +5,31 @@
 //! the Stone Protocol's `OracleQueryMsg` interface for price queries.
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Coin, Decimal, Uint128};
+
+/// Descriptive metadata for a price feed.
+///
+/// Purely informational: stored alongside the feed ID so operators can see
+/// what a feed represents. No price logic reads it.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "description": "ATOM/USD",
+///   "decimals": 6,
+///   "asset_class": "crypto"
+/// }
+/// ```
+#[cw_serde]
+pub struct PriceFeedMetadata {
+    /// Human-readable description of the feed (e.g., "ATOM/USD").
+    pub description: String,
+    /// Decimals of the denom the feed prices.
+    pub decimals: u8,
+    /// Asset class of the feed (e.g., "crypto", "fx", "equity").
+    pub asset_class: String,
+}
 
 /// Price feed configuration for a denom.
 ///
@@ -27,6 +51,8 @@ pub struct PriceFeedConfig {
     /// The Pyth price feed ID (64-character hex string).
     /// Can optionally include a "0x" prefix which will be stripped during parsing.
     pub feed_id: String,
+    /// Optional descriptive metadata for the feed.
+    pub metadata: Option<PriceFeedMetadata>,
 }
 
 /// Instantiate message for the Pyth oracle adapter.
@@ -40,7 +66,9 @@ pub struct PriceFeedConfig {
 /// * `owner` - Address with admin privileges (can update config, add/remove feeds)
 /// * `pyth_contract_addr` - Address of the deployed Pyth price feed contract
 /// * `max_confidence_ratio` - Maximum allowed confidence/price ratio (e.g., 0.01 for 1%)
+/// * `min_confidence_ratio` - Minimum allowed confidence/price ratio (optional)
 /// * `price_feeds` - Initial price feed configurations (can be empty)
+/// * `pyth_fee_denom` / `pyth_fee_amount` - Fee Pyth charges for price updates (optional)
 ///
 /// # Example
 ///
@@ -63,21 +91,37 @@ pub struct InstantiateMsg {
     /// Maximum confidence ratio (e.g., 0.01 for 1%).
     /// Must be greater than 0 and less than or equal to 1.
     pub max_confidence_ratio: Decimal,
+    /// Minimum confidence ratio. Must be less than `max_confidence_ratio`.
+    /// `None` means no minimum.
+    pub min_confidence_ratio: Option<Decimal>,
     /// Initial price feeds to configure.
     pub price_feeds: Vec<PriceFeedConfig>,
+    /// Denom Pyth charges for price updates. `None` means no fee.
+    pub pyth_fee_denom: Option<String>,
+    /// Amount of `pyth_fee_denom` charged per price update.
+    pub pyth_fee_amount: Option<Uint128>,
 }
 
+/// Migrate message for the Pyth oracle adapter.
+///
+/// Upgrades stored state to the layout expected by the new code (see
+/// `state::migrate_config`).
+#[cw_serde]
+pub struct MigrateMsg {}
+
 /// Execute messages for the Pyth oracle adapter.
 ///
 /// These messages modify the contract state and require authorization.
 /// All state-changing operations are restricted to the contract owner
-/// except for `AcceptOwnership` which must be called by the pending owner.
+/// except for `AcceptOwnership`, which must be called by the pending owner,
+/// and `RefreshPriceCache` and `FetchPriceForLog`, which anyone can call.
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Set a price feed for a denom.
     ///
     /// Adds or updates the mapping from a denom to a Pyth feed ID.
-    /// If the denom already exists, its feed ID is updated.
+    /// If the denom already exists, its feed ID is updated. Metadata is
+    /// replaced as well, so omitting it clears any previously stored metadata.
     ///
     /// # Authorization
     ///
@@ -87,7 +131,46 @@ pub enum ExecuteMsg {
     ///
     /// * `Unauthorized` - Caller is not the owner
     /// * `InvalidFeedId` - Feed ID is not a valid 64-character hex string
-    SetPriceFeed { denom: String, feed_id: String },
+    SetPriceFeed {
+        denom: String,
+        feed_id: String,
+        metadata: Option<PriceFeedMetadata>,
+    },
+
+    /// Set a price feed for a denom after checking that Pyth serves it.
+    ///
+    /// Queries the Pyth contract for the feed before storing it, so a feed
+    /// ID that doesn't exist in Pyth is rejected up front instead of failing
+    /// later price queries. Otherwise behaves like `SetPriceFeed`, except
+    /// that metadata already stored for the denom is kept.
+    ///
+    /// # Authorization
+    ///
+    /// Requires caller to be the contract owner.
+    ///
+    /// # Errors
+    ///
+    /// * `Unauthorized` - Caller is not the owner
+    /// * `InvalidFeedId` - Feed ID is not a valid 64-character hex string
+    /// * `FeedNotLiveInPyth` - The Pyth query for the feed failed
+    SetPriceFeedWithValidation { denom: String, feed_id: String },
+
+    /// Set multiple price feeds atomically.
+    ///
+    /// All feed IDs are validated before any are stored, so either every
+    /// feed in the batch is saved or none are. Existing denoms are updated,
+    /// matching `SetPriceFeed`.
+    ///
+    /// # Authorization
+    ///
+    /// Requires caller to be the contract owner.
+    ///
+    /// # Errors
+    ///
+    /// * `Unauthorized` - Caller is not the owner
+    /// * `DuplicateDenom` - The same denom appears more than once in the batch
+    /// * `InvalidFeedId` - A feed ID is not a valid 64-character hex string
+    BatchSetPriceFeeds { feeds: Vec<PriceFeedConfig> },
 
     /// Remove a price feed for a denom.
     ///
@@ -115,12 +198,53 @@ pub enum ExecuteMsg {
     /// # Errors
     ///
     /// * `Unauthorized` - Caller is not the owner
-    /// * `InvalidConfidenceRatio` - New ratio is 0 or greater than 1
+    /// * `InvalidConfidenceRatio` - New ratio is 0 or greater than 1, or the
+    ///   minimum ratio is not below the maximum
+    /// * `InvalidPythFeeDenom` - New fee denom is empty
+    ///
+    /// Setting `pyth_fee_amount` to zero disables the update fee, setting
+    /// `min_confidence_ratio` to zero removes the minimum, and setting
+    /// `min_update_interval_secs` to zero disables the price cache. Changing the
+    /// Pyth contract or either confidence ratio clears all cached prices.
     UpdateConfig {
         pyth_contract_addr: Option<String>,
         max_confidence_ratio: Option<Decimal>,
+        min_confidence_ratio: Option<Decimal>,
+        pyth_fee_denom: Option<String>,
+        pyth_fee_amount: Option<Uint128>,
+        min_update_interval_secs: Option<u64>,
     },
 
+    /// Read the latest price for a denom from Pyth and cache it.
+    ///
+    /// `Price` queries serve the cached value while it is younger than
+    /// `min_update_interval_secs`. The price goes through the same validation
+    /// as the `Price` query before it is stored.
+    ///
+    /// # Authorization
+    ///
+    /// Anyone can refresh the cache.
+    ///
+    /// # Errors
+    ///
+    /// Same as the `Price` query.
+    RefreshPriceCache { denom: String },
+
+    /// Read a denom's price exactly as the `Price` query would and record it
+    /// in the transaction's events.
+    ///
+    /// Nothing is stored; this lets keeper scripts log the prices they act on
+    /// in TX history. Emits a `stone.pyth_adapter.price_queried` event.
+    ///
+    /// # Authorization
+    ///
+    /// Anyone can fetch a price.
+    ///
+    /// # Errors
+    ///
+    /// Same as the `Price` query.
+    FetchPriceForLog { denom: String },
+
     /// Transfer ownership to a new address.
     ///
     /// Initiates a two-step ownership transfer. The new owner must call
@@ -155,6 +279,26 @@ pub enum ExecuteMsg {
     /// * `PendingOwnerNotSet` - No ownership transfer is pending
     /// * `NotPendingOwner` - Caller is not the pending owner
     AcceptOwnership {},
+
+    /// Immediately set a new owner, skipping the pending-owner step.
+    ///
+    /// **Test-only.** Exists only when built with the
+    /// `unsafe-single-step-owner-transfer` feature (and without `library`),
+    /// for development and testnet deployments. Mainnet builds must use
+    /// `TransferOwnership` / `AcceptOwnership`.
+    ///
+    /// # Authorization
+    ///
+    /// Requires caller to be the current contract owner.
+    ///
+    /// # Errors
+    ///
+    /// * `Unauthorized` - Caller is not the owner
+    #[cfg(all(
+        feature = "unsafe-single-step-owner-transfer",
+        not(feature = "library")
+    ))]
+    ForceSetOwner { new_owner: String },
 }
 
 /// Query messages for the Pyth oracle adapter.
@@ -182,6 +326,7 @@ pub enum QueryMsg {
     /// * `PriceFeedNotConfigured` - No feed ID configured for denom
     /// * `NegativeOrZeroPrice` - Pyth returned price <= 0
     /// * `ConfidenceTooHigh` - Confidence ratio exceeds max_confidence_ratio
+    /// * `ConfidenceTooLow` - Confidence ratio is below min_confidence_ratio
     /// * `InvalidTimestamp` - Pyth returned negative publish_time
     #[returns(stone_types::PriceResponse)]
     Price { denom: String },
@@ -218,6 +363,32 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+
+    /// Validate all configured price feeds against the Pyth contract.
+    ///
+    /// Queries Pyth for every configured feed and reports which feeds
+    /// resolve and which do not. A failing feed does not abort the query;
+    /// it is recorded in `invalid` with the error message.
+    ///
+    /// This is intended for operators verifying a deployment. It iterates
+    /// every configured feed, so gas usage grows with the number of feeds.
+    #[returns(ValidateFeedsResponse)]
+    ValidateFeeds {},
+
+    /// Query descriptive metadata for a denom's price feed.
+    ///
+    /// Returns `None` if no metadata was stored for the denom, including
+    /// when the denom has no feed configured.
+    #[returns(Option<PriceFeedMetadata>)]
+    PriceFeedMetadata { denom: String },
+
+    /// Query the fee to attach when pushing a Pyth price update.
+    ///
+    /// Queries cannot emit attributes or messages, so callers that bundle a
+    /// Pyth update with their transaction use this to know which funds to
+    /// send along. Returns `None` when no fee is configured.
+    #[returns(Option<Coin>)]
+    PriceUpdateFeeHint {},
 }
 
 /// Configuration response.
@@ -232,6 +403,14 @@ pub struct ConfigResponse {
     pub pyth_contract_addr: String,
     /// Maximum confidence ratio (e.g., 0.01 for 1%).
     pub max_confidence_ratio: Decimal,
+    /// Minimum confidence ratio, if any.
+    pub min_confidence_ratio: Option<Decimal>,
+    /// Denom Pyth charges for price updates, if any.
+    pub pyth_fee_denom: Option<String>,
+    /// Amount of `pyth_fee_denom` charged per price update.
+    pub pyth_fee_amount: Option<Uint128>,
+    /// How long a cached price is served before Pyth is queried again (0 = no caching).
+    pub min_update_interval_secs: u64,
 }
 
 /// Price feed information response.
@@ -244,4 +423,27 @@ pub struct PriceFeedInfo {
     pub denom: String,
     /// The Pyth price feed ID (64-character hex string).
     pub feed_id: String,
+    /// Descriptive metadata, if any was stored for the feed.
+    pub metadata: Option<PriceFeedMetadata>,
+}
+
+/// Feed validation response.
+///
+/// Returned by the `ValidateFeeds` query. Lists denoms whose feeds were
+/// successfully queried from Pyth and denoms whose feeds failed.
+#[cw_serde]
+pub struct ValidateFeedsResponse {
+    /// Denoms whose Pyth feed query succeeded.
+    pub valid: Vec<String>,
+    /// Denoms whose Pyth feed query failed, with the reason.
+    pub invalid: Vec<FeedValidationError>,
+}
+
+/// A single feed validation failure.
+#[cw_serde]
+pub struct FeedValidationError {
+    /// The denom whose feed failed validation.
+    pub denom: String,
+    /// The error returned when querying Pyth.
+    pub reason: String,
 }
@@ -45,12 +45,13 @@ use crate::error::ContractError;
 /// # Arguments
 ///
 /// * `price` - The raw price value from Pyth (must be positive)
-/// * `expo` - The exponent (typically negative, |expo| ≤ 18)
+/// * `expo` - The exponent (typically negative, -18 ≤ expo ≤ 18)
 ///
 /// # Errors
 ///
 /// * `ContractError::InvalidPrice` - If price ≤ 0
-/// * `ContractError::ExponentOutOfRange` - If |expo| > 18
+/// * `ContractError::ExponentOutOfRange` - If expo < -18
+/// * `ContractError::PriceExponentTooLarge` - If expo > 18
 /// * `ContractError::Overflow` - If the calculation overflows
 pub fn pyth_price_to_decimal(price: i64, expo: i32) -> Result<Decimal, ContractError> {
     // 1. Reject negative or zero price
@@ -60,23 +61,24 @@ pub fn pyth_price_to_decimal(price: i64, expo: i32) -> Result<Decimal, ContractE
         });
     }
 
-    // 2. Reject out-of-range exponents (|expo| > 18)
+    // 2. Reject out-of-range exponents
     // Decimal has 18 decimal places max, exponents beyond this overflow or lose precision
-    if expo.unsigned_abs() > 18 {
+    if expo > 18 {
+        return Err(ContractError::PriceExponentTooLarge { expo });
+    }
+    if expo < -18 {
         return Err(ContractError::ExponentOutOfRange { expo });
     }
 
     // 3. Convert based on exponent sign
     let price_u128 = price as u128;
     if expo >= 0 {
-        // price * 10^expo
-        let multiplier = 10u128
-            .checked_pow(expo as u32)
-            .ok_or(ContractError::Overflow)?;
-        let scaled = price_u128
-            .checked_mul(multiplier)
-            .ok_or(ContractError::Overflow)?;
-        Decimal::from_atomics(scaled, 0).map_err(|_| ContractError::Overflow)
+        // price * 10^expo, multiplied as Decimals so overflow is checked
+        let base = Decimal::from_atomics(price_u128, 0).map_err(|_| ContractError::Overflow)?;
+        let multiplier = Decimal::from_atomics(10u128.pow(expo as u32), 0)
+            .map_err(|_| ContractError::Overflow)?;
+        base.checked_mul(multiplier)
+            .map_err(|_| ContractError::Overflow)
     } else {
         // price / 10^|expo| → use Decimal::from_atomics
         let decimal_places = (-expo) as u32;
@@ -658,11 +660,11 @@ mod tests {
 
     #[test]
     fn expo_too_large() {
-        // (100, 19) → ExponentOutOfRange error
+        // (100, 19) → PriceExponentTooLarge error
         let result = pyth_price_to_decimal(100, 19);
         assert!(matches!(
             result,
-            Err(ContractError::ExponentOutOfRange { expo: 19 })
+            Err(ContractError::PriceExponentTooLarge { expo: 19 })
         ));
     }
 
@@ -727,11 +729,14 @@ mod tests {
             result
         );
 
-        // Verify exponent out of range is caught separately from overflow
+        // Verify a too-large exponent is caught separately from overflow
         let result = pyth_price_to_decimal(100, 19);
         assert!(
-            matches!(result, Err(ContractError::ExponentOutOfRange { expo: 19 })),
-            "Expected ExponentOutOfRange for expo=19, got {:?}",
+            matches!(
+                result,
+                Err(ContractError::PriceExponentTooLarge { expo: 19 })
+            ),
+            "Expected PriceExponentTooLarge for expo=19, got {:?}",
             result
         );
 
@@ -745,4 +750,43 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn positive_exponents() {
+        // expo = 0 → price unchanged
+        assert_eq!(
+            pyth_price_to_decimal(100, 0).unwrap(),
+            Decimal::from_atomics(100u128, 0).unwrap()
+        );
+
+        // (100, 3) → 100_000
+        assert_eq!(
+            pyth_price_to_decimal(100, 3).unwrap(),
+            Decimal::from_atomics(100_000u128, 0).unwrap()
+        );
+
+        // (100, 5) → $10,000,000
+        assert_eq!(
+            pyth_price_to_decimal(100, 5).unwrap(),
+            Decimal::from_atomics(10_000_000u128, 0).unwrap()
+        );
+
+        // (1, 18) → 10^18, the largest supported exponent
+        assert_eq!(
+            pyth_price_to_decimal(1, 18).unwrap(),
+            Decimal::from_atomics(10u128.pow(18), 0).unwrap()
+        );
+
+        // (1, 19) → beyond Decimal's precision
+        assert!(matches!(
+            pyth_price_to_decimal(1, 19),
+            Err(ContractError::PriceExponentTooLarge { expo: 19 })
+        ));
+
+        // Negative exponents are unaffected: (1_052_000_000, -8) → 10.52
+        assert_eq!(
+            pyth_price_to_decimal(1_052_000_000, -8).unwrap(),
+            Decimal::from_atomics(1052u128, 2).unwrap()
+        );
+    }
 }
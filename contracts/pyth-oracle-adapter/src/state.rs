@@ -2,20 +2,26 @@
 //!
 //! This module defines the contract's state storage structures and their
 //! default values. State is organized into:
-//! - Configuration (owner, Pyth contract, confidence settings)
+//! - Configuration (owner, Pyth contract, confidence settings), plus the
+//!   legacy layout it is migrated from
 //! - Ownership transfer state (pending owner)
 //! - Price feed mappings (denom → Pyth feed ID)
+//! - Price feed metadata (denom → descriptive metadata)
+//! - Cached prices (denom → last price read from Pyth)
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Coin, Decimal, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 
+use crate::msg::PriceFeedMetadata;
 use crate::pyth_types::PriceIdentifier;
 
-/// Contract configuration.
+/// Contract configuration (V2 layout).
 ///
 /// Stores the core configuration parameters for the adapter.
 /// This is set at instantiation and can be updated via `ExecuteMsg::UpdateConfig`.
+/// Configs stored in the original `ConfigV1` layout are converted by
+/// `migrate_config` when the contract is migrated.
 #[cw_serde]
 pub struct Config {
     /// Contract owner address.
@@ -44,6 +50,64 @@ pub struct Config {
     /// The confidence ratio is 0.02 (2%), which exceeds 0.01, so the
     /// price query will fail with `ContractError::ConfidenceTooHigh`.
     pub max_confidence_ratio: Decimal,
+    /// Minimum confidence ratio, if any.
+    ///
+    /// Prices with confidence/price ratios below this value are rejected
+    /// with `ContractError::ConfidenceTooLow`. Always less than
+    /// `max_confidence_ratio`; `None` disables the check.
+    pub min_confidence_ratio: Option<Decimal>,
+    /// Denom Pyth charges for price updates (e.g., "untrn").
+    ///
+    /// `None` for deployments where Pyth charges no update fee.
+    pub pyth_fee_denom: Option<String>,
+    /// Amount of `pyth_fee_denom` Pyth charges per price update.
+    pub pyth_fee_amount: Option<Uint128>,
+    /// How long a cached price is served before Pyth is queried again.
+    ///
+    /// `0` disables the cache.
+    pub min_update_interval_secs: u64,
+}
+
+/// Contract configuration as stored before Pyth update fees and the price
+/// cache were introduced (V1 layout).
+///
+/// Kept only so `migrate_config` can read it back; never written.
+#[cw_serde]
+pub struct ConfigV1 {
+    pub owner: Addr,
+    pub pyth_contract_addr: Addr,
+    pub max_confidence_ratio: Decimal,
+}
+
+impl From<ConfigV1> for Config {
+    /// No update fee is charged and the price cache is disabled, matching how
+    /// a V1 deployment behaved.
+    fn from(v1: ConfigV1) -> Self {
+        Self {
+            owner: v1.owner,
+            pyth_contract_addr: v1.pyth_contract_addr,
+            max_confidence_ratio: v1.max_confidence_ratio,
+            min_confidence_ratio: None,
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+            min_update_interval_secs: 0,
+        }
+    }
+}
+
+impl Config {
+    /// Fee callers must attach when pushing a Pyth price update, if any.
+    ///
+    /// Only returned when both the denom and a non-zero amount are set.
+    pub fn price_update_fee(&self) -> Option<Coin> {
+        match (&self.pyth_fee_denom, self.pyth_fee_amount) {
+            (Some(denom), Some(amount)) if !amount.is_zero() => Some(Coin {
+                denom: denom.clone(),
+                amount,
+            }),
+            _ => None,
+        }
+    }
 }
 
 /// Contract name for cw2 version tracking.
@@ -67,6 +131,23 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Stores the `Config` struct at a fixed key "config".
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// The same "config" key read in the V1 layout, for `migrate_config`.
+const CONFIG_V1: Item<ConfigV1> = Item::new("config");
+
+/// Rewrite a V1 config in the current layout.
+///
+/// Tries the current layout first; only if that fails to deserialize is the
+/// value read as `ConfigV1` and converted. Returns whether a conversion
+/// happened. Fails if the stored value matches neither layout.
+pub fn migrate_config(storage: &mut dyn Storage) -> StdResult<bool> {
+    if CONFIG.load(storage).is_ok() {
+        return Ok(false);
+    }
+    let config = Config::from(CONFIG_V1.load(storage)?);
+    CONFIG.save(storage, &config)?;
+    Ok(true)
+}
+
 /// Pending owner for ownership transfer.
 ///
 /// Stores the address of the pending owner during a two-step ownership
@@ -103,6 +184,30 @@ pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
 /// ```
 pub const PRICE_FEEDS: Map<&str, PriceIdentifier> = Map::new("price_feeds");
 
+/// Price feed metadata: denom -> PriceFeedMetadata.
+///
+/// Optional, informational descriptions of configured feeds. Entries are
+/// written and removed together with `PRICE_FEEDS`; a feed configured
+/// without metadata has no entry here.
+pub const PRICE_FEED_METADATA: Map<&str, PriceFeedMetadata> = Map::new("price_feed_metadata");
+
+/// A price read from Pyth and kept for reuse by `Price` queries.
+#[cw_serde]
+pub struct CachedPrice {
+    /// Validated price, already converted to a Decimal.
+    pub price: Decimal,
+    /// Pyth publish time of the price (Unix seconds).
+    pub updated_at: u64,
+}
+
+/// Cached prices: denom -> CachedPrice.
+///
+/// Written by `ExecuteMsg::RefreshPriceCache` (queries cannot write state)
+/// and cleared whenever the denom's feed is set or removed. `Price` queries
+/// serve the cached value while it is younger than
+/// `Config::min_update_interval_secs`.
+pub const CACHED_PRICES: Map<&str, CachedPrice> = Map::new("cached_prices");
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +221,10 @@ mod tests {
             owner: Addr::unchecked("owner"),
             pyth_contract_addr: Addr::unchecked("pyth"),
             max_confidence_ratio: Decimal::percent(1),
+            min_confidence_ratio: None,
+            pyth_fee_denom: None,
+            pyth_fee_amount: None,
+            min_update_interval_secs: 0,
         };
 
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
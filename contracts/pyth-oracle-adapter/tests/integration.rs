@@ -25,8 +25,8 @@ use stone_testing::{
     MockPythInstantiateMsg, COLLATERAL_DENOM, DEBT_DENOM,
 };
 use stone_types::{
-    FactoryExecuteMsg, FactoryInstantiateMsg, MarketConfigResponse, MarketQueryMsg,
-    OracleConfigUnchecked, OracleType, PriceResponse,
+    ContractError as StoneError, FactoryExecuteMsg, FactoryInstantiateMsg, MarketConfigResponse,
+    MarketQueryMsg, OracleConfigUnchecked, OracleType, PriceResponse,
 };
 
 // Feed IDs for testing (64-character hex strings)
@@ -80,6 +80,7 @@ fn setup_env() -> TestEnv {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![
                     MockPriceFeedInit {
                         id: ATOM_FEED_ID.to_string(),
@@ -117,16 +118,21 @@ fn setup_env() -> TestEnv {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(2), // 2% max confidence
+                min_confidence_ratio: None,
                 price_feeds: vec![
                     PriceFeedConfig {
                         denom: "uatom".to_string(),
                         feed_id: ATOM_FEED_ID.to_string(),
+                        metadata: None,
                     },
                     PriceFeedConfig {
                         denom: "uusdc".to_string(),
                         feed_id: USDC_FEED_ID.to_string(),
+                        metadata: None,
                     },
                 ],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -178,6 +184,41 @@ fn test_adapter_queries_pyth_and_converts_price() {
     assert_eq!(usdc_resp.denom, "uusdc");
     // Price: 100_000_000 * 10^-8 = 1.00
     assert_eq!(usdc_resp.price, Decimal::one());
+    assert_eq!(usdc_resp.updated_at, 1_700_000_000u64);
+}
+
+/// Test: Pyth sub-query errors surface as PythContractError
+#[test]
+fn test_adapter_wraps_pyth_query_error() {
+    let mut env = setup_env();
+
+    // BTC feed is configured on the adapter but unknown to the mock Pyth
+    env.app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "ubtc".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let result: Result<PriceResponse, _> = env.app.wrap().query_wasm_smart(
+        env.adapter_addr.clone(),
+        &AdapterQueryMsg::Price {
+            denom: "ubtc".to_string(),
+        },
+    );
+
+    let err_str = result.unwrap_err().to_string();
+    assert!(
+        err_str.contains("Pyth contract error"),
+        "Expected Pyth contract error, got: {}",
+        err_str
+    );
 }
 
 /// Test: Update mock Pyth prices → verify adapter reflects changes
@@ -253,6 +294,7 @@ fn test_adapter_rejects_high_confidence_feed() {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![MockPriceFeedInit {
                     id: ATOM_FEED_ID.to_string(),
                     price: 1_052_000_000i64,
@@ -278,10 +320,14 @@ fn test_adapter_rejects_high_confidence_feed() {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(2), // 2% max
+                min_confidence_ratio: None,
                 price_feeds: vec![PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: ATOM_FEED_ID.to_string(),
+                    metadata: None,
                 }],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -380,6 +426,7 @@ fn test_set_price_feed_as_owner() {
             &AdapterExecuteMsg::SetPriceFeed {
                 denom: "ubtc".to_string(),
                 feed_id: BTC_FEED_ID.to_string(),
+                metadata: None,
             },
             &[],
         )
@@ -414,6 +461,7 @@ fn test_set_price_feed_unauthorized() {
         &AdapterExecuteMsg::SetPriceFeed {
             denom: "ubtc".to_string(),
             feed_id: BTC_FEED_ID.to_string(),
+            metadata: None,
         },
         &[],
     );
@@ -427,6 +475,97 @@ fn test_set_price_feed_unauthorized() {
     );
 }
 
+/// Test: Validated feed setup queries Pyth and rejects feeds it doesn't serve
+#[test]
+fn test_set_price_feed_with_validation() {
+    let mut env = setup_env();
+
+    // BTC isn't listed in the mock Pyth
+    let err = env
+        .app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeedWithValidation {
+                denom: "ubtc".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause()
+            .downcast_ref::<pyth_oracle_adapter::ContractError>(),
+        Some(&pyth_oracle_adapter::ContractError::FeedNotLiveInPyth {
+            feed_id: BTC_FEED_ID.to_string(),
+        })
+    );
+    let missing: Result<pyth_oracle_adapter::msg::PriceFeedInfo, _> =
+        env.app.wrap().query_wasm_smart(
+            env.adapter_addr.clone(),
+            &AdapterQueryMsg::PriceFeed {
+                denom: "ubtc".to_string(),
+            },
+        );
+    assert!(missing.is_err());
+
+    // A live feed is stored, keeping the metadata already set for the denom
+    let metadata = pyth_oracle_adapter::msg::PriceFeedMetadata {
+        description: "ATOM/USD".to_string(),
+        decimals: 6,
+        asset_class: "crypto".to_string(),
+    };
+    env.app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "uatom2".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+                metadata: Some(metadata.clone()),
+            },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeedWithValidation {
+                denom: "uatom2".to_string(),
+                feed_id: ATOM_FEED_ID.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let feed_info: pyth_oracle_adapter::msg::PriceFeedInfo = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.adapter_addr.clone(),
+            &AdapterQueryMsg::PriceFeed {
+                denom: "uatom2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(feed_info.feed_id, ATOM_FEED_ID);
+    assert_eq!(feed_info.metadata, Some(metadata));
+
+    // The plain SetPriceFeed still accepts feeds Pyth doesn't serve
+    env.app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "ubtc".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+}
+
 /// Test: Remove price feed (owner only)
 #[test]
 fn test_remove_price_feed() {
@@ -474,6 +613,37 @@ fn test_remove_price_feed() {
     assert_eq!(feeds_after[0].denom, "uusdc");
 }
 
+/// Test: ValidateFeeds reports feeds missing from Pyth without failing the query
+#[test]
+fn test_validate_feeds_mixed() {
+    let mut env = setup_env();
+
+    // BTC feed is not registered in the mock Pyth contract
+    env.app
+        .execute_contract(
+            env.owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "ubtc".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res: pyth_oracle_adapter::msg::ValidateFeedsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.adapter_addr.clone(), &AdapterQueryMsg::ValidateFeeds {})
+        .unwrap();
+
+    assert_eq!(res.valid, vec!["uatom".to_string(), "uusdc".to_string()]);
+    assert_eq!(res.invalid.len(), 1);
+    assert_eq!(res.invalid[0].denom, "ubtc");
+    assert!(!res.invalid[0].reason.is_empty());
+}
+
 /// Test: Query for unconfigured denom fails
 #[test]
 fn test_query_unconfigured_denom_fails() {
@@ -513,6 +683,7 @@ fn test_adapter_rejects_negative_price() {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![MockPriceFeedInit {
                     id: ATOM_FEED_ID.to_string(),
                     price: -100i64, // Negative price
@@ -538,10 +709,14 @@ fn test_adapter_rejects_negative_price() {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(10),
+                min_confidence_ratio: None,
                 price_feeds: vec![PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: ATOM_FEED_ID.to_string(),
+                    metadata: None,
                 }],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -584,6 +759,7 @@ fn test_adapter_rejects_zero_price() {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![MockPriceFeedInit {
                     id: ATOM_FEED_ID.to_string(),
                     price: 0i64, // Zero price
@@ -609,10 +785,14 @@ fn test_adapter_rejects_zero_price() {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(10),
+                min_confidence_ratio: None,
                 price_feeds: vec![PriceFeedConfig {
                     denom: "uatom".to_string(),
                     feed_id: ATOM_FEED_ID.to_string(),
+                    metadata: None,
                 }],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -650,6 +830,10 @@ fn test_update_config() {
             &AdapterExecuteMsg::UpdateConfig {
                 pyth_contract_addr: None,
                 max_confidence_ratio: Some(Decimal::percent(5)),
+                min_confidence_ratio: None,
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
+                min_update_interval_secs: None,
             },
             &[],
         )
@@ -703,6 +887,117 @@ fn test_transfer_ownership() {
     assert_eq!(config.owner, new_owner.to_string());
 }
 
+/// Test: After an ownership transfer the new owner holds every owner-only
+/// permission and the previous owner holds none
+#[test]
+fn test_transfer_ownership_moves_feed_and_config_authority() {
+    let mut env = setup_env();
+    let old_owner = env.owner.clone();
+    let new_owner = "new_owner".into_addr();
+
+    env.app
+        .execute_contract(
+            old_owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::TransferOwnership {
+                new_owner: new_owner.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            new_owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap();
+
+    // New owner can configure feeds
+    env.app
+        .execute_contract(
+            new_owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "ubtc".to_string(),
+                feed_id: BTC_FEED_ID.to_string(),
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let feed_info: pyth_oracle_adapter::msg::PriceFeedInfo = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.adapter_addr.clone(),
+            &AdapterQueryMsg::PriceFeed {
+                denom: "ubtc".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(feed_info.feed_id, BTC_FEED_ID);
+
+    // Old owner can no longer configure feeds
+    let err = env
+        .app
+        .execute_contract(
+            old_owner,
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::SetPriceFeed {
+                denom: "ubtc".to_string(),
+                feed_id: ATOM_FEED_ID.to_string(),
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.root_cause().to_string().contains("Unauthorized"),
+        "Expected unauthorized error, got: {}",
+        err.root_cause()
+    );
+
+    // New owner can update config
+    env.app
+        .execute_contract(
+            new_owner.clone(),
+            env.adapter_addr.clone(),
+            &AdapterExecuteMsg::UpdateConfig {
+                pyth_contract_addr: None,
+                max_confidence_ratio: Some(Decimal::percent(3)),
+                min_confidence_ratio: None,
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
+                min_update_interval_secs: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let config: pyth_oracle_adapter::msg::ConfigResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(env.adapter_addr.clone(), &AdapterQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.owner, new_owner.to_string());
+    assert_eq!(config.max_confidence_ratio, Decimal::percent(3));
+
+    // The rejected call left the feed untouched
+    let feed_info: pyth_oracle_adapter::msg::PriceFeedInfo = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            env.adapter_addr,
+            &AdapterQueryMsg::PriceFeed {
+                denom: "ubtc".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(feed_info.feed_id, BTC_FEED_ID);
+}
+
 // ============================================================================
 // Full Stack Tests (Pyth → adapter → factory → market)
 // ============================================================================
@@ -780,6 +1075,7 @@ fn setup_full_stack_env() -> FullStackEnv {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![
                     MockPriceFeedInit {
                         id: ATOM_FEED_ID.to_string(),
@@ -817,16 +1113,21 @@ fn setup_full_stack_env() -> FullStackEnv {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
                 price_feeds: vec![
                     PriceFeedConfig {
                         denom: COLLATERAL_DENOM.to_string(),
                         feed_id: ATOM_FEED_ID.to_string(),
+                        metadata: None,
                     },
                     PriceFeedConfig {
                         denom: DEBT_DENOM.to_string(),
                         feed_id: USDC_FEED_ID.to_string(),
+                        metadata: None,
                     },
                 ],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -872,10 +1173,12 @@ fn test_create_market_with_pyth_oracle() {
                 expected_code_id: env.adapter_code_id,
                 max_staleness_secs: 300,
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     env.app
@@ -907,6 +1210,7 @@ fn test_create_market_with_pyth_oracle() {
             &stone_types::FactoryQueryMsg::Markets {
                 start_after: None,
                 limit: None,
+                sort_by: None,
             },
         )
         .unwrap();
@@ -942,10 +1246,12 @@ fn test_factory_rejects_wrong_adapter_code_id() {
                 expected_code_id: 99999, // Wrong code ID
                 max_staleness_secs: 300,
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     let result = env.app.execute_contract(
@@ -955,12 +1261,17 @@ fn test_factory_rejects_wrong_adapter_code_id() {
         &[coin(1_000, "uosmo")],
     );
 
-    assert!(result.is_err());
-    let err_str = result.unwrap_err().root_cause().to_string();
-    assert!(
-        err_str.contains("Oracle code ID mismatch"),
-        "Expected code ID mismatch error, got: {}",
-        err_str
+    let err = result.unwrap_err();
+    let root = err.root_cause().downcast_ref::<StoneError>();
+    assert_eq!(
+        root,
+        Some(&StoneError::OracleContractCodeMismatch {
+            oracle: env.adapter_addr.to_string(),
+            expected_code_id: 99999,
+            actual_code_id: env.adapter_code_id,
+        }),
+        "Expected oracle code mismatch error, got: {}",
+        err
     );
 }
 
@@ -979,10 +1290,12 @@ fn test_factory_rejects_missing_feed_for_denom() {
                 expected_code_id: env.adapter_code_id,
                 max_staleness_secs: 300,
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     let result = env.app.execute_contract(
@@ -1035,6 +1348,7 @@ fn test_market_rejects_stale_price() {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![
                     MockPriceFeedInit {
                         id: ATOM_FEED_ID.to_string(),
@@ -1071,16 +1385,21 @@ fn test_market_rejects_stale_price() {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr.to_string(),
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
                 price_feeds: vec![
                     PriceFeedConfig {
                         denom: COLLATERAL_DENOM.to_string(),
                         feed_id: ATOM_FEED_ID.to_string(),
+                        metadata: None,
                     },
                     PriceFeedConfig {
                         denom: DEBT_DENOM.to_string(),
                         feed_id: USDC_FEED_ID.to_string(),
+                        metadata: None,
                     },
                 ],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter",
@@ -1115,10 +1434,12 @@ fn test_market_rejects_stale_price() {
                 expected_code_id: adapter_code_id,
                 max_staleness_secs: 300, // 5 minutes tolerance
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     app.execute_contract(
@@ -1139,6 +1460,7 @@ fn test_market_rejects_stale_price() {
             pyth_code_id,
             owner.clone(),
             &MockPythInstantiateMsg {
+                allow_reset: false,
                 feeds: vec![
                     MockPriceFeedInit {
                         id: ATOM_FEED_ID.to_string(),
@@ -1175,16 +1497,21 @@ fn test_market_rejects_stale_price() {
                 owner: owner.to_string(),
                 pyth_contract_addr: pyth_addr_stale.to_string(),
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
                 price_feeds: vec![
                     PriceFeedConfig {
                         denom: COLLATERAL_DENOM.to_string(),
                         feed_id: ATOM_FEED_ID.to_string(),
+                        metadata: None,
                     },
                     PriceFeedConfig {
                         denom: DEBT_DENOM.to_string(),
                         feed_id: USDC_FEED_ID.to_string(),
+                        metadata: None,
                     },
                 ],
+                pyth_fee_denom: None,
+                pyth_fee_amount: None,
             },
             &[],
             "pyth-adapter-stale",
@@ -1209,10 +1536,12 @@ fn test_market_rejects_stale_price() {
                 expected_code_id: adapter_code_id,
                 max_staleness_secs: 60, // Only 60 seconds tolerance
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: Some(1u64),
+        fee_amount: None,
     };
 
     let result = app.execute_contract(
@@ -1246,10 +1575,12 @@ fn test_price_drop_propagates_through_adapter() {
                 expected_code_id: env.adapter_code_id,
                 max_staleness_secs: 3600, // 1 hour for testing
                 max_confidence_ratio: Decimal::percent(2),
+                min_confidence_ratio: None,
             },
         },
         params: Box::new(default_market_params()),
         salt: None,
+        fee_amount: None,
     };
 
     env.app
@@ -1270,6 +1601,7 @@ fn test_price_drop_propagates_through_adapter() {
             &stone_types::FactoryQueryMsg::Markets {
                 start_after: None,
                 limit: None,
+                sort_by: None,
             },
         )
         .unwrap();
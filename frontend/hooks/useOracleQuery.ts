@@ -24,6 +24,7 @@ export interface OracleConfig {
   owner: string;
   pyth_contract_addr: string;
   max_confidence_ratio: string;
+  min_confidence_ratio?: string | null;
 }
 
 export interface OraclePriceFeed {
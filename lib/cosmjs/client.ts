@@ -10,6 +10,7 @@ import {
   MarketStateResponse,
   UserPositionResponse,
   UserBalanceResponse,
+  UserSupplyResponse,
   IsLiquidatableResponse,
   FactoryConfigResponse,
   MarketsResponse,
@@ -79,7 +80,7 @@ export class QueryClient {
     return client.queryContractSmart(marketAddress, msg);
   }
 
-  async getUserSupply(marketAddress: string, userAddress: string): Promise<UserBalanceResponse> {
+  async getUserSupply(marketAddress: string, userAddress: string): Promise<UserSupplyResponse> {
     const client = await this.connect();
     const msg: MarketQueryMsg = { user_supply: { user: userAddress } };
     return client.queryContractSmart(marketAddress, msg);
@@ -129,9 +130,9 @@ export class SigningClient {
   }
 
   // Market Execute Functions
-  async supply(marketAddress: string, amount: Coin, recipient?: string) {
+  async supply(marketAddress: string, amount: Coin, recipient?: string, referrer?: string) {
     const client = await this.connect();
-    const msg: MarketExecuteMsg = { supply: { recipient } };
+    const msg: MarketExecuteMsg = { supply: { recipient, referrer } };
 
     return client.execute(
       this.address,
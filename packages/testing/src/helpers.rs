@@ -1,5 +1,5 @@
 use cosmwasm_std::{Coin, Decimal, Uint128};
-use stone_types::{CreateMarketParams, InterestRateModel};
+use stone_types::{CreateMarketParams, InterestRateModel, MarketParams};
 
 /// Default test addresses
 pub const OWNER: &str = "owner";
@@ -37,6 +37,38 @@ pub fn default_market_params() -> CreateMarketParams {
     }
 }
 
+/// Stored market params for `default_market_params()`, as a newly instantiated
+/// market holds them: enabled, with every optional limit and fee turned off.
+pub fn default_stored_market_params() -> MarketParams {
+    let params = default_market_params();
+    MarketParams {
+        loan_to_value: params.loan_to_value,
+        liquidation_threshold: params.liquidation_threshold,
+        liquidation_bonus: params.liquidation_bonus,
+        liquidation_protocol_fee: params.liquidation_protocol_fee,
+        close_factor: params.close_factor,
+        dust_debt_threshold: params.dust_debt_threshold,
+        interest_rate_model: params.interest_rate_model,
+        protocol_fee: params.protocol_fee,
+        curator_fee: params.curator_fee,
+        supply_cap: params.supply_cap,
+        borrow_cap: params.borrow_cap,
+        min_borrow_amount: Uint128::zero(),
+        min_reserve_ratio: Decimal::zero(),
+        min_supply_blocks: 0,
+        max_liquidation_value_usd: None,
+        collateral_whitelist_enabled: false,
+        min_post_borrow_health_factor: None,
+        protocol_borrow_fee_flat: Uint128::zero(),
+        auto_clear_dust: false,
+        liquidation_bonus_by_health_factor: vec![],
+        withdraw_fee: Decimal::zero(),
+        enabled: true,
+        is_mutable: params.is_mutable,
+        ltv_last_update: 0,
+    }
+}
+
 /// Create market params with custom LTV and liquidation threshold.
 pub fn market_params_with_ltv(ltv: u64, liq_threshold: u64) -> CreateMarketParams {
     CreateMarketParams {
@@ -1,7 +1,11 @@
 mod helpers;
 mod mock_oracle;
 mod mock_pyth;
+mod mock_receipt_nft;
+mod mock_router;
 
 pub use helpers::*;
 pub use mock_oracle::*;
 pub use mock_pyth::*;
+pub use mock_receipt_nft::*;
+pub use mock_router::*;
@@ -6,12 +6,15 @@
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
 
 /// Instantiate message for the mock Pyth contract.
 #[cw_serde]
 pub struct MockPythInstantiateMsg {
     pub feeds: Vec<MockPriceFeedInit>,
+    /// Allow `ResetAllFeeds` (only set in tests that need teardown)
+    #[serde(default)]
+    pub allow_reset: bool,
 }
 
 /// Initial price feed configuration for instantiation.
@@ -47,6 +50,8 @@ pub enum MockPythExecuteMsg {
         /// New publish timestamp
         publish_time: i64,
     },
+    /// Remove all price feeds (requires `allow_reset` at instantiation)
+    ResetAllFeeds {},
 }
 
 /// Price identifier - 32 bytes serialized as 64-character hex string.
@@ -171,9 +176,19 @@ pub struct StoredFeed {
     pub ema_conf: u64,
 }
 
+/// Mock Pyth configuration.
+#[cw_serde]
+pub struct MockPythConfig {
+    /// Whether `ResetAllFeeds` is permitted
+    pub allow_reset: bool,
+}
+
 /// Storage: feed_id (hex string) → stored feed data
 pub const FEEDS: Map<&str, StoredFeed> = Map::new("feeds");
 
+/// Storage: mock Pyth configuration
+pub const CONFIG: Item<MockPythConfig> = Item::new("config");
+
 /// Mock Pyth contract instantiate entry point for use in tests.
 pub fn mock_pyth_instantiate(
     deps: DepsMut,
@@ -181,6 +196,12 @@ pub fn mock_pyth_instantiate(
     _info: MessageInfo,
     msg: MockPythInstantiateMsg,
 ) -> StdResult<Response> {
+    CONFIG.save(
+        deps.storage,
+        &MockPythConfig {
+            allow_reset: msg.allow_reset,
+        },
+    )?;
     for feed in msg.feeds {
         FEEDS.save(
             deps.storage,
@@ -223,6 +244,16 @@ pub fn mock_pyth_execute(
                 .add_attribute("action", "update_feed")
                 .add_attribute("feed_id", id))
         }
+        MockPythExecuteMsg::ResetAllFeeds {} => {
+            let config = CONFIG.load(deps.storage)?;
+            if !config.allow_reset {
+                return Err(cosmwasm_std::StdError::generic_err(
+                    "ResetAllFeeds is disabled for this mock Pyth instance",
+                ));
+            }
+            FEEDS.clear(deps.storage);
+            Ok(Response::new().add_attribute("action", "reset_all_feeds"))
+        }
     }
 }
 
@@ -298,6 +329,7 @@ mod tests {
 
         let feed_id = atom_feed_id();
         let msg = MockPythInstantiateMsg {
+            allow_reset: false,
             feeds: vec![MockPriceFeedInit {
                 id: feed_id.clone(),
                 price: 1_052_000_000i64,
@@ -340,6 +372,7 @@ mod tests {
 
         let feed_id = atom_feed_id();
         let msg = MockPythInstantiateMsg {
+            allow_reset: false,
             feeds: vec![MockPriceFeedInit {
                 id: feed_id.clone(),
                 price: 1_052_000_000i64,
@@ -390,7 +423,10 @@ mod tests {
         let info = message_info(&creator, &[]);
 
         // Instantiate with no feeds
-        let msg = MockPythInstantiateMsg { feeds: vec![] };
+        let msg = MockPythInstantiateMsg {
+            feeds: vec![],
+            allow_reset: false,
+        };
         mock_pyth_instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // Query non-existent feed
@@ -401,6 +437,96 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_mock_pyth_reset_all_feeds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addrs();
+        let info = message_info(&creator, &[]);
+
+        let feed_ids = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        let msg = MockPythInstantiateMsg {
+            feeds: feed_ids
+                .iter()
+                .map(|id| MockPriceFeedInit {
+                    id: hex::encode(id),
+                    price: 1_000_000_000i64,
+                    conf: 1_000_000u64,
+                    expo: -8,
+                    publish_time: 1_700_000_000i64,
+                    ema_price: None,
+                    ema_conf: None,
+                })
+                .collect(),
+            allow_reset: true,
+        };
+        mock_pyth_instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for id in feed_ids {
+            let query_msg = PythQueryMsg::PriceFeed {
+                id: PriceIdentifier(id),
+            };
+            mock_pyth_query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        }
+
+        let res = mock_pyth_execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            MockPythExecuteMsg::ResetAllFeeds {},
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "reset_all_feeds"));
+
+        for id in feed_ids {
+            let query_msg = PythQueryMsg::PriceFeed {
+                id: PriceIdentifier(id),
+            };
+            let err = mock_pyth_query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+            assert!(matches!(err, cosmwasm_std::StdError::NotFound { .. }));
+        }
+    }
+
+    #[test]
+    fn test_mock_pyth_reset_disabled() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = test_addrs();
+        let info = message_info(&creator, &[]);
+
+        let msg = MockPythInstantiateMsg {
+            feeds: vec![MockPriceFeedInit {
+                id: atom_feed_id(),
+                price: 1_052_000_000i64,
+                conf: 1_000_000u64,
+                expo: -8,
+                publish_time: 1_700_000_000i64,
+                ema_price: None,
+                ema_conf: None,
+            }],
+            allow_reset: false,
+        };
+        mock_pyth_instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let err = mock_pyth_execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            MockPythExecuteMsg::ResetAllFeeds {},
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("disabled"));
+
+        // Feed is still present
+        let query_msg = PythQueryMsg::PriceFeed {
+            id: PriceIdentifier(atom_feed_id_bytes()),
+        };
+        mock_pyth_query(deps.as_ref(), env, query_msg).unwrap();
+    }
+
     #[test]
     fn test_mock_pyth_contract_helper() {
         // Just verify the helper compiles and returns a contract
@@ -424,6 +550,7 @@ mod tests {
                 code_id,
                 "admin".into_addr(),
                 &MockPythInstantiateMsg {
+                    allow_reset: false,
                     feeds: vec![MockPriceFeedInit {
                         id: feed_id_hex,
                         price: 1_052_000_000i64,
@@ -0,0 +1,108 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+    StdResult,
+};
+use cw_storage_plus::{Item, Map};
+use stone_types::{DepositInfo, ReceiptNftExecuteMsg};
+
+/// Mock receipt NFT instantiate message
+#[cw_serde]
+pub struct MockReceiptNftInstantiateMsg {
+    /// Reject every mint, to test that a failing NFT contract can't block supplies
+    pub fail_mint: bool,
+}
+
+/// Mock receipt NFT query message
+#[cw_serde]
+pub enum MockReceiptNftQueryMsg {
+    /// Token IDs owned by `owner`
+    Tokens { owner: String },
+}
+
+const FAIL_MINT: Item<bool> = Item::new("fail_mint");
+/// Key: token ID, Value: (owner, deposit info)
+const TOKENS: Map<&str, (Addr, DepositInfo)> = Map::new("tokens");
+
+/// Mock receipt NFT contract entry points for use in tests.
+pub fn mock_receipt_nft_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockReceiptNftInstantiateMsg,
+) -> StdResult<Response> {
+    FAIL_MINT.save(deps.storage, &msg.fail_mint)?;
+    Ok(Response::new().add_attribute("action", "instantiate_mock_receipt_nft"))
+}
+
+pub fn mock_receipt_nft_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ReceiptNftExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ReceiptNftExecuteMsg::Mint(mint) => {
+            if FAIL_MINT.load(deps.storage)? {
+                return Err(StdError::generic_err("minting disabled"));
+            }
+            if TOKENS.has(deps.storage, &mint.token_id) {
+                return Err(StdError::generic_err("token already minted"));
+            }
+            let owner = deps.api.addr_validate(&mint.owner)?;
+            TOKENS.save(deps.storage, &mint.token_id, &(owner, mint.extension))?;
+            Ok(Response::new()
+                .add_attribute("action", "mint")
+                .add_attribute("token_id", mint.token_id))
+        }
+        ReceiptNftExecuteMsg::Burn { token_id } => {
+            if !TOKENS.has(deps.storage, &token_id) {
+                return Err(StdError::generic_err("token not found"));
+            }
+            TOKENS.remove(deps.storage, &token_id);
+            Ok(Response::new()
+                .add_attribute("action", "burn")
+                .add_attribute("token_id", token_id))
+        }
+    }
+}
+
+pub fn mock_receipt_nft_query(
+    deps: Deps,
+    _env: Env,
+    msg: MockReceiptNftQueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        MockReceiptNftQueryMsg::Tokens { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let tokens = TOKENS
+                .range(deps.storage, None, None, Order::Ascending)
+                .filter_map(|item| match item {
+                    Ok((token_id, (token_owner, _))) if token_owner == owner => Some(Ok(token_id)),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e)),
+                })
+                .collect::<StdResult<Vec<String>>>()?;
+            to_json_binary(&tokens)
+        }
+    }
+}
+
+/// Helper to create a mock receipt NFT contract for cw-multi-test.
+#[allow(dead_code)]
+pub fn mock_receipt_nft_contract() -> cw_multi_test::ContractWrapper<
+    ReceiptNftExecuteMsg,
+    MockReceiptNftInstantiateMsg,
+    MockReceiptNftQueryMsg,
+    StdError,
+    StdError,
+    StdError,
+> {
+    use cw_multi_test::ContractWrapper;
+
+    ContractWrapper::new(
+        mock_receipt_nft_execute,
+        mock_receipt_nft_instantiate,
+        mock_receipt_nft_query,
+    )
+}
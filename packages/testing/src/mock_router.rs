@@ -0,0 +1,81 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+
+/// Mock DEX router instantiate message
+#[cw_serde]
+pub struct MockRouterInstantiateMsg {}
+
+/// Mock DEX router execute message
+#[cw_serde]
+pub enum MockRouterExecuteMsg {
+    /// Accept any funds and send `amount_out` of `denom_out` back to the caller.
+    /// The router must be pre-funded with `denom_out`.
+    Swap {
+        denom_out: String,
+        amount_out: Uint128,
+    },
+}
+
+/// Mock DEX router query message (no queries supported)
+#[cw_serde]
+pub enum MockRouterQueryMsg {}
+
+/// Mock router contract entry points for use in tests.
+pub fn mock_router_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: MockRouterInstantiateMsg,
+) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "instantiate_mock_router"))
+}
+
+pub fn mock_router_execute(
+    _deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: MockRouterExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockRouterExecuteMsg::Swap {
+            denom_out,
+            amount_out,
+        } => {
+            if info.funds.is_empty() {
+                return Err(StdError::generic_err("no funds to swap"));
+            }
+            Ok(Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(amount_out.u128(), denom_out),
+                })
+                .add_attribute("action", "swap")
+                .add_attribute("amount_out", amount_out))
+        }
+    }
+}
+
+pub fn mock_router_query(_deps: Deps, _env: Env, msg: MockRouterQueryMsg) -> StdResult<Binary> {
+    match msg {}
+}
+
+/// Helper to create a mock router contract for cw-multi-test.
+#[allow(dead_code)]
+pub fn mock_router_contract() -> cw_multi_test::ContractWrapper<
+    MockRouterExecuteMsg,
+    MockRouterInstantiateMsg,
+    MockRouterQueryMsg,
+    StdError,
+    StdError,
+    StdError,
+> {
+    use cw_multi_test::ContractWrapper;
+
+    ContractWrapper::new(
+        mock_router_execute,
+        mock_router_instantiate,
+        mock_router_query,
+    )
+}
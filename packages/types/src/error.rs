@@ -27,6 +27,30 @@ pub enum ContractError {
     #[error("Invalid liquidation bonus: must be between {min} and {max}")]
     InvalidLiquidationBonus { min: String, max: String },
 
+    #[error("Invalid liquidation incentive: bonus {bonus} + protocol fee {protocol_fee} must be less than 50%")]
+    InvalidLiquidationIncentive { bonus: String, protocol_fee: String },
+
+    #[error("Invalid liquidation bonus tiers: {reason}")]
+    InvalidLiquidationBonusTiers { reason: String },
+
+    #[error("Invalid close factor {value}: must be greater than 0 and at most 1")]
+    InvalidCloseFactor { value: String },
+
+    #[error("Invalid minimum reserve ratio {value}: must be at most 0.5")]
+    InvalidMinReserveRatio { value: String },
+
+    #[error("Invalid max liquidation value: must be greater than zero")]
+    InvalidMaxLiquidationValue,
+
+    #[error("Invalid flat borrow fee: {fee} exceeds 1% of the borrow cap ({max})")]
+    InvalidProtocolBorrowFeeFlat { fee: String, max: String },
+
+    #[error("Invalid min post-borrow health factor: {value} (must be at least 1)")]
+    InvalidMinPostBorrowHealthFactor { value: String },
+
+    #[error("Invalid withdraw fee {value}: must be at most 0.02")]
+    InvalidWithdrawFee { value: String },
+
     #[error("Invalid fee: protocol_fee + curator_fee must be less than 1.0")]
     InvalidFees,
 
@@ -77,16 +101,27 @@ pub enum ContractError {
     #[error("Invalid oracle: failed to query price for {denom}")]
     InvalidOracle { denom: String },
 
-    #[error("Oracle code ID mismatch: expected {expected}, got {actual}")]
-    OracleCodeIdMismatch { expected: u64, actual: u64 },
+    #[error("Oracle contract {oracle} has code ID {actual_code_id}, but the oracle config expects code ID {expected_code_id}")]
+    OracleContractCodeMismatch {
+        oracle: String,
+        expected_code_id: u64,
+        actual_code_id: u64,
+    },
 
-    #[error("Oracle price stale: updated at {updated_at}, current time {current_time}, max staleness {max_staleness}s")]
+    #[error("Oracle price is stale for {denom}: age={age_seconds}s, max={max_staleness}s")]
     OraclePriceStale {
-        updated_at: u64,
-        current_time: u64,
+        denom: String,
+        age_seconds: u64,
         max_staleness: u64,
     },
 
+    #[error("Oracle price timestamp is in the future for {denom}: updated_at={updated_at}, current={current}")]
+    OraclePriceFuture {
+        denom: String,
+        updated_at: u64,
+        current: u64,
+    },
+
     #[error("Oracle returned zero price for {denom}")]
     OracleZeroPrice { denom: String },
 
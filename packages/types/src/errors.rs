@@ -0,0 +1,76 @@
+//! Errors shared by every Stone contract.
+//!
+//! Validation in this crate fails with [`TypesError`]. Contracts keep their own
+//! `ContractError` and wrap `TypesError` in a `Types` variant, so `?` converts
+//! shared errors into local ones:
+//!
+//! ```
+//! use cosmwasm_std::StdError;
+//! use stone_types::errors::TypesError;
+//! use thiserror::Error;
+//!
+//! #[derive(Error, Debug, PartialEq)]
+//! pub enum ContractError {
+//!     #[error("{0}")]
+//!     Std(#[from] StdError),
+//!
+//!     #[error("{0}")]
+//!     Types(#[from] TypesError),
+//!
+//!     #[error("Unauthorized")]
+//!     Unauthorized,
+//! }
+//!
+//! fn check_denoms(collateral: &str, debt: &str) -> Result<(), ContractError> {
+//!     if collateral == debt {
+//!         return Err(TypesError::SameDenom.into());
+//!     }
+//!     Ok(())
+//! }
+//!
+//! assert_eq!(
+//!     check_denoms("uatom", "uatom"),
+//!     Err(ContractError::Types(TypesError::SameDenom))
+//! );
+//!
+//! // Match on a shared error through the local wrapper
+//! match check_denoms("uatom", "uatom") {
+//!     Err(ContractError::Types(TypesError::SameDenom)) => {}
+//!     other => panic!("unexpected result: {other:?}"),
+//! }
+//! ```
+
+pub use crate::error::*;
+
+/// The types crate's error, named so it doesn't clash with a contract's own
+/// `ContractError` when both are imported.
+pub type TypesError = ContractError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::StdError;
+    use thiserror::Error;
+
+    #[derive(Error, Debug, PartialEq)]
+    enum LocalError {
+        #[error("{0}")]
+        Std(#[from] StdError),
+
+        #[error("{0}")]
+        Types(#[from] TypesError),
+    }
+
+    // Fails to compile if `TypesError` can't be wrapped with `#[from]`
+    const _: fn(TypesError) -> LocalError = LocalError::from;
+
+    #[test]
+    fn test_types_error_converts_into_local_error() {
+        let err: LocalError = TypesError::Unauthorized.into();
+        assert_eq!(err, LocalError::Types(TypesError::Unauthorized));
+        assert_eq!(err.to_string(), "Unauthorized");
+
+        let err: LocalError = StdError::generic_err("boom").into();
+        assert!(matches!(err, LocalError::Std(_)));
+    }
+}
@@ -1,19 +1,26 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Decimal, Event, Uint128};
 
 use crate::{CreateMarketParams, OracleConfigUnchecked};
 
-/// Factory contract configuration.
+/// Governance parameters of the factory, stored as a single item.
 #[cw_serde]
-pub struct FactoryConfig {
+pub struct FactoryParams {
     /// Contract owner (can update config)
     pub owner: Addr,
     /// Address to receive protocol fees
     pub protocol_fee_collector: Addr,
-    /// Fee required to create a new market
+    /// Minimum fee required to create a new market
     pub market_creation_fee: Coin,
+    /// Maximum fee charged to create a market; anything sent above it is refunded.
+    /// Equal to `market_creation_fee` for a fixed fee.
+    pub max_market_creation_fee: Coin,
     /// Code ID for instantiating market contracts
     pub market_code_id: u64,
+    /// When set, `CreateMarket` and `BatchCreateMarkets` are rejected.
+    /// Existing markets are unaffected.
+    #[serde(default)]
+    pub paused: bool,
 }
 
 /// Record of a created market.
@@ -33,6 +40,59 @@ pub struct MarketRecord {
     pub created_at: u64,
 }
 
+/// A `CreateMarket` instantiation that has been dispatched but whose reply has
+/// not registered the market yet.
+#[cw_serde]
+pub struct CreateMarketAttempt {
+    /// Submessage reply ID the attempt is tracked under
+    pub reply_id: u64,
+    /// Market ID the market will be registered under
+    pub market_id: String,
+    /// Curator who requested the market
+    pub curator: Addr,
+    /// Collateral asset denom
+    pub collateral_denom: String,
+    /// Debt asset denom
+    pub debt_denom: String,
+    /// Salt used to compute the market ID
+    pub salt: Option<u64>,
+    /// Block time the instantiation was dispatched
+    pub started_at: u64,
+    /// Creation fee charged for the attempt (forwarded on success, refunded on failure)
+    pub fee_paid: Coin,
+}
+
+/// Outcome of a successful market instantiation, emitted by the factory reply
+/// as a `stone.factory.market_created` event for indexers.
+#[cw_serde]
+pub struct CreateMarketResult {
+    /// Market contract address
+    pub market_address: String,
+    /// Collateral asset denom
+    pub collateral_denom: String,
+    /// Debt asset denom
+    pub debt_denom: String,
+    /// Code ID the market was instantiated from
+    pub code_id: u64,
+    /// Block time the market was registered
+    pub created_at: u64,
+}
+
+impl CreateMarketResult {
+    /// Event type of the typed market creation event
+    pub const EVENT_TYPE: &'static str = "stone.factory.market_created";
+
+    /// Build the typed event, one attribute per field.
+    pub fn to_event(&self) -> Event {
+        Event::new(Self::EVENT_TYPE)
+            .add_attribute("market_address", &self.market_address)
+            .add_attribute("collateral_denom", &self.collateral_denom)
+            .add_attribute("debt_denom", &self.debt_denom)
+            .add_attribute("code_id", self.code_id.to_string())
+            .add_attribute("created_at", self.created_at.to_string())
+    }
+}
+
 // ============================================================================
 // Factory Contract Messages
 // ============================================================================
@@ -44,7 +104,7 @@ pub struct FactoryInstantiateMsg {
     pub owner: String,
     /// Address to receive protocol fees
     pub protocol_fee_collector: String,
-    /// Fee required to create a market
+    /// Fee required to create a market (used as both the minimum and maximum fee)
     pub market_creation_fee: Coin,
     /// Code ID for market contracts
     pub market_code_id: u64,
@@ -65,25 +125,139 @@ pub enum FactoryExecuteMsg {
         params: Box<CreateMarketParams>,
         /// Optional salt for creating multiple markets with same pair
         salt: Option<u64>,
+        /// Creation fee to pay, at least the minimum creation fee. Defaults to the
+        /// minimum; anything above the maximum creation fee is refunded.
+        #[serde(default)]
+        fee_amount: Option<Uint128>,
     },
 
-    /// Update factory configuration (owner only)
+    /// Create several markets atomically (send N * minimum creation fee with msg)
+    BatchCreateMarkets { markets: Vec<CreateMarketRequest> },
+
+    /// Update factory configuration (owner only).
+    /// `market_creation_fee` sets a fixed fee (minimum and maximum both set to it).
+    /// The protocol fee collector can only change through
+    /// `ProposeProtocolFeeCollector` and `AcceptProtocolFeeCollectorRole`.
     UpdateConfig {
-        protocol_fee_collector: Option<String>,
         market_creation_fee: Option<Coin>,
+        /// Pause or resume market creation
+        #[serde(default)]
+        paused: Option<bool>,
+    },
+
+    /// Set the range of creation fees curators may pay (owner only).
+    /// Both fees must use the same denom and `min <= max`; `min == max` is a fixed fee.
+    SetMinMarketCreationFee {
+        min_market_creation_fee: Coin,
+        max_market_creation_fee: Coin,
     },
 
     /// Update market code ID for future deployments (owner only)
     UpdateMarketCodeId { code_id: u64 },
 
+    /// Disable a factory-created market in an emergency (owner only).
+    /// Sends `Pause {}` to the market; only `EnableMarket` lifts it.
+    DisableMarket { market: String },
+
+    /// Lift an emergency pause on a factory-created market (owner only).
+    /// Sends `Unpause {}` to the market.
+    EnableMarket { market: String },
+
+    /// Recover the accrued protocol fees of a factory-created market (owner only).
+    /// Sends `EmergencyWithdraw { recipient }` to the market.
+    EmergencyWithdrawMarket { market: String, recipient: String },
+
+    /// Correct rounding drift in the liquidity index of a factory-created market
+    /// (owner only). Sends `RebalanceLiquidityIndex { target_index }` to the market.
+    RebalanceMarketLiquidityIndex {
+        market: String,
+        target_index: Decimal,
+    },
+
+    /// Permanently lock the LTV of a factory-created market (owner only).
+    /// Sends `FreezeLtv {}` to the market; this cannot be undone.
+    FreezeMarketLtv { market: String },
+
+    /// Switch the collateral denom of a factory-created market after a collateral
+    /// token migration (owner only). The market's oracle must return a fresh price
+    /// for `new_denom`. The market keeps its ID; the denom indices are updated.
+    UpdateMarketCollateralDenom { market: String, new_denom: String },
+
+    /// Rotate the CosmWasm migrate admin of a factory-created market (owner only).
+    /// The factory must be the market's current admin. `None` clears the admin,
+    /// making the market permanently non-migratable.
+    SetMarketAdmin {
+        market: String,
+        admin: Option<String>,
+    },
+
     /// Transfer ownership
     TransferOwnership { new_owner: String },
 
     /// Accept ownership transfer
     AcceptOwnership {},
+
+    /// Propose a new protocol fee collector (owner only). Takes effect once the
+    /// proposed address calls `AcceptProtocolFeeCollectorRole {}`.
+    ProposeProtocolFeeCollector { new_collector: String },
+
+    /// Accept the protocol fee collector role (pending collector only).
+    /// Updates the factory and sends `UpdateProtocolFeeCollector` to the first
+    /// page of markets; `PropagateFeeCollector` covers the rest.
+    AcceptProtocolFeeCollectorRole {},
+
+    /// Send the current protocol fee collector to a page of markets, ordered by
+    /// market ID (anyone can call). Follow the `next_start_after` attribute until
+    /// it is no longer emitted.
+    PropagateFeeCollector {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// A single market in a `BatchCreateMarkets` call (same fields as `CreateMarket`).
+#[cw_serde]
+pub struct CreateMarketRequest {
+    /// Collateral asset denom
+    pub collateral_denom: String,
+    /// Debt asset denom
+    pub debt_denom: String,
+    /// Oracle configuration with address and type
+    pub oracle_config: OracleConfigUnchecked,
+    /// Market parameters
+    pub params: CreateMarketParams,
+    /// Optional salt for creating multiple markets with same pair
+    pub salt: Option<u64>,
 }
 
 /// Query messages for factory contract.
+/// Ordering for the `Markets` query. Markets sharing a sort key are ordered by
+/// market ID.
+#[cw_serde]
+pub enum MarketSortField {
+    /// Market contract address
+    Address,
+    /// Collateral denom
+    CollateralDenom,
+    /// Debt denom
+    DebtDenom,
+    /// Creation time, oldest first
+    CreatedAt,
+}
+
+/// Which markets the `MarketsBy` query returns.
+#[cw_serde]
+pub enum MarketFilter {
+    /// Markets using this collateral denom
+    CollateralDenom(String),
+    /// Markets lending this debt denom
+    DebtDenom(String),
+    /// Markets created by this curator
+    Curator(String),
+    /// Every market, ordered by market ID
+    All,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum FactoryQueryMsg {
@@ -99,11 +273,21 @@ pub enum FactoryQueryMsg {
     #[returns(MarketResponse)]
     MarketByAddress { address: String },
 
-    /// List all markets (paginated)
+    /// Get the first market created for a collateral/debt denom pair, if any
+    #[returns(Option<MarketResponse>)]
+    MarketByDenoms {
+        collateral_denom: String,
+        debt_denom: String,
+    },
+
+    /// List all markets (paginated). Ordered by market ID unless `sort_by` is set;
+    /// `start_after` is always the market ID of the last market on the previous page.
     #[returns(MarketsResponse)]
     Markets {
         start_after: Option<String>,
         limit: Option<u32>,
+        #[serde(default)]
+        sort_by: Option<MarketSortField>,
     },
 
     /// List markets by curator
@@ -130,10 +314,22 @@ pub enum FactoryQueryMsg {
         limit: Option<u32>,
     },
 
+    /// List markets matching `filter` (paginated by market ID)
+    #[returns(MarketsResponse)]
+    MarketsBy {
+        filter: MarketFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
     /// Get total number of markets
     #[returns(MarketCountResponse)]
     MarketCount {},
 
+    /// Get the code ID used for new market instantiations
+    #[returns(MarketCodeResponse)]
+    MarketCode {},
+
     /// Compute market ID for given parameters (useful for prediction)
     #[returns(ComputeMarketIdResponse)]
     ComputeMarketId {
@@ -142,8 +338,34 @@ pub enum FactoryQueryMsg {
         curator: String,
         salt: Option<u64>,
     },
+
+    /// Whether an explicit salt has already seeded a registered market
+    #[returns(bool)]
+    IsSaltUsed { salt: u64 },
+
+    /// List `CreateMarket` attempts whose instantiation reply has not
+    /// registered a market (e.g. the market contract failed to instantiate),
+    /// ordered by reply ID. Pass the last `reply_id` as `start_after` for the next page.
+    #[returns(Vec<CreateMarketAttempt>)]
+    PendingMarkets {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Get the full factory governance parameters
+    #[returns(FactoryParams)]
+    FactoryParams {},
+
+    /// Sum the USD value supplied to and borrowed from markets, from each
+    /// market's `MarketSummary`. Covers at most the first 20 markets by ID.
+    #[returns(ProtocolTvlResponse)]
+    TotalProtocolTvl {},
 }
 
+/// Migrate message for factory contract.
+#[cw_serde]
+pub struct FactoryMigrateMsg {}
+
 // ============================================================================
 // Query Responses
 // ============================================================================
@@ -152,7 +374,10 @@ pub enum FactoryQueryMsg {
 pub struct FactoryConfigResponse {
     pub owner: String,
     pub protocol_fee_collector: String,
+    /// Minimum market creation fee
     pub market_creation_fee: Coin,
+    /// Maximum market creation fee
+    pub max_market_creation_fee: Coin,
     pub market_code_id: u64,
 }
 
@@ -176,11 +401,29 @@ pub struct MarketCountResponse {
     pub count: u64,
 }
 
+#[cw_serde]
+pub struct MarketCodeResponse {
+    pub code_id: u64,
+}
+
 #[cw_serde]
 pub struct ComputeMarketIdResponse {
     pub market_id: String,
 }
 
+#[cw_serde]
+pub struct ProtocolTvlResponse {
+    /// Total supply across the valued markets, in USD
+    pub total_supply_usd: Decimal,
+    /// Total debt across the valued markets, in USD
+    pub total_debt_usd: Decimal,
+    /// Number of markets included in the totals
+    pub market_count: u64,
+    /// IDs of markets left out because their summary or debt asset price
+    /// was unavailable
+    pub failed_markets: Vec<String>,
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -1,5 +1,7 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, StdError};
+
+use crate::ContractError;
 
 /// Interest rate model for calculating borrow rates based on utilization.
 #[cw_serde]
@@ -17,6 +19,19 @@ pub enum InterestRateModel {
         /// Interest rate slope above optimal utilization (e.g., 3.0 = 300%)
         slope_2: Decimal,
     },
+    /// Compound-style jump rate model.
+    /// - rate = base + min(utilization, kink) * multiplier
+    ///   + max(utilization - kink, 0) * jump_multiplier
+    JumpRate {
+        /// Base interest rate when utilization is 0 (e.g., 0.02 = 2%)
+        base: Decimal,
+        /// Utilization at which the jump multiplier applies (e.g., 0.8 = 80%)
+        kink: Decimal,
+        /// Rate increase per unit of utilization up to the kink (e.g., 0.1 = 10%)
+        multiplier: Decimal,
+        /// Rate increase per unit of utilization above the kink (e.g., 2.0 = 200%)
+        jump_multiplier: Decimal,
+    },
 }
 
 impl InterestRateModel {
@@ -47,9 +62,45 @@ impl InterestRateModel {
                     }
                 }
             }
+            InterestRateModel::JumpRate {
+                base,
+                kink,
+                multiplier,
+                jump_multiplier,
+            } => {
+                let normal = utilization.min(*kink) * *multiplier;
+                let excess = utilization.saturating_sub(*kink) * *jump_multiplier;
+                *base + normal + excess
+            }
         }
     }
 
+    /// Rate earned by suppliers: the borrow rate scaled by utilization, net of
+    /// protocol and curator fees.
+    ///
+    /// `liquidity_rate = borrow_rate * utilization * (1 - protocol_fee - curator_fee)`
+    ///
+    /// Fails with `InvalidFees` unless `protocol_fee + curator_fee < 1`.
+    pub fn calculate_liquidity_rate(
+        borrow_rate: Decimal,
+        utilization: Decimal,
+        protocol_fee: Decimal,
+        curator_fee: Decimal,
+    ) -> Result<Decimal, ContractError> {
+        let total_fee = protocol_fee
+            .checked_add(curator_fee)
+            .map_err(|_| ContractError::InvalidFees)?;
+        if total_fee >= Decimal::one() {
+            return Err(ContractError::InvalidFees);
+        }
+
+        let supplier_share = Decimal::one() - total_fee;
+        Ok(borrow_rate
+            .checked_mul(utilization)
+            .and_then(|rate| rate.checked_mul(supplier_share))
+            .map_err(StdError::from)?)
+    }
+
     /// Validate the interest rate model parameters.
     pub fn validate(&self) -> bool {
         match self {
@@ -67,6 +118,20 @@ impl InterestRateModel {
                     && *slope_1 >= Decimal::zero()
                     && *slope_2 >= Decimal::zero()
             }
+            InterestRateModel::JumpRate {
+                base,
+                kink,
+                multiplier,
+                jump_multiplier,
+            } => {
+                // Kink must be between 0 and 1
+                *kink <= Decimal::one()
+                    // Base rate must be non-negative
+                    && *base >= Decimal::zero()
+                    // Multipliers must be non-negative
+                    && *multiplier >= Decimal::zero()
+                    && *jump_multiplier >= Decimal::zero()
+            }
         }
     }
 }
@@ -128,6 +193,96 @@ mod tests {
         assert_eq!(rate, Decimal::percent(304));
     }
 
+    #[test]
+    fn test_liquidity_rate_zero_utilization() {
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            Decimal::percent(10),
+            Decimal::zero(),
+            Decimal::percent(10),
+            Decimal::percent(5),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::zero());
+    }
+
+    #[test]
+    fn test_liquidity_rate_full_utilization() {
+        // 10% * 100% * (1 - 0.10 - 0.05) = 8.5%
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            Decimal::percent(10),
+            Decimal::one(),
+            Decimal::percent(10),
+            Decimal::percent(5),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::permille(85));
+    }
+
+    #[test]
+    fn test_liquidity_rate_fee_combinations() {
+        let borrow_rate = Decimal::percent(20);
+        let utilization = Decimal::percent(50);
+
+        // No fees: suppliers get borrow_rate * utilization
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            borrow_rate,
+            utilization,
+            Decimal::zero(),
+            Decimal::zero(),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::percent(10));
+
+        // Protocol fee only: 10% * (1 - 0.2) = 8%
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            borrow_rate,
+            utilization,
+            Decimal::percent(20),
+            Decimal::zero(),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::percent(8));
+
+        // Curator fee only: 10% * (1 - 0.25) = 7.5%
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            borrow_rate,
+            utilization,
+            Decimal::zero(),
+            Decimal::percent(25),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::permille(75));
+
+        // Fees just under 100%: 10% * 0.01 = 0.1%
+        let rate = InterestRateModel::calculate_liquidity_rate(
+            borrow_rate,
+            utilization,
+            Decimal::percent(74),
+            Decimal::percent(25),
+        )
+        .unwrap();
+        assert_eq!(rate, Decimal::permille(1));
+    }
+
+    #[test]
+    fn test_liquidity_rate_rejects_fees_of_one_or_more() {
+        for (protocol_fee, curator_fee) in [
+            (Decimal::percent(75), Decimal::percent(25)),
+            (Decimal::one(), Decimal::zero()),
+            (Decimal::percent(90), Decimal::percent(20)),
+            (Decimal::MAX, Decimal::one()),
+        ] {
+            let err = InterestRateModel::calculate_liquidity_rate(
+                Decimal::percent(10),
+                Decimal::percent(50),
+                protocol_fee,
+                curator_fee,
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::InvalidFees);
+        }
+    }
+
     #[test]
     fn test_validate() {
         let valid = InterestRateModel::default();
@@ -141,4 +296,80 @@ mod tests {
         };
         assert!(!invalid.validate());
     }
+
+    fn jump_rate_model() -> InterestRateModel {
+        InterestRateModel::JumpRate {
+            base: Decimal::percent(2),
+            kink: Decimal::percent(80),
+            multiplier: Decimal::percent(10),
+            jump_multiplier: Decimal::percent(200),
+        }
+    }
+
+    #[test]
+    fn test_jump_rate_model_below_kink() {
+        let model = jump_rate_model();
+
+        // At 0% utilization
+        let rate = model.calculate_borrow_rate(Decimal::zero());
+        assert_eq!(rate, Decimal::percent(2));
+
+        // At 40% utilization: 2% + 40% * 10% = 6%
+        let rate = model.calculate_borrow_rate(Decimal::percent(40));
+        assert_eq!(rate, Decimal::percent(6));
+    }
+
+    #[test]
+    fn test_jump_rate_model_at_kink() {
+        let model = jump_rate_model();
+
+        // At 80% utilization: 2% + 80% * 10% = 10%
+        let rate = model.calculate_borrow_rate(Decimal::percent(80));
+        assert_eq!(rate, Decimal::percent(10));
+    }
+
+    #[test]
+    fn test_jump_rate_model_above_kink() {
+        let model = jump_rate_model();
+
+        // At 90% utilization: 2% + 80% * 10% + 10% * 200% = 30%
+        let rate = model.calculate_borrow_rate(Decimal::percent(90));
+        assert_eq!(rate, Decimal::percent(30));
+    }
+
+    #[test]
+    fn test_jump_rate_model_full_utilization() {
+        let model = jump_rate_model();
+
+        // At 100% utilization: 2% + 80% * 10% + 20% * 200% = 50%
+        let rate = model.calculate_borrow_rate(Decimal::percent(100));
+        assert_eq!(rate, Decimal::percent(50));
+    }
+
+    #[test]
+    fn test_jump_rate_model_zero_kink() {
+        let model = InterestRateModel::JumpRate {
+            base: Decimal::zero(),
+            kink: Decimal::zero(),
+            multiplier: Decimal::percent(10),
+            jump_multiplier: Decimal::percent(200),
+        };
+
+        // Entire utilization is above the kink: 50% * 200% = 100%
+        let rate = model.calculate_borrow_rate(Decimal::percent(50));
+        assert_eq!(rate, Decimal::percent(100));
+    }
+
+    #[test]
+    fn test_jump_rate_validate() {
+        assert!(jump_rate_model().validate());
+
+        let invalid = InterestRateModel::JumpRate {
+            base: Decimal::zero(),
+            kink: Decimal::percent(101), // > 100%
+            multiplier: Decimal::percent(10),
+            jump_multiplier: Decimal::percent(200),
+        };
+        assert!(!invalid.validate());
+    }
 }
@@ -1,4 +1,5 @@
 mod error;
+pub mod errors;
 mod factory;
 mod interest_rate_model;
 mod market;
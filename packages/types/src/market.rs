@@ -1,6 +1,7 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 
+use crate::error::ContractError;
 use crate::{InterestRateModel, OracleConfig, OracleConfigUnchecked, OracleType};
 
 /// Market configuration set at instantiation (mostly immutable).
@@ -20,8 +21,46 @@ pub struct MarketConfig {
     pub protocol_fee_collector: Addr,
     /// Optional salt used when creating this market (required for correct market_id computation)
     pub salt: Option<u64>,
+    /// Whether borrowers may swap their collateral through a DEX router in place
+    #[serde(default)]
+    pub allow_collateral_swap: bool,
+    /// NFT contract minting a receipt for each supply (and burning them when
+    /// the position is withdrawn), if any
+    #[serde(default)]
+    pub receipt_nft_contract: Option<Addr>,
 }
 
+/// Deposit recorded in a supply receipt NFT's extension.
+#[cw_serde]
+pub struct DepositInfo {
+    /// Debt asset supplied
+    pub amount: Uint128,
+    /// Scaled supply units credited for `amount`
+    pub scaled: Uint128,
+    /// Block time of the supply (seconds)
+    pub timestamp: u64,
+}
+
+/// Mint a supply receipt NFT.
+#[cw_serde]
+pub struct MintMsg {
+    pub token_id: String,
+    /// Owner of the supply position
+    pub owner: String,
+    pub extension: DepositInfo,
+}
+
+/// cw721-style execute messages the market sends to its receipt NFT contract.
+/// The market must be allowed to mint and to burn the receipts it minted.
+#[cw_serde]
+pub enum ReceiptNftExecuteMsg {
+    Mint(MintMsg),
+    Burn { token_id: String },
+}
+
+/// Maximum `MarketParams::withdraw_fee` (2%).
+pub const MAX_WITHDRAW_FEE: Decimal = Decimal::raw(20_000_000_000_000_000);
+
 /// Market parameters that control risk and fees.
 #[cw_serde]
 pub struct MarketParams {
@@ -48,6 +87,45 @@ pub struct MarketParams {
     pub supply_cap: Option<Uint128>,
     /// Maximum borrow allowed (None = unlimited)
     pub borrow_cap: Option<Uint128>,
+    /// Smallest amount a single borrow may take (0 = no minimum)
+    #[serde(default)]
+    pub min_borrow_amount: Uint128,
+    /// Share of total supply that must stay available after a supply withdrawal
+    /// (0 = no reserve requirement, max 0.5)
+    #[serde(default)]
+    pub min_reserve_ratio: Decimal,
+    /// Blocks a supplier must wait after supplying before withdrawing (0 = no cooldown)
+    #[serde(default)]
+    pub min_supply_blocks: u64,
+    /// Largest collateral value (in oracle price units) a single liquidation may
+    /// seize; larger liquidations are scaled down (None = unlimited)
+    #[serde(default)]
+    pub max_liquidation_value_usd: Option<Decimal>,
+    /// Whether collateral deposits are restricted to whitelisted addresses
+    #[serde(default)]
+    pub collateral_whitelist_enabled: bool,
+    /// Health factor a position must keep immediately after a borrow, on top of
+    /// the LTV check (None = no extra requirement, must be at least 1)
+    #[serde(default)]
+    pub min_post_borrow_health_factor: Option<Decimal>,
+    /// Flat protocol fee taken from every borrow's proceeds, in debt asset
+    /// (0 = no fee, at most 1% of the borrow cap)
+    #[serde(default)]
+    pub protocol_borrow_fee_flat: Uint128,
+    /// Whether a repayment leaving at most `dust_debt_threshold` of debt clears
+    /// the remainder
+    #[serde(default)]
+    pub auto_clear_dust: bool,
+    /// Liquidation bonus tiers as `(health_factor_threshold, bonus)` pairs, sorted
+    /// by strictly descending threshold. A liquidation uses the bonus of the tier
+    /// with the lowest threshold at or above the borrower's health factor, and
+    /// `liquidation_bonus` when no tier matches (empty = always `liquidation_bonus`)
+    #[serde(default)]
+    pub liquidation_bonus_by_health_factor: Vec<(Decimal, Decimal)>,
+    /// Share of each supply withdrawal kept as a curator fee, at most
+    /// `MAX_WITHDRAW_FEE` (0 = no fee)
+    #[serde(default)]
+    pub withdraw_fee: Decimal,
     /// Whether the market is enabled for new operations
     pub enabled: bool,
     /// Whether LTV can be modified by curator
@@ -56,6 +134,133 @@ pub struct MarketParams {
     pub ltv_last_update: u64,
 }
 
+impl MarketParams {
+    /// Check the risk parameters are consistent with each other.
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.loan_to_value >= self.liquidation_threshold {
+            return Err(ContractError::InvalidLtv);
+        }
+
+        if self.liquidation_threshold >= Decimal::one() {
+            return Err(ContractError::InvalidLiquidationThreshold);
+        }
+
+        // Bonus and protocol fee are both paid out of seized collateral
+        let liquidation_incentive = self
+            .liquidation_bonus
+            .checked_add(self.liquidation_protocol_fee)
+            .map_err(|_| ContractError::MathOverflow)?;
+        if liquidation_incentive >= Decimal::percent(50) {
+            return Err(ContractError::InvalidLiquidationIncentive {
+                bonus: self.liquidation_bonus.to_string(),
+                protocol_fee: self.liquidation_protocol_fee.to_string(),
+            });
+        }
+
+        if self.close_factor.is_zero() || self.close_factor > Decimal::one() {
+            return Err(ContractError::InvalidCloseFactor {
+                value: self.close_factor.to_string(),
+            });
+        }
+
+        if !self.interest_rate_model.validate() {
+            return Err(ContractError::InvalidInterestRateModel);
+        }
+
+        if self.min_reserve_ratio > Decimal::percent(50) {
+            return Err(ContractError::InvalidMinReserveRatio {
+                value: self.min_reserve_ratio.to_string(),
+            });
+        }
+
+        if self.max_liquidation_value_usd == Some(Decimal::zero()) {
+            return Err(ContractError::InvalidMaxLiquidationValue);
+        }
+
+        if let Some(borrow_cap) = self.borrow_cap {
+            // A threshold above the cap would make every position dust
+            if self.dust_debt_threshold > borrow_cap {
+                return Err(ContractError::DustDebtThresholdTooHigh {
+                    value: self.dust_debt_threshold.to_string(),
+                    max: borrow_cap.to_string(),
+                });
+            }
+
+            let max_fee = borrow_cap / Uint128::new(100);
+            if self.protocol_borrow_fee_flat > max_fee {
+                return Err(ContractError::InvalidProtocolBorrowFeeFlat {
+                    fee: self.protocol_borrow_fee_flat.to_string(),
+                    max: max_fee.to_string(),
+                });
+            }
+        }
+
+        if let Some(min_health_factor) = self.min_post_borrow_health_factor {
+            if min_health_factor < Decimal::one() {
+                return Err(ContractError::InvalidMinPostBorrowHealthFactor {
+                    value: min_health_factor.to_string(),
+                });
+            }
+        }
+
+        if self.withdraw_fee > MAX_WITHDRAW_FEE {
+            return Err(ContractError::InvalidWithdrawFee {
+                value: self.withdraw_fee.to_string(),
+            });
+        }
+
+        self.validate_liquidation_bonus_tiers()?;
+
+        Ok(())
+    }
+
+    /// Tiers must be sorted by strictly descending threshold in (0, 1], and each
+    /// tier's bonus is held to the same incentive limit as `liquidation_bonus`.
+    fn validate_liquidation_bonus_tiers(&self) -> Result<(), ContractError> {
+        let tiers = &self.liquidation_bonus_by_health_factor;
+        for (threshold, bonus) in tiers {
+            if threshold.is_zero() || *threshold > Decimal::one() {
+                return Err(ContractError::InvalidLiquidationBonusTiers {
+                    reason: format!("threshold {threshold} must be greater than 0 and at most 1"),
+                });
+            }
+            let liquidation_incentive = bonus
+                .checked_add(self.liquidation_protocol_fee)
+                .map_err(|_| ContractError::MathOverflow)?;
+            if liquidation_incentive >= Decimal::percent(50) {
+                return Err(ContractError::InvalidLiquidationIncentive {
+                    bonus: bonus.to_string(),
+                    protocol_fee: self.liquidation_protocol_fee.to_string(),
+                });
+            }
+        }
+        if tiers.windows(2).any(|pair| pair[0].0 <= pair[1].0) {
+            return Err(ContractError::InvalidLiquidationBonusTiers {
+                reason: "thresholds must be sorted in strictly descending order".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Liquidation bonus for a position at `health_factor`: the bonus of the tier
+    /// with the lowest threshold at or above it, so more undercollateralised
+    /// positions reach deeper tiers, or `liquidation_bonus` when none matches.
+    pub fn liquidation_bonus_for(&self, health_factor: Decimal) -> Decimal {
+        self.liquidation_bonus_by_health_factor
+            .iter()
+            .rev()
+            .find(|(threshold, _)| *threshold >= health_factor)
+            .map_or(self.liquidation_bonus, |(_, bonus)| *bonus)
+    }
+
+    /// Whether the dust debt threshold exceeds 10% of the borrow cap. Valid, but
+    /// most positions would then be fully liquidatable, so it is flagged as a
+    /// likely misconfiguration.
+    pub fn has_high_dust_debt_threshold(&self) -> bool {
+        self.dust_debt_threshold > self.borrow_cap.unwrap_or(Uint128::MAX) / Uint128::new(10)
+    }
+}
+
 /// Current market state with indices and totals.
 #[cw_serde]
 pub struct MarketState {
@@ -113,6 +318,11 @@ impl MarketState {
         }
     }
 
+    /// Whether the market is fully utilized (utilization clamped at 1.0).
+    pub fn is_at_max_utilization(&self) -> bool {
+        self.utilization() == Decimal::one()
+    }
+
     /// Get actual total supply (unscaled).
     /// scaled_amount * index = actual_amount
     pub fn total_supply(&self) -> Uint128 {
@@ -126,14 +336,9 @@ impl MarketState {
     }
 
     /// Get available liquidity for borrowing/withdrawal.
+    /// Saturates at zero when accrued debt exceeds supply.
     pub fn available_liquidity(&self) -> Uint128 {
-        let supply = self.total_supply();
-        let debt = self.total_debt();
-        if supply > debt {
-            supply - debt
-        } else {
-            Uint128::zero()
-        }
+        self.total_supply().saturating_sub(self.total_debt())
     }
 }
 
@@ -156,8 +361,43 @@ pub struct CreateMarketParams {
     pub is_mutable: bool,
 }
 
+impl CreateMarketParams {
+    /// Parameters a newly instantiated market starts with. Settings that
+    /// `CreateMarketParams` does not carry start disabled.
+    pub fn into_market_params(self, ltv_last_update: u64) -> MarketParams {
+        MarketParams {
+            loan_to_value: self.loan_to_value,
+            liquidation_threshold: self.liquidation_threshold,
+            liquidation_bonus: self.liquidation_bonus,
+            liquidation_protocol_fee: self.liquidation_protocol_fee,
+            close_factor: self.close_factor,
+            dust_debt_threshold: self.dust_debt_threshold,
+            interest_rate_model: self.interest_rate_model,
+            protocol_fee: self.protocol_fee,
+            curator_fee: self.curator_fee,
+            supply_cap: self.supply_cap,
+            borrow_cap: self.borrow_cap,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
+            enabled: true,
+            is_mutable: self.is_mutable,
+            ltv_last_update,
+        }
+    }
+}
+
 /// Allowed updates to market parameters by curator.
+/// Every field is optional; `..Default::default()` leaves the rest unchanged.
 #[cw_serde]
+#[derive(Default)]
 pub struct MarketParamsUpdate {
     /// New LTV (only if market is mutable, subject to ±5% and 7-day cooldown)
     pub loan_to_value: Option<Decimal>,
@@ -167,10 +407,42 @@ pub struct MarketParamsUpdate {
     pub curator_fee: Option<Decimal>,
     /// New dust debt threshold (max 10_000_000 micro-units)
     pub dust_debt_threshold: Option<Uint128>,
+    /// New close factor (must be in (0, 1])
+    pub close_factor: Option<Decimal>,
     /// New supply cap
     pub supply_cap: Option<Option<Uint128>>,
     /// New borrow cap
     pub borrow_cap: Option<Option<Uint128>>,
+    /// New minimum borrow amount (0 disables the minimum)
+    #[serde(default)]
+    pub min_borrow_amount: Option<Uint128>,
+    /// New minimum reserve ratio (must be in [0, 0.5], 0 disables the requirement)
+    #[serde(default)]
+    pub min_reserve_ratio: Option<Decimal>,
+    /// New supply-to-withdraw cooldown in blocks (0 disables the cooldown)
+    #[serde(default)]
+    pub min_supply_blocks: Option<u64>,
+    /// New per-liquidation collateral value cap (`Some(None)` removes the cap)
+    #[serde(default)]
+    pub max_liquidation_value_usd: Option<Option<Decimal>>,
+    /// Restrict (true) or open (false) collateral deposits to whitelisted addresses
+    #[serde(default)]
+    pub collateral_whitelist_enabled: Option<bool>,
+    /// New minimum post-borrow health factor (`Some(None)` removes the requirement)
+    #[serde(default)]
+    pub min_post_borrow_health_factor: Option<Option<Decimal>>,
+    /// New flat borrow fee (0 disables the fee)
+    #[serde(default)]
+    pub protocol_borrow_fee_flat: Option<Uint128>,
+    /// Clear (true) or keep (false) dust debt left behind by a repayment
+    #[serde(default)]
+    pub auto_clear_dust: Option<bool>,
+    /// New liquidation bonus tiers (an empty list removes the tiers)
+    #[serde(default)]
+    pub liquidation_bonus_by_health_factor: Option<Vec<(Decimal, Decimal)>>,
+    /// New withdraw fee (0 disables the fee)
+    #[serde(default)]
+    pub withdraw_fee: Option<Decimal>,
     /// Enable/disable market
     pub enabled: Option<bool>,
 }
@@ -188,18 +460,62 @@ pub struct MarketInstantiateMsg {
     pub debt_denom: String,
     pub protocol_fee_collector: String,
     pub params: CreateMarketParams,
+    /// Enable in-place collateral swaps (defaults to false)
+    #[serde(default)]
+    pub allow_collateral_swap: bool,
+    /// NFT contract to mint supply receipts on (defaults to none)
+    #[serde(default)]
+    pub receipt_nft_contract: Option<String>,
 }
 
 /// Execute messages for market contract.
 #[cw_serde]
 pub enum MarketExecuteMsg {
-    /// Supply debt asset to earn interest (send debt_denom with msg)
-    Supply { recipient: Option<String> },
+    /// Supply debt asset to earn interest (send debt_denom with msg).
+    /// `referrer` is credited with the supplied amount in the referral volumes.
+    /// A second supply of the same amount by the same sender in one block is
+    /// rejected as a likely duplicate unless `force` is set.
+    Supply {
+        recipient: Option<String>,
+        #[serde(default)]
+        referrer: Option<String>,
+        #[serde(default)]
+        force: bool,
+        /// Idempotency key: a nonce the sender already used for a supply or
+        /// withdrawal in the last 24 hours is rejected
+        #[serde(default)]
+        nonce: Option<u64>,
+    },
+
+    /// Supply debt asset on behalf of several recipients (send debt_denom with msg).
+    /// The funds must equal the sum of the amounts. `force` overrides the
+    /// duplicate-supply check, as with `Supply`.
+    SupplyOnBehalfMany {
+        recipients: Vec<(String, Uint128)>,
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Preview the totals and rates after supplying `amount` without supplying.
+    /// Fails like `Supply` would (disabled market, zero amount, supply cap);
+    /// the `SupplyPreviewResponse` is returned as the response data.
+    SupplyPreview { amount: Uint128 },
+
+    /// Move `amount` scaled supply units (shares) from the sender to `recipient`
+    /// without withdrawing. Transferring to yourself is a no-op.
+    TransferSupplyShares { recipient: String, amount: Uint128 },
 
     /// Withdraw supplied debt asset
     Withdraw {
         amount: Option<Uint128>,
         recipient: Option<String>,
+        /// Withdraw the full supply position; fails instead of partially
+        /// withdrawing if liquidity is insufficient. Cannot be combined with `amount`.
+        #[serde(default)]
+        max_withdraw: bool,
+        /// Idempotency key, shared with `Supply` (see there)
+        #[serde(default)]
+        nonce: Option<u64>,
     },
 
     /// Supply collateral asset (send collateral_denom with msg)
@@ -217,14 +533,118 @@ pub enum MarketExecuteMsg {
         recipient: Option<String>,
     },
 
+    /// Preview the utilization and borrow rate after the sender borrows `amount`
+    /// without borrowing. Ineligible borrows are reported in the
+    /// `BorrowPreviewResponse` returned as the response data rather than failing.
+    BorrowPreview { amount: Uint128 },
+
+    /// Supply collateral (send collateral_denom with msg) and borrow against it
+    /// in one step. Collateral sent beyond `supply_amount` is refunded.
+    SupplyAndBorrow {
+        supply_amount: Uint128,
+        borrow_amount: Uint128,
+    },
+
+    /// Deposit exactly `collateral_amount` of collateral (send collateral_denom
+    /// with msg) and borrow against it in one step.
+    SupplyCollateralAndBorrow {
+        collateral_amount: Uint128,
+        borrow_amount: Uint128,
+    },
+
     /// Repay borrowed debt (send debt_denom with msg)
     Repay { on_behalf_of: Option<String> },
 
+    /// Repay the sender's entire debt (send at least the debt in debt_denom).
+    /// The repayment is capped at the outstanding debt, including interest up to
+    /// this block, and the excess is refunded.
+    RepayMax {},
+
     /// Liquidate an unhealthy position (send debt_denom with msg)
     Liquidate { borrower: String },
 
+    /// Liquidate an unhealthy position and keep the liquidator's share of the seized
+    /// collateral in the market as the sender's collateral (send debt_denom with msg).
+    /// Requires the market to be enabled.
+    LiquidateAndSupply { borrower: String },
+
+    /// Liquidate several unhealthy positions as `(borrower, debt_to_repay)` pairs
+    /// (send debt_denom with msg). The funds must equal the sum of the amounts and
+    /// the whole batch fails if any position can't be liquidated. A
+    /// `Vec<LiquidationResult>` is returned as the response data.
+    LiquidateMultiple { borrowers: Vec<(String, Uint128)> },
+
+    /// Clear a healthy dust position's debt without repayment (anyone but the borrower).
+    /// Only positions with debt at or below dust_debt_threshold and health factor >= 1
+    /// are eligible. Collateral is left untouched; the cleared debt is socialized across
+    /// suppliers and added to `SocializedLosses`.
+    SweepDust { borrower: String },
+
+    /// Swap the sender's entire collateral through a DEX router in one transaction.
+    /// The router is called with `swap_msg` and the collateral as funds, and must return
+    /// at least `min_receive` of the collateral denom. The position must satisfy LTV afterwards.
+    /// Only available on markets with `allow_collateral_swap` enabled.
+    CollateralSwap {
+        router: String,
+        swap_msg: Binary,
+        min_receive: Uint128,
+    },
+
+    /// Repay debt by swapping some of the sender's collateral through a DEX router.
+    /// `collateral_to_use` is removed from the position and sent to `router` with
+    /// `route_msg`; the debt asset it returns repays up to `max_debt_repaid` and any
+    /// excess is refunded. The position must satisfy LTV afterwards.
+    RepayWithCollateral {
+        collateral_to_use: Uint128,
+        max_debt_repaid: Uint128,
+        router: String,
+        route_msg: Binary,
+    },
+
     /// Update market parameters (curator only)
-    UpdateParams { updates: MarketParamsUpdate },
+    UpdateParams { updates: Box<MarketParamsUpdate> },
+
+    /// Reserve `limit` of liquidity for `borrower` (curator only).
+    /// Privileged borrowers can draw on the reserve beyond the general pool;
+    /// other borrowers cannot. A zero limit removes the borrower.
+    SetPrivilegedBorrower { borrower: String, limit: Uint128 },
+
+    /// Add (`allowed: true`) or remove addresses from the collateral depositor
+    /// whitelist (curator only). Only enforced while `collateral_whitelist_enabled`.
+    SetCollateralWhitelist {
+        addresses: Vec<String>,
+        allowed: bool,
+    },
+
+    /// Pause the market for new operations (factory only).
+    /// Used by the factory owner to halt a market in an emergency. The pause is
+    /// separate from `enabled`, so the curator cannot lift it with `UpdateParams`.
+    Pause {},
+
+    /// Lift an emergency pause (factory only).
+    Unpause {},
+
+    /// Permanently lock the LTV at its current value (factory only).
+    /// Marks the market immutable, so later `UpdateParams` LTV changes are rejected.
+    FreezeLtv {},
+
+    /// Switch the collateral denom after the collateral token has been migrated
+    /// (factory only). The oracle must serve a fresh price for `new_denom`.
+    /// Existing collateral balances carry over unchanged in the new denom.
+    UpdateCollateralDenom { new_denom: String },
+
+    /// Replace the address that receives protocol fees (factory only).
+    /// Sent by the factory when a new collector accepts the role.
+    UpdateProtocolFeeCollector { new_collector: String },
+
+    /// Send accrued protocol fees to `recipient`, ignoring the available
+    /// liquidity cap but not the market's balance (factory only). For recovering
+    /// fees from a market being sunset.
+    EmergencyWithdraw { recipient: String },
+
+    /// Correct rounding drift in the liquidity index (factory only). The target
+    /// must be within 0.0001 of the current index.
+    RebalanceLiquidityIndex { target_index: Decimal },
 
     /// Accrue interest (can be called by anyone)
     AccrueInterest {},
@@ -233,6 +653,15 @@ pub enum MarketExecuteMsg {
     /// Only callable by protocol fee collector or curator.
     /// Claims are limited by available liquidity (fees must be backed by actual tokens).
     ClaimFees {},
+
+    /// Claim exact amounts of accrued fees.
+    /// `protocol_amount` is only claimable by the protocol fee collector and
+    /// `curator_amount` only by the curator. Each must not exceed its accrued
+    /// fees, and together they must be covered by available liquidity.
+    ClaimFeesPartial {
+        protocol_amount: Option<Uint128>,
+        curator_amount: Option<Uint128>,
+    },
 }
 
 /// Query messages for market contract.
@@ -255,8 +684,8 @@ pub enum MarketQueryMsg {
     #[returns(UserPositionResponse)]
     UserPosition { user: String },
 
-    /// Get user's supply balance
-    #[returns(UserBalanceResponse)]
+    /// Get user's supply balance, with interest accrued up to the current block
+    #[returns(UserSupplyResponse)]
     UserSupply { user: String },
 
     /// Get user's collateral balance
@@ -274,6 +703,63 @@ pub enum MarketQueryMsg {
     /// Get accrued protocol and curator fees (not yet claimed)
     #[returns(AccruedFeesResponse)]
     AccruedFees {},
+
+    /// Get the fees the protocol fee collector and the curator would each receive
+    /// from `ClaimFees` right now, given current liquidity
+    #[returns(ClaimableFeesPreviewResponse)]
+    ClaimableFeesPreview {},
+
+    /// Get a snapshot of market TVL, utilization, rates, and fees with
+    /// interest accrued up to the current block
+    #[returns(MarketSummaryResponse)]
+    MarketSummary {},
+
+    /// Get a user's complete risk profile in a single read
+    #[returns(HealthMetrics)]
+    HealthMetrics { user: String },
+
+    /// Get borrow and supply rates as they would be after accruing interest
+    /// up to the current block
+    #[returns(CurrentRatesResponse)]
+    CurrentRates {},
+
+    /// Get a user's raw supply share balance
+    #[returns(UserShareBalanceResponse)]
+    UserShareBalance { user: String },
+
+    /// Get the current value of a user's supply shares in the debt asset,
+    /// with interest accrued up to the current block
+    #[returns(UserShareValueResponse)]
+    UserShareValue { user: String },
+
+    /// Get the most recent liquidations, newest first
+    #[returns(LiquidationHistoryResponse)]
+    LiquidationHistory { limit: Option<u32> },
+
+    /// Get the most recent interest accruals that moved the indices, newest first
+    #[returns(InterestHistoryResponse)]
+    InterestHistory { limit: Option<u32> },
+
+    /// Simulate a borrow of `amount` by `user` without executing it
+    #[returns(PreviewBorrowResponse)]
+    PreviewBorrow { user: String, amount: Uint128 },
+
+    /// Simulate liquidating `borrower` with `debt_amount` of the debt asset,
+    /// with interest accrued up to the current block
+    #[returns(LiquidationSimulationResponse)]
+    SimulateLiquidation {
+        borrower: String,
+        debt_amount: Uint128,
+    },
+
+    /// Get the total debt asset supplied with `referrer` as the referrer
+    #[returns(Uint128)]
+    ReferralVolume { referrer: String },
+
+    /// Get the cumulative bad debt socialized across suppliers after liquidations,
+    /// dust sweeps and dust forgiveness on repay
+    #[returns(Uint128)]
+    SocializedLosses {},
 }
 
 // ============================================================================
@@ -293,6 +779,10 @@ pub struct MarketConfigResponse {
     pub protocol_fee_collector: String,
     /// Salt used when creating this market (required for correct market_id computation)
     pub salt: Option<u64>,
+    /// Whether in-place collateral swaps are enabled
+    pub allow_collateral_swap: bool,
+    /// NFT contract minting supply receipts, if any
+    pub receipt_nft_contract: Option<String>,
 }
 
 #[cw_serde]
@@ -309,6 +799,16 @@ pub struct MarketParamsResponse {
     pub curator_fee: Decimal,
     pub supply_cap: Option<Uint128>,
     pub borrow_cap: Option<Uint128>,
+    pub min_borrow_amount: Uint128,
+    pub min_reserve_ratio: Decimal,
+    pub min_supply_blocks: u64,
+    pub max_liquidation_value_usd: Option<Decimal>,
+    pub collateral_whitelist_enabled: bool,
+    pub min_post_borrow_health_factor: Option<Decimal>,
+    pub protocol_borrow_fee_flat: Uint128,
+    pub auto_clear_dust: bool,
+    pub liquidation_bonus_by_health_factor: Vec<(Decimal, Decimal)>,
+    pub withdraw_fee: Decimal,
     pub enabled: bool,
     pub is_mutable: bool,
     pub ltv_last_update: u64,
@@ -351,6 +851,17 @@ pub struct UserBalanceResponse {
     pub value: Decimal,
 }
 
+#[cw_serde]
+pub struct UserSupplyResponse {
+    pub user: String,
+    /// Scaled supply as stored
+    pub scaled_supply: Uint128,
+    /// Debt asset the supply is worth at `liquidity_index` (rounded down)
+    pub actual_supply: Uint128,
+    /// Liquidity index projected to the current block
+    pub liquidity_index: Decimal,
+}
+
 #[cw_serde]
 pub struct IsLiquidatableResponse {
     pub is_liquidatable: bool,
@@ -366,10 +877,520 @@ pub struct AccruedFeesResponse {
     pub accrued_curator_fees: Uint128,
 }
 
+#[cw_serde]
+pub struct ClaimableFeesPreviewResponse {
+    /// Amount the protocol fee collector would receive
+    pub protocol_claimable: Uint128,
+    /// Amount the curator would receive
+    pub curator_claimable: Uint128,
+    /// Liquidity available to cover fee claims
+    pub available_liquidity: Uint128,
+    /// Whether available liquidity is short of the total accrued fees
+    pub fee_coverage_partial: bool,
+}
+
+#[cw_serde]
+pub struct MarketSummaryResponse {
+    /// Total supplied debt asset, including interest accrued up to the current block
+    pub total_supply: Uint128,
+    /// Total borrowed debt asset, including interest accrued up to the current block
+    pub total_debt: Uint128,
+    /// Total collateral deposited
+    pub total_collateral: Uint128,
+    /// Current utilization (total_debt / total_supply)
+    pub utilization: Decimal,
+    /// Current annual borrow rate
+    pub borrow_rate: Decimal,
+    /// Current annual supply rate
+    pub supply_rate: Decimal,
+    /// Protocol and curator fees accrued but not yet claimed (in debt token)
+    pub total_accrued_fees: Uint128,
+    /// Total supply valued in USD, if a debt asset price updated within the last 300 seconds is available
+    pub total_supply_usd: Option<Decimal>,
+    /// Timestamp interest has been accrued up to (current block time)
+    pub last_update: u64,
+}
+
+#[cw_serde]
+pub struct UserShareBalanceResponse {
+    /// Supply shares held by the user
+    pub shares: Uint128,
+    /// Supply shares outstanding across all suppliers
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct UserShareValueResponse {
+    /// Supply shares held by the user
+    pub shares: Uint128,
+    /// Debt asset the shares are redeemable for (rounded down)
+    pub value: Uint128,
+}
+
+#[cw_serde]
+pub struct CurrentRatesResponse {
+    /// Annual borrow rate at the post-accrual utilization
+    pub borrow_rate: Decimal,
+    /// Annual supply rate at the post-accrual utilization
+    pub supply_rate: Decimal,
+    /// Utilization after accruing interest up to `timestamp`
+    pub utilization: Decimal,
+    /// Block time the rates were computed for
+    pub timestamp: u64,
+}
+
+/// A completed liquidation, kept in the market's bounded audit trail.
+#[cw_serde]
+pub struct LiquidationEvent {
+    pub borrower: String,
+    /// Debt asset repaid by the liquidator
+    pub debt_repaid: Uint128,
+    /// Collateral removed from the position, including bonus and protocol fee
+    pub collateral_seized: Uint128,
+    /// Health factor of the position when it was liquidated
+    pub health_factor_before: Decimal,
+    /// Block time of the liquidation
+    pub timestamp: u64,
+}
+
+/// Outcome of one liquidation in a `LiquidateMultiple` batch.
+#[cw_serde]
+pub struct LiquidationResult {
+    pub borrower: String,
+    /// Debt asset repaid by the liquidator
+    pub debt_repaid: Uint128,
+    /// Collateral removed from the position, including bonus and protocol fee
+    pub collateral_seized: Uint128,
+    /// Share of the seized collateral sent to the liquidator
+    pub liquidator_collateral: Uint128,
+    /// Share of the seized collateral sent to the fee collector
+    pub protocol_fee: Uint128,
+    /// Part of the amount sent for this borrower that was not needed and is refunded
+    pub refund: Uint128,
+    /// Health factor of the position before the liquidation
+    pub health_factor_before: Decimal,
+    /// Health factor after the liquidation (None if no debt is left)
+    pub health_factor_after: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct LiquidationHistoryResponse {
+    pub liquidations: Vec<LiquidationEvent>,
+}
+
+/// An interest accrual that moved the market indices, kept in the market's
+/// bounded interest history.
+#[cw_serde]
+pub struct InterestAccrualEvent {
+    /// Borrow index after the accrual
+    pub borrow_index: Decimal,
+    /// Liquidity index after the accrual
+    pub liquidity_index: Decimal,
+    /// Borrow rate applied over the elapsed period
+    pub borrow_rate: Decimal,
+    /// Supply rate applied over the elapsed period
+    pub liquidity_rate: Decimal,
+    /// Block time of the accrual
+    pub timestamp: u64,
+    /// Seconds since the previous accrual
+    pub elapsed_secs: u64,
+}
+
+#[cw_serde]
+pub struct InterestHistoryResponse {
+    pub events: Vec<InterestAccrualEvent>,
+}
+
+#[cw_serde]
+pub struct PreviewBorrowResponse {
+    /// Whether the borrow would succeed at the current block
+    pub allowed: bool,
+    /// Health factor after the borrow (None if it cannot be calculated)
+    pub health_factor_after: Option<Decimal>,
+    /// Largest amount the user could borrow, limited by LTV and available liquidity
+    pub max_allowed_amount: Uint128,
+    /// Why the borrow would fail, if it would
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct BorrowPreviewResponse {
+    /// Whether the sender could borrow the amount at the current block
+    pub eligible: bool,
+    /// Utilization after the borrow, with interest accrued to the current block
+    pub new_utilization: Decimal,
+    /// Annual borrow rate at the new utilization
+    pub new_borrow_rate: Decimal,
+    /// Largest amount the sender could borrow, limited by LTV and available liquidity
+    pub max_borrow: Uint128,
+    /// Why the borrow would fail, if it would
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct SupplyPreviewResponse {
+    /// Total supply after the supply, with interest accrued to the current block
+    pub new_total_supply: Uint128,
+    /// Utilization after the supply
+    pub new_utilization: Decimal,
+    /// Annual borrow rate at the new utilization
+    pub borrow_rate: Decimal,
+    /// Annual supply rate at the new utilization
+    pub supply_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct LiquidationSimulationResponse {
+    /// Debt that would be repaid on behalf of the borrower
+    pub debt_repaid: Uint128,
+    /// Collateral the liquidator would receive, protocol fee excluded
+    pub collateral_received: Uint128,
+    /// Seized collateral that would go to the protocol fee collector
+    pub protocol_fee_collateral: Uint128,
+    /// Part of `debt_amount` that would be refunded to the liquidator
+    pub refund: Uint128,
+    /// Borrower's health factor after the liquidation (None if no debt remains)
+    pub health_factor_after: Option<Decimal>,
+}
+
+/// A single health metric. When the calculation fails (e.g. overflow with
+/// extreme values) `value` is None and `calculation_error` holds the reason.
+#[cw_serde]
+pub struct HealthMetric<T> {
+    pub value: Option<T>,
+    pub calculation_error: Option<String>,
+}
+
+#[cw_serde]
+pub struct HealthMetrics {
+    pub collateral_amount: Uint128,
+    pub debt_amount: Uint128,
+    /// None with no error when the user has no debt (always healthy)
+    pub health_factor: HealthMetric<Decimal>,
+    /// Collateral price at which the position becomes liquidatable.
+    /// None with no error when the user has no debt or no collateral.
+    pub liquidation_price: HealthMetric<Decimal>,
+    /// Additional debt tokens that can be borrowed without exceeding LTV
+    pub max_borrow: HealthMetric<Uint128>,
+    /// Collateral tokens that can be withdrawn without exceeding LTV
+    pub max_withdraw: HealthMetric<Uint128>,
+    /// debt_value / collateral_value
+    pub debt_ratio: HealthMetric<Decimal>,
+    pub is_liquidatable: HealthMetric<bool>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn valid_params() -> MarketParams {
+        MarketParams {
+            loan_to_value: Decimal::percent(80),
+            liquidation_threshold: Decimal::percent(85),
+            liquidation_bonus: Decimal::percent(5),
+            liquidation_protocol_fee: Decimal::percent(2),
+            close_factor: Decimal::percent(50),
+            dust_debt_threshold: Uint128::new(100),
+            interest_rate_model: InterestRateModel::default(),
+            protocol_fee: Decimal::percent(10),
+            curator_fee: Decimal::percent(5),
+            supply_cap: None,
+            borrow_cap: None,
+            min_borrow_amount: Uint128::zero(),
+            min_reserve_ratio: Decimal::zero(),
+            min_supply_blocks: 0,
+            max_liquidation_value_usd: None,
+            collateral_whitelist_enabled: false,
+            min_post_borrow_health_factor: None,
+            protocol_borrow_fee_flat: Uint128::zero(),
+            auto_clear_dust: false,
+            liquidation_bonus_by_health_factor: vec![],
+            withdraw_fee: Decimal::zero(),
+            enabled: true,
+            is_mutable: false,
+            ltv_last_update: 0,
+        }
+    }
+
+    #[test]
+    fn test_market_params_validate_accepts_realistic_params() {
+        assert_eq!(valid_params().validate(), Ok(()));
+
+        // Full close factor is allowed
+        let params = MarketParams {
+            close_factor: Decimal::one(),
+            ..valid_params()
+        };
+        assert_eq!(params.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_market_params_validate_ltv_below_threshold() {
+        let params = MarketParams {
+            loan_to_value: Decimal::percent(85),
+            ..valid_params()
+        };
+        assert_eq!(params.validate(), Err(ContractError::InvalidLtv));
+    }
+
+    #[test]
+    fn test_market_params_validate_threshold_below_one() {
+        let params = MarketParams {
+            loan_to_value: Decimal::percent(90),
+            liquidation_threshold: Decimal::one(),
+            ..valid_params()
+        };
+        assert_eq!(
+            params.validate(),
+            Err(ContractError::InvalidLiquidationThreshold)
+        );
+    }
+
+    #[test]
+    fn test_market_params_validate_liquidation_incentive() {
+        let params = MarketParams {
+            liquidation_bonus: Decimal::percent(40),
+            liquidation_protocol_fee: Decimal::percent(10),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::InvalidLiquidationIncentive { .. })
+        ));
+    }
+
+    fn tiered_params() -> MarketParams {
+        MarketParams {
+            liquidation_bonus_by_health_factor: vec![
+                (Decimal::percent(95), Decimal::percent(6)),
+                (Decimal::percent(90), Decimal::percent(8)),
+                (Decimal::percent(80), Decimal::percent(12)),
+            ],
+            withdraw_fee: Decimal::zero(),
+            ..valid_params()
+        }
+    }
+
+    #[test]
+    fn test_liquidation_bonus_for_selects_tier() {
+        let params = tiered_params();
+        assert_eq!(params.validate(), Ok(()));
+
+        let cases = [
+            // Above every threshold: flat bonus
+            (Decimal::percent(97), Decimal::percent(5)),
+            (Decimal::percent(95), Decimal::percent(6)),
+            (Decimal::percent(92), Decimal::percent(6)),
+            (Decimal::percent(90), Decimal::percent(8)),
+            (Decimal::percent(85), Decimal::percent(8)),
+            (Decimal::percent(50), Decimal::percent(12)),
+        ];
+        for (health_factor, bonus) in cases {
+            assert_eq!(
+                params.liquidation_bonus_for(health_factor),
+                bonus,
+                "health factor {health_factor}"
+            );
+        }
+
+        // Without tiers the flat bonus always applies
+        assert_eq!(
+            valid_params().liquidation_bonus_for(Decimal::percent(50)),
+            Decimal::percent(5)
+        );
+    }
+
+    #[test]
+    fn test_market_params_validate_liquidation_bonus_tiers() {
+        let invalid_tiers = [
+            // Ascending
+            vec![
+                (Decimal::percent(90), Decimal::percent(8)),
+                (Decimal::percent(95), Decimal::percent(6)),
+            ],
+            // Duplicate threshold
+            vec![
+                (Decimal::percent(90), Decimal::percent(8)),
+                (Decimal::percent(90), Decimal::percent(10)),
+            ],
+            vec![(Decimal::zero(), Decimal::percent(8))],
+            vec![(Decimal::percent(101), Decimal::percent(8))],
+        ];
+        for tiers in invalid_tiers {
+            let params = MarketParams {
+                liquidation_bonus_by_health_factor: tiers,
+                ..valid_params()
+            };
+            assert!(matches!(
+                params.validate(),
+                Err(ContractError::InvalidLiquidationBonusTiers { .. })
+            ));
+        }
+
+        // A tier bonus is held to the same incentive limit as the flat bonus
+        let params = MarketParams {
+            liquidation_bonus_by_health_factor: vec![(Decimal::percent(90), Decimal::percent(48))],
+            withdraw_fee: Decimal::zero(),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::InvalidLiquidationIncentive { .. })
+        ));
+    }
+
+    #[test]
+    fn test_market_params_validate_close_factor() {
+        for close_factor in [Decimal::zero(), Decimal::percent(101)] {
+            let params = MarketParams {
+                close_factor,
+                ..valid_params()
+            };
+            assert!(matches!(
+                params.validate(),
+                Err(ContractError::InvalidCloseFactor { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_market_params_validate_interest_rate_model() {
+        let params = MarketParams {
+            interest_rate_model: InterestRateModel::Linear {
+                optimal_utilization: Decimal::percent(150),
+                base_rate: Decimal::zero(),
+                slope_1: Decimal::percent(4),
+                slope_2: Decimal::percent(75),
+            },
+            ..valid_params()
+        };
+        assert_eq!(
+            params.validate(),
+            Err(ContractError::InvalidInterestRateModel)
+        );
+    }
+
+    #[test]
+    fn test_market_params_validate_min_reserve_ratio() {
+        let params = MarketParams {
+            min_reserve_ratio: Decimal::percent(50),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+
+        let params = MarketParams {
+            min_reserve_ratio: Decimal::percent(51),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::InvalidMinReserveRatio { .. })
+        ));
+    }
+
+    #[test]
+    fn test_market_params_validate_max_liquidation_value() {
+        let params = MarketParams {
+            max_liquidation_value_usd: Some(Decimal::one()),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+
+        let params = MarketParams {
+            max_liquidation_value_usd: Some(Decimal::zero()),
+            ..valid_params()
+        };
+        assert_eq!(
+            params.validate(),
+            Err(ContractError::InvalidMaxLiquidationValue)
+        );
+    }
+
+    #[test]
+    fn test_market_params_validate_protocol_borrow_fee_flat() {
+        // No borrow cap, no bound
+        let params = MarketParams {
+            protocol_borrow_fee_flat: Uint128::new(1_000_000),
+            borrow_cap: None,
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+
+        let params = MarketParams {
+            protocol_borrow_fee_flat: Uint128::new(100),
+            borrow_cap: Some(Uint128::new(10_000)),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+
+        let params = MarketParams {
+            protocol_borrow_fee_flat: Uint128::new(101),
+            borrow_cap: Some(Uint128::new(10_000)),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::InvalidProtocolBorrowFeeFlat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_market_params_dust_debt_threshold_vs_borrow_cap() {
+        let params = MarketParams {
+            dust_debt_threshold: Uint128::new(1_000),
+            borrow_cap: Some(Uint128::new(10_000)),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+        assert!(!params.has_high_dust_debt_threshold());
+
+        // Above 10% of the cap is allowed but flagged
+        let params = MarketParams {
+            dust_debt_threshold: Uint128::new(1_001),
+            borrow_cap: Some(Uint128::new(10_000)),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+        assert!(params.has_high_dust_debt_threshold());
+
+        let params = MarketParams {
+            dust_debt_threshold: Uint128::new(10_001),
+            borrow_cap: Some(Uint128::new(10_000)),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::DustDebtThresholdTooHigh { .. })
+        ));
+
+        // Without a cap the threshold is never flagged
+        let params = MarketParams {
+            dust_debt_threshold: Uint128::new(10_000_000),
+            borrow_cap: None,
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+        assert!(!params.has_high_dust_debt_threshold());
+    }
+
+    #[test]
+    fn test_market_params_validate_min_post_borrow_health_factor() {
+        let params = MarketParams {
+            min_post_borrow_health_factor: Some(Decimal::one()),
+            ..valid_params()
+        };
+        assert!(params.validate().is_ok());
+
+        let params = MarketParams {
+            min_post_borrow_health_factor: Some(Decimal::percent(99)),
+            ..valid_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(ContractError::InvalidMinPostBorrowHealthFactor { .. })
+        ));
+    }
+
     #[test]
     fn test_market_state_new() {
         let state = MarketState::new(1000);
@@ -434,6 +1455,21 @@ mod tests {
         assert_eq!(state.total_debt(), Uint128::new(600));
     }
 
+    #[test]
+    fn test_available_liquidity_zero_supply() {
+        let state = MarketState::new(1000);
+        assert_eq!(state.available_liquidity(), Uint128::zero());
+    }
+
+    #[test]
+    fn test_available_liquidity_zero_debt() {
+        // All supply is available, at the current liquidity index
+        let mut state = MarketState::new(1000);
+        state.total_supply_scaled = Uint128::new(1000);
+        state.liquidity_index = Decimal::from_ratio(11u128, 10u128); // 1.1
+        assert_eq!(state.available_liquidity(), Uint128::new(1100));
+    }
+
     #[test]
     fn test_available_liquidity() {
         let mut state = MarketState::new(1000);
@@ -460,6 +1496,17 @@ mod tests {
         assert_eq!(state.available_liquidity(), Uint128::zero());
     }
 
+    #[test]
+    fn test_available_liquidity_over_utilized_by_indices() {
+        // Equal scaled amounts, but the borrow index has outgrown the liquidity index
+        let mut state = MarketState::new(1000);
+        state.total_supply_scaled = Uint128::new(1000);
+        state.total_debt_scaled = Uint128::new(1000);
+        state.liquidity_index = Decimal::from_ratio(11u128, 10u128); // 1.1
+        state.borrow_index = Decimal::from_ratio(12u128, 10u128); // 1.2
+        assert_eq!(state.available_liquidity(), Uint128::zero());
+    }
+
     #[test]
     fn test_utilization_clamped_to_one() {
         // When debt > supply, utilization should be clamped to 1.0
@@ -522,4 +1569,21 @@ mod tests {
         // 75% utilization
         assert_eq!(state.utilization(), Decimal::percent(75));
     }
+
+    #[test]
+    fn test_is_at_max_utilization() {
+        let mut state = MarketState::new(1000);
+        assert!(!state.is_at_max_utilization());
+
+        state.total_supply_scaled = Uint128::new(1000);
+        state.total_debt_scaled = Uint128::new(999);
+        assert!(!state.is_at_max_utilization());
+
+        state.total_debt_scaled = Uint128::new(1000);
+        assert!(state.is_at_max_utilization());
+
+        // Debt exceeding supply is capped, so still at max
+        state.total_debt_scaled = Uint128::new(1500);
+        assert!(state.is_at_max_utilization());
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal, Fraction, Uint128};
+use cosmwasm_std::{Decimal, Decimal256, Fraction, Uint128};
 
 use crate::error::ContractError;
 
@@ -8,6 +8,15 @@ pub fn mul_decimal(amount: Uint128, decimal: Decimal) -> Uint128 {
     amount.mul_floor(decimal)
 }
 
+/// Multiply Uint128 by Decimal, rounding down, computed in Decimal256.
+/// Returns `MathOverflow` instead of panicking if the result exceeds Uint128.
+pub fn safe_mul_decimal(a: Uint128, b: Decimal) -> Result<Uint128, ContractError> {
+    let product = Decimal256::from_ratio(a, 1u128)
+        .checked_mul(Decimal256::from(b))
+        .map_err(|_| ContractError::MathOverflow)?;
+    Uint128::try_from(product.to_uint_floor()).map_err(|_| ContractError::MathOverflow)
+}
+
 /// Multiply Uint128 by Decimal, rounding up.
 /// Use for debt calculations to ensure protocol never understates debt.
 pub fn mul_decimal_ceil(amount: Uint128, decimal: Decimal) -> Uint128 {
@@ -73,6 +82,33 @@ pub fn scaled_to_amount_ceil(scaled: Uint128, index: Decimal) -> Uint128 {
     mul_decimal_ceil(scaled, index)
 }
 
+/// Conversions between `Decimal` and the wider `Decimal256` used for
+/// intermediate calculations on large token amounts.
+pub trait Decimal256Ext {
+    /// Widen a `Decimal`; always exact.
+    fn from_decimal(decimal: Decimal) -> Decimal256;
+
+    /// Narrow to `Decimal`, returning `Decimal::MAX` if the value is out of range.
+    fn to_decimal_saturating(&self) -> Decimal;
+
+    /// Narrow to `Decimal`, returning `MathOverflow` if the value is out of range.
+    fn to_decimal_checked(&self) -> Result<Decimal, ContractError>;
+}
+
+impl Decimal256Ext for Decimal256 {
+    fn from_decimal(decimal: Decimal) -> Decimal256 {
+        Decimal256::from(decimal)
+    }
+
+    fn to_decimal_saturating(&self) -> Decimal {
+        Decimal::try_from(*self).unwrap_or(Decimal::MAX)
+    }
+
+    fn to_decimal_checked(&self) -> Result<Decimal, ContractError> {
+        Decimal::try_from(*self).map_err(|_| ContractError::MathOverflow)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +127,36 @@ mod tests {
         assert_eq!(mul_decimal(amount, index), Uint128::new(1100));
     }
 
+    #[test]
+    fn test_safe_mul_decimal() {
+        let amount = Uint128::new(1000);
+        assert_eq!(
+            safe_mul_decimal(amount, Decimal::percent(5)).unwrap(),
+            Uint128::new(50)
+        );
+        // Rounds down like checked_mul_floor
+        assert_eq!(
+            safe_mul_decimal(Uint128::new(999), Decimal::percent(5)).unwrap(),
+            Uint128::new(49)
+        );
+    }
+
+    #[test]
+    fn test_safe_mul_decimal_max_by_one() {
+        assert_eq!(
+            safe_mul_decimal(Uint128::MAX, Decimal::one()).unwrap(),
+            Uint128::MAX
+        );
+    }
+
+    #[test]
+    fn test_safe_mul_decimal_overflow() {
+        assert_eq!(
+            safe_mul_decimal(Uint128::MAX, Decimal::percent(101)),
+            Err(ContractError::MathOverflow)
+        );
+    }
+
     #[test]
     fn test_mul_decimal_ceil() {
         // Exact division: 1000 * 0.5 = 500
@@ -288,4 +354,42 @@ mod tests {
         // 1 share remains = protocol profit (protects against bad debt)
         assert!(scaled_from_borrow > scaled_for_repay);
     }
+
+    #[test]
+    fn test_decimal256_round_trip_zero_and_one() {
+        for value in [Decimal::zero(), Decimal::one()] {
+            let wide = Decimal256::from_decimal(value);
+            assert_eq!(wide.to_decimal_checked().unwrap(), value);
+            assert_eq!(wide.to_decimal_saturating(), value);
+        }
+    }
+
+    #[test]
+    fn test_decimal256_round_trip_max() {
+        let wide = Decimal256::from_decimal(Decimal::MAX);
+        assert_eq!(wide.to_decimal_checked().unwrap(), Decimal::MAX);
+        assert_eq!(wide.to_decimal_saturating(), Decimal::MAX);
+    }
+
+    #[test]
+    fn test_decimal256_just_over_max() {
+        let over = Decimal256::from_decimal(Decimal::MAX)
+            .checked_add(Decimal256::raw(1))
+            .unwrap();
+        assert_eq!(over.to_decimal_saturating(), Decimal::MAX);
+        assert_eq!(over.to_decimal_checked(), Err(ContractError::MathOverflow));
+    }
+
+    #[test]
+    fn test_decimal256_health_factor() {
+        // 10000 * 0.85 / 4000 = 2.125
+        let health_factor = Decimal256::from_ratio(10000u128, 1u128)
+            .checked_mul(Decimal256::from_decimal(Decimal::percent(85)))
+            .unwrap()
+            .checked_div(Decimal256::from_ratio(4000u128, 1u128))
+            .unwrap();
+        let expected = Decimal::from_ratio(2125u128, 1000u128);
+        assert_eq!(health_factor.to_decimal_checked().unwrap(), expected);
+        assert_eq!(health_factor.to_decimal_saturating(), expected);
+    }
 }
@@ -1,5 +1,7 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Api, Decimal, StdResult};
+use cosmwasm_std::{Addr, Api, Decimal, Decimal256, Deps, StdResult, Uint128};
+
+use crate::ContractError;
 
 /// Oracle query interface.
 /// This defines the interface that oracles must implement.
@@ -22,6 +24,37 @@ pub struct PriceResponse {
     pub updated_at: u64,
 }
 
+impl PriceResponse {
+    /// Whether the price is more than `max_staleness` seconds old at `current_time`.
+    /// A price exactly `max_staleness` old is not stale; a future price is never stale.
+    pub fn is_stale(&self, current_time: u64, max_staleness: u64) -> bool {
+        current_time.saturating_sub(self.updated_at) > max_staleness
+    }
+
+    /// Whether the price timestamp is ahead of `current_time` (clock skew).
+    pub fn is_future(&self, current_time: u64) -> bool {
+        self.updated_at > current_time
+    }
+
+    /// Value of `amount` collateral tokens at this price.
+    pub fn collateral_value(&self, amount: Uint128) -> Result<Decimal256, ContractError> {
+        self.value_of(amount)
+    }
+
+    /// Value of `amount` debt tokens at this price.
+    pub fn debt_value(&self, amount: Uint128) -> Result<Decimal256, ContractError> {
+        self.value_of(amount)
+    }
+
+    /// `amount * price` in `Decimal256`, which holds any `Uint128` amount times
+    /// any `Decimal` price where `Decimal` itself would overflow.
+    fn value_of(&self, amount: Uint128) -> Result<Decimal256, ContractError> {
+        Decimal256::from_ratio(amount, 1u128)
+            .checked_mul(Decimal256::from(self.price))
+            .map_err(|_| ContractError::MathOverflow)
+    }
+}
+
 /// Named oracle types with their configuration and validation rules.
 #[cw_serde]
 pub enum OracleType {
@@ -50,6 +83,14 @@ pub enum OracleType {
         /// Maximum allowed staleness in seconds (default: 3600s / 1 hour)
         max_staleness_secs: u64,
     },
+    /// One-off oracle that doesn't fit the named types.
+    /// Must implement Stone's OracleQueryMsg::Price; the code ID is never validated.
+    Custom {
+        /// Code ID the oracle is expected to run, recorded for reference only.
+        expected_code_id: Option<u64>,
+        /// Maximum allowed staleness in seconds
+        max_staleness_secs: u64,
+    },
 }
 
 impl OracleType {
@@ -65,10 +106,14 @@ impl OracleType {
             OracleType::Chainlink {
                 max_staleness_secs, ..
             } => *max_staleness_secs,
+            OracleType::Custom {
+                max_staleness_secs, ..
+            } => *max_staleness_secs,
         }
     }
 
-    /// Returns the expected code ID if validation is required
+    /// Returns the expected code ID if validation is required.
+    /// Always None for `Custom`, which bypasses code ID validation.
     pub fn expected_code_id(&self) -> Option<u64> {
         match self {
             OracleType::Generic {
@@ -80,7 +125,27 @@ impl OracleType {
             OracleType::Chainlink {
                 expected_code_id, ..
             } => Some(*expected_code_id),
+            OracleType::Custom { .. } => None,
+        }
+    }
+
+    /// Verify the oracle contract at `contract_addr` was instantiated from the
+    /// expected code ID. Passes without querying if no code ID is expected.
+    pub fn validate_code_id(&self, deps: Deps, contract_addr: &Addr) -> Result<(), ContractError> {
+        let Some(expected_code_id) = self.expected_code_id() else {
+            return Ok(());
+        };
+
+        let contract_info = deps.querier.query_wasm_contract_info(contract_addr)?;
+        if contract_info.code_id != expected_code_id {
+            return Err(ContractError::OracleContractCodeMismatch {
+                oracle: contract_addr.to_string(),
+                expected_code_id,
+                actual_code_id: contract_info.code_id,
+            });
         }
+
+        Ok(())
     }
 }
 
@@ -102,6 +167,36 @@ pub struct OracleConfig {
     pub oracle_type: OracleType,
 }
 
+impl OracleConfig {
+    /// Check that a price response is usable at `current_time`: its timestamp must
+    /// not be in the future (clock skew) and its age must not exceed the oracle
+    /// type's max staleness. A price exactly `max_staleness_secs` old is still fresh.
+    pub fn validate_fresh(
+        &self,
+        response: &PriceResponse,
+        current_time: u64,
+    ) -> Result<(), ContractError> {
+        if response.is_future(current_time) {
+            return Err(ContractError::OraclePriceFuture {
+                denom: response.denom.clone(),
+                updated_at: response.updated_at,
+                current: current_time,
+            });
+        }
+
+        let max_staleness = self.oracle_type.max_staleness_secs();
+        if response.is_stale(current_time, max_staleness) {
+            return Err(ContractError::OraclePriceStale {
+                denom: response.denom.clone(),
+                age_seconds: current_time - response.updated_at,
+                max_staleness,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 /// Oracle configuration passed during instantiation (unchecked addresses).
 #[cw_serde]
 pub struct OracleConfigUnchecked {
@@ -140,4 +235,226 @@ mod tests {
         assert_eq!(parsed.price, Decimal::from_ratio(10u128, 1u128));
         assert_eq!(parsed.updated_at, 1000);
     }
+
+    fn price_response(price: Decimal) -> PriceResponse {
+        PriceResponse {
+            denom: "uatom".to_string(),
+            price,
+            updated_at: 1000,
+        }
+    }
+
+    #[test]
+    fn test_price_response_values_zero_amount() {
+        let response = price_response(Decimal::from_ratio(10u128, 1u128));
+        assert_eq!(
+            response.collateral_value(Uint128::zero()).unwrap(),
+            Decimal256::zero()
+        );
+        assert_eq!(
+            response.debt_value(Uint128::zero()).unwrap(),
+            Decimal256::zero()
+        );
+    }
+
+    #[test]
+    fn test_price_response_values_typical_amount() {
+        // 1_500_000 tokens at $10.25
+        let response = price_response(Decimal::from_ratio(41u128, 4u128));
+        let expected = Decimal256::from_ratio(15_375_000u128, 1u128);
+        assert_eq!(
+            response.collateral_value(Uint128::new(1_500_000)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            response.debt_value(Uint128::new(1_500_000)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_price_response_values_near_max() {
+        // 10^18 tokens at $1,000,000 is 10^24, far past Decimal's ~3.4 * 10^20
+        let amount = Uint128::new(10u128.pow(18));
+        let price = Decimal::from_ratio(1_000_000u128, 1u128);
+        assert!(Decimal::from_ratio(amount, 1u128)
+            .checked_mul(price)
+            .is_err());
+
+        let response = price_response(price);
+        let expected = Decimal256::from_ratio(10u128.pow(24), 1u128);
+        assert_eq!(response.collateral_value(amount).unwrap(), expected);
+        assert_eq!(response.debt_value(amount).unwrap(), expected);
+
+        // Even the largest amount at the largest Decimal price fits Decimal256
+        let response = price_response(Decimal::MAX);
+        assert!(response.collateral_value(Uint128::MAX).is_ok());
+    }
+
+    fn fresh_config() -> OracleConfig {
+        OracleConfig {
+            address: Addr::unchecked("oracle"),
+            oracle_type: OracleType::Generic {
+                expected_code_id: None,
+                max_staleness_secs: 300,
+            },
+        }
+    }
+
+    fn price_at(updated_at: u64) -> PriceResponse {
+        PriceResponse {
+            denom: "uatom".to_string(),
+            price: Decimal::one(),
+            updated_at,
+        }
+    }
+
+    #[test]
+    fn test_validate_fresh_accepts_recent_price() {
+        let config = fresh_config();
+        assert_eq!(config.validate_fresh(&price_at(1000), 1000), Ok(()));
+        assert_eq!(config.validate_fresh(&price_at(1000), 1100), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_fresh_rejects_stale_price() {
+        let config = fresh_config();
+        assert_eq!(
+            config.validate_fresh(&price_at(1000), 1301),
+            Err(ContractError::OraclePriceStale {
+                denom: "uatom".to_string(),
+                age_seconds: 301,
+                max_staleness: 300,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_fresh_rejects_future_price() {
+        let config = fresh_config();
+        assert_eq!(
+            config.validate_fresh(&price_at(1001), 1000),
+            Err(ContractError::OraclePriceFuture {
+                denom: "uatom".to_string(),
+                updated_at: 1001,
+                current: 1000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_fresh_at_staleness_boundary() {
+        let config = fresh_config();
+        // Exactly max_staleness old is still accepted
+        assert_eq!(config.validate_fresh(&price_at(1000), 1300), Ok(()));
+    }
+
+    #[test]
+    fn test_is_stale_boundaries() {
+        let price = price_at(1000);
+        // Exactly max_staleness old is still fresh
+        assert!(!price.is_stale(1300, 300));
+        assert!(price.is_stale(1301, 300));
+        // Future prices are never stale
+        assert!(!price.is_stale(999, 300));
+    }
+
+    #[test]
+    fn test_is_stale_zero_timestamp() {
+        let price = price_at(0);
+        assert!(!price.is_stale(0, 0));
+        assert!(!price.is_stale(300, 300));
+        assert!(price.is_stale(301, 300));
+    }
+
+    #[test]
+    fn test_is_future() {
+        let price = price_at(1000);
+        assert!(price.is_future(999));
+        assert!(!price.is_future(1000));
+        assert!(!price.is_future(1001));
+        assert!(!price_at(0).is_future(0));
+    }
+
+    fn deps_with_code_id(
+        code_id: u64,
+    ) -> cosmwasm_std::OwnedDeps<
+        cosmwasm_std::MemoryStorage,
+        cosmwasm_std::testing::MockApi,
+        cosmwasm_std::testing::MockQuerier,
+    > {
+        use cosmwasm_std::{Binary, ContractResult, SystemResult, WasmQuery};
+
+        let mut deps = cosmwasm_std::testing::mock_dependencies();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let info = format!(
+                    r#"{{"code_id":{code_id},"creator":"creator","admin":null,"pinned":false,"ibc_port":null}}"#
+                );
+                SystemResult::Ok(ContractResult::Ok(Binary::from(info.into_bytes())))
+            }
+            _ => panic!("unexpected query"),
+        });
+        deps
+    }
+
+    #[test]
+    fn test_validate_code_id_match() {
+        let deps = deps_with_code_id(7);
+        let oracle_type = OracleType::Pyth {
+            expected_code_id: 7,
+            max_staleness_secs: 60,
+            max_confidence_ratio: Decimal::percent(1),
+        };
+
+        oracle_type
+            .validate_code_id(deps.as_ref(), &Addr::unchecked("oracle"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validate_code_id_mismatch() {
+        let deps = deps_with_code_id(8);
+        let oracle_type = OracleType::Chainlink {
+            expected_code_id: 7,
+            max_staleness_secs: 3600,
+        };
+
+        let err = oracle_type
+            .validate_code_id(deps.as_ref(), &Addr::unchecked("oracle"))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OracleContractCodeMismatch {
+                oracle: "oracle".to_string(),
+                expected_code_id: 7,
+                actual_code_id: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_code_id_skipped_without_expectation() {
+        // Default querier knows no contracts, so this only passes if no query is made
+        let deps = cosmwasm_std::testing::mock_dependencies();
+        OracleType::default()
+            .validate_code_id(deps.as_ref(), &Addr::unchecked("oracle"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_custom_oracle_skips_code_id_validation() {
+        // Default querier knows no contracts, so this only passes if no query is made
+        let deps = cosmwasm_std::testing::mock_dependencies();
+        let oracle_type = OracleType::Custom {
+            expected_code_id: Some(7),
+            max_staleness_secs: 120,
+        };
+
+        assert_eq!(oracle_type.expected_code_id(), None);
+        assert_eq!(oracle_type.max_staleness_secs(), 120);
+        oracle_type
+            .validate_code_id(deps.as_ref(), &Addr::unchecked("oracle"))
+            .unwrap();
+    }
 }
@@ -23,23 +23,41 @@ export type MarketQueryMsg =
 
 // Market Execute Messages
 export type MarketExecuteMsg =
-  | { supply: { recipient?: string } }
-  | { withdraw: { amount?: string; recipient?: string } }
+  | { supply: { recipient?: string; referrer?: string; force?: boolean; nonce?: number } }
+  | { supply_preview: { amount: string } }
+  | { withdraw: { amount?: string; recipient?: string; nonce?: number } }
+  | { transfer_supply_shares: { recipient: string; amount: string } }
   | { supply_collateral: { recipient?: string } }
   | { withdraw_collateral: { amount?: string; recipient?: string } }
   | { borrow: { amount: string; recipient?: string } }
+  | { borrow_preview: { amount: string } }
   | { repay: { on_behalf_of?: string } }
+  | { repay_max: {} }
   | { liquidate: { borrower: string } }
+  | { liquidate_multiple: { borrowers: [string, string][] } }
   | { accrue_interest: {} };
 
+// Ordering for the factory markets query (ties are ordered by market ID)
+export type MarketSortField = "address" | "collateral_denom" | "debt_denom" | "created_at";
+
+// Filter for the factory markets_by query
+export type MarketFilter =
+  | { collateral_denom: string }
+  | { debt_denom: string }
+  | { curator: string }
+  | "all";
+
 // Factory Query Messages
 export type FactoryQueryMsg =
   | { config: {} }
   | { market: { market_id: string } }
   | { market_by_address: { address: string } }
-  | { markets: { start_after?: string; limit?: number } }
+  | { markets: { start_after?: string; limit?: number; sort_by?: MarketSortField } }
   | { markets_by_curator: { curator: string; start_after?: string; limit?: number } }
-  | { market_count: {} };
+  | { markets_by: { filter: MarketFilter; start_after?: string; limit?: number } }
+  | { market_count: {} }
+  | { factory_params: {} }
+  | { total_protocol_tvl: {} };
 
 // Market Config Response
 export interface MarketConfigResponse {
@@ -88,6 +106,23 @@ export interface MarketStateResponse {
   last_update: number;
 }
 
+// Borrow Preview Response (data of a borrow_preview execution)
+export interface BorrowPreviewResponse {
+  eligible: boolean;
+  new_utilization: Decimal;
+  new_borrow_rate: Decimal;
+  max_borrow: string;
+  error?: string;
+}
+
+// Supply Preview Response (data of a supply_preview execution)
+export interface SupplyPreviewResponse {
+  new_total_supply: string;
+  new_utilization: Decimal;
+  borrow_rate: Decimal;
+  supply_rate: Decimal;
+}
+
 // User Balance Response
 export interface UserBalanceResponse {
   scaled_amount: string;
@@ -95,6 +130,14 @@ export interface UserBalanceResponse {
   usd_value: Decimal;
 }
 
+// User Supply Response
+export interface UserSupplyResponse {
+  user: string;
+  scaled_supply: string;
+  actual_supply: string;
+  liquidity_index: Decimal;
+}
+
 // User Position Response
 export interface UserPositionResponse {
   collateral_amount: string;
@@ -115,12 +158,35 @@ export interface IsLiquidatableResponse {
   shortfall?: Decimal;
 }
 
+// Per-borrower result of liquidate_multiple (response data)
+export interface LiquidationResult {
+  borrower: string;
+  debt_repaid: string;
+  collateral_seized: string;
+  liquidator_collateral: string;
+  protocol_fee: string;
+  refund: string;
+  health_factor_before: Decimal;
+  health_factor_after?: Decimal;
+}
+
 // Factory Config Response
 export interface FactoryConfigResponse {
   owner: string;
   market_code_id: number;
   protocol_fee_collector: string;
   market_creation_fee?: Coin;
+  max_market_creation_fee?: Coin;
+}
+
+// Factory Params (full governance parameters)
+export interface FactoryParams {
+  owner: string;
+  protocol_fee_collector: string;
+  market_creation_fee: Coin;
+  max_market_creation_fee: Coin;
+  market_code_id: number;
+  paused: boolean;
 }
 
 // Market Info (from factory)
@@ -142,3 +208,11 @@ export interface MarketsResponse {
 export interface MarketCountResponse {
   count: number;
 }
+
+// Protocol TVL Response
+export interface ProtocolTvlResponse {
+  total_supply_usd: Decimal;
+  total_debt_usd: Decimal;
+  market_count: number;
+  failed_markets: string[];
+}